@@ -0,0 +1,289 @@
+// age_tier.rs — Friendly age labels for the /Tier column
+//
+// /Tier shows "new", "recent", "old", or "ancient" in place of reading a
+// date: each entry's age since creation is compared against three
+// cutoffs.  Younger than the first is new, younger than the second is
+// recent, younger than the third is old, and anything older is ancient.
+// The cutoffs default to 1d,30d,365d and can be changed with the RCDIR
+// entry AgeTiers=<new>,<recent>,<old> (units m, h, d, w, as /Recent).
+
+use crate::color::{FC_BROWN, FC_DARK_GREY, FC_GREEN, FC_LIGHT_GREEN};
+use crate::command_line::CommandLine;
+
+
+
+
+
+/// Width of the /Tier column: the longest label plus the trailing space.
+pub const TIER_COLUMN_WIDTH: usize = 8;
+
+/// FILETIME ticks in one day (100ns resolution).
+const TICKS_PER_DAY: u64 = 24 * 60 * 60 * 10_000_000;
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// How old an entry is, coarsely.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgeTier {
+    New,
+    Recent,
+    Old,
+    Ancient,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl AgeTier
+//
+//  Column labels and colors.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl AgeTier {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  label
+    //
+    //  The word shown in the /Tier column.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AgeTier::New     => "new",
+            AgeTier::Recent  => "recent",
+            AgeTier::Old     => "old",
+            AgeTier::Ancient => "ancient",
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  color
+    //
+    //  Bright for fresh entries, fading to dark grey for ancient ones.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn color(self) -> u16 {
+        match self {
+            AgeTier::New     => FC_LIGHT_GREEN,
+            AgeTier::Recent  => FC_GREEN,
+            AgeTier::Old     => FC_BROWN,
+            AgeTier::Ancient => FC_DARK_GREY,
+        }
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Upper age bounds (FILETIME ticks) of the new, recent, and old tiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AgeTierThresholds {
+    pub new:    u64,
+    pub recent: u64,
+    pub old:    u64,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl Default for AgeTierThresholds
+//
+//  1 day, 30 days, 365 days.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl Default for AgeTierThresholds {
+    fn default() -> Self {
+        AgeTierThresholds {
+            new:    TICKS_PER_DAY,
+            recent: TICKS_PER_DAY * 30,
+            old:    TICKS_PER_DAY * 365,
+        }
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl AgeTierThresholds
+//
+//  AgeTiers= parsing and tier lookup.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl AgeTierThresholds {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse
+    //
+    //  Parse "1d,30d,365d": three positive durations in increasing order.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn parse(value: &str) -> Option<Self> {
+        let cutoffs: Vec<u64> = value
+            .split (',')
+            .map (|part| CommandLine::parse_recent_window (part.trim()))
+            .collect::<Option<_>>()?;
+
+        match cutoffs[..] {
+            [new, recent, old] if new < recent && recent < old => Some (AgeTierThresholds { new, recent, old }),
+            _ => None,
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  tier_for_age
+    //
+    //  The tier of an entry `age` ticks old.  Each cutoff belongs to the
+    //  older tier.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn tier_for_age(&self, age: u64) -> AgeTier {
+        if age < self.new {
+            AgeTier::New
+        } else if age < self.recent {
+            AgeTier::Recent
+        } else if age < self.old {
+            AgeTier::Old
+        } else {
+            AgeTier::Ancient
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  tier
+    //
+    //  The tier of an entry created at `creation_time`, as of `now`.
+    //  Creation times in the future count as new.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn tier(&self, creation_time: u64, now: u64) -> AgeTier {
+        self.tier_for_age (now.saturating_sub (creation_time))
+    }
+}
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: u64 = TICKS_PER_DAY / 24;
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  default_thresholds_map_ages_to_tiers
+    //
+    //  Verify ages on either side of each default cutoff, and that each
+    //  cutoff itself falls in the older tier.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn default_thresholds_map_ages_to_tiers() {
+        let tiers = AgeTierThresholds::default();
+
+        assert_eq! (tiers.tier_for_age (0),                         AgeTier::New);
+        assert_eq! (tiers.tier_for_age (23 * HOUR),                 AgeTier::New);
+        assert_eq! (tiers.tier_for_age (TICKS_PER_DAY),             AgeTier::Recent);
+        assert_eq! (tiers.tier_for_age (29 * TICKS_PER_DAY),        AgeTier::Recent);
+        assert_eq! (tiers.tier_for_age (30 * TICKS_PER_DAY),        AgeTier::Old);
+        assert_eq! (tiers.tier_for_age (364 * TICKS_PER_DAY),       AgeTier::Old);
+        assert_eq! (tiers.tier_for_age (365 * TICKS_PER_DAY),       AgeTier::Ancient);
+        assert_eq! (tiers.tier_for_age (10 * 365 * TICKS_PER_DAY),  AgeTier::Ancient);
+
+        let now = 1_000 * TICKS_PER_DAY;
+        assert_eq! (tiers.tier (now - 2 * TICKS_PER_DAY, now), AgeTier::Recent);
+        assert_eq! (tiers.tier (now + HOUR, now),              AgeTier::New);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parsed_thresholds_map_ages_to_tiers
+    //
+    //  Verify AgeTiers= values with mixed units set the cutoffs, and bad
+    //  counts, units, or orderings are rejected.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parsed_thresholds_map_ages_to_tiers() {
+        let tiers = AgeTierThresholds::parse ("12h, 1w, 90d").unwrap();
+
+        assert_eq! (tiers.new, 12 * HOUR);
+        assert_eq! (tiers.tier_for_age (11 * HOUR),          AgeTier::New);
+        assert_eq! (tiers.tier_for_age (2 * TICKS_PER_DAY),  AgeTier::Recent);
+        assert_eq! (tiers.tier_for_age (30 * TICKS_PER_DAY), AgeTier::Old);
+        assert_eq! (tiers.tier_for_age (90 * TICKS_PER_DAY), AgeTier::Ancient);
+
+        assert_eq! (AgeTierThresholds::parse ("1d,30d,365d"), Some (AgeTierThresholds::default()));
+
+        for bad in ["", "1d,30d", "1d,30d,365d,900d", "30d,1d,365d", "1d,1d,365d", "1d,30x,365d", "0d,30d,365d"] {
+            assert_eq! (AgeTierThresholds::parse (bad), None, "{bad}");
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  labels_fit_column
+    //
+    //  Every label plus its trailing space fits TIER_COLUMN_WIDTH.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn labels_fit_column() {
+        let all = [AgeTier::New, AgeTier::Recent, AgeTier::Old, AgeTier::Ancient];
+
+        assert_eq! (all.iter().map (|t| t.label().len() + 1).max(), Some (TIER_COLUMN_WIDTH));
+    }
+}
@@ -0,0 +1,637 @@
+// archive.rs — List .zip contents as a directory (/InZip)
+//
+// When /InZip is given and a mask names a .zip file, the archive's
+// central directory is read and each entry becomes a synthetic FileInfo
+// (inner path, uncompressed size, DOS timestamp, DOS attributes), so the
+// normal sort/display/totals path renders it with colors and icons.  Only
+// the end of central directory record and the central directory are read
+// from disk; nothing is decompressed.  ZIP64 archives and multi-disk
+// archives are reported as unsupported.
+
+use std::ffi::OsString;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use windows::Win32::Foundation::{FILETIME, SYSTEMTIME};
+use windows::Win32::Storage::FileSystem::{
+    FILE_ATTRIBUTE_ARCHIVE, FILE_ATTRIBUTE_DIRECTORY, FILE_ATTRIBUTE_HIDDEN,
+    FILE_ATTRIBUTE_READONLY, FILE_ATTRIBUTE_SYSTEM,
+};
+use windows::Win32::System::Time::{SystemTimeToFileTime, TzSpecificLocalTimeToSystemTime};
+
+use crate::directory_info::DirectoryInfo;
+use crate::ehm::AppError;
+use crate::file_info::FileInfo;
+
+
+
+
+
+/// End of central directory record signature ("PK\x05\x06").
+const EOCD_SIGNATURE: u32 = 0x0605_4B50;
+
+/// Central directory file header signature ("PK\x01\x02").
+const CENTRAL_HEADER_SIGNATURE: u32 = 0x0201_4B50;
+
+/// Fixed size of the end of central directory record.
+const EOCD_LEN: usize = 22;
+
+/// Fixed size of a central directory file header (before name/extra/comment).
+const CENTRAL_HEADER_LEN: usize = 46;
+
+/// General purpose flag: file name is UTF-8.
+const FLAG_UTF8: u16 = 0x0800;
+
+/// DOS attribute bits carried in the low byte of the external attributes.
+const DOS_ATTRIBUTE_MASK: u32 = FILE_ATTRIBUTE_READONLY.0
+                              | FILE_ATTRIBUTE_HIDDEN.0
+                              | FILE_ATTRIBUTE_SYSTEM.0
+                              | FILE_ATTRIBUTE_DIRECTORY.0
+                              | FILE_ATTRIBUTE_ARCHIVE.0;
+
+/// "Version made by" host systems whose external attributes are DOS bits.
+const HOST_MSDOS: u8 = 0;
+const HOST_NTFS:  u8 = 10;
+const HOST_VFAT:  u8 = 14;
+
+
+
+
+
+/// Where the central directory lives, per the end of central directory record.
+struct CentralDirectory {
+    entry_count: u16,
+    size:        u32,
+    start:       u32,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  is_zip_file
+//
+//  True if `path` is an existing file with a .zip extension.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn is_zip_file(path: &Path) -> bool {
+    path.extension().is_some_and (|ext| ext.eq_ignore_ascii_case ("zip")) && path.is_file()
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  read_zip_file
+//
+//  Read the archive at `path` and return its entries.  Only the tail that
+//  can hold the end of central directory record (the record plus the
+//  longest possible comment) and then the central directory itself are
+//  read, so listing a large archive doesn't load its contents.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn read_zip_file(path: &Path) -> Result<Vec<FileInfo>, AppError> {
+    let mut file = std::fs::File::open (path)?;
+    let file_len = file.metadata()?.len();
+
+    let tail_len = file_len.min ((EOCD_LEN + u16::MAX as usize) as u64);
+    let mut tail = vec![0u8; tail_len as usize];
+    file.seek (SeekFrom::Start (file_len - tail_len))?;
+    file.read_exact (&mut tail)?;
+
+    let directory = parse_eocd (&tail)?;
+    if directory.start as u64 + directory.size as u64 > file_len {
+        return Err (invalid_zip ("truncated central directory"));
+    }
+
+    let mut central = vec![0u8; directory.size as usize];
+    file.seek (SeekFrom::Start (directory.start as u64))?;
+    file.read_exact (&mut central)?;
+
+    parse_central_directory (&central, directory.entry_count)
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  read_zip_entries
+//
+//  Parse an in-memory archive's central directory into synthetic
+//  FileInfo entries, in archive order.  Inner paths use '\' separators;
+//  directory entries (trailing '/') get FILE_ATTRIBUTE_DIRECTORY and lose
+//  the trailing separator.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn read_zip_entries(bytes: &[u8]) -> Result<Vec<FileInfo>, AppError> {
+    let directory = parse_eocd (bytes)?;
+    let start     = directory.start as usize;
+    let central   = bytes.get (start..start + directory.size as usize)
+        .ok_or_else (|| invalid_zip ("truncated central directory"))?;
+
+    parse_central_directory (central, directory.entry_count)
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  parse_eocd
+//
+//  Find the end of central directory record in `bytes` (the archive or
+//  its tail) and return the central directory's location.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn parse_eocd(bytes: &[u8]) -> Result<CentralDirectory, AppError> {
+    let eocd = find_eocd (bytes).ok_or_else (|| invalid_zip ("end of central directory not found"))?;
+
+    let disk_number = read_u16 (bytes, eocd + 4).unwrap_or (0);
+    let directory   = CentralDirectory {
+        entry_count: read_u16 (bytes, eocd + 10).unwrap_or (0),
+        size:        read_u32 (bytes, eocd + 12).unwrap_or (0),
+        start:       read_u32 (bytes, eocd + 16).unwrap_or (0),
+    };
+
+    if disk_number != 0 {
+        return Err (invalid_zip ("multi-disk archives are not supported"));
+    }
+
+    if directory.entry_count == 0xFFFF || directory.size == 0xFFFF_FFFF || directory.start == 0xFFFF_FFFF {
+        return Err (invalid_zip ("ZIP64 archives are not supported"));
+    }
+
+    Ok (directory)
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  parse_central_directory
+//
+//  Parse `entry_count` consecutive file headers from the central
+//  directory's bytes.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn parse_central_directory(central: &[u8], entry_count: u16) -> Result<Vec<FileInfo>, AppError> {
+    let mut entries = Vec::with_capacity (entry_count as usize);
+    let mut offset  = 0;
+
+    for _ in 0..entry_count {
+        let (entry, header_len) = parse_central_header (central, offset)
+            .ok_or_else (|| invalid_zip ("truncated central directory"))?;
+
+        entries.push (entry);
+        offset += header_len;
+    }
+
+    Ok (entries)
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  build_directory_info
+//
+//  Wrap archive entries in a DirectoryInfo headed by the archive path,
+//  with the same counts and sizes the lister would have tracked.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn build_directory_info(zip_path: &Path, entries: Vec<FileInfo>) -> DirectoryInfo {
+    let mut di = DirectoryInfo::new (zip_path.to_path_buf(), "*".to_string());
+
+    for entry in &entries {
+        let name_len = entry.file_name.to_string_lossy().chars().count();
+
+        if entry.is_directory() {
+            di.subdirectory_count += 1;
+            di.largest_file_name = di.largest_file_name.max (name_len + 2);
+        } else {
+            di.file_count += 1;
+            di.bytes_used += entry.file_size;
+            di.largest_file_size = di.largest_file_size.max (entry.file_size);
+            di.largest_file_name = di.largest_file_name.max (name_len);
+        }
+    }
+
+    di.matches = entries;
+    di
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  find_eocd
+//
+//  Locate the end of central directory record by scanning backwards over
+//  the maximum archive comment length.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn find_eocd(bytes: &[u8]) -> Option<usize> {
+    if bytes.len() < EOCD_LEN {
+        return None;
+    }
+
+    let last  = bytes.len() - EOCD_LEN;
+    let first = last.saturating_sub (u16::MAX as usize);
+
+    (first..=last).rev().find (|&pos| read_u32 (bytes, pos) == Some (EOCD_SIGNATURE))
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  parse_central_header
+//
+//  Parse one central directory file header at `offset`.  Returns the
+//  entry and the header's total length, or None if it is malformed.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn parse_central_header(bytes: &[u8], offset: usize) -> Option<(FileInfo, usize)> {
+    if read_u32 (bytes, offset)? != CENTRAL_HEADER_SIGNATURE {
+        return None;
+    }
+
+    let host         = *bytes.get (offset + 5)?;
+    let flags        = read_u16 (bytes, offset + 8)?;
+    let dos_time     = read_u16 (bytes, offset + 12)?;
+    let dos_date     = read_u16 (bytes, offset + 14)?;
+    let size         = read_u32 (bytes, offset + 24)?;
+    let name_len     = read_u16 (bytes, offset + 28)? as usize;
+    let extra_len    = read_u16 (bytes, offset + 30)? as usize;
+    let comment_len  = read_u16 (bytes, offset + 32)? as usize;
+    let external     = read_u32 (bytes, offset + 38)?;
+
+    let name_start = offset + CENTRAL_HEADER_LEN;
+    let raw_name   = bytes.get (name_start..name_start + name_len)?;
+
+    // Non-UTF-8 names are nominally CP437; ASCII names decode the same
+    let name = if flags & FLAG_UTF8 != 0 {
+        String::from_utf8_lossy (raw_name).into_owned()
+    } else {
+        raw_name.iter().map (|&b| b as char).collect()
+    };
+
+    let is_directory = name.ends_with ('/');
+    let name         = name.trim_end_matches ('/').replace ('/', "\\");
+
+    let mut attributes = match host {
+        HOST_MSDOS | HOST_NTFS | HOST_VFAT => external & DOS_ATTRIBUTE_MASK,
+        _                                  => 0,
+    };
+
+    if is_directory {
+        attributes |= FILE_ATTRIBUTE_DIRECTORY.0;
+    }
+
+    let write_time = dos_date_time_to_filetime (dos_date, dos_time);
+
+    let entry = FileInfo {
+        file_name:        OsString::from (name),
+        file_attributes:  attributes,
+        file_size:        if is_directory { 0 } else { size as u64 },
+        creation_time:    write_time,
+        last_write_time:  write_time,
+        last_access_time: write_time,
+        reparse_tag:      0,
+        reparse_target:   String::new(),
+        streams:          Vec::new(),
+        ..Default::default()
+    };
+
+    Some ((entry, CENTRAL_HEADER_LEN + name_len + extra_len + comment_len))
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  dos_date_time_to_filetime
+//
+//  Convert a local-time DOS date/time pair to a UTC FILETIME (as u64).
+//  Returns 0 for an unset or invalid date.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn dos_date_time_to_filetime(dos_date: u16, dos_time: u16) -> u64 {
+    let local = SYSTEMTIME {
+        wYear:         1980 + (dos_date >> 9),
+        wMonth:        (dos_date >> 5) & 0x0F,
+        wDayOfWeek:    0,
+        wDay:          dos_date & 0x1F,
+        wHour:         dos_time >> 11,
+        wMinute:       (dos_time >> 5) & 0x3F,
+        wSecond:       (dos_time & 0x1F) * 2,
+        wMilliseconds: 0,
+    };
+
+    let mut utc = SYSTEMTIME::default();
+    let mut ft  = FILETIME::default();
+
+    let converted = unsafe {
+        TzSpecificLocalTimeToSystemTime (None, &local, &mut utc).is_ok()
+            && SystemTimeToFileTime (&utc, &mut ft).is_ok()
+    };
+
+    if !converted {
+        return 0;
+    }
+
+    ((ft.dwHighDateTime as u64) << 32) | (ft.dwLowDateTime as u64)
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  read_u16 / read_u32
+//
+//  Little-endian field readers; None if the field runs past the end.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    let field = bytes.get (offset..offset + 2)?;
+    Some (u16::from_le_bytes ([field[0], field[1]]))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let field = bytes.get (offset..offset + 4)?;
+    Some (u32::from_le_bytes ([field[0], field[1], field[2], field[3]]))
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  invalid_zip
+//
+//  An InvalidData I/O error describing why the archive can't be listed.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn invalid_zip(reason: &str) -> AppError {
+    AppError::Io (std::io::Error::new (std::io::ErrorKind::InvalidData, format! ("invalid zip archive: {}", reason)))
+}
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date_format::filetime_to_local;
+    use crate::test_support::TempDir;
+
+    /// DOS date for 2024-03-15 and time for 14:30:20.
+    const DOS_DATE: u16 = ((2024 - 1980) << 9) | (3 << 5) | 15;
+    const DOS_TIME: u16 = (14 << 11) | (30 << 5) | (20 / 2);
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  build_zip
+    //
+    //  Helper: build a stored (uncompressed) archive in memory from
+    //  (name, contents) pairs, NTFS host, all stamped DOS_DATE/DOS_TIME.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn build_zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut out     = Vec::new();
+        let mut central = Vec::new();
+
+        for &(name, data) in files {
+            let local_offset = out.len() as u32;
+            let size         = data.len() as u32;
+            let external     = if name.ends_with ('/') { FILE_ATTRIBUTE_DIRECTORY.0 } else { FILE_ATTRIBUTE_ARCHIVE.0 };
+
+            out.extend_from_slice (&0x0403_4B50u32.to_le_bytes());
+            out.extend_from_slice (&20u16.to_le_bytes());
+            out.extend_from_slice (&FLAG_UTF8.to_le_bytes());
+            out.extend_from_slice (&0u16.to_le_bytes());
+            out.extend_from_slice (&DOS_TIME.to_le_bytes());
+            out.extend_from_slice (&DOS_DATE.to_le_bytes());
+            out.extend_from_slice (&0u32.to_le_bytes());
+            out.extend_from_slice (&size.to_le_bytes());
+            out.extend_from_slice (&size.to_le_bytes());
+            out.extend_from_slice (&(name.len() as u16).to_le_bytes());
+            out.extend_from_slice (&0u16.to_le_bytes());
+            out.extend_from_slice (name.as_bytes());
+            out.extend_from_slice (data);
+
+            central.extend_from_slice (&CENTRAL_HEADER_SIGNATURE.to_le_bytes());
+            central.extend_from_slice (&[20, HOST_NTFS]);
+            central.extend_from_slice (&20u16.to_le_bytes());
+            central.extend_from_slice (&FLAG_UTF8.to_le_bytes());
+            central.extend_from_slice (&0u16.to_le_bytes());
+            central.extend_from_slice (&DOS_TIME.to_le_bytes());
+            central.extend_from_slice (&DOS_DATE.to_le_bytes());
+            central.extend_from_slice (&0u32.to_le_bytes());
+            central.extend_from_slice (&size.to_le_bytes());
+            central.extend_from_slice (&size.to_le_bytes());
+            central.extend_from_slice (&(name.len() as u16).to_le_bytes());
+            central.extend_from_slice (&0u16.to_le_bytes());
+            central.extend_from_slice (&0u16.to_le_bytes());
+            central.extend_from_slice (&0u16.to_le_bytes());
+            central.extend_from_slice (&0u16.to_le_bytes());
+            central.extend_from_slice (&external.to_le_bytes());
+            central.extend_from_slice (&local_offset.to_le_bytes());
+            central.extend_from_slice (name.as_bytes());
+        }
+
+        let central_start = out.len() as u32;
+        out.extend_from_slice (&central);
+
+        out.extend_from_slice (&EOCD_SIGNATURE.to_le_bytes());
+        out.extend_from_slice (&0u16.to_le_bytes());
+        out.extend_from_slice (&0u16.to_le_bytes());
+        out.extend_from_slice (&(files.len() as u16).to_le_bytes());
+        out.extend_from_slice (&(files.len() as u16).to_le_bytes());
+        out.extend_from_slice (&(central.len() as u32).to_le_bytes());
+        out.extend_from_slice (&central_start.to_le_bytes());
+        out.extend_from_slice (&0u16.to_le_bytes());
+        out
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  entries_map_to_synthetic_file_info
+    //
+    //  Verify names, sizes, and attributes of a small archive's entries,
+    //  including a directory entry and a nested path.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn entries_map_to_synthetic_file_info() {
+        let zip = build_zip (&[
+            ("readme.txt",  b"hello, world"),
+            ("src/",        b""),
+            ("src/main.rs", b"fn main() {}\n"),
+        ]);
+
+        let entries = read_zip_entries (&zip).unwrap();
+        let names: Vec<_> = entries.iter().map (|e| e.file_name.to_string_lossy().into_owned()).collect();
+
+        assert_eq! (names, vec!["readme.txt", "src", "src\\main.rs"]);
+        assert_eq! (entries[0].file_size, 12);
+        assert_eq! (entries[2].file_size, 13);
+        assert! (!entries[0].is_directory());
+        assert! (entries[1].is_directory());
+        assert_eq! (entries[1].file_size, 0);
+        assert_eq! (entries[0].file_attributes, FILE_ATTRIBUTE_ARCHIVE.0);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  entry_timestamp_round_trips_dos_time
+    //
+    //  Verify the DOS local timestamp converts to a FILETIME that reads
+    //  back as the same local date and time.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn entry_timestamp_round_trips_dos_time() {
+        let zip     = build_zip (&[("a.txt", b"a")]);
+        let entries = read_zip_entries (&zip).unwrap();
+        let local   = filetime_to_local (entries[0].last_write_time).unwrap();
+
+        assert_eq! ((local.wYear, local.wMonth, local.wDay), (2024, 3, 15));
+        assert_eq! ((local.wHour, local.wMinute, local.wSecond), (14, 30, 20));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  directory_info_tracks_counts_and_sizes
+    //
+    //  Verify the synthesized DirectoryInfo counts files, directories, and
+    //  bytes the way the lister would.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn directory_info_tracks_counts_and_sizes() {
+        let zip = build_zip (&[
+            ("a.txt",     b"12345"),
+            ("docs/",     b""),
+            ("docs/b.md", b"123"),
+        ]);
+
+        let di = build_directory_info (Path::new ("C:\\x\\test.zip"), read_zip_entries (&zip).unwrap());
+
+        assert_eq! (di.file_count, 2);
+        assert_eq! (di.subdirectory_count, 1);
+        assert_eq! (di.bytes_used, 8);
+        assert_eq! (di.largest_file_size, 5);
+        assert_eq! (di.matches.len(), 3);
+        assert_eq! (di.dir_path, Path::new ("C:\\x\\test.zip"));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  malformed_archives_are_rejected
+    //
+    //  Verify non-zip data and a truncated central directory are errors.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn malformed_archives_are_rejected() {
+        assert! (read_zip_entries (b"not a zip file at all, just text").is_err());
+        assert! (read_zip_entries (&[]).is_err());
+
+        let mut zip = build_zip (&[("a.txt", b"a")]);
+        let eocd    = zip.len() - EOCD_LEN;
+        zip[eocd + 10] = 2;
+        assert! (read_zip_entries (&zip).is_err());
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  zip_file_reads_central_directory_past_tail
+    //
+    //  Verify reading from disk finds the central directory even when the
+    //  archive is larger than the tail searched for the EOCD record, and
+    //  that a missing or truncated archive is an error.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn zip_file_reads_central_directory_past_tail() {
+        let temp = TempDir::new ("zip_tail");
+        let big  = vec![b'x'; 2 * (EOCD_LEN + u16::MAX as usize)];
+        let zip  = build_zip (&[("big.bin", &big), ("small.txt", b"abc")]);
+
+        let path = temp.path().join ("test.zip");
+        std::fs::write (&path, &zip).unwrap();
+
+        let entries = read_zip_file (&path).unwrap();
+        let names: Vec<_> = entries.iter().map (|e| e.file_name.to_string_lossy().into_owned()).collect();
+        assert_eq! (names, vec!["big.bin", "small.txt"]);
+        assert_eq! (entries[0].file_size, big.len() as u64);
+
+        let truncated = temp.path().join ("truncated.zip");
+        std::fs::write (&truncated, &zip[zip.len() - EOCD_LEN..]).unwrap();
+        assert! (read_zip_file (&truncated).is_err());
+        assert! (read_zip_file (&temp.path().join ("missing.zip")).is_err());
+    }
+}
@@ -148,6 +148,57 @@ pub fn color_name_from_fg(value: u16) -> Option<&'static str> {
 
 
 
+////////////////////////////////////////////////////////////////////////////////
+//
+//  color_name
+//
+//  Reverse of parse_color_name for foreground values: color_name_from_fg,
+//  but None if attr carries any bits outside the foreground (e.g., a
+//  background).
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn color_name(attr: u16) -> Option<&'static str> {
+    if attr & !FC_MASK != 0 {
+        return None;
+    }
+
+    color_name_from_fg (attr)
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  format_color_spec
+//
+//  Format a color WORD in parse_color_spec syntax: "Fg" when the
+//  background is black, otherwise "Fg on Bg".  Values with bits outside
+//  the 8-bit color range return the hex form (e.g., "0x1234").
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn format_color_spec(attr: u16) -> String {
+    if attr & !(FC_MASK | BC_MASK) != 0 {
+        return format! ("0x{:04X}", attr);
+    }
+
+    let fore = color_name (attr & FC_MASK);
+    let back = color_name ((attr & BC_MASK) >> 4);
+
+    match (fore, back) {
+        (Some (fore), Some ("Black")) => fore.to_string(),
+        (Some (fore), Some (back))    => format! ("{} on {}", fore, back),
+        _                             => format! ("0x{:04X}", attr),
+    }
+}
+
+
+
+
+
 ////////////////////////////////////////////////////////////////////////////////
 //
 //  parse_color_spec
@@ -394,4 +445,83 @@ fn background_constants_are_shifted() {
         assert_eq!(BC_RED, FC_RED << 4);
         assert_eq!(BC_WHITE, FC_WHITE << 4);
     }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  color_name_round_trips_all_colors
+    //
+    //  Verify parse_color_name (color_name (x)) == x for all 16 colors.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn color_name_round_trips_all_colors () {
+        for &color in &ALL_FOREGROUND_COLORS {
+            let name = color_name (color).expect ("every foreground color has a name");
+            assert_eq! (parse_color_name (name, false).unwrap(), color, "{}", name);
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  color_name_rejects_non_foreground_values
+    //
+    //  Verify values with background bits have no single color name.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn color_name_rejects_non_foreground_values () {
+        assert_eq! (color_name (FC_YELLOW | BC_BLUE), None);
+        assert_eq! (color_name (0x100), None);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  format_color_spec_foreground_and_background
+    //
+    //  Verify "Fg" for black backgrounds and "Fg on Bg" otherwise, and that
+    //  the result parses back to the same value.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn format_color_spec_foreground_and_background () {
+        assert_eq! (format_color_spec (FC_YELLOW), "Yellow");
+        assert_eq! (format_color_spec (FC_YELLOW | BC_BLUE), "Yellow on Blue");
+        assert_eq! (format_color_spec (FC_BLACK | BC_WHITE), "Black on White");
+
+        for attr in 0u16..=0xFF {
+            assert_eq! (parse_color_spec (&format_color_spec (attr)).unwrap(), attr);
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  format_color_spec_unknown_bits_use_hex
+    //
+    //  Verify values outside the 8-bit color range format as hex.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn format_color_spec_unknown_bits_use_hex () {
+        assert_eq! (format_color_spec (0x8007), "0x8007");
+    }
 }
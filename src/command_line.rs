@@ -7,7 +7,15 @@
 use std::ffi::OsString;
 
 use crate::config::Config;
+use crate::date_format;
+use crate::dir_diff::DiffReference;
 use crate::ehm::AppError;
+use crate::environment_provider::EnvironmentProvider;
+use crate::file_info::{AttributeColumn, AttributeStyle, FileInfo};
+use crate::format_template::FormatTemplate;
+use crate::hashing::HashAlgorithm;
+use crate::mask_grouper;
+use crate::since_last::SinceLastRun;
 
 
 
@@ -22,6 +30,7 @@ pub enum SortOrder {
     Extension,  // /O:E — alphabetic by extension
     Size,       // /O:S — smallest first
     Date,       // /O:D — oldest first
+    Title,      // /O:title — by name, ignoring leading symbols and articles
 }
 
 
@@ -44,6 +53,19 @@ pub enum SortDirection {
 
 
 
+/// FILETIME resolution is 100ns.
+const FILETIME_TICKS_PER_MINUTE: u64 = 60 * 10_000_000;
+
+/// /Recent without a window: the last 24 hours.
+pub const RECENT_DEFAULT_WINDOW: u64 = FILETIME_TICKS_PER_MINUTE * 60 * 24;
+
+/// /Stale without a window: not accessed in the last 90 days.
+pub const STALE_DEFAULT_WINDOW: u64 = FILETIME_TICKS_PER_MINUTE * 60 * 24 * 90;
+
+
+
+
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TimeField {
     Written,    // /T:W — ftLastWriteTime (default)
@@ -60,6 +82,149 @@ pub enum SizeFormat {
     Default,    // Not explicitly set; tree mode uses Auto, non-tree uses Bytes
     Auto,       // Explorer-style abbreviated (1024-based, 3 sig digits, 7-char)
     Bytes,      // Exact byte count with comma separators (existing behavior)
+    Compact,    // 5-char 1024-based size with a one-letter unit ("12.3G")
+}
+
+
+
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    Auto,       // --Color=Auto — color only when stdout is a console
+    Always,     // --Color=Always — always emit ANSI color (default unless NO_COLOR, matches TCDir)
+    Never,      // --Color=Never / --NoColor — plain text output
+}
+
+
+
+
+
+/// Listing style chosen by the RCDIR Mode= entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputMode {
+    Normal,     // Mode=normal — the full listing
+    Wide,       // Mode=wide — as /W
+    Bare,       // Mode=bare — as /B
+    Tree,       // Mode=tree — as --Tree
+}
+
+
+
+
+
+/// Inclusive file size band in bytes (/XSZ:min-max).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizeBand {
+    pub min: u64,
+    pub max: u64,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl SizeBand
+//
+//  Size band membership test.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl SizeBand {
+    pub fn contains(&self, size: u64) -> bool {
+        (self.min..=self.max).contains (&size)
+    }
+}
+
+
+
+
+
+/// Which directories list their files in tree mode (/DirsFirst:tree,
+/// /Tree:dirs).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TreeFilePolicy {
+    /// Every directory lists its files (plain --Tree)
+    #[default]
+    All,
+    /// Only the targeted directory and leaf directories list files;
+    /// intermediate directories collapse to just their names
+    TargetsAndLeaves,
+    /// No directory lists files (/Tree:dirs); they are still counted
+    /// for the summary
+    DirectoriesOnly,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl TreeFilePolicy
+//
+//  Per-directory file visibility decision for the tree walk.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl TreeFilePolicy {
+    pub fn shows_files(self, is_target: bool, has_subdirectories: bool) -> bool {
+        match self {
+            TreeFilePolicy::All              => true,
+            TreeFilePolicy::TargetsAndLeaves => is_target || !has_subdirectories,
+            TreeFilePolicy::DirectoriesOnly  => false,
+        }
+    }
+}
+
+
+
+
+
+/// How much a directory or listing summary reports (/V:0|1|2).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SummaryVerbosity {
+    /// /V:0 — file, directory and byte totals only; no free-space footer
+    TotalsOnly,
+    /// /V:1 — totals plus the volume free-space footer
+    #[default]
+    Standard,
+    /// /V:2 — adds a per-extension breakdown, oldest/newest file, and a
+    /// size histogram to each directory summary
+    Detailed,
+}
+
+
+
+
+
+/// What happens when a listing matches nothing (/FailOnEmpty, /IgnoreEmpty).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EmptyMatchPolicy {
+    /// "No files matching '...' found." and a successful exit
+    #[default]
+    Report,
+    /// /FailOnEmpty — no message; the run fails with a non-zero exit
+    Fail,
+    /// /IgnoreEmpty — no message; the run succeeds
+    Ignore,
+}
+
+
+
+
+
+/// Where filenames start in the normal listing (/NameCol).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameColumn {
+    /// /NameCol — the widest name column seen so far in the run, so
+    /// directories with narrower size or owner columns line up with it
+    Auto,
+    /// /NameCol:N — names start at console column N (or later, if the
+    /// enabled columns need more room)
+    Fixed (usize),
 }
 
 
@@ -76,18 +241,31 @@ pub struct CommandLine {
     pub sort_order:       SortOrder,
     pub sort_direction:   SortDirection,
     pub sort_preference:  [SortOrder; SORT_ORDER_COUNT],
+    pub dir_sort:         Option<(SortOrder, SortDirection)>,
+    pub file_sort:        Option<(SortOrder, SortDirection)>,
     pub masks:            Vec<OsString>,
     pub wide_listing:     bool,
     pub bare_listing:     bool,
+    pub bare_sizes:       bool,
+    pub bare_numbered:    bool,
+    pub tsv_listing:      bool,
+    pub jsonl_listing:    bool,
+    pub count_only:       bool,
     pub perf_timer:       bool,
     pub multi_threaded:   bool,
     pub show_env_help:    bool,
     pub show_config:      bool,
+    pub config_json:      bool,
+    pub config_errors_json: bool,
     pub show_settings:    bool,
     pub show_help:        bool,
     pub switch_prefix:    char,
     pub time_field:       TimeField,
+    pub time_with_age:    bool,
+    pub date_format:      Option<String>,
+    pub format_template:  Option<FormatTemplate>,
     pub show_owner:       bool,
+    pub owner_sid:        bool,
     pub show_streams:     bool,
     pub icons:            Option<bool>,
     pub debug:            bool,
@@ -96,6 +274,74 @@ pub struct CommandLine {
     pub tree_indent:      i32,
     pub size_format:      SizeFormat,
     pub ellipsize:        Option<bool>,
+    pub color_mode:       Option<ColorMode>,
+    pub no_cloud:         bool,
+    pub no_cloud_auto:    bool,
+    pub highlight_attrs:  u32,
+    pub show_delta:       bool,
+    pub exclude_dirs:     Vec<String>,
+    pub full_path:        bool,
+    pub show_progress:    bool,
+    pub collapse_empty:   bool,
+    pub in_zip:           bool,
+    pub columns:          bool,
+    pub show_target:      bool,
+    pub full_attributes:  bool,
+    pub auto_attributes:  bool,
+    pub attr_style:       AttributeStyle,
+    pub group_by_ext:     bool,
+    pub no_summary:       bool,
+    pub has_streams:      bool,
+    pub wrap_names:       bool,
+    pub show_mask:        bool,
+    pub quiet:            bool,
+    pub contains_text:    Option<String>,
+    pub up_levels:        Option<usize>,
+    pub show_stats:       bool,
+    pub exclude_sizes:    Vec<SizeBand>,
+    pub base_path:        Option<String>,
+    pub show_percent:     bool,
+    pub tree_files:       TreeFilePolicy,
+    pub brief:            bool,
+    pub show_dots:        bool,
+    pub show_filtered:    bool,
+    pub icons_forced:     bool,
+    pub verbosity:        SummaryVerbosity,
+    pub bom:              bool,
+    pub raw_colors:       bool,
+    pub light:            bool,
+    pub show_file_id:     bool,
+    pub show_encoding:    bool,
+    pub show_tier:        bool,
+    pub show_version:     bool,
+    pub since_last:       bool,
+    pub since_last_run:   Option<SinceLastRun>,
+    pub name_column:      Option<NameColumn>,
+    pub literal:          bool,
+    pub dedupe:           bool,
+    pub no_reparse:       bool,
+    pub mine:             bool,
+    pub mark_dirs:        bool,
+    pub drive_pct:        bool,
+    pub error_summary:    bool,
+    pub on_disk:          bool,
+    pub show_file_type:   bool,
+    pub entry_limit:      Option<usize>,
+    pub status_line:      bool,
+    pub deref_arg:        bool,
+    pub empty_policy:     EmptyMatchPolicy,
+    pub hash_algorithm:   Option<HashAlgorithm>,
+    pub newer_than_file:  Option<String>,
+    pub older_than_file:  Option<String>,
+    pub newer_than:       Option<u64>,
+    pub older_than:       Option<u64>,
+    pub recent_window:    Option<u64>,
+    pub stale_window:     Option<u64>,
+    pub stale_before:     Option<u64>,
+    pub diff_dir:         Option<String>,
+    pub diff_metadata:    bool,
+    pub diff_reference:   Option<DiffReference>,
+    pub watch:            bool,
     pub set_aliases:      bool,
     pub get_aliases:      bool,
     pub remove_aliases:   bool,
@@ -129,18 +375,31 @@ fn default() -> Self {
                 SortOrder::Extension, // [3]
                 SortOrder::Size,      // [4]
             ],
+            dir_sort:        None,
+            file_sort:       None,
             masks:           Vec::new(),
             wide_listing:    false,
             bare_listing:    false,
+            bare_sizes:      false,
+            bare_numbered:   false,
+            tsv_listing:     false,
+            jsonl_listing:   false,
+            count_only:      false,
             perf_timer:      false,
             multi_threaded:  true,
             show_env_help:   false,
             show_config:     false,
+            config_json:     false,
+            config_errors_json: false,
             show_settings:   false,
             show_help:       false,
             switch_prefix:   '-',
             time_field:      TimeField::Written,
+            time_with_age:   false,
+            date_format:     None,
+            format_template: None,
             show_owner:      false,
+            owner_sid:       false,
             show_streams:    false,
             icons:           None,
             debug:           false,
@@ -149,6 +408,74 @@ fn default() -> Self {
             tree_indent:     4,
             size_format:     SizeFormat::Default,
             ellipsize:       None,
+            color_mode:      None,
+            no_cloud:        false,
+            no_cloud_auto:   false,
+            highlight_attrs: 0,
+            show_delta:      false,
+            exclude_dirs:    Vec::new(),
+            full_path:       false,
+            show_progress:   false,
+            collapse_empty:  false,
+            in_zip:          false,
+            columns:         false,
+            show_target:     false,
+            full_attributes: false,
+            auto_attributes: false,
+            attr_style:      AttributeStyle::Dir,
+            group_by_ext:    false,
+            no_summary:      false,
+            has_streams:     false,
+            wrap_names:      false,
+            show_mask:       false,
+            quiet:           false,
+            contains_text:   None,
+            up_levels:       None,
+            show_stats:      false,
+            exclude_sizes:   Vec::new(),
+            base_path:       None,
+            show_percent:    false,
+            tree_files:      TreeFilePolicy::All,
+            brief:           false,
+            show_dots:       false,
+            show_filtered:   false,
+            icons_forced:    false,
+            verbosity:       SummaryVerbosity::Standard,
+            bom:             false,
+            raw_colors:      false,
+            light:           false,
+            show_file_id:    false,
+            show_encoding:   false,
+            show_tier:       false,
+            show_version:    false,
+            since_last:      false,
+            since_last_run:  None,
+            name_column:     None,
+            literal:         false,
+            dedupe:          false,
+            no_reparse:      false,
+            mine:            false,
+            mark_dirs:       false,
+            drive_pct:       false,
+            error_summary:   false,
+            on_disk:         false,
+            show_file_type:  false,
+            entry_limit:     None,
+            status_line:     false,
+            deref_arg:       false,
+            empty_policy:    EmptyMatchPolicy::Report,
+            hash_algorithm:  None,
+            newer_than_file: None,
+            older_than_file: None,
+            newer_than:      None,
+            older_than:      None,
+            recent_window:   None,
+            stale_window:    None,
+            stale_before:    None,
+            diff_dir:        None,
+            diff_metadata:   false,
+            diff_reference:  None,
+            watch:           false,
             set_aliases:     false,
             get_aliases:     false,
             remove_aliases:  false,
@@ -315,6 +642,74 @@ fn is_recognized_long_switch(name: &str) -> bool {
             "treeindent",
             "size",
             "ellipsize",
+            "color",
+            "nocolor",
+            "nocloud",
+            "highlight",
+            "delta",
+            "tsv",
+            "jsonl",
+            "count",
+            "xd",
+            "fullpath",
+            "progress",
+            "collapseempty",
+            "inzip",
+            "columns",
+            "target",
+            "attr",
+            "attrstyle",
+            "group",
+            "nosummary",
+            "hasstreams",
+            "wrapnames",
+            "showmask",
+            "quiet",
+            "contains",
+            "up",
+            "stats",
+            "xsz",
+            "base",
+            "pct",
+            "dirsfirst",
+            "brief",
+            "showdots",
+            "showfiltered",
+            "bom",
+            "rawcolors",
+            "light",
+            "fileid",
+            "encoding",
+            "tier",
+            "verinfo",
+            "sincelast",
+            "namecol",
+            "literal",
+            "dedupe",
+            "noreparse",
+            "mine",
+            "mark",
+            "drivepct",
+            "errorsummary",
+            "ondisk",
+            "desc",
+            "limit",
+            "status",
+            "derefarg",
+            "failonempty",
+            "ignoreempty",
+            "odir",
+            "ofile",
+            "hash",
+            "newer",
+            "older",
+            "recent",
+            "stale",
+            "datefmt",
+            "fmt",
+            "diff",
+            "diffmeta",
+            "watch",
             "set-aliases",
             "get-aliases",
             "remove-aliases",
@@ -358,9 +753,9 @@ fn validate_switch_combinations(&self) -> Result<(), AppError> {
         }
 
         if alias_count == 1
-            && (tree || self.wide_listing || self.bare_listing || self.recurse
+            && (tree || self.wide_listing || self.bare_listing || self.tsv_listing || self.jsonl_listing || self.count_only || self.recurse
                 || self.show_owner || self.show_streams || self.show_env_help
-                || self.show_config || self.show_settings
+                || self.show_config || self.show_settings || self.watch
                 || self.sort_order != SortOrder::Default
                 || self.attrs_required != 0 || self.attrs_excluded != 0)
             {
@@ -386,6 +781,11 @@ fn validate_switch_combinations(&self) -> Result<(), AppError> {
                     "--Tree cannot be combined with /B (bare listing)".into()
                 ));
             }
+            if self.tsv_listing {
+                return Err (AppError::InvalidArg (
+                    "--Tree cannot be combined with --Tsv".into()
+                ));
+            }
             if self.recurse {
                 return Err (AppError::InvalidArg (
                     "--Tree cannot be combined with /S (recurse)".into()
@@ -396,6 +796,41 @@ fn validate_switch_combinations(&self) -> Result<(), AppError> {
                     "--Tree cannot be combined with --Owner".into()
                 ));
             }
+            if self.hash_algorithm.is_some() {
+                return Err (AppError::InvalidArg (
+                    "--Tree cannot be combined with --Hash".into()
+                ));
+            }
+            if self.show_file_id {
+                return Err (AppError::InvalidArg (
+                    "--Tree cannot be combined with --FileId".into()
+                ));
+            }
+            if self.show_encoding {
+                return Err (AppError::InvalidArg (
+                    "--Tree cannot be combined with --Encoding".into()
+                ));
+            }
+            if self.show_tier {
+                return Err (AppError::InvalidArg (
+                    "--Tree cannot be combined with --Tier".into()
+                ));
+            }
+            if self.show_version {
+                return Err (AppError::InvalidArg (
+                    "--Tree cannot be combined with --VerInfo".into()
+                ));
+            }
+            if self.show_file_type {
+                return Err (AppError::InvalidArg (
+                    "--Tree cannot be combined with --Desc".into()
+                ));
+            }
+            if self.name_column.is_some() {
+                return Err (AppError::InvalidArg (
+                    "--Tree cannot be combined with --NameCol".into()
+                ));
+            }
             if self.size_format == SizeFormat::Bytes {
                 return Err (AppError::InvalidArg (
                     "--Tree cannot be combined with --Size=Bytes".into()
@@ -409,12 +844,128 @@ fn validate_switch_combinations(&self) -> Result<(), AppError> {
             ));
         }
 
+        if self.tree_files != TreeFilePolicy::All && !tree {
+            let switch = if self.tree_files == TreeFilePolicy::DirectoriesOnly { "--Tree:dirs" } else { "--DirsFirst:tree" };
+            return Err (AppError::InvalidArg (
+                format! ("{} cannot be combined with --Tree-", switch)
+            ));
+        }
+
         if self.tree_indent != 4 && !tree {
             return Err (AppError::InvalidArg (
                 "--TreeIndent requires --Tree".into()
             ));
         }
 
+        if self.diff_dir.is_some() && (self.recurse || tree) {
+            return Err (AppError::InvalidArg (
+                "--Diff cannot be combined with /S (recurse) or --Tree".into()
+            ));
+        }
+
+        if self.diff_metadata && self.diff_dir.is_none() {
+            return Err (AppError::InvalidArg (
+                "--DiffMeta requires --Diff".into()
+            ));
+        }
+
+        if self.collapse_empty {
+            if !self.recurse {
+                return Err (AppError::InvalidArg (
+                    "--CollapseEmpty requires /S (recurse)".into()
+                ));
+            }
+            if self.bare_listing || self.tsv_listing {
+                return Err (AppError::InvalidArg (
+                    "--CollapseEmpty cannot be combined with /B (bare listing) or --Tsv".into()
+                ));
+            }
+        }
+
+        if self.drive_pct {
+            if !self.recurse {
+                return Err (AppError::InvalidArg (
+                    "--DrivePct requires /S (recurse)".into()
+                ));
+            }
+            if self.bare_listing || self.tsv_listing || self.brief || self.format_template.is_some() {
+                return Err (AppError::InvalidArg (
+                    "--DrivePct cannot be combined with /B, --Tsv, --Brief, or --Fmt".into()
+                ));
+            }
+        }
+
+        if (self.dir_sort.is_some() || self.file_sort.is_some()) && tree {
+            return Err (AppError::InvalidArg (
+                "--ODir and --OFile cannot be combined with --Tree (directories and files are interleaved)".into()
+            ));
+        }
+
+        if self.entry_limit.is_some()
+            && (tree || self.wide_listing || self.bare_listing || self.tsv_listing || self.jsonl_listing
+                || self.brief || self.format_template.is_some())
+        {
+            return Err (AppError::InvalidArg (
+                "--Limit applies only to the normal listing (not /W, /B, --Tsv, --Jsonl, --Brief, --Fmt, or --Tree)".into()
+            ));
+        }
+
+        if self.columns && (tree || self.wide_listing || self.bare_listing || self.tsv_listing) {
+            return Err (AppError::InvalidArg (
+                "--Columns applies only to the normal listing (not /W, /B, --Tsv, or --Tree)".into()
+            ));
+        }
+
+        if self.group_by_ext && (tree || self.wide_listing || self.bare_listing || self.tsv_listing) {
+            return Err (AppError::InvalidArg (
+                "--Group applies only to the normal listing (not /W, /B, --Tsv, or --Tree)".into()
+            ));
+        }
+
+        if self.wrap_names && (tree || self.wide_listing || self.bare_listing || self.tsv_listing) {
+            return Err (AppError::InvalidArg (
+                "--WrapNames applies only to the normal listing (not /W, /B, --Tsv, or --Tree)".into()
+            ));
+        }
+
+        if self.show_percent && (tree || self.wide_listing || self.bare_listing || self.tsv_listing) {
+            return Err (AppError::InvalidArg (
+                "--Pct applies only to the normal listing (not /W, /B, --Tsv, or --Tree)".into()
+            ));
+        }
+
+        if self.brief && (tree || self.wide_listing || self.bare_listing || self.tsv_listing) {
+            return Err (AppError::InvalidArg (
+                "--Brief cannot be combined with /W, /B, --Tsv, or --Tree".into()
+            ));
+        }
+
+        if self.format_template.is_some() && (tree || self.wide_listing || self.bare_listing || self.tsv_listing || self.brief) {
+            return Err (AppError::InvalidArg (
+                "--Fmt cannot be combined with /W, /B, --Tsv, --Brief, or --Tree".into()
+            ));
+        }
+
+        if self.jsonl_listing
+            && (tree || self.wide_listing || self.bare_listing || self.tsv_listing || self.brief || self.format_template.is_some())
+        {
+            return Err (AppError::InvalidArg (
+                "--Jsonl cannot be combined with /W, /B, --Tsv, --Brief, --Fmt, or --Tree".into()
+            ));
+        }
+
+        if self.show_dots && tree {
+            return Err (AppError::InvalidArg (
+                "--ShowDots cannot be combined with --Tree".into()
+            ));
+        }
+
+        if self.in_zip && (self.recurse || tree) {
+            return Err (AppError::InvalidArg (
+                "--InZip cannot be combined with /S (recurse) or --Tree".into()
+            ));
+        }
+
         Ok(())
     }
 
@@ -449,6 +1000,40 @@ pub fn resolved_size_format(&self) -> SizeFormat {
 
 
 
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  shows_empty_message
+    //
+    //  True if a directory with no matches reports "No files matching"
+    //  (not under /Quiet, /FailOnEmpty, or /IgnoreEmpty).
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn shows_empty_message(&self) -> bool {
+        !self.quiet && self.empty_policy == EmptyMatchPolicy::Report
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  attribute_column
+    //
+    //  The attribute column layout from /AttrStyle, /Attr:full, and
+    //  /Attr:auto.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn attribute_column(&self) -> AttributeColumn {
+        AttributeColumn { style: self.attr_style, full: self.full_attributes, auto: self.auto_attributes }
+    }
+
+
+
+
+
     ////////////////////////////////////////////////////////////////////////////
     //
     //  apply_config_defaults
@@ -461,6 +1046,10 @@ pub fn resolved_size_format(&self) -> SizeFormat {
     ////////////////////////////////////////////////////////////////////////////
 
     pub fn apply_config_defaults(&mut self, config: &Config) {
+        // Any listing-style switch on the command line beats Mode=
+        let cli_chose_mode = self.wide_listing || self.bare_listing || self.tree.is_some()
+                          || self.tsv_listing || self.jsonl_listing || self.brief || self.format_template.is_some();
+
         if let Some(v) = config.wide_listing   { self.wide_listing   = v; }
         if let Some(v) = config.bare_listing   { self.bare_listing   = v; }
         if let Some(v) = config.recurse        { self.recurse        = v; }
@@ -479,6 +1068,15 @@ pub fn apply_config_defaults(&mut self, config: &Config) {
             self.tree = config.tree;
         }
 
+        // Mode: replaces the W/B/Tree defaults above unless the CLI picked a style
+        if !cli_chose_mode
+            && let Some (mode) = config.output_mode
+        {
+            self.wide_listing = mode == OutputMode::Wide;
+            self.bare_listing = mode == OutputMode::Bare;
+            self.tree         = Some (mode == OutputMode::Tree);
+        }
+
         // Depth: only apply if CLI didn't set and tree is active
         if self.max_depth == 0
             && let Some (d) = config.max_depth
@@ -506,6 +1104,32 @@ pub fn apply_config_defaults(&mut self, config: &Config) {
         if self.ellipsize.is_none() {
             self.ellipsize = config.ellipsize;
         }
+
+        // Sort (Sort=): only apply if CLI didn't set /O
+        if self.sort_order == SortOrder::Default
+            && let Some ((order, direction)) = config.sort_default
+        {
+            self.sort_order         = order;
+            self.sort_preference[0] = order;
+            self.sort_direction     = direction;
+        }
+
+        // Time field (Time=): only apply if CLI didn't change from default
+        if self.time_field == TimeField::Written
+            && let Some (field) = config.time_field_default
+        {
+            self.time_field = field;
+        }
+
+        // DefaultPath: only apply if CLI didn't set /Base
+        if self.base_path.is_none() {
+            self.base_path = config.default_path.clone();
+        }
+
+        // HasStreams needs stream enumeration even if the config turned it off
+        if self.has_streams {
+            self.show_streams = true;
+        }
     }
 
 
@@ -514,43 +1138,26 @@ pub fn apply_config_defaults(&mut self, config: &Config) {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  handle_switch
-    //
-    //  Route a switch argument to the appropriate handler.
+    //  expand_paths
     //
-    //  Port of: CCommandLine::HandleSwitch
+    //  Expand %VAR% and a leading ~ in masks and every path-valued switch
+    //  (/Base, /Diff, /Newer, /Older).  Runs before anything resolves those
+    //  paths, so an undefined variable fails up front.
     //
     ////////////////////////////////////////////////////////////////////////////
 
-    fn handle_switch(&mut self, switch_arg: &str, args: &[String], idx: &mut usize) -> Result<(), AppError> {
-        // Check for long switch (3+ chars, no ':' or '-' at position 1)
-        if switch_arg.len() >= 3
-            && switch_arg.as_bytes().get(1) != Some(&b':')
-            && switch_arg.as_bytes().get(1) != Some(&b'-')
-        {
-            return self.handle_long_switch (switch_arg, args, idx);
+    pub fn expand_paths(&mut self, provider: &dyn EnvironmentProvider) -> Result<(), AppError> {
+        for mask in &mut self.masks {
+            if let Some (text) = mask.to_str() {
+                *mask = OsString::from (mask_grouper::expand_path (text, provider)?);
+            }
         }
 
-        // Single-character switch
-        let ch = switch_arg.chars().next()
-            .ok_or_else(|| AppError::InvalidArg(String::new()))?;
-
-        // Check for trailing '-' to disable
-        let disable = switch_arg.len() >= 2 && switch_arg.as_bytes()[1] == b'-';
-
-        let ch_lower = ch.to_ascii_lowercase();
-        match ch_lower {
-            's' => { self.recurse        = !disable; Ok(()) }
-            'w' => { self.wide_listing   = !disable; Ok(()) }
-            'b' => { self.bare_listing   = !disable; Ok(()) }
-            'p' => { self.perf_timer     = !disable; Ok(()) }
-            'm' => { self.multi_threaded = !disable; Ok(()) }
-            '?' => { self.show_help      = true;     Ok(()) }
-            'o' => self.order_by_handler(&switch_arg[1..]),
-            'a' => self.attribute_handler(&switch_arg[1..]),
-            't' => self.time_field_handler(&switch_arg[1..]),
-            _   => Err(AppError::InvalidArg(String::new())),
+        for path in [&mut self.base_path, &mut self.diff_dir, &mut self.newer_than_file, &mut self.older_than_file].into_iter().flatten() {
+            *path = mask_grouper::expand_path (path, provider)?;
         }
+
+        Ok(())
     }
 
 
@@ -559,22 +1166,368 @@ fn handle_switch(&mut self, switch_arg: &str, args: &[String], idx: &mut usize)
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  handle_long_switch
-    //
-    //  Handle long switches: env, config, owner, streams, icons, debug.
-    //  Table-driven dispatch with case-insensitive matching.
+    //  resolve_time_references
     //
-    //  Port of: CCommandLine::HandleLongSwitch
+    //  Read the last-write FILETIME of the /Newer and /Older reference
+    //  files once, so the listers compare against plain integers.  A
+    //  missing reference file is an error.
     //
     ////////////////////////////////////////////////////////////////////////////
 
-    fn handle_long_switch(&mut self, switch_arg: &str, args: &[String], idx: &mut usize) -> Result<(), AppError> {
-        // Split on '=' to extract key and optional value
-        let (key, inline_value) = match switch_arg.find ('=') {
-            Some (pos) => (&switch_arg[..pos], Some (&switch_arg[pos + 1..])),
-            None       => (switch_arg, None),
+    pub fn resolve_time_references(&mut self) -> Result<(), AppError> {
+        fn last_write_time(file: &str) -> Result<u64, AppError> {
+            use std::os::windows::fs::MetadataExt;
+
+            std::fs::metadata (file)
+                .map (|md| md.last_write_time())
+                .map_err (|_| AppError::PathNotFound (file.into()))
+        }
+
+        if let Some (file) = &self.newer_than_file {
+            self.newer_than = Some (last_write_time (file)?);
+        }
+
+        if let Some (file) = &self.older_than_file {
+            self.older_than = Some (last_write_time (file)?);
+        }
+
+        if self.recent_window.is_some() || self.stale_window.is_some() {
+            let now = unsafe { windows::Win32::System::SystemInformation::GetSystemTimeAsFileTime() };
+            let now = ((now.dwHighDateTime as u64) << 32) | (now.dwLowDateTime as u64);
+            self.apply_recent_window (now);
+            self.apply_stale_window (now);
+        }
+
+        Ok(())
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  apply_recent_window
+    //
+    //  Turn /Recent into a /Newer cutoff of `now` minus the window.  If
+    //  /Newer was also given, the later (stricter) cutoff wins.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn apply_recent_window(&mut self, now: u64) {
+        let Some (window) = self.recent_window else {
+            return;
+        };
+
+        let cutoff = now.saturating_sub (window);
+        self.newer_than = Some (self.newer_than.map_or (cutoff, |t| t.max (cutoff)));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  apply_stale_window
+    //
+    //  Turn /Stale into a last-access cutoff of `now` minus the window.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn apply_stale_window(&mut self, now: u64) {
+        self.stale_before = self.stale_window.map (|window| now.saturating_sub (window));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  is_stale
+    //
+    //  True if /Stale is on and a file was last accessed before the
+    //  cutoff.  Directories, and entries with no access time (0), are
+    //  never stale.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn is_stale(&self, file_info: &FileInfo) -> bool {
+        self.stale_before.is_some_and (|cutoff| {
+            !file_info.is_directory() && file_info.last_access_time != 0 && file_info.last_access_time < cutoff
+        })
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_recent_window
+    //
+    //  Parse a /Recent or /Stale window such as "30m", "24h", "7d", or "2w" into
+    //  FILETIME ticks.  The count must be positive.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub(crate) fn parse_recent_window(value: &str) -> Option<u64> {
+        let unit_pos = value.find (|c: char| !c.is_ascii_digit())?;
+        let (count, unit) = value.split_at (unit_pos);
+        let count: u64 = count.parse().ok().filter (|&n| n > 0)?;
+
+        let unit_ticks = match unit.to_ascii_lowercase().as_str() {
+            "m" => FILETIME_TICKS_PER_MINUTE,
+            "h" => FILETIME_TICKS_PER_MINUTE * 60,
+            "d" => FILETIME_TICKS_PER_MINUTE * 60 * 24,
+            "w" => FILETIME_TICKS_PER_MINUTE * 60 * 24 * 7,
+            _   => return None,
+        };
+
+        count.checked_mul (unit_ticks)
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_size_band
+    //
+    //  Parse a /XSZ band such as "0-0" or "1M-10M".  Each bound is a byte
+    //  count with an optional K, M, or G (1024-based) suffix; min must not
+    //  exceed max.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn parse_size_band(value: &str) -> Option<SizeBand> {
+        fn parse_bound(text: &str) -> Option<u64> {
+            let text = text.trim();
+            let (digits, multiplier) = match text.chars().last()?.to_ascii_uppercase() {
+                'K' => (&text[..text.len() - 1], 1u64 << 10),
+                'M' => (&text[..text.len() - 1], 1u64 << 20),
+                'G' => (&text[..text.len() - 1], 1u64 << 30),
+                _   => (text, 1),
+            };
+
+            digits.parse::<u64>().ok()?.checked_mul (multiplier)
+        }
+
+        let (min, max) = value.split_once ('-')?;
+        let band = SizeBand { min: parse_bound (min)?, max: parse_bound (max)? };
+
+        (band.min <= band.max).then_some (band)
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  resolve_diff_reference
+    //
+    //  Index the /Diff directory once, so the listers test each entry
+    //  against an in-memory name map.  A missing directory is an error.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn resolve_diff_reference(&mut self) -> Result<(), AppError> {
+        if let Some (dir) = &self.diff_dir {
+            self.diff_reference = Some (DiffReference::load (std::path::Path::new (dir), self.diff_metadata)?);
+        }
+
+        Ok(())
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  handle_switch
+    //
+    //  Route a switch argument to the appropriate handler.
+    //
+    //  Port of: CCommandLine::HandleSwitch
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn handle_switch(&mut self, switch_arg: &str, args: &[String], idx: &mut usize) -> Result<(), AppError> {
+        // Check for long switch (3+ chars, no ':' or '-' at position 1)
+        if switch_arg.len() >= 3
+            && switch_arg.as_bytes().get(1) != Some(&b':')
+            && switch_arg.as_bytes().get(1) != Some(&b'-')
+        {
+            return self.handle_long_switch (switch_arg, args, idx);
+        }
+
+        // Single-character switch
+        let ch = switch_arg.chars().next()
+            .ok_or_else(|| AppError::InvalidArg(String::new()))?;
+
+        // Check for trailing '-' to disable
+        let disable = switch_arg.len() >= 2 && switch_arg.as_bytes()[1] == b'-';
+
+        let ch_lower = ch.to_ascii_lowercase();
+        match ch_lower {
+            's' => { self.recurse        = !disable; Ok(()) }
+            'w' => { self.wide_listing   = !disable; Ok(()) }
+            'b' => self.bare_handler(&switch_arg[1..]),
+            'p' => { self.perf_timer     = !disable; Ok(()) }
+            'm' => { self.multi_threaded = !disable; Ok(()) }
+            '?' => { self.show_help      = true;     Ok(()) }
+            'o' => self.order_by_handler(&switch_arg[1..]),
+            'a' => self.attribute_handler(&switch_arg[1..]),
+            't' => self.time_field_handler(&switch_arg[1..]),
+            'v' => self.verbosity_handler(&switch_arg[1..]),
+            _   => Err(AppError::InvalidArg(String::new())),
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  handle_long_switch
+    //
+    //  Handle long switches: env, config, owner, streams, icons, debug.
+    //  Table-driven dispatch with case-insensitive matching.
+    //  Values may be given as --Key=Value, /Key:Value, or as the next arg.
+    //
+    //  Port of: CCommandLine::HandleLongSwitch
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn handle_long_switch(&mut self, switch_arg: &str, args: &[String], idx: &mut usize) -> Result<(), AppError> {
+        // Split on '=' or ':' to extract key and optional value
+        let (key, inline_value) = match switch_arg.find (['=', ':']) {
+            Some (pos) => (&switch_arg[..pos], Some (&switch_arg[pos + 1..])),
+            None       => (switch_arg, None),
         };
 
+        // --Config=JSON: machine-readable dump of the resolved configuration
+        // --Config=errors-json: machine-readable RCDIR env var errors
+        if key.eq_ignore_ascii_case ("config") && let Some (v) = inline_value {
+            if v.eq_ignore_ascii_case ("json") {
+                self.show_config = true;
+                self.config_json = true;
+            } else if v.eq_ignore_ascii_case ("errors-json") {
+                self.config_errors_json = true;
+            } else {
+                return Err (AppError::InvalidArg (
+                    format! ("Invalid --Config value '{}'. Use JSON or errors-json", v)
+                ));
+            }
+            return Ok(());
+        }
+
+        // --Recent[=window]: entries written within the window (default 24h)
+        if key.eq_ignore_ascii_case ("recent") {
+            let window = match inline_value {
+                None    => RECENT_DEFAULT_WINDOW,
+                Some (v) => Self::parse_recent_window (v).ok_or_else (|| AppError::InvalidArg (
+                    format! ("Invalid --Recent window '{}'. Use a count with m, h, d, or w (e.g. 24h, 7d)", v)
+                ))?,
+            };
+            self.recent_window = Some (window);
+            return Ok(());
+        }
+
+        // --Stale[:window]: dim files not accessed within the window (default 90d)
+        if key.eq_ignore_ascii_case ("stale") {
+            let window = match inline_value {
+                None    => STALE_DEFAULT_WINDOW,
+                Some (v) => Self::parse_recent_window (v).ok_or_else (|| AppError::InvalidArg (
+                    format! ("Invalid --Stale window '{}'. Use a count with m, h, d, or w (e.g. 90d, 12w)", v)
+                ))?,
+            };
+            self.stale_window = Some (window);
+            return Ok(());
+        }
+
+        // --Owner[:sid]: owner column, as account names or raw SID strings
+        if key.eq_ignore_ascii_case ("owner") {
+            match inline_value {
+                None => {}
+                Some (v) if v.eq_ignore_ascii_case ("sid") => self.owner_sid = true,
+                Some (v) => return Err (AppError::InvalidArg (
+                    format! ("Invalid --Owner value '{}'. Use --Owner or --Owner:sid", v)
+                )),
+            }
+            self.show_owner = true;
+            return Ok(());
+        }
+
+        // --Icons:force: icons on even without a console or detected Nerd Font
+        if key.eq_ignore_ascii_case ("icons") && let Some (v) = inline_value {
+            if !v.eq_ignore_ascii_case ("force") {
+                return Err (AppError::InvalidArg (
+                    format! ("Invalid --Icons value '{}'. Use --Icons, --Icons-, or --Icons:force", v)
+                ));
+            }
+            self.icons        = Some (true);
+            self.icons_forced = true;
+            return Ok(());
+        }
+
+        // --NoCloud:auto: drop the cloud column only outside a sync root
+        if key.eq_ignore_ascii_case ("nocloud") && let Some (v) = inline_value {
+            if !v.eq_ignore_ascii_case ("auto") {
+                return Err (AppError::InvalidArg (
+                    format! ("Invalid --NoCloud value '{}'. Use --NoCloud or --NoCloud:auto", v)
+                ));
+            }
+            self.no_cloud_auto = true;
+            return Ok(());
+        }
+
+        // --Tree:dirs: directory hierarchy only, files counted but not shown
+        if key.eq_ignore_ascii_case ("tree") && let Some (v) = inline_value {
+            if !v.eq_ignore_ascii_case ("dirs") {
+                return Err (AppError::InvalidArg (
+                    format! ("Invalid --Tree value '{}'. Use --Tree, --Tree-, or --Tree:dirs", v)
+                ));
+            }
+            self.tree_files = TreeFilePolicy::DirectoriesOnly;
+            self.tree       = Some (true);
+            return Ok(());
+        }
+
+        // --DirsFirst:tree: tree of directories, files only in targets and leaves
+        if key.eq_ignore_ascii_case ("dirsfirst") {
+            match inline_value {
+                Some (v) if v.eq_ignore_ascii_case ("tree") => {
+                    self.tree_files = TreeFilePolicy::TargetsAndLeaves;
+                    self.tree       = Some (true);
+                }
+                _ => return Err (AppError::InvalidArg (
+                    format! ("Invalid --DirsFirst value '{}'. Use --DirsFirst:tree", inline_value.unwrap_or_default())
+                )),
+            }
+            return Ok(());
+        }
+
+        // --NameCol[:N]: align names across the run, or at a fixed column
+        if key.eq_ignore_ascii_case ("namecol") {
+            let name_column = match inline_value {
+                None => NameColumn::Auto,
+                Some (v) => match v.parse::<usize>() {
+                    Ok (n) if n > 0 => NameColumn::Fixed (n),
+                    _ => return Err (AppError::InvalidArg (
+                        format! ("Invalid --NameCol value '{}'. Use --NameCol or --NameCol:N with a positive column number", v)
+                    )),
+                },
+            };
+            self.name_column = Some (name_column);
+            return Ok(());
+        }
+
         // Boolean switches (no value expected)
         type Setter = fn(&mut CommandLine);
 
@@ -582,7 +1535,6 @@ fn handle_long_switch(&mut self, switch_arg: &str, args: &[String], idx: &mut us
             ("env",      |cmd| cmd.show_env_help = true),
             ("config",   |cmd| cmd.show_config   = true),
             ("settings", |cmd| cmd.show_settings = true),
-            ("owner",    |cmd| cmd.show_owner    = true),
             ("streams", |cmd| cmd.show_streams  = true),
             ("icons",   |cmd| cmd.icons = Some (true)),
             ("icons-",  |cmd| cmd.icons = Some (false)),
@@ -590,6 +1542,52 @@ fn handle_long_switch(&mut self, switch_arg: &str, args: &[String], idx: &mut us
             ("tree-",   |cmd| cmd.tree = Some (false)),
             ("ellipsize",  |cmd| cmd.ellipsize = Some (true)),
             ("ellipsize-", |cmd| cmd.ellipsize = Some (false)),
+            ("nocolor",    |cmd| cmd.color_mode = Some (ColorMode::Never)),
+            ("nocloud",    |cmd| cmd.no_cloud = true),
+            ("delta",      |cmd| cmd.show_delta = true),
+            ("tsv",        |cmd| cmd.tsv_listing = true),
+            ("jsonl",      |cmd| cmd.jsonl_listing = true),
+            ("count",      |cmd| cmd.count_only = true),
+            ("fullpath",   |cmd| cmd.full_path = true),
+            ("progress",   |cmd| cmd.show_progress = true),
+            ("collapseempty", |cmd| cmd.collapse_empty = true),
+            ("inzip",      |cmd| cmd.in_zip = true),
+            ("columns",    |cmd| cmd.columns = true),
+            ("target",     |cmd| cmd.show_target = true),
+            ("nosummary",  |cmd| cmd.no_summary = true),
+            ("wrapnames",  |cmd| cmd.wrap_names = true),
+            ("showmask",   |cmd| cmd.show_mask = true),
+            ("quiet",      |cmd| cmd.quiet = true),
+            ("stats",      |cmd| cmd.show_stats = true),
+            ("pct",        |cmd| cmd.show_percent = true),
+            ("brief",      |cmd| cmd.brief = true),
+            ("showdots",   |cmd| cmd.show_dots = true),
+            ("showfiltered", |cmd| cmd.show_filtered = true),
+            ("bom",        |cmd| cmd.bom = true),
+            ("rawcolors",  |cmd| cmd.raw_colors = true),
+            ("light",      |cmd| cmd.light = true),
+            ("fileid",     |cmd| cmd.show_file_id = true),
+            ("encoding",   |cmd| cmd.show_encoding = true),
+            ("tier",       |cmd| cmd.show_tier = true),
+            ("verinfo",    |cmd| cmd.show_version = true),
+            ("sincelast",  |cmd| cmd.since_last = true),
+            ("literal",    |cmd| cmd.literal = true),
+            ("dedupe",     |cmd| cmd.dedupe = true),
+            ("noreparse",  |cmd| cmd.no_reparse = true),
+            ("mine",       |cmd| cmd.mine = true),
+            ("mark",       |cmd| cmd.mark_dirs = true),
+            ("drivepct",   |cmd| cmd.drive_pct = true),
+            ("errorsummary", |cmd| cmd.error_summary = true),
+            ("ondisk",     |cmd| cmd.on_disk = true),
+            ("desc",       |cmd| cmd.show_file_type = true),
+            ("status",     |cmd| cmd.status_line = true),
+            ("status-",    |cmd| cmd.status_line = false),
+            ("derefarg",   |cmd| cmd.deref_arg = true),
+            ("failonempty", |cmd| cmd.empty_policy = EmptyMatchPolicy::Fail),
+            ("ignoreempty", |cmd| cmd.empty_policy = EmptyMatchPolicy::Ignore),
+            ("hasstreams", |cmd| { cmd.has_streams = true; cmd.show_streams = true; }),
+            ("diffmeta",   |cmd| cmd.diff_metadata = true),
+            ("watch",      |cmd| cmd.watch = true),
             ("set-aliases",    |cmd| cmd.set_aliases    = true),
             ("get-aliases",    |cmd| cmd.get_aliases    = true),
             ("remove-aliases", |cmd| cmd.remove_aliases = true),
@@ -635,6 +1633,15 @@ fn handle_long_switch(&mut self, switch_arg: &str, args: &[String], idx: &mut us
                 self.max_depth = n;
                 Ok(())
             }
+            "limit" => {
+                match value.parse::<usize>() {
+                    Ok (n) if n > 0 => self.entry_limit = Some (n),
+                    _ => return Err (AppError::InvalidArg (
+                        format! ("Invalid --Limit value '{}'. Use --Limit:N with a positive entry count", value)
+                    )),
+                }
+                Ok(())
+            }
             "treeindent" => {
                 let n: i32 = value.parse().map_err (|_| {
                     AppError::InvalidArg (format! ("Invalid tree indent value: {}", value))
@@ -652,72 +1659,1569 @@ fn handle_long_switch(&mut self, switch_arg: &str, args: &[String], idx: &mut us
                     self.size_format = SizeFormat::Auto;
                 } else if value.eq_ignore_ascii_case ("bytes") {
                     self.size_format = SizeFormat::Bytes;
+                } else if value.eq_ignore_ascii_case ("compact") {
+                    self.size_format = SizeFormat::Compact;
                 } else {
                     return Err (AppError::InvalidArg (
-                        format! ("Invalid --Size value '{}'. Use Auto or Bytes", value)
+                        format! ("Invalid --Size value '{}'. Use Auto, Bytes, or Compact", value)
                     ));
                 }
                 Ok(())
             }
-            _ => Err (AppError::InvalidArg (String::new())),
-        }
-    }
-
-
-
-
-
-    ////////////////////////////////////////////////////////////////////////////
-    //
-    //  order_by_handler
-    //
-    //  Handle /O sort order switch.
-    //
-    //  Port of: CCommandLine::OrderByHandler
-    //
-    ////////////////////////////////////////////////////////////////////////////
-
-    fn order_by_handler(&mut self, arg: &str) -> Result<(), AppError> {
-        let mut chars = arg.chars().peekable();
-
-        // Skip optional colon
-        if chars.peek() == Some(&':') {
-            chars.next();
-        }
-
-        // Must have at least one char
-        if chars.peek().is_none() {
-            return Err(AppError::InvalidArg(String::new()));
-        }
-
-        // Check for reverse direction
-        if chars.peek() == Some(&'-') {
-            self.sort_direction = SortDirection::Descending;
-            chars.next();
+            "color" => {
+                if value.eq_ignore_ascii_case ("auto") {
+                    self.color_mode = Some (ColorMode::Auto);
+                } else if value.eq_ignore_ascii_case ("always") {
+                    self.color_mode = Some (ColorMode::Always);
+                } else if value.eq_ignore_ascii_case ("never") {
+                    self.color_mode = Some (ColorMode::Never);
+                } else {
+                    return Err (AppError::InvalidArg (
+                        format! ("Invalid --Color value '{}'. Use Auto, Always, or Never", value)
+                    ));
+                }
+                Ok(())
+            }
+            "highlight" => {
+                if value.is_empty() {
+                    return Err (AppError::InvalidArg (
+                        "--Highlight requires one or more attribute letters".to_string()
+                    ));
+                }
+                for ch in value.chars() {
+                    let flag = Self::attribute_flag (ch);
+                    if flag == 0 {
+                        return Err (AppError::InvalidArg (
+                            format! ("Invalid --Highlight attribute '{}'", ch)
+                        ));
+                    }
+                    self.highlight_attrs |= flag;
+                }
+                Ok(())
+            }
+            "xd" => {
+                if value.is_empty() {
+                    return Err (AppError::InvalidArg (
+                        "/XD requires a directory name or relative path".to_string()
+                    ));
+                }
+                self.exclude_dirs.push (value);
+                Ok(())
+            }
+            "up" => {
+                let n: usize = value.parse().map_err (|_| {
+                    AppError::InvalidArg (format! ("Invalid --Up value '{}'. Use a number of ancestor levels", value))
+                })?;
+                if n == 0 {
+                    return Err (AppError::InvalidArg (
+                        "--Up must be a positive number of ancestor levels".to_string()
+                    ));
+                }
+                self.up_levels = Some (n);
+                Ok(())
+            }
+            "xsz" => {
+                let band = Self::parse_size_band (&value).ok_or_else (|| AppError::InvalidArg (
+                    format! ("Invalid /XSZ value '{}'. Use min-max in bytes, optionally with K, M, or G (e.g. 0-0, 1M-10M)", value)
+                ))?;
+                self.exclude_sizes.push (band);
+                Ok(())
+            }
+            "base" => {
+                if value.is_empty() {
+                    return Err (AppError::InvalidArg (
+                        "--Base requires a directory path".to_string()
+                    ));
+                }
+                self.base_path = Some (value);
+                Ok(())
+            }
+            "contains" => {
+                if value.is_empty() {
+                    return Err (AppError::InvalidArg (
+                        "--Contains requires the text to search for".to_string()
+                    ));
+                }
+                // Stored lowercased; names are compared case-insensitively
+                self.contains_text = Some (value.to_lowercase());
+                Ok(())
+            }
+            "datefmt" => {
+                if !date_format::is_valid_pattern (&value) {
+                    return Err (AppError::InvalidArg (
+                        format! ("Invalid --DateFmt pattern '{}'. Use GetDateFormatEx/GetTimeFormatEx pictures, e.g. \"yyyy-MM-dd HH:mm\"", value)
+                    ));
+                }
+                self.date_format = Some (value);
+                Ok(())
+            }
+            "fmt" => {
+                let template = FormatTemplate::parse (&value).map_err (|e| AppError::InvalidArg (
+                    format! ("Invalid --Fmt template '{}': {}", value, e)
+                ))?;
+                self.format_template = Some (template);
+                Ok(())
+            }
+            "diff" => {
+                if value.is_empty() {
+                    return Err (AppError::InvalidArg (
+                        "/Diff requires a directory to compare against".to_string()
+                    ));
+                }
+                self.diff_dir = Some (value);
+                Ok(())
+            }
+            "hash" => {
+                let Some (algorithm) = HashAlgorithm::parse (&value) else {
+                    return Err (AppError::InvalidArg (
+                        format! ("Invalid --Hash value '{}'. Use CRC32 or SHA256", value)
+                    ));
+                };
+                self.hash_algorithm = Some (algorithm);
+                Ok(())
+            }
+            "odir" | "ofile" => {
+                let Some (sort_key) = Self::parse_sort_key (&value) else {
+                    return Err (AppError::InvalidArg (
+                        format! ("Invalid /{} value '{}'. Use N, E, S, or D, optionally prefixed with '-'", key, value)
+                    ));
+                };
+                if key_lower == "odir" {
+                    self.dir_sort = Some (sort_key);
+                } else {
+                    self.file_sort = Some (sort_key);
+                }
+                Ok(())
+            }
+            "attr" => {
+                if value.eq_ignore_ascii_case ("full") {
+                    self.full_attributes = true;
+                } else if value.eq_ignore_ascii_case ("auto") {
+                    self.auto_attributes = true;
+                } else {
+                    return Err (AppError::InvalidArg (
+                        format! ("Invalid /Attr value '{}'. Use /Attr:full or /Attr:auto", value)
+                    ));
+                }
+                Ok(())
+            }
+            "attrstyle" => {
+                self.attr_style = match value.to_ascii_lowercase().as_str() {
+                    "dir" => AttributeStyle::Dir,
+                    "ls"  => AttributeStyle::Ls,
+                    _     => return Err (AppError::InvalidArg (
+                        format! ("Invalid /AttrStyle value '{}'. Use dir or ls", value)
+                    )),
+                };
+                Ok(())
+            }
+            "group" => {
+                if !value.eq_ignore_ascii_case ("ext") {
+                    return Err (AppError::InvalidArg (
+                        format! ("Invalid /Group value '{}'. Use /Group:ext", value)
+                    ));
+                }
+                self.group_by_ext = true;
+                Ok(())
+            }
+            "newer" | "older" => {
+                if value.is_empty() {
+                    return Err (AppError::InvalidArg (
+                        format! ("/{} requires a reference file", key)
+                    ));
+                }
+                if key_lower == "newer" {
+                    self.newer_than_file = Some (value);
+                } else {
+                    self.older_than_file = Some (value);
+                }
+                Ok(())
+            }
+            _ => Err (AppError::InvalidArg (String::new())),
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  order_by_handler
+    //
+    //  Handle /O sort order switch.
+    //
+    //  Port of: CCommandLine::OrderByHandler
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn order_by_handler(&mut self, arg: &str) -> Result<(), AppError> {
+        let mut chars = arg.chars().peekable();
+
+        // Skip optional colon
+        if chars.peek() == Some(&':') {
+            chars.next();
+        }
+
+        // Must have at least one char
+        if chars.peek().is_none() {
+            return Err(AppError::InvalidArg(String::new()));
+        }
+
+        // Check for reverse direction
+        if chars.peek() == Some(&'-') {
+            self.sort_direction = SortDirection::Descending;
+            chars.next();
+
+            if chars.peek().is_none() {
+                return Err(AppError::InvalidArg(String::new()));
+            }
+        }
+
+        // Read sort key: a single letter, or the word "title"
+        let rest: String = chars.collect();
+        let order = if rest.eq_ignore_ascii_case("title") {
+            SortOrder::Title
+        } else {
+            let mut chars = rest.chars();
+            let key = chars.next().unwrap().to_ascii_lowercase();
+            let order = match key {
+                'n' => SortOrder::Name,
+                'e' => SortOrder::Extension,
+                's' => SortOrder::Size,
+                'd' => SortOrder::Date,
+                _   => return Err(AppError::InvalidArg(String::new())),
+            };
+
+            // Trailing characters are an error (e.g. /o:d- is invalid; use /o:-d)
+            if chars.next().is_some() {
+                return Err(AppError::InvalidArg(String::new()));
+            }
+
+            order
+        };
+
+        self.sort_order = order;
+        self.sort_preference[0] = order;
+        Ok(())
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_sort_key
+    //
+    //  Parse a /ODir or /OFile value: one sort letter (N, E, S, D) or
+    //  "title", optionally prefixed with '-' to reverse it.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub(crate) fn parse_sort_key(value: &str) -> Option<(SortOrder, SortDirection)> {
+        let (direction, key) = match value.strip_prefix ('-') {
+            Some (rest) => (SortDirection::Descending, rest),
+            None        => (SortDirection::Ascending,  value),
+        };
+
+        let order = match key.to_ascii_lowercase().as_str() {
+            "n"     => SortOrder::Name,
+            "e"     => SortOrder::Extension,
+            "s"     => SortOrder::Size,
+            "d"     => SortOrder::Date,
+            "title" => SortOrder::Title,
+            _       => return None,
+        };
+
+        Some ((order, direction))
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  attribute_handler
+    //
+    //  Handle /A attribute filter switch.
+    //
+    //  Port of: CCommandLine::AttributeHandler
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn attribute_handler(&mut self, arg: &str) -> Result<(), AppError> {
+        let mut chars = arg.chars().peekable();
+
+        // Skip optional colon prefix
+        if chars.peek() == Some(&':') {
+            chars.next();
+        }
+
+        // Must have at least one char
+        if chars.peek().is_none() {
+            return Err(AppError::InvalidArg(String::new()));
+        }
+
+        // Start in "required" mode
+        let mut excluding = false;
+
+        for ch in chars {
+            let ch_lower = ch.to_ascii_lowercase();
+
+            if ch_lower == '-' {
+                // Double '-' is an error
+                if excluding {
+                    return Err(AppError::InvalidArg(String::new()));
+                }
+                excluding = true;
+                continue;
+            }
+
+            // Unknown chars silently ignored (matches TCDir behavior)
+            let flag = Self::attribute_flag (ch);
+
+            if flag != 0 {
+                if excluding {
+                    self.attrs_excluded |= flag;
+                } else {
+                    self.attrs_required |= flag;
+                }
+            }
+
+            // Reset to required mode after each attribute char
+            excluding = false;
+        }
+
+        Ok(())
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  attribute_flag
+    //
+    //  Map an attribute letter (as used by /A and --Highlight, case-
+    //  insensitive) to its Win32 attribute flag(s).  Returns 0 for
+    //  unknown letters.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn attribute_flag(ch: char) -> u32 {
+        match ch.to_ascii_lowercase() {
+            'd' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_DIRECTORY.0,
+            'h' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_HIDDEN.0,
+            's' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_SYSTEM.0,
+            'r' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_READONLY.0,
+            'a' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_ARCHIVE.0,
+            't' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_TEMPORARY.0,
+            'e' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_ENCRYPTED.0,
+            'c' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_COMPRESSED.0,
+            'p' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_REPARSE_POINT.0,
+            '0' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_SPARSE_FILE.0,
+            'x' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_NOT_CONTENT_INDEXED.0,
+            'i' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_INTEGRITY_STREAM.0,
+            'b' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_NO_SCRUB_DATA.0,
+            'o' => {
+                // Cloud-only composite: OFFLINE | RECALL_ON_OPEN | RECALL_ON_DATA_ACCESS
+                windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_OFFLINE.0
+                    | windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_RECALL_ON_OPEN.0
+                    | windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS.0
+            }
+            'l' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_UNPINNED.0,
+            'v' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_PINNED.0,
+            _   => 0,
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  time_field_handler
+    //
+    //  Handle /T time field switch.  /T:both keeps the field and adds a
+    //  relative age after the absolute date/time.
+    //
+    //  Port of: CCommandLine::TimeFieldHandler
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn time_field_handler(&mut self, arg: &str) -> Result<(), AppError> {
+        if arg.strip_prefix (':').unwrap_or (arg).eq_ignore_ascii_case ("both") {
+            self.time_with_age = true;
+            return Ok(());
+        }
+
+        let mut chars = arg.chars();
+
+        // Skip optional colon
+        let first = chars.next()
+            .ok_or_else(|| AppError::InvalidArg(String::new()))?;
+
+        let field_char = if first == ':' {
+            chars.next()
+                .ok_or_else(|| AppError::InvalidArg(String::new()))?
+        } else {
+            first
+        };
+
+        match field_char.to_ascii_lowercase() {
+            'c' => { self.time_field = TimeField::Creation; Ok(()) }
+            'a' => { self.time_field = TimeField::Access;   Ok(()) }
+            'w' => { self.time_field = TimeField::Written;  Ok(()) }
+            _   => Err(AppError::InvalidArg(String::new())),
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  verbosity_handler
+    //
+    //  Handle /V:0|1|2 summary verbosity switch.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn verbosity_handler(&mut self, arg: &str) -> Result<(), AppError> {
+        let level = arg.strip_prefix(':').unwrap_or(arg);
+
+        self.verbosity = match level {
+            "0" => SummaryVerbosity::TotalsOnly,
+            "1" => SummaryVerbosity::Standard,
+            "2" => SummaryVerbosity::Detailed,
+            _   => return Err(AppError::InvalidArg(
+                format!("Invalid /V level '{}'. Use /V:0, /V:1, or /V:2", level)
+            )),
+        };
+
+        Ok(())
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  bare_handler
+    //
+    //  Handle /B, /B-, /B:size (bare listing with a size before each
+    //  name) and /B:numbered (with a 1-based index before each line).
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn bare_handler(&mut self, arg: &str) -> Result<(), AppError> {
+        let (listing, sizes, numbered) = match arg.strip_prefix(':') {
+            None if arg.is_empty() => (true,  false, false),
+            None if arg == "-"     => (false, false, false),
+            Some(option) if option.eq_ignore_ascii_case("size")     => (true, true,  false),
+            Some(option) if option.eq_ignore_ascii_case("numbered") => (true, false, true),
+            _ => return Err(AppError::InvalidArg(format!("Invalid /B option '{}'. Use /B:size or /B:numbered", arg))),
+        };
+
+        self.bare_listing  = listing;
+        self.bare_sizes    = sizes;
+        self.bare_numbered = numbered;
+        Ok(())
+    }
+}
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  default_values
+    //
+    //  Verify default CommandLine values.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn default_values() {
+        let cmd = CommandLine::default();
+        assert!(!cmd.recurse);
+        assert!(!cmd.wide_listing);
+        assert!(!cmd.bare_listing);
+        assert!(!cmd.perf_timer);
+        assert!(cmd.multi_threaded);
+        assert!(!cmd.show_help);
+        assert_eq!(cmd.sort_order, SortOrder::Default);
+        assert_eq!(cmd.sort_direction, SortDirection::Ascending);
+        assert_eq!(cmd.time_field, TimeField::Written);
+        assert_eq!(cmd.switch_prefix, '-');
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_slash_switches
+    //
+    //  Verify parsing of slash-prefixed switches.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_slash_switches() {
+        let cmd = CommandLine::parse_from(["/s", "/w", "/p"]).unwrap();
+        assert!(cmd.recurse);
+        assert!(cmd.wide_listing);
+        assert!(cmd.perf_timer);
+        assert_eq!(cmd.switch_prefix, '/');
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_dash_switches
+    //
+    //  Verify parsing of dash-prefixed switches.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_dash_switches() {
+        let cmd = CommandLine::parse_from(["-s", "-b"]).unwrap();
+        assert!(cmd.recurse);
+        assert!(cmd.bare_listing);
+        assert_eq!(cmd.switch_prefix, '-');
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_disable_with_trailing_dash
+    //
+    //  Verify trailing dash disables a switch.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_disable_with_trailing_dash() {
+        let cmd = CommandLine::parse_from(["/m-"]).unwrap();
+        assert!(!cmd.multi_threaded);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_help
+    //
+    //  Verify /? enables show_help.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_help() {
+        let cmd = CommandLine::parse_from(["/?"].iter()).unwrap();
+        assert!(cmd.show_help);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_sort_name
+    //
+    //  Verify /O:N sets sort order to Name.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_sort_name() {
+        let cmd = CommandLine::parse_from(["/on"]).unwrap();
+        assert_eq!(cmd.sort_order, SortOrder::Name);
+        assert_eq!(cmd.sort_direction, SortDirection::Ascending);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_sort_with_colon
+    //
+    //  Verify /O:S with colon prefix.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_sort_with_colon() {
+        let cmd = CommandLine::parse_from(["/o:s"]).unwrap();
+        assert_eq!(cmd.sort_order, SortOrder::Size);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_sort_descending
+    //
+    //  Verify /O:-D sets descending direction.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_sort_descending() {
+        let cmd = CommandLine::parse_from(["/o:-d"]).unwrap();
+        assert_eq!(cmd.sort_order, SortOrder::Date);
+        assert_eq!(cmd.sort_direction, SortDirection::Descending);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_sort_case_insensitive
+    //
+    //  Verify sort switch is case-insensitive.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_sort_case_insensitive() {
+        let cmd = CommandLine::parse_from(["/O:E"]).unwrap();
+        assert_eq!(cmd.sort_order, SortOrder::Extension);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_sort_empty_errors
+    //
+    //  Verify empty sort arg returns error.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_sort_empty_errors() {
+        assert!(CommandLine::parse_from(["/o"]).is_err());
+        assert!(CommandLine::parse_from(["/o:"]).is_err());
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_sort_trailing_chars_error
+    //
+    //  Verify trailing characters after the sort key produce an error.
+    //  e.g. /o:d- is invalid — use /o:-d for descending date.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_sort_trailing_chars_error() {
+        assert!(CommandLine::parse_from(["/o:d-"]).is_err());
+        assert!(CommandLine::parse_from(["/o:nx"]).is_err());
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_attrs_required
+    //
+    //  Verify /A:D sets required attribute.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_attrs_required() {
+        let cmd = CommandLine::parse_from(["/a:d"]).unwrap();
+        assert_ne!(cmd.attrs_required & 0x10, 0); // FILE_ATTRIBUTE_DIRECTORY = 0x10
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_attrs_excluded
+    //
+    //  Verify /A:-H sets excluded attribute.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_attrs_excluded() {
+        let cmd = CommandLine::parse_from(["/a:-h"]).unwrap();
+        assert_ne!(cmd.attrs_excluded & 0x02, 0); // FILE_ATTRIBUTE_HIDDEN = 0x02
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_attrs_mixed
+    //
+    //  Verify mixed required and excluded attributes.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_attrs_mixed() {
+        // /a:h-ds → require H, exclude D, require S
+        let cmd = CommandLine::parse_from(["/a:h-ds"]).unwrap();
+        assert_ne!(cmd.attrs_required & 0x02, 0); // H required
+        assert_ne!(cmd.attrs_excluded & 0x10, 0);  // D excluded
+        assert_ne!(cmd.attrs_required & 0x04, 0); // S required
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_attrs_double_dash_error
+    //
+    //  Verify double dash in /A returns error.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_attrs_double_dash_error() {
+        assert!(CommandLine::parse_from(["/a:--d"]).is_err());
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_time_creation
+    //
+    //  Verify /T:C sets creation time field.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_time_creation() {
+        let cmd = CommandLine::parse_from(["/t:c"]).unwrap();
+        assert_eq!(cmd.time_field, TimeField::Creation);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_time_both
+    //
+    //  Verify /T:both adds the relative age and keeps the time field, so
+    //  it combines with a later /T:C.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_time_both() {
+        let cmd = CommandLine::parse_from (["/T:both"]).unwrap();
+        assert! (cmd.time_with_age);
+        assert_eq! (cmd.time_field, TimeField::Written);
+
+        let cmd = CommandLine::parse_from (["/T:Both", "/T:C"]).unwrap();
+        assert! (cmd.time_with_age);
+        assert_eq! (cmd.time_field, TimeField::Creation);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_time_access
+    //
+    //  Verify /T:A sets access time field.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_time_access() {
+        let cmd = CommandLine::parse_from(["/t:a"]).unwrap();
+        assert_eq!(cmd.time_field, TimeField::Access);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_time_no_colon
+    //
+    //  Verify /TW without colon works.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_time_no_colon() {
+        let cmd = CommandLine::parse_from(["/tw"]).unwrap();
+        assert_eq!(cmd.time_field, TimeField::Written);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_long_switch_env_double_dash
+    //
+    //  Verify --env enables show_env_help.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_long_switch_env_double_dash() {
+        let cmd = CommandLine::parse_from(["--env"]).unwrap();
+        assert!(cmd.show_env_help);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_long_switch_env_slash
+    //
+    //  Verify /env enables show_env_help.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_long_switch_env_slash() {
+        let cmd = CommandLine::parse_from(["/env"]).unwrap();
+        assert!(cmd.show_env_help);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_long_switch_single_dash_error
+    //
+    //  Verify -env (single dash) returns error.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_long_switch_single_dash_error() {
+        // -env (single dash + long) should produce descriptive error
+        let err = CommandLine::parse_from(["-env"]).unwrap_err();
+        assert_eq!(err.to_string(), "Unknown switch: -env.  Did you mean --env?");
+
+        // Unrecognized long switch with single dash
+        let err = CommandLine::parse_from(["-notaswitch"]).unwrap_err();
+        assert_eq!(err.to_string(), "Unknown switch: -notaswitch.");
+
+        // Single-dash with '=' separator — strips = for lookup
+        let err = CommandLine::parse_from(["-icons=true"]).unwrap_err();
+        assert!(err.to_string().contains("Did you mean"));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_long_switch_config
+    //
+    //  Verify /config enables show_config.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_long_switch_config() {
+        let cmd = CommandLine::parse_from(["/config"]).unwrap();
+        assert!(cmd.show_config);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_long_switch_owner
+    //
+    //  Verify --owner enables show_owner.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_long_switch_owner() {
+        let cmd = CommandLine::parse_from(["--owner"]).unwrap();
+        assert!(cmd.show_owner);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_long_switch_streams
+    //
+    //  Verify /streams enables show_streams.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_long_switch_streams() {
+        let cmd = CommandLine::parse_from(["/streams"]).unwrap();
+        assert!(cmd.show_streams);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_long_switch_case_insensitive
+    //
+    //  Verify long switches are case-insensitive.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_long_switch_case_insensitive() {
+        let cmd = CommandLine::parse_from(["/ENV"]).unwrap();
+        assert!(cmd.show_env_help);
+
+        let cmd2 = CommandLine::parse_from(["--Owner"]).unwrap();
+        assert!(cmd2.show_owner);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_masks
+    //
+    //  Verify positional arguments parsed as masks.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_masks() {
+        let cmd = CommandLine::parse_from(["*.rs", "*.toml"]).unwrap();
+        assert_eq!(cmd.masks.len(), 2);
+        assert_eq!(cmd.masks[0], "*.rs");
+        assert_eq!(cmd.masks[1], "*.toml");
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_mixed_masks_and_switches
+    //
+    //  Verify masks and switches can be intermixed.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_mixed_masks_and_switches() {
+        let cmd = CommandLine::parse_from(["*.rs", "/s", "*.toml", "/o:n"]).unwrap();
+        assert_eq!(cmd.masks.len(), 2);
+        assert!(cmd.recurse);
+        assert_eq!(cmd.sort_order, SortOrder::Name);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  apply_config_defaults
+    //
+    //  Verify Config defaults are applied to CommandLine.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn apply_config_defaults() {
+        let mut cmd = CommandLine::default();
+        let mut config = Config::new();
+        config.wide_listing = Some(true);
+        config.perf_timer = Some(true);
+        config.multi_threaded = Some(false);
+
+        cmd.apply_config_defaults(&config);
+
+        assert!(cmd.wide_listing);
+        assert!(cmd.perf_timer);
+        assert!(!cmd.multi_threaded);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  switch_prefix_tracks_last_used
+    //
+    //  Verify switch_prefix tracks the last prefix used.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn switch_prefix_tracks_last_used() {
+        let cmd = CommandLine::parse_from(["-s", "/w"]).unwrap();
+        assert_eq!(cmd.switch_prefix, '/');
+
+        let cmd2 = CommandLine::parse_from(["/s", "-w"]).unwrap();
+        assert_eq!(cmd2.switch_prefix, '-');
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  sort_preference_default
+    //
+    //  Verify default sort preference chain.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn sort_preference_default() {
+        let cmd = CommandLine::default();
+        assert_eq!(cmd.sort_preference[0], SortOrder::Default);
+        assert_eq!(cmd.sort_preference[1], SortOrder::Name);
+        assert_eq!(cmd.sort_preference[2], SortOrder::Date);
+        assert_eq!(cmd.sort_preference[3], SortOrder::Extension);
+        assert_eq!(cmd.sort_preference[4], SortOrder::Size);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  sort_preference_updated_on_sort
+    //
+    //  Verify sort preference[0] updated on sort.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn sort_preference_updated_on_sort() {
+        let cmd = CommandLine::parse_from(["/o:s"]).unwrap();
+        assert_eq!(cmd.sort_preference[0], SortOrder::Size);
+        // Rest of chain unchanged
+        assert_eq!(cmd.sort_preference[1], SortOrder::Name);
+    }
+
+
+
+
+
+    // =========================================================================
+    //  Tree switch parsing tests (T013)
+    // =========================================================================
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_tree_switch_double_dash
+    //
+    //  Verify --Tree enables tree mode.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_tree_switch_double_dash () {
+        let cmd = CommandLine::parse_from (["--Tree"]).unwrap();
+        assert_eq! (cmd.tree, Some (true));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_tree_switch_slash
+    //
+    //  Verify /Tree enables tree mode.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_tree_switch_slash () {
+        let cmd = CommandLine::parse_from (["/Tree"]).unwrap();
+        assert_eq! (cmd.tree, Some (true));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_tree_disable_switch_double_dash
+    //
+    //  Verify --Tree- disables tree mode.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_tree_disable_switch_double_dash () {
+        let cmd = CommandLine::parse_from (["--Tree-"]).unwrap();
+        assert_eq! (cmd.tree, Some (false));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_tree_switch_case_insensitive
+    //
+    //  Verify tree switch is case-insensitive.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_tree_switch_case_insensitive () {
+        let cmd = CommandLine::parse_from (["--tree"]).unwrap();
+        assert_eq! (cmd.tree, Some (true));
+
+        let cmd2 = CommandLine::parse_from (["--TREE"]).unwrap();
+        assert_eq! (cmd2.tree, Some (true));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_alias_switches
+    //
+    //  Verify parsing of --set-aliases, --get-aliases, --remove-aliases.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_alias_switches () {
+        let cmd = CommandLine::parse_from (["--set-aliases"]).unwrap();
+        assert! (cmd.set_aliases);
+        assert! (!cmd.get_aliases);
+        assert! (!cmd.remove_aliases);
+
+        let cmd = CommandLine::parse_from (["--get-aliases"]).unwrap();
+        assert! (cmd.get_aliases);
+        assert! (!cmd.set_aliases);
+
+        let cmd = CommandLine::parse_from (["--remove-aliases"]).unwrap();
+        assert! (cmd.remove_aliases);
+        assert! (!cmd.set_aliases);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_whatif_with_alias_switch
+    //
+    //  Verify --whatif works with --set-aliases and --remove-aliases.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_whatif_with_alias_switch () {
+        let cmd = CommandLine::parse_from (["--set-aliases", "--whatif"]).unwrap();
+        assert! (cmd.set_aliases);
+        assert! (cmd.what_if);
+
+        let cmd = CommandLine::parse_from (["--remove-aliases", "--whatif"]).unwrap();
+        assert! (cmd.remove_aliases);
+        assert! (cmd.what_if);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  alias_switches_mutually_exclusive
+    //
+    //  Verify --set-aliases, --get-aliases, --remove-aliases are mutually
+    //  exclusive.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn alias_switches_mutually_exclusive () {
+        assert! (CommandLine::parse_from (["--set-aliases", "--get-aliases"]).is_err());
+        assert! (CommandLine::parse_from (["--set-aliases", "--remove-aliases"]).is_err());
+        assert! (CommandLine::parse_from (["--get-aliases", "--remove-aliases"]).is_err());
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  whatif_without_alias_switch_errors
+    //
+    //  Verify --whatif without an alias switch produces an error.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn whatif_without_alias_switch_errors () {
+        assert! (CommandLine::parse_from (["--whatif"]).is_err());
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  alias_switches_reject_listing_switches
+    //
+    //  Verify alias switches cannot be combined with directory listing
+    //  switches.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn alias_switches_reject_listing_switches () {
+        assert! (CommandLine::parse_from (["--set-aliases", "/w"]).is_err());
+        assert! (CommandLine::parse_from (["--get-aliases", "/s"]).is_err());
+        assert! (CommandLine::parse_from (["--remove-aliases", "--tree"]).is_err());
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_tree_switch_single_dash_fails
+    //
+    //  Verify -tree (single dash) produces error with hint.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_tree_switch_single_dash_fails () {
+        let err = CommandLine::parse_from (["-tree"]).unwrap_err();
+        assert! (err.to_string().contains ("Did you mean"));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_depth_single_dash_fails
+    //
+    //  Verify -depth=5 (single dash) produces error with hint.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_depth_single_dash_fails () {
+        let err = CommandLine::parse_from (["-depth=5"]).unwrap_err();
+        assert! (err.to_string().contains ("Did you mean"));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_depth_with_equals
+    //
+    //  Verify --Depth=5 parses depth with = separator.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_depth_with_equals () {
+        let cmd = CommandLine::parse_from (["--Tree", "--Depth=5"]).unwrap();
+        assert_eq! (cmd.max_depth, 5);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_depth_with_space
+    //
+    //  Verify --Depth 5 parses depth with space separator.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_depth_with_space () {
+        let cmd = CommandLine::parse_from (["--Tree", "--Depth", "5"]).unwrap();
+        assert_eq! (cmd.max_depth, 5);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_tree_indent_with_equals
+    //
+    //  Verify --TreeIndent=2 parses tree indent.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_tree_indent_with_equals () {
+        let cmd = CommandLine::parse_from (["--Tree", "--TreeIndent=2"]).unwrap();
+        assert_eq! (cmd.tree_indent, 2);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_tree_with_wide_fails
+    //
+    //  Verify --Tree with /W produces conflict error.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_tree_with_wide_fails () {
+        assert! (CommandLine::parse_from (["--Tree", "/w"]).is_err());
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_tree_with_bare_fails
+    //
+    //  Verify --Tree with /B produces conflict error.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_tree_with_bare_fails () {
+        assert! (CommandLine::parse_from (["--Tree", "/b"]).is_err());
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_tree_with_recurse_fails
+    //
+    //  Verify --Tree with /S produces conflict error.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_tree_with_recurse_fails () {
+        assert! (CommandLine::parse_from (["--Tree", "/s"]).is_err());
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_tree_with_owner_fails
+    //
+    //  Verify --Tree with --Owner produces conflict error.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_tree_with_owner_fails () {
+        assert! (CommandLine::parse_from (["--Tree", "--owner"]).is_err());
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_depth_without_tree_fails
+    //
+    //  Verify --Depth without --Tree produces error.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_depth_without_tree_fails () {
+        assert! (CommandLine::parse_from (["--Depth=5"]).is_err());
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_tree_indent_without_tree_fails
+    //
+    //  Verify --TreeIndent without --Tree produces error.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_tree_indent_without_tree_fails () {
+        assert! (CommandLine::parse_from (["--TreeIndent=2"]).is_err());
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_tree_indent_out_of_range_fails
+    //
+    //  Verify --TreeIndent=10 (>8) produces error.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_tree_indent_out_of_range_fails () {
+        assert! (CommandLine::parse_from (["--Tree", "--TreeIndent=10"]).is_err());
+        assert! (CommandLine::parse_from (["--Tree", "--TreeIndent=0"]).is_err());
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_depth_zero_fails
+    //
+    //  Verify --Depth=0 produces error (0 = unlimited is default, not user-set).
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_depth_zero_fails () {
+        assert! (CommandLine::parse_from (["--Tree", "--Depth=0"]).is_err());
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_depth_negative_fails
+    //
+    //  Verify --Depth=-1 produces error.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_depth_negative_fails () {
+        assert! (CommandLine::parse_from (["--Tree", "--Depth=-1"]).is_err());
+    }
 
-            if chars.peek().is_none() {
-                return Err(AppError::InvalidArg(String::new()));
-            }
-        }
 
-        // Read sort key
-        let key = chars.next().unwrap().to_ascii_lowercase();
-        let order = match key {
-            'n' => SortOrder::Name,
-            'e' => SortOrder::Extension,
-            's' => SortOrder::Size,
-            'd' => SortOrder::Date,
-            _   => return Err(AppError::InvalidArg(String::new())),
-        };
 
-        // Trailing characters are an error (e.g. /o:d- is invalid; use /o:-d)
-        if chars.next().is_some() {
-            return Err(AppError::InvalidArg(String::new()));
-        }
 
-        self.sort_order = order;
-        self.sort_preference[0] = order;
-        Ok(())
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_tree_with_owner_fails_even_with_icons
+    //
+    //  Verify --Tree + --Owner fails regardless of --Icons.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_tree_with_owner_fails_even_with_icons () {
+        assert! (CommandLine::parse_from (["--Tree", "--owner", "--icons"]).is_err());
     }
 
 
@@ -726,81 +3230,41 @@ fn order_by_handler(&mut self, arg: &str) -> Result<(), AppError> {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  attribute_handler
-    //
-    //  Handle /A attribute filter switch.
+    //  parse_tree_with_depth_and_indent_succeeds
     //
-    //  Port of: CCommandLine::AttributeHandler
+    //  Verify --Tree + --Depth + --TreeIndent all work together.
     //
     ////////////////////////////////////////////////////////////////////////////
 
-    fn attribute_handler(&mut self, arg: &str) -> Result<(), AppError> {
-        let mut chars = arg.chars().peekable();
-
-        // Skip optional colon prefix
-        if chars.peek() == Some(&':') {
-            chars.next();
-        }
+    #[test]
+    fn parse_tree_with_depth_and_indent_succeeds () {
+        let cmd = CommandLine::parse_from (["--Tree", "--Depth=3", "--TreeIndent=6"]).unwrap();
+        assert_eq! (cmd.tree, Some (true));
+        assert_eq! (cmd.max_depth, 3);
+        assert_eq! (cmd.tree_indent, 6);
+    }
 
-        // Must have at least one char
-        if chars.peek().is_none() {
-            return Err(AppError::InvalidArg(String::new()));
-        }
 
-        // Start in "required" mode
-        let mut excluding = false;
 
-        for ch in chars {
-            let ch_lower = ch.to_ascii_lowercase();
 
-            if ch_lower == '-' {
-                // Double '-' is an error
-                if excluding {
-                    return Err(AppError::InvalidArg(String::new()));
-                }
-                excluding = true;
-                continue;
-            }
 
-            // Map character to Win32 attribute flag
-            let flag = match ch_lower {
-                'd' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_DIRECTORY.0,
-                'h' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_HIDDEN.0,
-                's' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_SYSTEM.0,
-                'r' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_READONLY.0,
-                'a' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_ARCHIVE.0,
-                't' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_TEMPORARY.0,
-                'e' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_ENCRYPTED.0,
-                'c' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_COMPRESSED.0,
-                'p' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_REPARSE_POINT.0,
-                '0' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_SPARSE_FILE.0,
-                'x' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_NOT_CONTENT_INDEXED.0,
-                'i' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_INTEGRITY_STREAM.0,
-                'b' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_NO_SCRUB_DATA.0,
-                'o' => {
-                    // Cloud-only composite: OFFLINE | RECALL_ON_OPEN | RECALL_ON_DATA_ACCESS
-                    windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_OFFLINE.0
-                        | windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_RECALL_ON_OPEN.0
-                        | windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS.0
-                }
-                'l' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_UNPINNED.0,
-                'v' => windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_PINNED.0,
-                _   => 0, // Unknown chars silently ignored (matches TCDir behavior)
-            };
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  apply_config_defaults_tree_transfers
+    //
+    //  Verify config tree=true transfers to CommandLine.
+    //
+    ////////////////////////////////////////////////////////////////////////////
 
-            if flag != 0 {
-                if excluding {
-                    self.attrs_excluded |= flag;
-                } else {
-                    self.attrs_required |= flag;
-                }
-            }
+    #[test]
+    fn apply_config_defaults_tree_transfers () {
+        let mut cmd = CommandLine::default();
+        let mut config = Config::new();
+        config.tree = Some (true);
 
-            // Reset to required mode after each attribute char
-            excluding = false;
-        }
+        cmd.apply_config_defaults (&config);
 
-        Ok(())
+        assert_eq! (cmd.tree, Some (true));
     }
 
 
@@ -809,66 +3273,46 @@ fn attribute_handler(&mut self, arg: &str) -> Result<(), AppError> {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  time_field_handler
-    //
-    //  Handle /T time field switch.
+    //  apply_config_defaults_tree_with_depth_transfers
     //
-    //  Port of: CCommandLine::TimeFieldHandler
+    //  Verify config tree + depth both transfer.
     //
     ////////////////////////////////////////////////////////////////////////////
 
-    fn time_field_handler(&mut self, arg: &str) -> Result<(), AppError> {
-        let mut chars = arg.chars();
-
-        // Skip optional colon
-        let first = chars.next()
-            .ok_or_else(|| AppError::InvalidArg(String::new()))?;
+    #[test]
+    fn apply_config_defaults_tree_with_depth_transfers () {
+        let mut cmd = CommandLine::default();
+        let mut config = Config::new();
+        config.tree      = Some (true);
+        config.max_depth  = Some (5);
 
-        let field_char = if first == ':' {
-            chars.next()
-                .ok_or_else(|| AppError::InvalidArg(String::new()))?
-        } else {
-            first
-        };
+        cmd.apply_config_defaults (&config);
 
-        match field_char.to_ascii_lowercase() {
-            'c' => { self.time_field = TimeField::Creation; Ok(()) }
-            'a' => { self.time_field = TimeField::Access;   Ok(()) }
-            'w' => { self.time_field = TimeField::Written;  Ok(()) }
-            _   => Err(AppError::InvalidArg(String::new())),
-        }
+        assert_eq! (cmd.tree, Some (true));
+        assert_eq! (cmd.max_depth, 5);
     }
-}
 
 
 
 
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  default_values
+    //  apply_config_defaults_depth_without_tree_silently_ignored
     //
-    //  Verify default CommandLine values.
+    //  Verify config depth without tree is silently ignored.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn default_values() {
-        let cmd = CommandLine::default();
-        assert!(!cmd.recurse);
-        assert!(!cmd.wide_listing);
-        assert!(!cmd.bare_listing);
-        assert!(!cmd.perf_timer);
-        assert!(cmd.multi_threaded);
-        assert!(!cmd.show_help);
-        assert_eq!(cmd.sort_order, SortOrder::Default);
-        assert_eq!(cmd.sort_direction, SortDirection::Ascending);
-        assert_eq!(cmd.time_field, TimeField::Written);
-        assert_eq!(cmd.switch_prefix, '-');
+    fn apply_config_defaults_depth_without_tree_silently_ignored () {
+        let mut cmd = CommandLine::default();
+        let mut config = Config::new();
+        config.max_depth = Some (5);
+
+        cmd.apply_config_defaults (&config);
+
+        assert_eq! (cmd.max_depth, 0);
     }
 
 
@@ -877,19 +3321,21 @@ fn default_values() {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_slash_switches
+    //  cli_tree_disable_overrides_env_var_tree
     //
-    //  Verify parsing of slash-prefixed switches.
+    //  Verify CLI --Tree- overrides env var Tree.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_slash_switches() {
-        let cmd = CommandLine::parse_from(["/s", "/w", "/p"]).unwrap();
-        assert!(cmd.recurse);
-        assert!(cmd.wide_listing);
-        assert!(cmd.perf_timer);
-        assert_eq!(cmd.switch_prefix, '/');
+    fn cli_tree_disable_overrides_env_var_tree () {
+        let mut cmd = CommandLine::parse_from (["--Tree-"]).unwrap();
+        let mut config = Config::new();
+        config.tree = Some (true);
+
+        cmd.apply_config_defaults (&config);
+
+        assert_eq! (cmd.tree, Some (false));
     }
 
 
@@ -898,18 +3344,21 @@ fn parse_slash_switches() {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_dash_switches
+    //  cli_depth_overrides_env_var_depth
     //
-    //  Verify parsing of dash-prefixed switches.
+    //  Verify CLI --Depth overrides env var Depth.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_dash_switches() {
-        let cmd = CommandLine::parse_from(["-s", "-b"]).unwrap();
-        assert!(cmd.recurse);
-        assert!(cmd.bare_listing);
-        assert_eq!(cmd.switch_prefix, '-');
+    fn cli_depth_overrides_env_var_depth () {
+        let mut cmd = CommandLine::parse_from (["--Tree", "--Depth=3"]).unwrap();
+        let mut config = Config::new();
+        config.max_depth = Some (10);
+
+        cmd.apply_config_defaults (&config);
+
+        assert_eq! (cmd.max_depth, 3);
     }
 
 
@@ -918,34 +3367,38 @@ fn parse_dash_switches() {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_disable_with_trailing_dash
+    //  parse_size_default_resolves_to_bytes_without_tree
     //
-    //  Verify trailing dash disables a switch.
+    //  Verify SizeFormat::Default resolves to Bytes without tree mode.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_disable_with_trailing_dash() {
-        let cmd = CommandLine::parse_from(["/m-"]).unwrap();
-        assert!(!cmd.multi_threaded);
+    fn parse_size_default_resolves_to_bytes_without_tree () {
+        let cmd = CommandLine::default();
+        assert_eq! (cmd.resolved_size_format(), SizeFormat::Bytes);
     }
 
 
 
 
 
+    // =========================================================================
+    //  Size switch parsing tests (T014)
+    // =========================================================================
+
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_help
+    //  parse_size_bytes_without_tree
     //
-    //  Verify /? enables show_help.
+    //  Verify --Size=Bytes works without tree mode.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_help() {
-        let cmd = CommandLine::parse_from(["/?"].iter()).unwrap();
-        assert!(cmd.show_help);
+    fn parse_size_bytes_without_tree () {
+        let cmd = CommandLine::parse_from (["--Size=Bytes"]).unwrap();
+        assert_eq! (cmd.size_format, SizeFormat::Bytes);
     }
 
 
@@ -954,17 +3407,16 @@ fn parse_help() {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_sort_name
+    //  parse_size_auto_without_tree
     //
-    //  Verify /O:N sets sort order to Name.
+    //  Verify --Size=Auto works without tree mode.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_sort_name() {
-        let cmd = CommandLine::parse_from(["/on"]).unwrap();
-        assert_eq!(cmd.sort_order, SortOrder::Name);
-        assert_eq!(cmd.sort_direction, SortDirection::Ascending);
+    fn parse_size_auto_without_tree () {
+        let cmd = CommandLine::parse_from (["--Size=Auto"]).unwrap();
+        assert_eq! (cmd.size_format, SizeFormat::Auto);
     }
 
 
@@ -973,16 +3425,15 @@ fn parse_sort_name() {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_sort_with_colon
+    //  parse_size_invalid_fails
     //
-    //  Verify /O:S with colon prefix.
+    //  Verify --Size=Invalid produces error.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_sort_with_colon() {
-        let cmd = CommandLine::parse_from(["/o:s"]).unwrap();
-        assert_eq!(cmd.sort_order, SortOrder::Size);
+    fn parse_size_invalid_fails () {
+        assert! (CommandLine::parse_from (["--Size=Invalid"]).is_err());
     }
 
 
@@ -991,17 +3442,19 @@ fn parse_sort_with_colon() {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_sort_descending
+    //  parse_size_case_insensitive
     //
-    //  Verify /O:-D sets descending direction.
+    //  Verify --Size switch is case-insensitive.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_sort_descending() {
-        let cmd = CommandLine::parse_from(["/o:-d"]).unwrap();
-        assert_eq!(cmd.sort_order, SortOrder::Date);
-        assert_eq!(cmd.sort_direction, SortDirection::Descending);
+    fn parse_size_case_insensitive () {
+        let cmd = CommandLine::parse_from (["--Size=auto"]).unwrap();
+        assert_eq! (cmd.size_format, SizeFormat::Auto);
+
+        let cmd2 = CommandLine::parse_from (["--Size=BYTES"]).unwrap();
+        assert_eq! (cmd2.size_format, SizeFormat::Bytes);
     }
 
 
@@ -1010,16 +3463,20 @@ fn parse_sort_descending() {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_sort_case_insensitive
+    //  parse_size_compact
     //
-    //  Verify sort switch is case-insensitive.
+    //  Verify --Size=Compact parses and is allowed with /W and --Tree.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_sort_case_insensitive() {
-        let cmd = CommandLine::parse_from(["/O:E"]).unwrap();
-        assert_eq!(cmd.sort_order, SortOrder::Extension);
+    fn parse_size_compact () {
+        let cmd = CommandLine::parse_from (["--Size=compact", "/W"]).unwrap();
+        assert_eq! (cmd.size_format, SizeFormat::Compact);
+        assert_eq! (cmd.resolved_size_format(), SizeFormat::Compact);
+
+        let cmd2 = CommandLine::parse_from (["--Tree", "--Size=Compact"]).unwrap();
+        assert_eq! (cmd2.resolved_size_format(), SizeFormat::Compact);
     }
 
 
@@ -1028,16 +3485,17 @@ fn parse_sort_case_insensitive() {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_sort_empty_errors
+    //  parse_size_auto_with_tree
     //
-    //  Verify empty sort arg returns error.
+    //  Verify --Tree + --Size=Auto succeeds.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_sort_empty_errors() {
-        assert!(CommandLine::parse_from(["/o"]).is_err());
-        assert!(CommandLine::parse_from(["/o:"]).is_err());
+    fn parse_size_auto_with_tree () {
+        let cmd = CommandLine::parse_from (["--Tree", "--Size=Auto"]).unwrap();
+        assert_eq! (cmd.size_format, SizeFormat::Auto);
+        assert_eq! (cmd.tree, Some (true));
     }
 
 
@@ -1046,17 +3504,15 @@ fn parse_sort_empty_errors() {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_sort_trailing_chars_error
+    //  parse_size_bytes_with_tree_fails
     //
-    //  Verify trailing characters after the sort key produce an error.
-    //  e.g. /o:d- is invalid — use /o:-d for descending date.
+    //  Verify --Tree + --Size=Bytes produces conflict error.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_sort_trailing_chars_error() {
-        assert!(CommandLine::parse_from(["/o:d-"]).is_err());
-        assert!(CommandLine::parse_from(["/o:nx"]).is_err());
+    fn parse_size_bytes_with_tree_fails () {
+        assert! (CommandLine::parse_from (["--Tree", "--Size=Bytes"]).is_err());
     }
 
 
@@ -1065,16 +3521,16 @@ fn parse_sort_trailing_chars_error() {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_attrs_required
+    //  parse_size_default_resolves_to_auto_with_tree
     //
-    //  Verify /A:D sets required attribute.
+    //  Verify SizeFormat::Default resolves to Auto with tree mode.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_attrs_required() {
-        let cmd = CommandLine::parse_from(["/a:d"]).unwrap();
-        assert_ne!(cmd.attrs_required & 0x10, 0); // FILE_ATTRIBUTE_DIRECTORY = 0x10
+    fn parse_size_default_resolves_to_auto_with_tree () {
+        let cmd = CommandLine::parse_from (["--Tree"]).unwrap();
+        assert_eq! (cmd.resolved_size_format(), SizeFormat::Auto);
     }
 
 
@@ -1083,16 +3539,21 @@ fn parse_attrs_required() {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_attrs_excluded
+    //  apply_config_defaults_size_auto_transfers
     //
-    //  Verify /A:-H sets excluded attribute.
+    //  Verify config size_format=Auto transfers to CommandLine.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_attrs_excluded() {
-        let cmd = CommandLine::parse_from(["/a:-h"]).unwrap();
-        assert_ne!(cmd.attrs_excluded & 0x02, 0); // FILE_ATTRIBUTE_HIDDEN = 0x02
+    fn apply_config_defaults_size_auto_transfers () {
+        let mut cmd = CommandLine::default();
+        let mut config = Config::new();
+        config.size_format = Some (SizeFormat::Auto);
+
+        cmd.apply_config_defaults (&config);
+
+        assert_eq! (cmd.size_format, SizeFormat::Auto);
     }
 
 
@@ -1101,54 +3562,53 @@ fn parse_attrs_excluded() {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_attrs_mixed
+    //  apply_config_defaults_size_bytes_not_overridden_by_cli
     //
-    //  Verify mixed required and excluded attributes.
+    //  Verify CLI-set size_format is not overridden by config.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_attrs_mixed() {
-        // /a:h-ds → require H, exclude D, require S
-        let cmd = CommandLine::parse_from(["/a:h-ds"]).unwrap();
-        assert_ne!(cmd.attrs_required & 0x02, 0); // H required
-        assert_ne!(cmd.attrs_excluded & 0x10, 0);  // D excluded
-        assert_ne!(cmd.attrs_required & 0x04, 0); // S required
-    }
+    fn apply_config_defaults_size_bytes_not_overridden_by_cli () {
+        let mut cmd = CommandLine::parse_from (["--Size=Bytes"]).unwrap();
+        let mut config = Config::new();
+        config.size_format = Some (SizeFormat::Auto);
 
+        cmd.apply_config_defaults (&config);
 
+        assert_eq! (cmd.size_format, SizeFormat::Bytes);
+    }
 
 
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_attrs_double_dash_error
+    //  parse_long_switch_settings_double_dash
     //
-    //  Verify double dash in /A returns error.
+    //  Verify --settings enables show_settings.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_attrs_double_dash_error() {
-        assert!(CommandLine::parse_from(["/a:--d"]).is_err());
+    fn parse_long_switch_settings_double_dash () {
+        let cmd = CommandLine::parse_from (["--settings"]).unwrap();
+        assert! (cmd.show_settings);
     }
 
 
 
-
-
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_time_creation
+    //  alias_switch_rejects_settings
     //
-    //  Verify /T:C sets creation time field.
+    //  Verify alias switches cannot be combined with --settings.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_time_creation() {
-        let cmd = CommandLine::parse_from(["/t:c"]).unwrap();
-        assert_eq!(cmd.time_field, TimeField::Creation);
+    fn alias_switch_rejects_settings () {
+        let result = CommandLine::parse_from (["--set-aliases", "--settings"]);
+        assert! (result.is_err());
     }
 
 
@@ -1157,16 +3617,17 @@ fn parse_time_creation() {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_time_access
+    //  color_mode_default_is_unset
     //
-    //  Verify /T:A sets access time field.
+    //  Verify no color switch leaves the mode unset, so the console keeps
+    //  its default (always color, matching TCDir, unless NO_COLOR is set).
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_time_access() {
-        let cmd = CommandLine::parse_from(["/t:a"]).unwrap();
-        assert_eq!(cmd.time_field, TimeField::Access);
+    fn color_mode_default_is_unset () {
+        let cmd = CommandLine::parse_from (Vec::<String>::new()).unwrap();
+        assert_eq! (cmd.color_mode, None);
     }
 
 
@@ -1175,16 +3636,23 @@ fn parse_time_access() {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_time_no_colon
+    //  color_mode_parses_all_values
     //
-    //  Verify /TW without colon works.
+    //  Verify --Color accepts Auto, Always, and Never (case-insensitive)
+    //  with '=', ':', or a separate argument.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_time_no_colon() {
-        let cmd = CommandLine::parse_from(["/tw"]).unwrap();
-        assert_eq!(cmd.time_field, TimeField::Written);
+    fn color_mode_parses_all_values () {
+        let cmd = CommandLine::parse_from (["--Color=Auto"]).unwrap();
+        assert_eq! (cmd.color_mode, Some (ColorMode::Auto));
+
+        let cmd = CommandLine::parse_from (["/color:always"]).unwrap();
+        assert_eq! (cmd.color_mode, Some (ColorMode::Always));
+
+        let cmd = CommandLine::parse_from (["--Color", "NEVER"]).unwrap();
+        assert_eq! (cmd.color_mode, Some (ColorMode::Never));
     }
 
 
@@ -1193,16 +3661,15 @@ fn parse_time_no_colon() {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_long_switch_env_double_dash
+    //  color_mode_invalid_value_errors
     //
-    //  Verify --env enables show_env_help.
+    //  Verify an unknown --Color value produces an error.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_long_switch_env_double_dash() {
-        let cmd = CommandLine::parse_from(["--env"]).unwrap();
-        assert!(cmd.show_env_help);
+    fn color_mode_invalid_value_errors () {
+        assert! (CommandLine::parse_from (["--Color=Sometimes"]).is_err());
     }
 
 
@@ -1211,16 +3678,16 @@ fn parse_long_switch_env_double_dash() {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_long_switch_env_slash
+    //  no_color_is_alias_for_never
     //
-    //  Verify /env enables show_env_help.
+    //  Verify --NoColor selects ColorMode::Never.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_long_switch_env_slash() {
-        let cmd = CommandLine::parse_from(["/env"]).unwrap();
-        assert!(cmd.show_env_help);
+    fn no_color_is_alias_for_never () {
+        let cmd = CommandLine::parse_from (["/NoColor"]).unwrap();
+        assert_eq! (cmd.color_mode, Some (ColorMode::Never));
     }
 
 
@@ -1229,25 +3696,24 @@ fn parse_long_switch_env_slash() {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_long_switch_single_dash_error
+    //  config_json_value_enables_json_export
     //
-    //  Verify -env (single dash) returns error.
+    //  Verify --Config=JSON and /Config:json select the JSON export.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_long_switch_single_dash_error() {
-        // -env (single dash + long) should produce descriptive error
-        let err = CommandLine::parse_from(["-env"]).unwrap_err();
-        assert_eq!(err.to_string(), "Unknown switch: -env.  Did you mean --env?");
+    fn config_json_value_enables_json_export () {
+        let cmd = CommandLine::parse_from (["--Config=JSON"]).unwrap();
+        assert! (cmd.show_config);
+        assert! (cmd.config_json);
 
-        // Unrecognized long switch with single dash
-        let err = CommandLine::parse_from(["-notaswitch"]).unwrap_err();
-        assert_eq!(err.to_string(), "Unknown switch: -notaswitch.");
+        let cmd = CommandLine::parse_from (["/config:json"]).unwrap();
+        assert! (cmd.config_json);
 
-        // Single-dash with '=' separator — strips = for lookup
-        let err = CommandLine::parse_from(["-icons=true"]).unwrap_err();
-        assert!(err.to_string().contains("Did you mean"));
+        let cmd = CommandLine::parse_from (["--Config"]).unwrap();
+        assert! (cmd.show_config);
+        assert! (!cmd.config_json);
     }
 
 
@@ -1256,16 +3722,15 @@ fn parse_long_switch_single_dash_error() {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_long_switch_config
+    //  config_invalid_value_errors
     //
-    //  Verify /config enables show_config.
+    //  Verify an unknown --Config value produces an error.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_long_switch_config() {
-        let cmd = CommandLine::parse_from(["/config"]).unwrap();
-        assert!(cmd.show_config);
+    fn config_invalid_value_errors () {
+        assert! (CommandLine::parse_from (["--Config=xml"]).is_err());
     }
 
 
@@ -1274,16 +3739,18 @@ fn parse_long_switch_config() {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_long_switch_owner
+    //  watch_switch_enables_watch_mode
     //
-    //  Verify --owner enables show_owner.
+    //  Verify --Watch sets watch and is rejected alongside alias switches.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_long_switch_owner() {
-        let cmd = CommandLine::parse_from(["--owner"]).unwrap();
-        assert!(cmd.show_owner);
+    fn watch_switch_enables_watch_mode () {
+        let cmd = CommandLine::parse_from (["/Watch"]).unwrap();
+        assert! (cmd.watch);
+
+        assert! (CommandLine::parse_from (["--set-aliases", "--Watch"]).is_err());
     }
 
 
@@ -1292,16 +3759,23 @@ fn parse_long_switch_owner() {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_long_switch_streams
+    //  no_cloud_switch_hides_cloud_column
     //
-    //  Verify /streams enables show_streams.
+    //  Verify --NoCloud (either prefix, any case) sets no_cloud, and
+    //  --NoCloud:auto sets only no_cloud_auto.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_long_switch_streams() {
-        let cmd = CommandLine::parse_from(["/streams"]).unwrap();
-        assert!(cmd.show_streams);
+    fn no_cloud_switch_hides_cloud_column () {
+        assert! (!CommandLine::parse_from (Vec::<&str>::new()).unwrap().no_cloud);
+        assert! (CommandLine::parse_from (["--NoCloud"]).unwrap().no_cloud);
+        assert! (CommandLine::parse_from (["/nocloud"]).unwrap().no_cloud);
+
+        let auto = CommandLine::parse_from (["/NoCloud:auto"]).unwrap();
+        assert! (auto.no_cloud_auto);
+        assert! (!auto.no_cloud);
+        assert! (CommandLine::parse_from (["/NoCloud:bogus"]).is_err());
     }
 
 
@@ -1310,19 +3784,23 @@ fn parse_long_switch_streams() {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_long_switch_case_insensitive
+    //  highlight_switch_accepts_attribute_letters
     //
-    //  Verify long switches are case-insensitive.
+    //  Verify --Highlight accepts one or more /A letters (any case) and
+    //  rejects unknown letters and an empty value.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_long_switch_case_insensitive() {
-        let cmd = CommandLine::parse_from(["/ENV"]).unwrap();
-        assert!(cmd.show_env_help);
+    fn highlight_switch_accepts_attribute_letters () {
+        let cmd = CommandLine::parse_from (["/Highlight:R"]).unwrap();
+        assert_eq! (cmd.highlight_attrs, 0x01); // FILE_ATTRIBUTE_READONLY
 
-        let cmd2 = CommandLine::parse_from(["--Owner"]).unwrap();
-        assert!(cmd2.show_owner);
+        let cmd = CommandLine::parse_from (["--Highlight=rh"]).unwrap();
+        assert_eq! (cmd.highlight_attrs, 0x01 | 0x02); // READONLY | HIDDEN
+
+        assert! (CommandLine::parse_from (["--Highlight=RZ"]).is_err());
+        assert! (CommandLine::parse_from (["--Highlight="]).is_err());
     }
 
 
@@ -1331,18 +3809,16 @@ fn parse_long_switch_case_insensitive() {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_masks
+    //  delta_switch_enables_delta_column
     //
-    //  Verify positional arguments parsed as masks.
+    //  Verify --Delta sets show_delta.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_masks() {
-        let cmd = CommandLine::parse_from(["*.rs", "*.toml"]).unwrap();
-        assert_eq!(cmd.masks.len(), 2);
-        assert_eq!(cmd.masks[0], "*.rs");
-        assert_eq!(cmd.masks[1], "*.toml");
+    fn delta_switch_enables_delta_column () {
+        assert! (!CommandLine::parse_from (Vec::<&str>::new()).unwrap().show_delta);
+        assert! (CommandLine::parse_from (["/Delta"]).unwrap().show_delta);
     }
 
 
@@ -1351,18 +3827,17 @@ fn parse_masks() {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_mixed_masks_and_switches
+    //  tsv_switch_enables_tsv_listing
     //
-    //  Verify masks and switches can be intermixed.
+    //  Verify --Tsv sets tsv_listing and is rejected alongside --Tree.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_mixed_masks_and_switches() {
-        let cmd = CommandLine::parse_from(["*.rs", "/s", "*.toml", "/o:n"]).unwrap();
-        assert_eq!(cmd.masks.len(), 2);
-        assert!(cmd.recurse);
-        assert_eq!(cmd.sort_order, SortOrder::Name);
+    fn tsv_switch_enables_tsv_listing () {
+        assert! (CommandLine::parse_from (["--Tsv"]).unwrap().tsv_listing);
+        assert! (CommandLine::parse_from (["/tsv", "/s"]).unwrap().tsv_listing);
+        assert! (CommandLine::parse_from (["--Tsv", "--Tree"]).is_err());
     }
 
 
@@ -1371,25 +3846,21 @@ fn parse_mixed_masks_and_switches() {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  apply_config_defaults
+    //  jsonl_switch_enables_jsonl_listing
     //
-    //  Verify Config defaults are applied to CommandLine.
+    //  Verify --Jsonl sets jsonl_listing, works with /S, and is rejected
+    //  alongside the other listing styles.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn apply_config_defaults() {
-        let mut cmd = CommandLine::default();
-        let mut config = Config::new();
-        config.wide_listing = Some(true);
-        config.perf_timer = Some(true);
-        config.multi_threaded = Some(false);
-
-        cmd.apply_config_defaults(&config);
+    fn jsonl_switch_enables_jsonl_listing () {
+        assert! (CommandLine::parse_from (["--Jsonl"]).unwrap().jsonl_listing);
+        assert! (CommandLine::parse_from (["/jsonl", "/s", "/m"]).unwrap().jsonl_listing);
 
-        assert!(cmd.wide_listing);
-        assert!(cmd.perf_timer);
-        assert!(!cmd.multi_threaded);
+        for other in ["--Tsv", "/B", "/W", "--Brief", "--Tree", "/Fmt:{name}"] {
+            assert! (CommandLine::parse_from (["--Jsonl", other]).is_err(), "{other}");
+        }
     }
 
 
@@ -1398,19 +3869,20 @@ fn apply_config_defaults() {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  switch_prefix_tracks_last_used
+    //  exclude_dir_switch_collects_patterns
     //
-    //  Verify switch_prefix tracks the last prefix used.
+    //  Verify /XD may repeat, keeps names and relative paths verbatim,
+    //  and rejects an empty value.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn switch_prefix_tracks_last_used() {
-        let cmd = CommandLine::parse_from(["-s", "/w"]).unwrap();
-        assert_eq!(cmd.switch_prefix, '/');
+    fn exclude_dir_switch_collects_patterns () {
+        let cmd = CommandLine::parse_from (["/XD:obj", "--xd=src\\generated"]).unwrap();
+        assert_eq! (cmd.exclude_dirs, vec!["obj".to_string(), "src\\generated".to_string()]);
 
-        let cmd2 = CommandLine::parse_from(["/s", "-w"]).unwrap();
-        assert_eq!(cmd2.switch_prefix, '-');
+        assert! (CommandLine::parse_from (Vec::<&str>::new()).unwrap().exclude_dirs.is_empty());
+        assert! (CommandLine::parse_from (["/XD:"]).is_err());
     }
 
 
@@ -1419,20 +3891,17 @@ fn switch_prefix_tracks_last_used() {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  sort_preference_default
+    //  full_path_switch_enables_absolute_names
     //
-    //  Verify default sort preference chain.
+    //  Verify /FullPath (either prefix, any case) sets full_path.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn sort_preference_default() {
-        let cmd = CommandLine::default();
-        assert_eq!(cmd.sort_preference[0], SortOrder::Default);
-        assert_eq!(cmd.sort_preference[1], SortOrder::Name);
-        assert_eq!(cmd.sort_preference[2], SortOrder::Date);
-        assert_eq!(cmd.sort_preference[3], SortOrder::Extension);
-        assert_eq!(cmd.sort_preference[4], SortOrder::Size);
+    fn full_path_switch_enables_absolute_names () {
+        assert! (!CommandLine::parse_from (Vec::<&str>::new()).unwrap().full_path);
+        assert! (CommandLine::parse_from (["/FullPath"]).unwrap().full_path);
+        assert! (CommandLine::parse_from (["--fullpath"]).unwrap().full_path);
     }
 
 
@@ -1441,40 +3910,51 @@ fn sort_preference_default() {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  sort_preference_updated_on_sort
+    //  newer_older_switches_store_reference_files
     //
-    //  Verify sort preference[0] updated on sort.
+    //  Verify /Newer and /Older record their reference files (resolved
+    //  later) and reject an empty value.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn sort_preference_updated_on_sort() {
-        let cmd = CommandLine::parse_from(["/o:s"]).unwrap();
-        assert_eq!(cmd.sort_preference[0], SortOrder::Size);
-        // Rest of chain unchanged
-        assert_eq!(cmd.sort_preference[1], SortOrder::Name);
-    }
+    fn newer_older_switches_store_reference_files () {
+        let cmd = CommandLine::parse_from (["/Newer:ref.txt", "--older=old.txt"]).unwrap();
+        assert_eq! (cmd.newer_than_file.as_deref(), Some ("ref.txt"));
+        assert_eq! (cmd.older_than_file.as_deref(), Some ("old.txt"));
+        assert_eq! (cmd.newer_than, None);
 
+        assert! (CommandLine::parse_from (["/Newer:"]).is_err());
+    }
 
 
 
 
-    // =========================================================================
-    //  Tree switch parsing tests (T013)
-    // =========================================================================
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_tree_switch_double_dash
+    //  resolve_time_references_reads_last_write_time
     //
-    //  Verify --Tree enables tree mode.
+    //  Verify an existing reference file resolves to its last-write time
+    //  and a missing one is a PathNotFound error.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_tree_switch_double_dash () {
-        let cmd = CommandLine::parse_from (["--Tree"]).unwrap();
-        assert_eq! (cmd.tree, Some (true));
+    fn resolve_time_references_reads_last_write_time () {
+        use std::os::windows::fs::MetadataExt;
+
+        let temp      = TempDir::new ("newer_ref");
+        let reference = temp.path().join ("reference.txt");
+        std::fs::write (&reference, b"x").unwrap();
+        let expected = std::fs::metadata (&reference).unwrap().last_write_time();
+
+        let mut cmd = CommandLine::parse_from ([format! ("/Newer:{}", reference.display())]).unwrap();
+        cmd.resolve_time_references().unwrap();
+        assert_eq! (cmd.newer_than, Some (expected));
+
+        let mut cmd = CommandLine::parse_from (["/Older:no_such_rcdir_reference.txt"]).unwrap();
+        assert! (matches! (cmd.resolve_time_references(), Err (AppError::PathNotFound (_))));
     }
 
 
@@ -1483,16 +3963,32 @@ fn parse_tree_switch_double_dash () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_tree_switch_slash
+    //  bare_size_option_enables_sizes
     //
-    //  Verify /Tree enables tree mode.
+    //  Verify /B:size enables bare mode with sizes, /B:numbered with an
+    //  index, /B alone adds neither, and unknown /B options are rejected.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_tree_switch_slash () {
-        let cmd = CommandLine::parse_from (["/Tree"]).unwrap();
-        assert_eq! (cmd.tree, Some (true));
+    fn bare_size_option_enables_sizes () {
+        let cmd = CommandLine::parse_from (["/B:Size"]).unwrap();
+        assert! (cmd.bare_listing);
+        assert! (cmd.bare_sizes);
+
+        let cmd = CommandLine::parse_from (["/B"]).unwrap();
+        assert! (cmd.bare_listing);
+        assert! (!cmd.bare_sizes);
+
+        let cmd = CommandLine::parse_from (["/B:size", "/B-"]).unwrap();
+        assert! (!cmd.bare_listing);
+
+        let cmd = CommandLine::parse_from (["/B:Numbered"]).unwrap();
+        assert! (cmd.bare_listing);
+        assert! (cmd.bare_numbered);
+        assert! (!cmd.bare_sizes);
+
+        assert! (CommandLine::parse_from (["/B:names"]).is_err());
     }
 
 
@@ -1501,16 +3997,17 @@ fn parse_tree_switch_slash () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_tree_disable_switch_double_dash
+    //  progress_switch_enables_progress
     //
-    //  Verify --Tree- disables tree mode.
+    //  Verify /Progress sets show_progress (off by default).
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_tree_disable_switch_double_dash () {
-        let cmd = CommandLine::parse_from (["--Tree-"]).unwrap();
-        assert_eq! (cmd.tree, Some (false));
+    fn progress_switch_enables_progress () {
+        assert! (!CommandLine::parse_from (Vec::<&str>::new()).unwrap().show_progress);
+        assert! (CommandLine::parse_from (["/Progress"]).unwrap().show_progress);
+        assert! (CommandLine::parse_from (["--progress"]).unwrap().show_progress);
     }
 
 
@@ -1519,19 +4016,21 @@ fn parse_tree_disable_switch_double_dash () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_tree_switch_case_insensitive
+    //  hash_switch_selects_algorithm
     //
-    //  Verify tree switch is case-insensitive.
+    //  Verify /Hash accepts crc32 and sha256 (any case), rejects other
+    //  algorithms, and cannot be combined with /Tree.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_tree_switch_case_insensitive () {
-        let cmd = CommandLine::parse_from (["--tree"]).unwrap();
-        assert_eq! (cmd.tree, Some (true));
+    fn hash_switch_selects_algorithm () {
+        assert_eq! (CommandLine::parse_from (Vec::<&str>::new()).unwrap().hash_algorithm, None);
+        assert_eq! (CommandLine::parse_from (["/Hash:crc32"]).unwrap().hash_algorithm, Some (HashAlgorithm::Crc32));
+        assert_eq! (CommandLine::parse_from (["--hash=SHA256"]).unwrap().hash_algorithm, Some (HashAlgorithm::Sha256));
 
-        let cmd2 = CommandLine::parse_from (["--TREE"]).unwrap();
-        assert_eq! (cmd2.tree, Some (true));
+        assert! (CommandLine::parse_from (["/Hash:md5"]).is_err());
+        assert! (CommandLine::parse_from (["--Tree", "/Hash:crc32"]).is_err());
     }
 
 
@@ -1540,26 +4039,22 @@ fn parse_tree_switch_case_insensitive () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_alias_switches
+    //  collapse_empty_requires_recurse
     //
-    //  Verify parsing of --set-aliases, --get-aliases, --remove-aliases.
+    //  Verify /CollapseEmpty is accepted with /S and rejected without it
+    //  or with bare/TSV output (which have no headers to collapse).
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_alias_switches () {
-        let cmd = CommandLine::parse_from (["--set-aliases"]).unwrap();
-        assert! (cmd.set_aliases);
-        assert! (!cmd.get_aliases);
-        assert! (!cmd.remove_aliases);
-
-        let cmd = CommandLine::parse_from (["--get-aliases"]).unwrap();
-        assert! (cmd.get_aliases);
-        assert! (!cmd.set_aliases);
-
-        let cmd = CommandLine::parse_from (["--remove-aliases"]).unwrap();
-        assert! (cmd.remove_aliases);
-        assert! (!cmd.set_aliases);
+    fn collapse_empty_requires_recurse () {
+        let cmd = CommandLine::parse_from (["/S", "/CollapseEmpty"]).unwrap();
+        assert! (cmd.collapse_empty);
+        assert! (!CommandLine::parse_from (["/S"]).unwrap().collapse_empty);
+
+        assert! (CommandLine::parse_from (["/CollapseEmpty"]).is_err());
+        assert! (CommandLine::parse_from (["/S", "/B", "/CollapseEmpty"]).is_err());
+        assert! (CommandLine::parse_from (["/S", "--Tsv", "/CollapseEmpty"]).is_err());
     }
 
 
@@ -1568,21 +4063,22 @@ fn parse_alias_switches () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_whatif_with_alias_switch
+    //  config_errors_json_value_enables_error_report
     //
-    //  Verify --whatif works with --set-aliases and --remove-aliases.
+    //  Verify --Config=errors-json selects the JSON error report without
+    //  the config help or JSON config export.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_whatif_with_alias_switch () {
-        let cmd = CommandLine::parse_from (["--set-aliases", "--whatif"]).unwrap();
-        assert! (cmd.set_aliases);
-        assert! (cmd.what_if);
-
-        let cmd = CommandLine::parse_from (["--remove-aliases", "--whatif"]).unwrap();
-        assert! (cmd.remove_aliases);
-        assert! (cmd.what_if);
+    fn config_errors_json_value_enables_error_report () {
+        let cmd = CommandLine::parse_from (["/Config:errors-json"]).unwrap();
+        assert! (cmd.config_errors_json);
+        assert! (!cmd.config_json);
+        assert! (!cmd.show_config);
+
+        let cmd = CommandLine::parse_from (["--config=Errors-JSON"]).unwrap();
+        assert! (cmd.config_errors_json);
     }
 
 
@@ -1591,18 +4087,24 @@ fn parse_whatif_with_alias_switch () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  alias_switches_mutually_exclusive
+    //  diff_switches_store_directory_and_validate
     //
-    //  Verify --set-aliases, --get-aliases, --remove-aliases are mutually
-    //  exclusive.
+    //  Verify /Diff records the other directory, /DiffMeta requires /Diff,
+    //  and /Diff is rejected with recursion or tree mode.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn alias_switches_mutually_exclusive () {
-        assert! (CommandLine::parse_from (["--set-aliases", "--get-aliases"]).is_err());
-        assert! (CommandLine::parse_from (["--set-aliases", "--remove-aliases"]).is_err());
-        assert! (CommandLine::parse_from (["--get-aliases", "--remove-aliases"]).is_err());
+    fn diff_switches_store_directory_and_validate () {
+        let cmd = CommandLine::parse_from (["/Diff:..\\backup", "/DiffMeta"]).unwrap();
+        assert_eq! (cmd.diff_dir.as_deref(), Some ("..\\backup"));
+        assert! (cmd.diff_metadata);
+        assert! (cmd.diff_reference.is_none());
+
+        assert! (CommandLine::parse_from (["/Diff:"]).is_err());
+        assert! (CommandLine::parse_from (["/DiffMeta"]).is_err());
+        assert! (CommandLine::parse_from (["/Diff:other", "/S"]).is_err());
+        assert! (CommandLine::parse_from (["/Diff:other", "--Tree"]).is_err());
     }
 
 
@@ -1611,15 +4113,16 @@ fn alias_switches_mutually_exclusive () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  whatif_without_alias_switch_errors
+    //  resolve_diff_reference_missing_directory_errors
     //
-    //  Verify --whatif without an alias switch produces an error.
+    //  Verify a /Diff directory that doesn't exist is a PathNotFound error.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn whatif_without_alias_switch_errors () {
-        assert! (CommandLine::parse_from (["--whatif"]).is_err());
+    fn resolve_diff_reference_missing_directory_errors () {
+        let mut cmd = CommandLine::parse_from (["/Diff:no_such_rcdir_diff_dir"]).unwrap();
+        assert! (matches! (cmd.resolve_diff_reference(), Err (AppError::PathNotFound (_))));
     }
 
 
@@ -1628,18 +4131,20 @@ fn whatif_without_alias_switch_errors () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  alias_switches_reject_listing_switches
+    //  date_format_switch_stores_valid_pattern
     //
-    //  Verify alias switches cannot be combined with directory listing
-    //  switches.
+    //  Verify /DateFmt keeps a valid pattern (including its ':' and
+    //  spaces) and rejects an empty one.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn alias_switches_reject_listing_switches () {
-        assert! (CommandLine::parse_from (["--set-aliases", "/w"]).is_err());
-        assert! (CommandLine::parse_from (["--get-aliases", "/s"]).is_err());
-        assert! (CommandLine::parse_from (["--remove-aliases", "--tree"]).is_err());
+    fn date_format_switch_stores_valid_pattern () {
+        let cmd = CommandLine::parse_from (["/DateFmt:yyyy-MM-dd HH:mm:ss"]).unwrap();
+        assert_eq! (cmd.date_format.as_deref(), Some ("yyyy-MM-dd HH:mm:ss"));
+        assert_eq! (CommandLine::parse_from (Vec::<&str>::new()).unwrap().date_format, None);
+
+        assert! (CommandLine::parse_from (["/DateFmt:"]).is_err());
     }
 
 
@@ -1648,16 +4153,19 @@ fn alias_switches_reject_listing_switches () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_tree_switch_single_dash_fails
+    //  in_zip_rejects_recursive_modes
     //
-    //  Verify -tree (single dash) produces error with hint.
+    //  Verify /InZip is accepted alone and rejected with /S or --Tree.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_tree_switch_single_dash_fails () {
-        let err = CommandLine::parse_from (["-tree"]).unwrap_err();
-        assert! (err.to_string().contains ("Did you mean"));
+    fn in_zip_rejects_recursive_modes () {
+        assert! (CommandLine::parse_from (["/InZip", "a.zip"]).unwrap().in_zip);
+        assert! (!CommandLine::parse_from (["a.zip"]).unwrap().in_zip);
+
+        assert! (CommandLine::parse_from (["/InZip", "/S"]).is_err());
+        assert! (CommandLine::parse_from (["/InZip", "--Tree"]).is_err());
     }
 
 
@@ -1666,16 +4174,27 @@ fn parse_tree_switch_single_dash_fails () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_depth_single_dash_fails
+    //  partition_sort_switches_parse_keys
     //
-    //  Verify -depth=5 (single dash) produces error with hint.
+    //  Verify /ODir and /OFile parse a key with optional '-', reject bad
+    //  keys, and are not allowed with --Tree.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_depth_single_dash_fails () {
-        let err = CommandLine::parse_from (["-depth=5"]).unwrap_err();
-        assert! (err.to_string().contains ("Did you mean"));
+    fn partition_sort_switches_parse_keys () {
+        let cmd = CommandLine::parse_from (["/ODir:n", "/OFile:-d"]).unwrap();
+        assert_eq! (cmd.dir_sort,  Some ((SortOrder::Name, SortDirection::Ascending)));
+        assert_eq! (cmd.file_sort, Some ((SortOrder::Date, SortDirection::Descending)));
+        assert_eq! (cmd.sort_order, SortOrder::Default);
+
+        let cmd = CommandLine::parse_from (["--OFile=S"]).unwrap();
+        assert_eq! (cmd.dir_sort,  None);
+        assert_eq! (cmd.file_sort, Some ((SortOrder::Size, SortDirection::Ascending)));
+
+        assert! (CommandLine::parse_from (["/ODir:x"]).is_err());
+        assert! (CommandLine::parse_from (["/OFile:nd"]).is_err());
+        assert! (CommandLine::parse_from (["/ODir:n", "--Tree"]).is_err());
     }
 
 
@@ -1684,16 +4203,27 @@ fn parse_depth_single_dash_fails () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_depth_with_equals
+    //  recent_switch_parses_window
     //
-    //  Verify --Depth=5 parses depth with = separator.
+    //  Verify /Recent defaults to 24 hours, accepts m/h/d/w windows, and
+    //  rejects a missing unit, unknown unit, or zero count.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_depth_with_equals () {
-        let cmd = CommandLine::parse_from (["--Tree", "--Depth=5"]).unwrap();
-        assert_eq! (cmd.max_depth, 5);
+    fn recent_switch_parses_window () {
+        const HOUR: u64 = FILETIME_TICKS_PER_MINUTE * 60;
+
+        assert_eq! (CommandLine::parse_from (["/Recent"]).unwrap().recent_window, Some (RECENT_DEFAULT_WINDOW));
+        assert_eq! (CommandLine::parse_from (["/Recent:7d"]).unwrap().recent_window, Some (7 * 24 * HOUR));
+        assert_eq! (CommandLine::parse_from (["--recent=12H"]).unwrap().recent_window, Some (12 * HOUR));
+        assert_eq! (CommandLine::parse_from (["/Recent:2w", "/S"]).unwrap().recent_window, Some (14 * 24 * HOUR));
+        assert_eq! (CommandLine::parse_from (["/Recent:90m"]).unwrap().recent_window, Some (90 * FILETIME_TICKS_PER_MINUTE));
+
+        assert! (CommandLine::parse_from (["/Recent:7"]).is_err());
+        assert! (CommandLine::parse_from (["/Recent:7y"]).is_err());
+        assert! (CommandLine::parse_from (["/Recent:0d"]).is_err());
+        assert! (CommandLine::parse_from (["/Recent:d"]).is_err());
     }
 
 
@@ -1702,16 +4232,28 @@ fn parse_depth_with_equals () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_depth_with_space
+    //  recent_window_sets_newer_cutoff
     //
-    //  Verify --Depth 5 parses depth with space separator.
+    //  Verify /Recent becomes a /Newer cutoff of now minus the window, and
+    //  the stricter of /Recent and /Newer wins when both are given.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_depth_with_space () {
-        let cmd = CommandLine::parse_from (["--Tree", "--Depth", "5"]).unwrap();
-        assert_eq! (cmd.max_depth, 5);
+    fn recent_window_sets_newer_cutoff () {
+        const NOW: u64 = 133_500_000_000_000_000;
+
+        let mut cmd = CommandLine::parse_from (["/Recent"]).unwrap();
+        cmd.apply_recent_window (NOW);
+        assert_eq! (cmd.newer_than, Some (NOW - RECENT_DEFAULT_WINDOW));
+
+        cmd.newer_than = Some (NOW - 10);
+        cmd.apply_recent_window (NOW);
+        assert_eq! (cmd.newer_than, Some (NOW - 10));
+
+        let mut cmd = CommandLine::default();
+        cmd.apply_recent_window (NOW);
+        assert_eq! (cmd.newer_than, None);
     }
 
 
@@ -1720,16 +4262,20 @@ fn parse_depth_with_space () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_tree_indent_with_equals
+    //  columns_only_with_normal_listing
     //
-    //  Verify --TreeIndent=2 parses tree indent.
+    //  Verify --Columns is accepted for the normal listing and rejected
+    //  with the formats that have no column layout.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_tree_indent_with_equals () {
-        let cmd = CommandLine::parse_from (["--Tree", "--TreeIndent=2"]).unwrap();
-        assert_eq! (cmd.tree_indent, 2);
+    fn columns_only_with_normal_listing () {
+        assert! (CommandLine::parse_from (["/Columns", "/S", "--Owner"]).unwrap().columns);
+
+        for other in ["/W", "/B", "--Tsv", "--Tree"] {
+            assert! (CommandLine::parse_from (["/Columns", other]).is_err(), "{other}");
+        }
     }
 
 
@@ -1738,15 +4284,17 @@ fn parse_tree_indent_with_equals () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_tree_with_wide_fails
+    //  target_switch_enables_mount_volume_lookup
     //
-    //  Verify --Tree with /W produces conflict error.
+    //  Verify /Target is off by default and turned on by the switch.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_tree_with_wide_fails () {
-        assert! (CommandLine::parse_from (["--Tree", "/w"]).is_err());
+    fn target_switch_enables_mount_volume_lookup () {
+        assert! (!CommandLine::parse_from (["/S"]).unwrap().show_target);
+        assert! (CommandLine::parse_from (["/Target"]).unwrap().show_target);
+        assert! (CommandLine::parse_from (["--target"]).unwrap().show_target);
     }
 
 
@@ -1755,15 +4303,19 @@ fn parse_tree_with_wide_fails () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_tree_with_bare_fails
+    //  attr_full_switch
     //
-    //  Verify --Tree with /B produces conflict error.
+    //  Verify /Attr:full enables the full attribute column, any other value
+    //  is rejected, and /A attribute filters are unaffected.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_tree_with_bare_fails () {
-        assert! (CommandLine::parse_from (["--Tree", "/b"]).is_err());
+    fn attr_full_switch () {
+        assert! (CommandLine::parse_from (["/Attr:full"]).unwrap().full_attributes);
+        assert! (CommandLine::parse_from (["--attr=FULL"]).unwrap().full_attributes);
+        assert! (!CommandLine::parse_from (["/A:H"]).unwrap().full_attributes);
+        assert! (CommandLine::parse_from (["/Attr:some"]).is_err());
     }
 
 
@@ -1772,15 +4324,21 @@ fn parse_tree_with_bare_fails () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_tree_with_recurse_fails
+    //  attr_auto_switch
     //
-    //  Verify --Tree with /S produces conflict error.
+    //  Verify /Attr:auto sets the auto column, and combines with
+    //  /Attr:full when both are given.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_tree_with_recurse_fails () {
-        assert! (CommandLine::parse_from (["--Tree", "/s"]).is_err());
+    fn attr_auto_switch () {
+        let cmd = CommandLine::parse_from (["/Attr:auto"]).unwrap();
+        assert! (cmd.attribute_column().auto);
+        assert! (!cmd.attribute_column().full);
+
+        let cmd = CommandLine::parse_from (["/Attr:full", "--attr=AUTO"]).unwrap();
+        assert! (cmd.attribute_column().auto && cmd.attribute_column().full);
     }
 
 
@@ -1789,15 +4347,20 @@ fn parse_tree_with_recurse_fails () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_tree_with_owner_fails
+    //  attr_style_switch
     //
-    //  Verify --Tree with --Owner produces conflict error.
+    //  Verify /AttrStyle selects the letter scheme, defaulting to dir.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_tree_with_owner_fails () {
-        assert! (CommandLine::parse_from (["--Tree", "--owner"]).is_err());
+    fn attr_style_switch () {
+        use crate::file_info::AttributeStyle;
+
+        assert_eq! (CommandLine::parse_from (["/S"]).unwrap().attr_style, AttributeStyle::Dir);
+        assert_eq! (CommandLine::parse_from (["/AttrStyle:ls"]).unwrap().attr_style, AttributeStyle::Ls);
+        assert_eq! (CommandLine::parse_from (["--attrstyle=DIR"]).unwrap().attr_style, AttributeStyle::Dir);
+        assert! (CommandLine::parse_from (["/AttrStyle:unix"]).is_err());
     }
 
 
@@ -1806,15 +4369,21 @@ fn parse_tree_with_owner_fails () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_depth_without_tree_fails
+    //  group_ext_only_with_normal_listing
     //
-    //  Verify --Depth without --Tree produces error.
+    //  Verify /Group:ext is accepted for the normal listing, rejects other
+    //  values, and is refused with the formats that have no sections.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_depth_without_tree_fails () {
-        assert! (CommandLine::parse_from (["--Depth=5"]).is_err());
+    fn group_ext_only_with_normal_listing () {
+        assert! (CommandLine::parse_from (["/Group:ext", "/S"]).unwrap().group_by_ext);
+        assert! (CommandLine::parse_from (["/Group:size"]).is_err());
+
+        for other in ["/W", "/B", "--Tsv", "--Tree"] {
+            assert! (CommandLine::parse_from (["/Group:ext", other]).is_err(), "{other}");
+        }
     }
 
 
@@ -1823,15 +4392,31 @@ fn parse_depth_without_tree_fails () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_tree_indent_without_tree_fails
+    //  apply_config_defaults_sort_and_time
     //
-    //  Verify --TreeIndent without --Tree produces error.
+    //  Verify env Sort=/Time= defaults apply when the command line leaves
+    //  sort and time field unset, and an explicit /O wins.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_tree_indent_without_tree_fails () {
-        assert! (CommandLine::parse_from (["--TreeIndent=2"]).is_err());
+    fn apply_config_defaults_sort_and_time () {
+        let mut config = Config::new();
+        config.sort_default       = Some ((SortOrder::Date, SortDirection::Descending));
+        config.time_field_default = Some (TimeField::Creation);
+
+        let mut cmd = CommandLine::default();
+        cmd.apply_config_defaults (&config);
+        assert_eq! (cmd.sort_order, SortOrder::Date);
+        assert_eq! (cmd.sort_preference[0], SortOrder::Date);
+        assert_eq! (cmd.sort_direction, SortDirection::Descending);
+        assert_eq! (cmd.time_field, TimeField::Creation);
+
+        let mut cmd = CommandLine::parse_from (["/O:S", "/T:A"]).unwrap();
+        cmd.apply_config_defaults (&config);
+        assert_eq! (cmd.sort_order, SortOrder::Size);
+        assert_eq! (cmd.sort_direction, SortDirection::Ascending);
+        assert_eq! (cmd.time_field, TimeField::Access);
     }
 
 
@@ -1840,16 +4425,41 @@ fn parse_tree_indent_without_tree_fails () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_tree_indent_out_of_range_fails
+    //  env_sort_default_reaches_command_line
     //
-    //  Verify --TreeIndent=10 (>8) produces error.
+    //  Verify the RCDIR Sort= entry flows through config parsing and
+    //  apply_config_defaults: Sort=-d is date-descending (newest first),
+    //  and Sort=d is date-ascending, as /O:d is.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_tree_indent_out_of_range_fails () {
-        assert! (CommandLine::parse_from (["--Tree", "--TreeIndent=10"]).is_err());
-        assert! (CommandLine::parse_from (["--Tree", "--TreeIndent=0"]).is_err());
+    fn env_sort_default_reaches_command_line () {
+        use crate::config::RCDIR_ENV_VAR_NAME;
+        use crate::environment_provider::MockEnvironmentProvider;
+
+        let resolve = |rcdir: &str| {
+            let mut env = MockEnvironmentProvider::new();
+            env.set (RCDIR_ENV_VAR_NAME, rcdir);
+
+            let mut config = Config::new();
+            config.initialize_with_provider (crate::color::FC_LIGHT_GREY, &env);
+            assert! (!config.last_parse_result.has_issues(), "{rcdir}");
+
+            let mut cmd = CommandLine::parse_from (Vec::<&str>::new()).unwrap();
+            cmd.apply_config_defaults (&config);
+            cmd
+        };
+
+        let cmd = resolve ("Sort=-d");
+        assert_eq! (cmd.sort_order, SortOrder::Date);
+        assert_eq! (cmd.sort_preference[0], SortOrder::Date);
+        assert_eq! (cmd.sort_direction, SortDirection::Descending);
+
+        let cmd = resolve ("Sort=d;Time=c");
+        assert_eq! (cmd.sort_order, SortOrder::Date);
+        assert_eq! (cmd.sort_direction, SortDirection::Ascending);
+        assert_eq! (cmd.time_field, TimeField::Creation);
     }
 
 
@@ -1858,15 +4468,27 @@ fn parse_tree_indent_out_of_range_fails () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_depth_zero_fails
+    //  has_streams_implies_stream_enumeration
     //
-    //  Verify --Depth=0 produces error (0 = unlimited is default, not user-set).
+    //  Verify /HasStreams turns on stream enumeration and keeps it on even
+    //  when the config default disables streams.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_depth_zero_fails () {
-        assert! (CommandLine::parse_from (["--Tree", "--Depth=0"]).is_err());
+    fn has_streams_implies_stream_enumeration () {
+        let cmd = CommandLine::parse_from (["/S"]).unwrap();
+        assert! (!cmd.has_streams);
+        assert! (!cmd.show_streams);
+
+        let mut cmd = CommandLine::parse_from (["/HasStreams", "/S"]).unwrap();
+        assert! (cmd.has_streams);
+        assert! (cmd.show_streams);
+
+        let mut config = Config::new();
+        config.show_streams = Some (false);
+        cmd.apply_config_defaults (&config);
+        assert! (cmd.show_streams);
     }
 
 
@@ -1875,15 +4497,21 @@ fn parse_depth_zero_fails () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_depth_negative_fails
+    //  wrap_names_only_with_normal_listing
     //
-    //  Verify --Depth=-1 produces error.
+    //  Verify /WrapNames is accepted for the normal listing and refused
+    //  with the formats that have no name column.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_depth_negative_fails () {
-        assert! (CommandLine::parse_from (["--Tree", "--Depth=-1"]).is_err());
+    fn wrap_names_only_with_normal_listing () {
+        assert! (!CommandLine::parse_from (["/S"]).unwrap().wrap_names);
+        assert! (CommandLine::parse_from (["/WrapNames", "/S"]).unwrap().wrap_names);
+
+        for other in ["/W", "/B", "--Tsv", "--Tree"] {
+            assert! (CommandLine::parse_from (["/WrapNames", other]).is_err(), "{other}");
+        }
     }
 
 
@@ -1892,15 +4520,17 @@ fn parse_depth_negative_fails () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_tree_with_owner_fails_even_with_icons
+    //  quiet_switch_parses
     //
-    //  Verify --Tree + --Owner fails regardless of --Icons.
+    //  Verify /Quiet and --quiet set quiet, and it is off by default.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_tree_with_owner_fails_even_with_icons () {
-        assert! (CommandLine::parse_from (["--Tree", "--owner", "--icons"]).is_err());
+    fn quiet_switch_parses () {
+        assert! (!CommandLine::parse_from (["/S"]).unwrap().quiet);
+        assert! (CommandLine::parse_from (["/Quiet"]).unwrap().quiet);
+        assert! (CommandLine::parse_from (["--quiet"]).unwrap().quiet);
     }
 
 
@@ -1909,18 +4539,18 @@ fn parse_tree_with_owner_fails_even_with_icons () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_tree_with_depth_and_indent_succeeds
+    //  up_requires_positive_level_count
     //
-    //  Verify --Tree + --Depth + --TreeIndent all work together.
+    //  Verify /Up:N stores the ancestor count and rejects 0 or non-numbers.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_tree_with_depth_and_indent_succeeds () {
-        let cmd = CommandLine::parse_from (["--Tree", "--Depth=3", "--TreeIndent=6"]).unwrap();
-        assert_eq! (cmd.tree, Some (true));
-        assert_eq! (cmd.max_depth, 3);
-        assert_eq! (cmd.tree_indent, 6);
+    fn up_requires_positive_level_count () {
+        assert_eq! (CommandLine::parse_from (["/S"]).unwrap().up_levels, None);
+        assert_eq! (CommandLine::parse_from (["/Up:2"]).unwrap().up_levels, Some (2));
+        assert! (CommandLine::parse_from (["/Up:0"]).is_err());
+        assert! (CommandLine::parse_from (["/Up:x"]).is_err());
     }
 
 
@@ -1929,21 +4559,27 @@ fn parse_tree_with_depth_and_indent_succeeds () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  apply_config_defaults_tree_transfers
+    //  xsz_parses_size_bands
     //
-    //  Verify config tree=true transfers to CommandLine.
+    //  Verify /XSZ accepts byte and K/M/G bounds, may repeat, and rejects
+    //  malformed or inverted bands.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn apply_config_defaults_tree_transfers () {
-        let mut cmd = CommandLine::default();
-        let mut config = Config::new();
-        config.tree = Some (true);
-
-        cmd.apply_config_defaults (&config);
+    fn xsz_parses_size_bands () {
+        let cmd = CommandLine::parse_from (["/XSZ:0-0", "/XSZ:1k-2M"]).unwrap();
+        assert_eq! (cmd.exclude_sizes, [
+            SizeBand { min: 0,    max: 0 },
+            SizeBand { min: 1024, max: 2 * 1024 * 1024 },
+        ]);
+
+        for bad in ["/XSZ:10", "/XSZ:5-1", "/XSZ:a-b", "/XSZ:1T-2T", "/XSZ:"] {
+            assert! (CommandLine::parse_from ([bad]).is_err(), "{bad}");
+        }
 
-        assert_eq! (cmd.tree, Some (true));
+        assert! (SizeBand { min: 0, max: 0 }.contains (0));
+        assert! (!SizeBand { min: 0, max: 0 }.contains (1));
     }
 
 
@@ -1952,23 +4588,24 @@ fn apply_config_defaults_tree_transfers () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  apply_config_defaults_tree_with_depth_transfers
+    //  parse_owner_sid
     //
-    //  Verify config tree + depth both transfer.
+    //  Verify --Owner:sid enables the owner column with raw SIDs, and
+    //  rejects any other value.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn apply_config_defaults_tree_with_depth_transfers () {
-        let mut cmd = CommandLine::default();
-        let mut config = Config::new();
-        config.tree      = Some (true);
-        config.max_depth  = Some (5);
+    fn parse_owner_sid () {
+        let cmd = CommandLine::parse_from (["/Owner:sid"]).unwrap();
+        assert! (cmd.show_owner);
+        assert! (cmd.owner_sid);
 
-        cmd.apply_config_defaults (&config);
+        let cmd = CommandLine::parse_from (["/Owner"]).unwrap();
+        assert! (cmd.show_owner);
+        assert! (!cmd.owner_sid);
 
-        assert_eq! (cmd.tree, Some (true));
-        assert_eq! (cmd.max_depth, 5);
+        assert! (CommandLine::parse_from (["/Owner:name"]).is_err());
     }
 
 
@@ -1977,21 +4614,28 @@ fn apply_config_defaults_tree_with_depth_transfers () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  apply_config_defaults_depth_without_tree_silently_ignored
+    //  base_path_from_switch_or_config
     //
-    //  Verify config depth without tree is silently ignored.
+    //  Verify /Base:dir sets the base directory, DefaultPath= supplies it
+    //  when no /Base is given, and an explicit /Base wins.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn apply_config_defaults_depth_without_tree_silently_ignored () {
-        let mut cmd = CommandLine::default();
+    fn base_path_from_switch_or_config () {
+        let cmd = CommandLine::parse_from (["/Base:C:\\Projects"]).unwrap();
+        assert_eq! (cmd.base_path.as_deref(), Some ("C:\\Projects"));
+
         let mut config = Config::new();
-        config.max_depth = Some (5);
+        config.default_path = Some ("D:\\Work".to_string());
 
+        let mut cmd = CommandLine::default();
         cmd.apply_config_defaults (&config);
+        assert_eq! (cmd.base_path.as_deref(), Some ("D:\\Work"));
 
-        assert_eq! (cmd.max_depth, 0);
+        let mut cmd = CommandLine::parse_from (["/Base:C:\\Projects"]).unwrap();
+        cmd.apply_config_defaults (&config);
+        assert_eq! (cmd.base_path.as_deref(), Some ("C:\\Projects"));
     }
 
 
@@ -2000,21 +4644,19 @@ fn apply_config_defaults_depth_without_tree_silently_ignored () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  cli_tree_disable_overrides_env_var_tree
+    //  parse_pct_rejects_non_normal_listings
     //
-    //  Verify CLI --Tree- overrides env var Tree.
+    //  Verify --Pct parses alone and is rejected with /W, /B, --Tsv, --Tree.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn cli_tree_disable_overrides_env_var_tree () {
-        let mut cmd = CommandLine::parse_from (["--Tree-"]).unwrap();
-        let mut config = Config::new();
-        config.tree = Some (true);
-
-        cmd.apply_config_defaults (&config);
+    fn parse_pct_rejects_non_normal_listings () {
+        assert! (CommandLine::parse_from (["/Pct"]).unwrap().show_percent);
 
-        assert_eq! (cmd.tree, Some (false));
+        for other in ["/W", "/B", "/Tsv", "/Tree"] {
+            assert! (CommandLine::parse_from (["/Pct", other]).is_err(), "{}", other);
+        }
     }
 
 
@@ -2023,21 +4665,29 @@ fn cli_tree_disable_overrides_env_var_tree () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  cli_depth_overrides_env_var_depth
+    //  tree_file_policy_shows_targets_and_leaves
     //
-    //  Verify CLI --Depth overrides env var Depth.
+    //  Verify /DirsFirst:tree lists files in the targeted directory and in
+    //  leaf directories, collapses intermediate ones, and that plain --Tree
+    //  lists files everywhere.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn cli_depth_overrides_env_var_depth () {
-        let mut cmd = CommandLine::parse_from (["--Tree", "--Depth=3"]).unwrap();
-        let mut config = Config::new();
-        config.max_depth = Some (10);
+    fn tree_file_policy_shows_targets_and_leaves () {
+        let cmd = CommandLine::parse_from (["/DirsFirst:tree"]).unwrap();
+        assert_eq! (cmd.tree_files, TreeFilePolicy::TargetsAndLeaves);
+        assert_eq! (cmd.tree, Some (true));
 
-        cmd.apply_config_defaults (&config);
+        let policy = cmd.tree_files;
+        assert! (policy.shows_files (true, true),    "target with subdirectories");
+        assert! (policy.shows_files (false, false),  "leaf directory");
+        assert! (!policy.shows_files (false, true),  "intermediate directory collapses");
 
-        assert_eq! (cmd.max_depth, 3);
+        assert! (TreeFilePolicy::All.shows_files (false, true));
+
+        assert! (CommandLine::parse_from (["/DirsFirst"]).is_err());
+        assert! (CommandLine::parse_from (["/DirsFirst:flat"]).is_err());
     }
 
 
@@ -2046,38 +4696,48 @@ fn cli_depth_overrides_env_var_depth () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_size_default_resolves_to_bytes_without_tree
+    //  parse_brief_rejects_other_formats
     //
-    //  Verify SizeFormat::Default resolves to Bytes without tree mode.
+    //  Verify --Brief parses with /S and is rejected with the other listing
+    //  formats.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_size_default_resolves_to_bytes_without_tree () {
-        let cmd = CommandLine::default();
-        assert_eq! (cmd.resolved_size_format(), SizeFormat::Bytes);
-    }
+    fn parse_brief_rejects_other_formats () {
+        let cmd = CommandLine::parse_from (["/S", "/Brief"]).unwrap();
+        assert! (cmd.brief);
+        assert! (cmd.recurse);
 
+        for other in ["/W", "/B", "/Tsv", "/Tree"] {
+            assert! (CommandLine::parse_from (["/Brief", other]).is_err(), "{}", other);
+        }
+    }
 
 
 
 
-    // =========================================================================
-    //  Size switch parsing tests (T014)
-    // =========================================================================
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_size_bytes_without_tree
+    //  parse_icons_force
     //
-    //  Verify --Size=Bytes works without tree mode.
+    //  Verify /Icons:force turns icons on and marks them forced, and that
+    //  any other inline value is rejected.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_size_bytes_without_tree () {
-        let cmd = CommandLine::parse_from (["--Size=Bytes"]).unwrap();
-        assert_eq! (cmd.size_format, SizeFormat::Bytes);
+    fn parse_icons_force () {
+        let cmd = CommandLine::parse_from (["/Icons:force"]).unwrap();
+        assert_eq! (cmd.icons, Some (true));
+        assert! (cmd.icons_forced);
+
+        let cmd = CommandLine::parse_from (["/Icons"]).unwrap();
+        assert_eq! (cmd.icons, Some (true));
+        assert! (!cmd.icons_forced);
+
+        assert! (CommandLine::parse_from (["/Icons:bogus"]).is_err());
     }
 
 
@@ -2086,16 +4746,22 @@ fn parse_size_bytes_without_tree () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_size_auto_without_tree
+    //  parse_verbosity_levels
     //
-    //  Verify --Size=Auto works without tree mode.
+    //  Verify /V:0, /V:1 and /V:2 select their summary verbosity, the
+    //  default is Standard, and other levels are rejected.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_size_auto_without_tree () {
-        let cmd = CommandLine::parse_from (["--Size=Auto"]).unwrap();
-        assert_eq! (cmd.size_format, SizeFormat::Auto);
+    fn parse_verbosity_levels () {
+        assert_eq! (CommandLine::parse_from (std::iter::empty::<&str>()).unwrap().verbosity, SummaryVerbosity::Standard);
+        assert_eq! (CommandLine::parse_from (["/V:0"]).unwrap().verbosity, SummaryVerbosity::TotalsOnly);
+        assert_eq! (CommandLine::parse_from (["/V:1"]).unwrap().verbosity, SummaryVerbosity::Standard);
+        assert_eq! (CommandLine::parse_from (["-V:2"]).unwrap().verbosity, SummaryVerbosity::Detailed);
+
+        assert! (CommandLine::parse_from (["/V:3"]).is_err());
+        assert! (CommandLine::parse_from (["/V"]).is_err());
     }
 
 
@@ -2104,15 +4770,27 @@ fn parse_size_auto_without_tree () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_size_invalid_fails
+    //  parse_sort_title
     //
-    //  Verify --Size=Invalid produces error.
+    //  Verify /O:title and /O:-title select the title sort, as does
+    //  /OFile:title.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_size_invalid_fails () {
-        assert! (CommandLine::parse_from (["--Size=Invalid"]).is_err());
+    fn parse_sort_title () {
+        let cmd = CommandLine::parse_from (["/O:Title"]).unwrap();
+        assert_eq! (cmd.sort_order, SortOrder::Title);
+        assert_eq! (cmd.sort_preference[0], SortOrder::Title);
+
+        let cmd = CommandLine::parse_from (["/O:-title"]).unwrap();
+        assert_eq! (cmd.sort_order, SortOrder::Title);
+        assert_eq! (cmd.sort_direction, SortDirection::Descending);
+
+        let cmd = CommandLine::parse_from (["/OFile:title"]).unwrap();
+        assert_eq! (cmd.file_sort, Some ((SortOrder::Title, SortDirection::Ascending)));
+
+        assert! (CommandLine::parse_from (["/O:titles"]).is_err());
     }
 
 
@@ -2121,19 +4799,22 @@ fn parse_size_invalid_fails () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_size_case_insensitive
+    //  parse_name_column
     //
-    //  Verify --Size switch is case-insensitive.
+    //  Verify /NameCol selects auto alignment, /NameCol:N a fixed column,
+    //  and that zero, non-numbers and /Tree are rejected.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_size_case_insensitive () {
-        let cmd = CommandLine::parse_from (["--Size=auto"]).unwrap();
-        assert_eq! (cmd.size_format, SizeFormat::Auto);
-
-        let cmd2 = CommandLine::parse_from (["--Size=BYTES"]).unwrap();
-        assert_eq! (cmd2.size_format, SizeFormat::Bytes);
+    fn parse_name_column () {
+        assert_eq! (CommandLine::parse_from (Vec::<&str>::new()).unwrap().name_column, None);
+        assert_eq! (CommandLine::parse_from (["/NameCol"]).unwrap().name_column, Some (NameColumn::Auto));
+        assert_eq! (CommandLine::parse_from (["/NameCol:48"]).unwrap().name_column, Some (NameColumn::Fixed (48)));
+
+        assert! (CommandLine::parse_from (["/NameCol:0"]).is_err());
+        assert! (CommandLine::parse_from (["/NameCol:wide"]).is_err());
+        assert! (CommandLine::parse_from (["/NameCol", "/Tree"]).is_err());
     }
 
 
@@ -2142,17 +4823,23 @@ fn parse_size_case_insensitive () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_size_auto_with_tree
+    //  parse_entry_limit
     //
-    //  Verify --Tree + --Size=Auto succeeds.
+    //  Verify /Limit:N takes a positive count, and is rejected with a zero
+    //  or non-numeric value and outside the normal listing.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_size_auto_with_tree () {
-        let cmd = CommandLine::parse_from (["--Tree", "--Size=Auto"]).unwrap();
-        assert_eq! (cmd.size_format, SizeFormat::Auto);
-        assert_eq! (cmd.tree, Some (true));
+    fn parse_entry_limit () {
+        assert_eq! (CommandLine::parse_from (["/Limit:20"]).unwrap().entry_limit, Some (20));
+        assert_eq! (CommandLine::parse_from (["--Limit=5", "/S"]).unwrap().entry_limit, Some (5));
+        assert_eq! (CommandLine::parse_from (["/S"]).unwrap().entry_limit, None);
+
+        assert! (CommandLine::parse_from (["/Limit:0"]).is_err());
+        assert! (CommandLine::parse_from (["/Limit:ten"]).is_err());
+        assert! (CommandLine::parse_from (["/Limit:5", "/W"]).is_err());
+        assert! (CommandLine::parse_from (["/Limit:5", "--Tree"]).is_err());
     }
 
 
@@ -2161,15 +4848,24 @@ fn parse_size_auto_with_tree () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_size_bytes_with_tree_fails
+    //  parse_tree_dirs
     //
-    //  Verify --Tree + --Size=Bytes produces conflict error.
+    //  Verify /Tree:dirs turns the tree on with files suppressed in every
+    //  directory, and that other values and /Tree- are rejected.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_size_bytes_with_tree_fails () {
-        assert! (CommandLine::parse_from (["--Tree", "--Size=Bytes"]).is_err());
+    fn parse_tree_dirs () {
+        let cmd = CommandLine::parse_from (["/Tree:dirs"]).unwrap();
+        assert_eq! (cmd.tree, Some (true));
+        assert_eq! (cmd.tree_files, TreeFilePolicy::DirectoriesOnly);
+        assert! (!cmd.tree_files.shows_files (true, false));
+        assert! (!cmd.tree_files.shows_files (false, true));
+
+        assert_eq! (CommandLine::parse_from (["/Tree"]).unwrap().tree_files, TreeFilePolicy::All);
+        assert! (CommandLine::parse_from (["/Tree:files"]).is_err());
+        assert! (CommandLine::parse_from (["/Tree:dirs", "/Tree-"]).is_err());
     }
 
 
@@ -2178,16 +4874,27 @@ fn parse_size_bytes_with_tree_fails () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_size_default_resolves_to_auto_with_tree
+    //  expand_paths_covers_masks_and_path_switches
     //
-    //  Verify SizeFormat::Default resolves to Auto with tree mode.
+    //  Verify expand_paths rewrites masks and /Base through the provider
+    //  and fails on an undefined variable.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_size_default_resolves_to_auto_with_tree () {
-        let cmd = CommandLine::parse_from (["--Tree"]).unwrap();
-        assert_eq! (cmd.resolved_size_format(), SizeFormat::Auto);
+    fn expand_paths_covers_masks_and_path_switches () {
+        use crate::environment_provider::MockEnvironmentProvider;
+
+        let mut env = MockEnvironmentProvider::new();
+        env.set ("TEMP", "C:\\Temp");
+
+        let mut cmd = CommandLine::parse_from (["%TEMP%\\foo\\*.log", "/Base:%TEMP%"]).unwrap();
+        cmd.expand_paths (&env).unwrap();
+        assert_eq! (cmd.masks, vec![OsString::from ("C:\\Temp\\foo\\*.log")]);
+        assert_eq! (cmd.base_path.as_deref(), Some ("C:\\Temp"));
+
+        let mut cmd = CommandLine::parse_from (["%MISSING%\\*"]).unwrap();
+        assert! (matches! (cmd.expand_paths (&env), Err (AppError::InvalidArg (_))));
     }
 
 
@@ -2196,21 +4903,27 @@ fn parse_size_default_resolves_to_auto_with_tree () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  apply_config_defaults_size_auto_transfers
+    //  parse_stale_window
     //
-    //  Verify config size_format=Auto transfers to CommandLine.
+    //  Verify /Stale defaults to 90 days, accepts a window, resolves to a
+    //  last-access cutoff, and rejects a malformed window.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn apply_config_defaults_size_auto_transfers () {
-        let mut cmd = CommandLine::default();
-        let mut config = Config::new();
-        config.size_format = Some (SizeFormat::Auto);
+    fn parse_stale_window () {
+        const NOW: u64 = 133_500_000_000_000_000;
+        let day = FILETIME_TICKS_PER_MINUTE * 60 * 24;
 
-        cmd.apply_config_defaults (&config);
+        assert_eq! (CommandLine::parse_from (["/Stale"]).unwrap().stale_window, Some (STALE_DEFAULT_WINDOW));
 
-        assert_eq! (cmd.size_format, SizeFormat::Auto);
+        let mut cmd = CommandLine::parse_from (["/Stale:2w"]).unwrap();
+        assert_eq! (cmd.stale_window, Some (14 * day));
+        cmd.apply_stale_window (NOW);
+        assert_eq! (cmd.stale_before, Some (NOW - 14 * day));
+        assert_eq! (cmd.newer_than, None);
+
+        assert! (CommandLine::parse_from (["/Stale:soon"]).is_err());
     }
 
 
@@ -2219,52 +4932,78 @@ fn apply_config_defaults_size_auto_transfers () {
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  apply_config_defaults_size_bytes_not_overridden_by_cli
+    //  fmt_switch_parses_template
     //
-    //  Verify CLI-set size_format is not overridden by config.
+    //  Verify /Fmt stores the parsed template, rejects unknown placeholders,
+    //  and can't be combined with the other listing formats.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn apply_config_defaults_size_bytes_not_overridden_by_cli () {
-        let mut cmd = CommandLine::parse_from (["--Size=Bytes"]).unwrap();
-        let mut config = Config::new();
-        config.size_format = Some (SizeFormat::Auto);
+    fn fmt_switch_parses_template () {
+        let cmd = CommandLine::parse_from (["/Fmt:{size:>12} {name}"]).unwrap();
+        assert_eq! (cmd.format_template, Some (FormatTemplate::parse ("{size:>12} {name}").unwrap()));
+        assert_eq! (CommandLine::parse_from (Vec::<&str>::new()).unwrap().format_template, None);
+
+        assert! (CommandLine::parse_from (["--Fmt={name} {color}"]).is_err());
+        assert! (CommandLine::parse_from (["/Fmt:{name}", "/B"]).is_err());
+        assert! (CommandLine::parse_from (["/Fmt:{name}", "--Tsv"]).is_err());
+    }
 
-        cmd.apply_config_defaults (&config);
 
-        assert_eq! (cmd.size_format, SizeFormat::Bytes);
-    }
 
 
 
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  parse_long_switch_settings_double_dash
+    //  apply_config_defaults_mode
     //
-    //  Verify --settings enables show_settings.
+    //  Verify Mode= sets the listing style over the W/B/Tree defaults, and
+    //  is ignored when the command line picks a style.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn parse_long_switch_settings_double_dash () {
-        let cmd = CommandLine::parse_from (["--settings"]).unwrap();
-        assert! (cmd.show_settings);
+    fn apply_config_defaults_mode () {
+        let mut config = Config::new();
+        config.wide_listing = Some (true);
+        config.output_mode  = Some (OutputMode::Tree);
+
+        let mut cmd = CommandLine::default();
+        cmd.apply_config_defaults (&config);
+        assert_eq! (cmd.tree, Some (true));
+        assert! (!cmd.wide_listing);
+
+        let mut cmd = CommandLine::parse_from (["/B"]).unwrap();
+        cmd.apply_config_defaults (&config);
+        assert! (cmd.bare_listing);
+        assert_eq! (cmd.tree, None);
+
+        config.output_mode = Some (OutputMode::Normal);
+        let mut cmd = CommandLine::default();
+        cmd.apply_config_defaults (&config);
+        assert_eq! (cmd.tree, Some (false));
+        assert! (!cmd.wide_listing && !cmd.bare_listing);
     }
 
 
 
+
+
     ////////////////////////////////////////////////////////////////////////////
     //
-    //  alias_switch_rejects_settings
+    //  drive_pct_requires_recurse
     //
-    //  Verify alias switches cannot be combined with --settings.
+    //  Verify --DrivePct is accepted with /S and rejected without it or
+    //  with a machine-readable output style.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     #[test]
-    fn alias_switch_rejects_settings () {
-        let result = CommandLine::parse_from (["--set-aliases", "--settings"]);
-        assert! (result.is_err());
+    fn drive_pct_requires_recurse () {
+        assert! (CommandLine::parse_from (["/S", "/DrivePct"]).unwrap().drive_pct);
+        assert! (CommandLine::parse_from (["/DrivePct"]).is_err());
+        assert! (CommandLine::parse_from (["/S", "/B", "/DrivePct"]).is_err());
+        assert! (CommandLine::parse_from (["/S", "--Tsv", "/DrivePct"]).is_err());
     }
 }
\ No newline at end of file
@@ -5,14 +5,16 @@
 // Extends impl Config with all methods that parse the RCDIR environment
 // variable and apply color, icon, and switch overrides.
 
+use crate::age_tier::AgeTierThresholds;
 use crate::color::parse_color_name;
-use crate::command_line::SizeFormat;
+use crate::command_line::{CommandLine, OutputMode, SizeFormat, TimeField};
 use crate::environment_provider::EnvironmentProvider;
+use crate::file_comparator::GROUP_SORT_CATEGORIES;
 use crate::file_info::FILE_ATTRIBUTE_MAP;
 
 use super::{
     Attribute, AttributeSource, Config, ErrorInfo, FileAttrStyle,
-    RCDIR_ENV_VAR_NAME,
+    active_env_var_name,
 };
 
 
@@ -43,8 +45,9 @@ impl Config {
     //
     //  apply_user_color_overrides
     //
-    //  Parse the RCDIR environment variable for user color overrides and
-    //  switch defaults.
+    //  Parse the RCDIR environment variable (or the RCDIR_PROFILE-selected
+    //  RCDIR_<profile> variable) for user color overrides and switch
+    //  defaults.
     //
     //  Port of: CConfig::ApplyUserColorOverrides
     //
@@ -54,7 +57,7 @@ pub(super) fn apply_user_color_overrides(&mut self, provider: &dyn EnvironmentPr
         self.last_parse_result.errors.clear();
         self.current_source = AttributeSource::Environment;
 
-        let env_value = match provider.get_env_var (RCDIR_ENV_VAR_NAME) {
+        let env_value = match provider.get_env_var (&active_env_var_name (provider)) {
             Some (v) => v,
             None => return,
         };
@@ -404,8 +407,12 @@ fn process_switch_override(&mut self, entry: &str) {
     //  try_process_parameterized_switch
     //
     //  Handle parameterized env var switches: Depth=N, TreeIndent=N,
-    //  Size=Auto|Bytes.  Returns true if handled, false if not a
-    //  parameterized switch (caller continues with color parsing).
+    //  Size=Auto|Bytes|Compact, SepChar=X, SepWidth=N, icons:dir|file=On|Off,
+    //  Sort=[-]N|E|S|D|title, Time=C|A|W, DefaultPath=dir, AgeTiers=new,recent,old,
+    //  Mode=normal|wide|bare|tree, groupsort:<cat>=[-]key and
+    //  grouplabel:.ext=Label[,Color].
+    //  Returns true if handled, false if not a parameterized switch
+    //  (caller continues with color parsing).
     //
     ////////////////////////////////////////////////////////////////////////////
 
@@ -451,8 +458,149 @@ fn try_process_parameterized_switch (&mut self, entry: &str) -> bool {
                 } else if value.eq_ignore_ascii_case ("bytes") {
                     self.size_format = Some (SizeFormat::Bytes);
                     self.size_format_source = self.current_source;
+                } else if value.eq_ignore_ascii_case ("compact") {
+                    self.size_format = Some (SizeFormat::Compact);
+                    self.size_format_source = self.current_source;
+                } else {
+                    self.active_errors().push (ErrorInfo::new ("Invalid Size value (expected Auto, Bytes, or Compact)".into(), entry.into(), value.into(), eq_pos + 1));
+                }
+                true
+            }
+            "sepchar" => {
+                match parse_separator_char (value) {
+                    Some (ch) => self.separator_char = Some (ch),
+                    None      => {
+                        self.active_errors().push (ErrorInfo::new ("Invalid SepChar value (expected single glyph or U+XXXX)".into(), entry.into(), value.into(), eq_pos + 1));
+                    }
+                }
+                true
+            }
+            "sepwidth" => {
+                match value.parse::<usize>() {
+                    Ok (n) if (1..=MAX_SEPARATOR_WIDTH).contains (&n) => self.separator_width = Some (n),
+                    _ => {
+                        self.active_errors().push (ErrorInfo::new ("Invalid SepWidth value (must be 1-1000)".into(), entry.into(), value.into(), eq_pos + 1));
+                    }
+                }
+                true
+            }
+            "sort" => {
+                match CommandLine::parse_sort_key (value) {
+                    Some (sort) => self.sort_default = Some (sort),
+                    None        => {
+                        self.active_errors().push (ErrorInfo::new ("Invalid Sort value (expected N, E, S, D, or title, optionally prefixed with '-')".into(), entry.into(), value.into(), eq_pos + 1));
+                    }
+                }
+                true
+            }
+            "time" => {
+                let field = match value.to_ascii_lowercase().as_str() {
+                    "c" => Some (TimeField::Creation),
+                    "a" => Some (TimeField::Access),
+                    "w" => Some (TimeField::Written),
+                    _   => None,
+                };
+
+                match field {
+                    Some (field) => self.time_field_default = Some (field),
+                    None         => {
+                        self.active_errors().push (ErrorInfo::new ("Invalid Time value (expected C, A, or W)".into(), entry.into(), value.into(), eq_pos + 1));
+                    }
+                }
+                true
+            }
+            "agetiers" => {
+                match AgeTierThresholds::parse (value) {
+                    Some (tiers) => self.age_tiers = Some (tiers),
+                    None         => {
+                        self.active_errors().push (ErrorInfo::new ("Invalid AgeTiers value (expected three increasing durations, e.g. 1d,30d,365d)".into(), entry.into(), value.into(), eq_pos + 1));
+                    }
+                }
+                true
+            }
+            "mode" => {
+                let mode = match value.to_ascii_lowercase().as_str() {
+                    "normal" => Some (OutputMode::Normal),
+                    "wide"   => Some (OutputMode::Wide),
+                    "bare"   => Some (OutputMode::Bare),
+                    "tree"   => Some (OutputMode::Tree),
+                    _        => None,
+                };
+
+                match mode {
+                    Some (mode) => self.output_mode = Some (mode),
+                    None        => {
+                        self.active_errors().push (ErrorInfo::new ("Invalid Mode value (expected Normal, Wide, Bare, or Tree)".into(), entry.into(), value.into(), eq_pos + 1));
+                    }
+                }
+                true
+            }
+            "defaultpath" => {
+                if value.is_empty() {
+                    self.active_errors().push (ErrorInfo::new ("Invalid DefaultPath value (expected a directory path)".into(), entry.into(), value.into(), eq_pos + 1));
+                } else {
+                    self.default_path = Some (value.to_string());
+                }
+                true
+            }
+            k if k.starts_with ("groupsort:") => {
+                let category = &k["groupsort:".len()..];
+
+                if !GROUP_SORT_CATEGORIES.iter().any (|&(name, _, _)| name == category) {
+                    self.active_errors().push (ErrorInfo::new ("Invalid groupsort category (expected img, doc, or bin)".into(), entry.into(), key.into(), entry.find (key).unwrap_or (0)));
+                    return true;
+                }
+
+                match CommandLine::parse_sort_key (value) {
+                    Some (sort) => { self.group_sorts.insert (category.to_string(), sort); }
+                    None        => {
+                        self.active_errors().push (ErrorInfo::new ("Invalid groupsort value (expected N, E, S, D, or title, optionally prefixed with '-')".into(), entry.into(), value.into(), eq_pos + 1));
+                    }
+                }
+                true
+            }
+            k if k.starts_with ("grouplabel:") => {
+                let extension = &k["grouplabel:".len()..];
+
+                if extension.len() < 2 || !extension.starts_with ('.') {
+                    self.active_errors().push (ErrorInfo::new ("Invalid grouplabel extension (expected .ext)".into(), entry.into(), key.into(), entry.find (key).unwrap_or (0)));
+                    return true;
+                }
+
+                // A trailing ",Color" sets the heading color; the label
+                // itself can't contain a comma
+                let (label, color) = match value.rsplit_once (',') {
+                    Some ((label, color)) => {
+                        let Some (attr) = self.parse_color_value (entry, color) else {
+                            return true;
+                        };
+                        (label.trim(), Some (attr))
+                    }
+                    None => (value, None),
+                };
+
+                if label.is_empty() {
+                    self.active_errors().push (ErrorInfo::new ("Invalid grouplabel value (expected a label, optionally followed by ,Color)".into(), entry.into(), value.into(), eq_pos + 1));
+                    return true;
+                }
+
+                self.group_labels.insert (extension.to_string(), (label.to_string(), color));
+                true
+            }
+            "icons:dir" | "icons:file" => {
+                let off = if value.eq_ignore_ascii_case ("off") {
+                    true
+                } else if value.eq_ignore_ascii_case ("on") {
+                    false
+                } else {
+                    self.active_errors().push (ErrorInfo::new ("Invalid icon category value (expected On or Off)".into(), entry.into(), value.into(), eq_pos + 1));
+                    return true;
+                };
+
+                if key_lower == "icons:dir" {
+                    self.dir_icons_off = off;
                 } else {
-                    self.active_errors().push (ErrorInfo::new ("Invalid Size value (expected Auto or Bytes)".into(), entry.into(), value.into(), eq_pos + 1));
+                    self.file_icons_off = off;
                 }
                 true
             }
@@ -534,6 +682,9 @@ fn process_file_attribute_override(&mut self, key: &str, color_attr: u16, entry:
                     attr:   color_attr,
                     source: self.current_source,
                 });
+
+                // The letter in the attribute column takes the same color
+                self.attr_letter_colors.insert(flag, color_attr);
                 return;
             }
         }
@@ -784,6 +935,50 @@ fn switch_name_to_source_index(name: &str) -> Option<usize> {
 
 
 
+////////////////////////////////////////////////////////////////////////////////
+//
+//  MAX_SEPARATOR_WIDTH
+//
+//  Upper bound for SepWidth=N; guards against typos allocating huge rules.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+const MAX_SEPARATOR_WIDTH: usize = 1000;
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  parse_separator_char
+//
+//  Parse a SepChar value: a single printable glyph, or U+XXXX (4–6 hex
+//  digits) like the icon syntax.  Returns None if invalid.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn parse_separator_char(value: &str) -> Option<char> {
+    let mut chars = value.chars();
+
+    if let (Some (ch), None) = (chars.next(), chars.next()) {
+        return (!ch.is_control()).then_some (ch);
+    }
+
+    let hex = value.strip_prefix ("U+").or_else (|| value.strip_prefix ("u+"))?;
+    if !(4..=6).contains (&hex.len()) {
+        return None;
+    }
+
+    u32::from_str_radix (hex, 16).ok()
+        .and_then (char::from_u32)
+        .filter (|ch| !ch.is_control())
+}
+
+
+
+
+
 ////////////////////////////////////////////////////////////////////////////////
 //
 //  parse_key_and_value
@@ -0,0 +1,410 @@
+// json_export.rs — Serialize the resolved configuration as JSON
+//
+// Extends impl Config with to_json(), used by --Config=JSON so tooling
+// (editor themes, scripts) can read the effective RCDir colors and icons,
+// and ValidationResult with to_json(), used by --Config=errors-json so an
+// editor can surface RCDIR env var mistakes inline.
+// Hand-rolled writer: the output shape is small and fixed, and the crate
+// has no serialization dependency.
+
+use std::fmt::Write;
+
+use crate::color::format_color_spec;
+use crate::file_info::FILE_ATTRIBUTE_MAP;
+
+use super::{Attribute, Config, ValidationResult};
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl Config — JSON export
+//
+//  Serialization of the resolved (merged) configuration.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl Config {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  to_json
+    //
+    //  Serialize display item colors, extension colors, file attribute
+    //  colors, and icon mappings as a pretty-printed JSON object.  Map
+    //  keys are sorted so the output is stable between runs.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn to_json(&self) -> String {
+        let mut out = String::with_capacity (16 * 1024);
+
+        out.push_str ("{\n");
+
+        // Display item colors
+        let attributes: Vec<(String, String)> = Attribute::ALL.iter()
+            .map (|attr| (attr.name().to_string(), format_color_spec (self.attributes[*attr as usize])))
+            .collect();
+        write_object (&mut out, "attributes", &attributes, 1);
+        out.push_str (",\n");
+
+        // Extension colors
+        let mut extensions: Vec<(String, String)> = self.extension_colors.iter()
+            .map (|(ext, &attr)| (ext.clone(), format_color_spec (attr)))
+            .collect();
+        extensions.sort();
+        write_object (&mut out, "extensions", &extensions, 1);
+        out.push_str (",\n");
+
+        // File attribute colors, in display order (RHSATECP0)
+        let file_attributes: Vec<(String, String)> = FILE_ATTRIBUTE_MAP.iter()
+            .filter_map (|&(flag, ch)| {
+                self.file_attr_colors.get (&flag).map (|style| (ch.to_string(), format_color_spec (style.attr)))
+            })
+            .collect();
+        write_object (&mut out, "fileAttributes", &file_attributes, 1);
+        out.push_str (",\n");
+
+        // Icons
+        out.push_str ("  \"icons\": {\n");
+
+        let enabled = match self.icons {
+            Some (true)  => "true",
+            Some (false) => "false",
+            None         => "null",
+        };
+        let _ = writeln! (out, "    \"enabled\": {},", enabled);
+
+        let fallbacks: Vec<(String, String)> = vec![
+            ("directory".into(),              code_point (self.icon_directory_default)),
+            ("file".into(),                   code_point (self.icon_file_default)),
+            ("symlink".into(),                code_point (self.icon_symlink)),
+            ("junction".into(),               code_point (self.icon_junction)),
+            ("cloudOnly".into(),              code_point (self.icon_cloud_only)),
+            ("locallyAvailable".into(),       code_point (self.icon_locally_available)),
+            ("alwaysLocallyAvailable".into(), code_point (self.icon_always_local)),
+        ];
+        write_object (&mut out, "defaults", &fallbacks, 2);
+        out.push_str (",\n");
+
+        let mut extension_icons: Vec<(String, String)> = self.extension_icons.iter()
+            .map (|(ext, &ch)| (ext.clone(), code_point (ch)))
+            .collect();
+        extension_icons.sort();
+        write_object (&mut out, "extensions", &extension_icons, 2);
+        out.push_str (",\n");
+
+        let mut dir_icons: Vec<(String, String)> = self.well_known_dir_icons.iter()
+            .map (|(dir, &ch)| (dir.clone(), code_point (ch)))
+            .collect();
+        dir_icons.sort();
+        write_object (&mut out, "wellKnownDirs", &dir_icons, 2);
+        out.push_str (",\n");
+
+        let attr_icons: Vec<(String, String)> = FILE_ATTRIBUTE_MAP.iter()
+            .filter_map (|&(flag, ch)| {
+                self.file_attr_icons.get (&flag).map (|&icon| (ch.to_string(), code_point (icon)))
+            })
+            .collect();
+        write_object (&mut out, "fileAttributes", &attr_icons, 2);
+        out.push_str ("\n  }\n");
+
+        out.push_str ("}\n");
+        out
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl ValidationResult — JSON export
+//
+//  Serialization of parse errors for tooling.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl ValidationResult {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  to_json
+    //
+    //  Serialize the errors as {"errors": [...]}, one object per ErrorInfo
+    //  with its message, entry, invalid text, and the invalid text's byte
+    //  offset within the entry.  The shape is stable: an empty result is
+    //  still an object with an empty array.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn to_json(&self) -> String {
+        let mut out = String::from ("{\n  \"errors\": [");
+
+        for (i, error) in self.errors.iter().enumerate() {
+            let separator = if i + 1 < self.errors.len() { "," } else { "" };
+
+            out.push_str ("\n    {\n");
+            let _ = writeln! (out, "      \"message\": \"{}\",", json_escape (&error.message));
+            let _ = writeln! (out, "      \"entry\": \"{}\",", json_escape (&error.entry));
+            let _ = writeln! (out, "      \"invalidText\": \"{}\",", json_escape (&error.invalid_text));
+            let _ = writeln! (out, "      \"offset\": {}", error.invalid_text_offset);
+            let _ = write! (out, "    }}{}", separator);
+        }
+
+        if !self.errors.is_empty() {
+            out.push_str ("\n  ");
+        }
+
+        out.push_str ("]\n}\n");
+        out
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  write_object
+//
+//  Write a named JSON object of string → string pairs at the given
+//  indent level (two spaces per level).  No trailing comma or newline.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn write_object(out: &mut String, name: &str, entries: &[(String, String)], level: usize) {
+    let indent       = "  ".repeat (level);
+    let inner_indent = "  ".repeat (level + 1);
+
+    let _ = write! (out, "{}\"{}\": {{", indent, json_escape (name));
+
+    if entries.is_empty() {
+        out.push ('}');
+        return;
+    }
+
+    out.push ('\n');
+
+    for (i, (key, value)) in entries.iter().enumerate() {
+        let separator = if i + 1 < entries.len() { "," } else { "" };
+        let _ = writeln! (out, "{}\"{}\": \"{}\"{}", inner_indent, json_escape (key), json_escape (value), separator);
+    }
+
+    let _ = write! (out, "{}}}", indent);
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  code_point
+//
+//  Format an icon character as "U+XXXX" (the config-file icon syntax).
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn code_point(ch: char) -> String {
+    format! ("U+{:04X}", ch as u32)
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  json_escape
+//
+//  Escape a string for inclusion in a JSON string literal.  Also used by
+//  the --Jsonl listing.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(crate) fn json_escape(s: &str) -> String {
+    let mut out = String::with_capacity (s.len());
+
+    for ch in s.chars() {
+        match ch {
+            '"'  => out.push_str ("\\\""),
+            '\\' => out.push_str ("\\\\"),
+            '\n' => out.push_str ("\\n"),
+            '\r' => out.push_str ("\\r"),
+            '\t' => out.push_str ("\\t"),
+            c if (c as u32) < 0x20 => { let _ = write! (out, "\\u{:04x}", c as u32); }
+            c    => out.push (c),
+        }
+    }
+
+    out
+}
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::FC_LIGHT_GREY;
+    use crate::config::RCDIR_ENV_VAR_NAME;
+    use crate::environment_provider::MockEnvironmentProvider;
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  make_config
+    //
+    //  Creates a Config initialized with an optional RCDIR env var value.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn make_config (env_value: Option<&str>) -> Config {
+        let mut config = Config::new();
+        let mut mock = MockEnvironmentProvider::new();
+        if let Some (val) = env_value {
+            mock.set (RCDIR_ENV_VAR_NAME, val);
+        }
+        config.initialize_with_provider (FC_LIGHT_GREY, &mock);
+        config
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  json_contains_default_directory_color
+    //
+    //  Verify the directory display item serializes by color name.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn json_contains_default_directory_color () {
+        let json = make_config (None).to_json();
+        assert! (json.contains ("\"Directory\": \"LightBlue\""), "{}", json);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  json_reflects_user_overrides
+    //
+    //  Verify env var overrides (display item with background, extension)
+    //  appear in the exported JSON.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn json_reflects_user_overrides () {
+        let json = make_config (Some ("R=Yellow on Blue;.xyz=LightRed")).to_json();
+        assert! (json.contains ("\"Directory\": \"Yellow on Blue\""), "{}", json);
+        assert! (json.contains ("\".xyz\": \"LightRed\""), "{}", json);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  json_has_all_sections
+    //
+    //  Verify all top-level sections are present and braces balance.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn json_has_all_sections () {
+        let json = make_config (None).to_json();
+
+        for section in ["\"attributes\"", "\"extensions\"", "\"fileAttributes\"", "\"icons\"", "\"wellKnownDirs\""] {
+            assert! (json.contains (section), "missing {}", section);
+        }
+
+        assert_eq! (json.matches ('{').count(), json.matches ('}').count());
+        assert! (json.trim_end().ends_with ('}'));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  json_escape_special_characters
+    //
+    //  Verify quotes, backslashes, and control characters are escaped.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn json_escape_special_characters () {
+        assert_eq! (json_escape ("a\"b\\c\n"), "a\\\"b\\\\c\\n");
+        assert_eq! (json_escape ("\u{1}"), "\\u0001");
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  errors_json_reports_each_env_var_error
+    //
+    //  Verify known-bad RCDIR entries serialize as one object each, with
+    //  the message, entry, invalid text, and offset from ErrorInfo.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn errors_json_reports_each_env_var_error () {
+        let config = make_config (Some ("Depth=abc;S=Yellow;/W"));
+        let json   = config.last_parse_result.to_json();
+
+        let expected = "{\n  \"errors\": [\n\
+            \x20   {\n\
+            \x20     \"message\": \"Invalid Depth value (must be positive integer)\",\n\
+            \x20     \"entry\": \"Depth=abc\",\n\
+            \x20     \"invalidText\": \"abc\",\n\
+            \x20     \"offset\": 6\n\
+            \x20   },\n\
+            \x20   {\n\
+            \x20     \"message\": \"Switch prefixes (/, -, --) are not allowed in env var\",\n\
+            \x20     \"entry\": \"/W\",\n\
+            \x20     \"invalidText\": \"/\",\n\
+            \x20     \"offset\": 0\n\
+            \x20   }\n\
+            \x20 ]\n}\n";
+
+        assert_eq! (json, expected);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  errors_json_empty_when_valid
+    //
+    //  Verify a valid env var yields an object with an empty errors array.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn errors_json_empty_when_valid () {
+        let json = make_config (Some ("S=Yellow")).last_parse_result.to_json();
+        assert_eq! (json, "{\n  \"errors\": []\n}\n");
+    }
+}
@@ -6,13 +6,17 @@
 
 mod env_overrides;
 pub mod file_reader;
+mod json_export;
+
+pub(crate) use json_export::json_escape;
 
 use std::collections::HashMap;
 use std::ffi::OsStr;
 use std::path::Path;
 
+use crate::age_tier::AgeTierThresholds;
 use crate::color::*;
-use crate::command_line::SizeFormat;
+use crate::command_line::{OutputMode, SizeFormat, SortDirection, SortOrder, TimeField};
 use crate::environment_provider::{DefaultEnvironmentProvider, EnvironmentProvider};
 use crate::file_attribute_map::ATTRIBUTE_PRECEDENCE;
 use crate::file_info::{
@@ -32,6 +36,36 @@
 /// Environment variable name
 pub const RCDIR_ENV_VAR_NAME: &str = "RCDIR";
 
+/// Profile selector: RCDIR_PROFILE=work reads settings from RCDIR_work
+pub const RCDIR_PROFILE_ENV_VAR_NAME: &str = "RCDIR_PROFILE";
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  active_env_var_name
+//
+//  Name of the variable the settings are read from: RCDIR_<profile> when
+//  RCDIR_PROFILE names a profile whose variable is set, else RCDIR.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn active_env_var_name(provider: &dyn EnvironmentProvider) -> String {
+    if let Some (profile) = provider.get_env_var (RCDIR_PROFILE_ENV_VAR_NAME) {
+        let profile = profile.trim();
+        if !profile.is_empty() {
+            let name = format! ("{}_{}", RCDIR_ENV_VAR_NAME, profile);
+            if provider.get_env_var (&name).is_some() {
+                return name;
+            }
+        }
+    }
+
+    RCDIR_ENV_VAR_NAME.to_string()
+}
+
 
 
 
@@ -326,6 +360,9 @@ pub struct Config {
     /// File attribute flag → color+source
     pub file_attr_colors:   HashMap<u32, FileAttrStyle>,
 
+    /// File attribute flag → attribute-column letter color (from attr:X overrides)
+    pub attr_letter_colors: HashMap<u32, u16>,
+
     ////////////////////////////////////////////////////////////////////////////
 
     /// Extension → icon code point (keys lowercase with leading dot)
@@ -375,6 +412,51 @@ pub struct Config {
     pub size_format:    Option<SizeFormat>,
     pub ellipsize:      Option<bool>,
 
+    /// Section separator rule (SepChar=, SepWidth=); drawn only when set
+    pub separator_char:  Option<char>,
+    pub separator_width: Option<usize>,
+
+    /// Category-wide icon suppression (icons:dir=off, icons:file=off);
+    /// the entries keep their colors and icon-column alignment
+    pub dir_icons_off:   bool,
+    pub file_icons_off:  bool,
+
+    /// Emit configured colors verbatim, skipping the fore == back
+    /// visibility adjustment (set from /RawColors)
+    pub raw_colors:      bool,
+
+    /// Every entry gets a glyph, even where its icon is suppressed, so a
+    /// listing with icons but no color still tells directories from files
+    /// (set for /NoColor with icons active)
+    pub glyphs_required: bool,
+
+    /// Start from the light-background preset instead of the dark
+    /// defaults (set from /Light, before initialize)
+    pub light_background: bool,
+
+    /// Default sort (Sort=) and time field (Time=), applied when the command
+    /// line leaves them at their defaults
+    pub sort_default:        Option<(SortOrder, SortDirection)>,
+    pub time_field_default:  Option<TimeField>,
+
+    /// /Tier age cutoffs (AgeTiers=); None uses 1d,30d,365d
+    pub age_tiers:           Option<AgeTierThresholds>,
+
+    /// Default listing style (Mode=), applied when the command line
+    /// picks none
+    pub output_mode:         Option<OutputMode>,
+
+    /// Directory listed when no path is given (DefaultPath=)
+    pub default_path:        Option<String>,
+
+    /// /Group:ext sort overrides per file-type category (groupsort:img=d),
+    /// keyed by GROUP_SORT_CATEGORIES name
+    pub group_sorts:         HashMap<String, (SortOrder, SortDirection)>,
+
+    /// /Group:ext heading text and optional color per extension
+    /// (grouplabel:.cpp=C++ sources), keyed by lowercase ".ext"
+    pub group_labels:        HashMap<String, (String, Option<u16>)>,
+
     /// Validation results from last env var parse
     pub last_parse_result: ValidationResult,
 
@@ -462,6 +544,7 @@ pub fn new() -> Self {
             extension_colors:           HashMap::new(),
             extension_sources:          HashMap::new(),
             file_attr_colors:           HashMap::new(),
+            attr_letter_colors:         HashMap::new(),
             extension_icons:            HashMap::new(),
             extension_icon_sources:     HashMap::new(),
             well_known_dir_icons:       HashMap::new(),
@@ -487,6 +570,20 @@ pub fn new() -> Self {
             tree_indent:       None,
             size_format:       None,
             ellipsize:         None,
+            separator_char:    None,
+            separator_width:   None,
+            age_tiers:         None,
+            dir_icons_off:     false,
+            file_icons_off:    false,
+            raw_colors:        false,
+            glyphs_required:   false,
+            light_background:  false,
+            sort_default:        None,
+            time_field_default:  None,
+            default_path:        None,
+            output_mode:         None,
+            group_sorts:         HashMap::new(),
+            group_labels:        HashMap::new(),
             last_parse_result: ValidationResult::default(),
             config_file_path:         String::new(),
             config_file_loaded:       false,
@@ -696,6 +793,13 @@ pub fn initialize_with_provider(&mut self, default_attr: u16, provider: &dyn Env
         self.initialize_file_attr_colors();
         self.initialize_extension_icons();
         self.initialize_well_known_dir_icons();
+
+        // /Light swaps the defaults before the config file and env var
+        // get a chance to override them
+        if self.light_background {
+            self.apply_light_background_preset();
+        }
+
         self.load_config_file (provider);
         self.apply_user_color_overrides(provider);
     }
@@ -805,6 +909,11 @@ pub fn get_cloud_status_icon(&self, status: crate::cloud_status::CloudStatus) ->
     //    Directories:  fallback dir icon  < well-known dir < attributes
     //    Files:        fallback file icon < extension      < attributes
     //
+    //  icons:dir=off / icons:file=off then suppress the category's icon.
+    //  With glyphs_required, an entry left without an icon falls back to
+    //  the default directory or file icon.  Unless raw_colors is set, a color whose foreground matches its
+    //  background is given a contrasting background.
+    //
     //  Port of: CConfig::GetDisplayStyleForFile
     //
     ////////////////////////////////////////////////////////////////////////////
@@ -826,12 +935,28 @@ pub fn get_display_style_for_file(&self, file_info: &crate::file_info::FileInfo)
 
         self.resolve_file_attribute_style (file_info, &mut style);
 
+        // icons:dir=off / icons:file=off win over every icon source
+        let is_directory = file_info.file_attributes & FILE_ATTRIBUTE_DIRECTORY != 0;
+        if (is_directory && self.dir_icons_off) || (!is_directory && self.file_icons_off) {
+            style.icon_code_point = None;
+            style.icon_suppressed = true;
+        }
+
+        // Without color the glyph is the only directory/file cue
+        if self.glyphs_required && (style.icon_suppressed || style.icon_code_point.is_none()) {
+            let fallback = if is_directory { self.icon_directory_default } else { self.icon_file_default };
+            style.icon_code_point = Some (fallback);
+            style.icon_suppressed = false;
+        }
+
         // Inherit default background if none set
         if style.text_attr & BC_MASK == 0 {
             style.text_attr |= default_attr & BC_MASK;
         }
 
-        style.text_attr = ensure_visible_color_attr (style.text_attr, default_attr);
+        if !self.raw_colors {
+            style.text_attr = ensure_visible_color_attr (style.text_attr, default_attr);
+        }
 
         style
     }
@@ -1236,6 +1361,7 @@ fn initialize_extension_colors(&mut self) {
 
     fn initialize_file_attr_colors(&mut self) {
         self.file_attr_colors.clear();
+        self.attr_letter_colors.clear();
 
         // Hidden files → DarkGrey
         self.file_attr_colors.insert(0x02, FileAttrStyle {
@@ -1254,6 +1380,36 @@ fn initialize_file_attr_colors(&mut self) {
 
 
 
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  apply_light_background_preset
+    //
+    //  Rework the dark-background defaults for a light terminal: black on
+    //  white for default text, every display item on the same white
+    //  background, and bright foregrounds (yellow, white, light cyan, ...)
+    //  swapped for their dark counterparts so they stay readable.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn apply_light_background_preset(&mut self) {
+        for attr in self.attributes.iter_mut() {
+            *attr = light_background_fore (*attr & FC_MASK) | BC_WHITE;
+        }
+        self.attributes[Attribute::Default as usize] = FC_BLACK | BC_WHITE;
+
+        for attr in self.extension_colors.values_mut() {
+            *attr = light_background_fore (*attr & FC_MASK) | (*attr & BC_MASK);
+        }
+
+        for style in self.file_attr_colors.values_mut() {
+            style.attr = light_background_fore (style.attr & FC_MASK) | (style.attr & BC_MASK);
+        }
+    }
+
+
+
+
+
     ////////////////////////////////////////////////////////////////////////////
     //
     //  initialize_extension_icons
@@ -1360,10 +1516,38 @@ fn ensure_visible_color_attr(color_attr: u16, default_attr: u16) -> u16 {
 
 
 
+////////////////////////////////////////////////////////////////////////////////
+//
+//  light_background_fore
+//
+//  The /Light replacement for a foreground color: bright colors that wash
+//  out on white become their dark counterparts; the rest are unchanged.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn light_background_fore(fore: u16) -> u16 {
+    match fore {
+        FC_WHITE         => FC_BLACK,
+        FC_LIGHT_GREY    => FC_DARK_GREY,
+        FC_YELLOW        => FC_BROWN,
+        FC_LIGHT_GREEN   => FC_GREEN,
+        FC_LIGHT_CYAN    => FC_CYAN,
+        FC_LIGHT_BLUE    => FC_BLUE,
+        FC_LIGHT_MAGENTA => FC_MAGENTA,
+        FC_LIGHT_RED     => FC_RED,
+        _                => fore,
+    }
+}
+
+
+
+
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::environment_provider::MockEnvironmentProvider;
+    use crate::test_support::make_file;
 
 
     ////////////////////////////////////////////////////////////////////////////
@@ -1684,6 +1868,29 @@ fn env_file_attribute_override() {
 
 
 
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  env_file_attribute_override_sets_letter_color
+    //
+    //  Verifies Attr:R=Yellow also records the attribute-column letter
+    //  color, and that default attribute colors (hidden, encrypted) do not.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn env_file_attribute_override_sets_letter_color() {
+        let config = make_config(Some("Attr:R=Yellow"));
+        assert_eq!(config.attr_letter_colors.get(&0x01), Some(&FC_YELLOW));
+        assert!(!config.attr_letter_colors.contains_key(&0x02));
+
+        let config = make_config(None);
+        assert!(config.attr_letter_colors.is_empty());
+    }
+
+
+
+
+
     ////////////////////////////////////////////////////////////////////////////
     //
     //  env_prefix_error
@@ -2040,6 +2247,7 @@ fn well_known_dir_icon_lookup() {
             reparse_tag:      0,
             reparse_target:  String::new(),
             streams:          Vec::new(),
+            ..Default::default()
         };
         let style = cfg.get_display_style_for_file (&fi_git);
         assert!(style.icon_code_point.is_some(), ".git should have an icon");
@@ -2056,6 +2264,7 @@ fn well_known_dir_icon_lookup() {
             reparse_tag:      0,
             reparse_target:  String::new(),
             streams:          Vec::new(),
+            ..Default::default()
         };
         let style_upper = cfg.get_display_style_for_file (&fi_git_upper);
         assert_eq!(style_upper.icon_code_point, style.icon_code_point);
@@ -2091,6 +2300,7 @@ fn dir_prefix_overrides_default_icon() {
             reparse_tag:      0,
             reparse_target:  String::new(),
             streams:          Vec::new(),
+            ..Default::default()
         };
         let style = cfg.get_display_style_for_file (&fi_git);
         assert_eq!(style.icon_code_point, Some ('X'), "dir: override should replace default icon");
@@ -2351,6 +2561,158 @@ fn env_var_tree_indent_out_of_range_records_error () {
 
 
 
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  env_var_separator_sets_char_and_width
+    //
+    //  Verify RCDIR=SepChar=X;SepWidth=N sets the separator settings, with
+    //  either a literal glyph or U+XXXX.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn env_var_separator_sets_char_and_width () {
+        let config = make_config (Some ("SepChar=\u{2550};SepWidth=40"));
+        assert_eq! (config.separator_char, Some ('\u{2550}'));
+        assert_eq! (config.separator_width, Some (40));
+        assert! (!config.last_parse_result.has_issues());
+
+        let config = make_config (Some ("sepchar=U+002D"));
+        assert_eq! (config.separator_char, Some ('-'));
+        assert_eq! (config.separator_width, None);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  env_var_separator_invalid_records_error
+    //
+    //  Verify bad SepChar/SepWidth values record errors and stay unset.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn env_var_separator_invalid_records_error () {
+        for entry in ["SepChar=ab", "SepChar=U+12", "SepWidth=0", "SepWidth=wide"] {
+            let config = make_config (Some (entry));
+            assert! (config.last_parse_result.has_issues(), "{}", entry);
+            assert_eq! (config.separator_char, None, "{}", entry);
+            assert_eq! (config.separator_width, None, "{}", entry);
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  env_var_default_path_sets_base_directory
+    //
+    //  Verify RCDIR=DefaultPath=dir records the base directory (drive
+    //  colons included), and an empty value records an error.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn env_var_default_path_sets_base_directory () {
+        let config = make_config (Some ("W;DefaultPath=C:\\Projects\\app"));
+        assert_eq! (config.default_path.as_deref(), Some ("C:\\Projects\\app"));
+        assert! (!config.last_parse_result.has_issues());
+
+        let config = make_config (Some ("DefaultPath="));
+        assert_eq! (config.default_path, None);
+        assert! (config.last_parse_result.has_issues());
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  env_var_age_tiers_sets_thresholds
+    //
+    //  Verify RCDIR=AgeTiers=a,b,c sets the /Tier cutoffs, and an
+    //  out-of-order list records an error and keeps the defaults.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn env_var_age_tiers_sets_thresholds () {
+        let config = make_config (Some ("AgeTiers=1h,7d,90d"));
+        assert_eq! (config.age_tiers, AgeTierThresholds::parse ("1h,7d,90d"));
+        assert! (config.age_tiers.is_some());
+        assert! (!config.last_parse_result.has_issues());
+
+        let config = make_config (Some ("AgeTiers=30d,1d,365d"));
+        assert_eq! (config.age_tiers, None);
+        assert! (config.last_parse_result.has_issues());
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  env_var_profile_selects_profile_variable
+    //
+    //  Verify RCDIR_PROFILE=work reads RCDIR_work instead of RCDIR.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn env_var_profile_selects_profile_variable () {
+        let mut mock = MockEnvironmentProvider::new();
+        mock.set (RCDIR_ENV_VAR_NAME, "D=Yellow");
+        mock.set ("RCDIR_work", "D=Green");
+        mock.set (RCDIR_PROFILE_ENV_VAR_NAME, "work");
+
+        assert_eq! (active_env_var_name (&mock), "RCDIR_work");
+
+        let mut config = Config::new();
+        config.initialize_with_provider (FC_LIGHT_GREY, &mock);
+        assert_eq! (config.attributes[Attribute::Date as usize], FC_GREEN);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  env_var_profile_falls_back_to_rcdir
+    //
+    //  Verify RCDIR is used when RCDIR_PROFILE is unset or empty, or names
+    //  a profile whose variable is unset.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn env_var_profile_falls_back_to_rcdir () {
+        let mut mock = MockEnvironmentProvider::new();
+        mock.set (RCDIR_ENV_VAR_NAME, "D=Yellow");
+        assert_eq! (active_env_var_name (&mock), RCDIR_ENV_VAR_NAME);
+
+        mock.set (RCDIR_PROFILE_ENV_VAR_NAME, "");
+        assert_eq! (active_env_var_name (&mock), RCDIR_ENV_VAR_NAME);
+
+        mock.set (RCDIR_PROFILE_ENV_VAR_NAME, "home");
+        assert_eq! (active_env_var_name (&mock), RCDIR_ENV_VAR_NAME);
+
+        let mut config = Config::new();
+        config.initialize_with_provider (FC_LIGHT_GREY, &mock);
+        assert_eq! (config.attributes[Attribute::Date as usize], FC_YELLOW);
+    }
+
+
+
+
+
     // =========================================================================
     //  Size config env var tests (T020)
     // =========================================================================
@@ -3266,6 +3628,7 @@ fn display_style_plain_directory_returns_directory_color () {
             reparse_tag:     0,
             reparse_target:  String::new(),
             streams:         Vec::new(),
+            ..Default::default()
         };
 
         let style = config.get_display_style_for_file (&fi);
@@ -3299,6 +3662,7 @@ fn display_style_unknown_extension_returns_default_file_icon () {
             reparse_tag:     0,
             reparse_target:  String::new(),
             streams:         Vec::new(),
+            ..Default::default()
         };
 
         let style = config.get_display_style_for_file (&fi);
@@ -3332,6 +3696,7 @@ fn display_style_cpp_file_returns_cpp_icon () {
             reparse_tag:     0,
             reparse_target:  String::new(),
             streams:         Vec::new(),
+            ..Default::default()
         };
 
         let style = config.get_display_style_for_file (&fi);
@@ -3366,6 +3731,7 @@ fn display_style_directory_returns_folder_icon () {
             reparse_tag:     0,
             reparse_target:  String::new(),
             streams:         Vec::new(),
+            ..Default::default()
         };
 
         let style = config.get_display_style_for_file (&fi);
@@ -3399,6 +3765,7 @@ fn display_style_symlink_dir_returns_symlink_icon () {
             reparse_tag:     IO_REPARSE_TAG_SYMLINK,
             reparse_target:  String::new(),
             streams:         Vec::new(),
+            ..Default::default()
         };
 
         let style = config.get_display_style_for_file (&fi);
@@ -3432,6 +3799,7 @@ fn display_style_junction_dir_returns_junction_icon () {
             reparse_tag:     IO_REPARSE_TAG_MOUNT_POINT,
             reparse_target:  String::new(),
             streams:         Vec::new(),
+            ..Default::default()
         };
 
         let style = config.get_display_style_for_file (&fi);
@@ -3465,6 +3833,7 @@ fn display_style_well_known_dir_returns_specific_icon () {
             reparse_tag:     0,
             reparse_target:  String::new(),
             streams:         Vec::new(),
+            ..Default::default()
         };
 
         let style = config.get_display_style_for_file (&fi);
@@ -3501,6 +3870,7 @@ fn display_style_hidden_file_attribute_wins_over_extension () {
             reparse_tag:     0,
             reparse_target:  String::new(),
             streams:         Vec::new(),
+            ..Default::default()
         };
 
         let style = config.get_display_style_for_file (&fi);
@@ -4008,4 +4378,333 @@ fn config_file_not_found_silent_skip() {
         assert! (!config.config_file_loaded);
         assert! (!config.config_file_parse_result.has_issues());
     }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  icons_dir_off_suppresses_only_directory_icons
+    //
+    //  Verify icons:dir=off removes every directory icon (including
+    //  well-known dirs) while keeping directory colors, and file icons
+    //  remain.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn icons_dir_off_suppresses_only_directory_icons () {
+        let cfg = make_config (Some ("icons:dir=off"));
+        assert! (cfg.last_parse_result.errors.is_empty());
+        assert! (cfg.dir_icons_off);
+        assert! (!cfg.file_icons_off);
+
+        for name in ["src", ".git"] {
+            let style = cfg.get_display_style_for_file (&make_file (name, FILE_ATTRIBUTE_DIRECTORY, 0));
+            assert_eq! (style.icon_code_point, None, "{name} icon should be suppressed");
+            assert! (style.icon_suppressed);
+            assert_eq! (style.text_attr & FC_MASK, cfg.attributes[Attribute::Directory as usize] & FC_MASK);
+        }
+
+        let style = cfg.get_display_style_for_file (&make_file ("main.rs", 0x20, 0));
+        assert! (style.icon_code_point.is_some());
+        assert! (!style.icon_suppressed);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  icons_file_off_suppresses_only_file_icons
+    //
+    //  Verify icons:file=off removes file icons, leaves directory icons,
+    //  and an invalid category value records an error.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn icons_file_off_suppresses_only_file_icons () {
+        let cfg = make_config (Some ("icons:file=Off;icons:dir=on"));
+        assert! (cfg.last_parse_result.errors.is_empty());
+
+        let style = cfg.get_display_style_for_file (&make_file ("main.rs", 0x20, 0));
+        assert_eq! (style.icon_code_point, None);
+        assert! (style.icon_suppressed);
+
+        let style = cfg.get_display_style_for_file (&make_file ("src", FILE_ATTRIBUTE_DIRECTORY, 0));
+        assert! (style.icon_code_point.is_some());
+
+        let cfg = make_config (Some ("icons:dir=hidden"));
+        assert_eq! (cfg.last_parse_result.errors.len(), 1);
+        assert! (!cfg.dir_icons_off);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  env_var_sort_and_time_defaults
+    //
+    //  Verify Sort= and Time= set the switch defaults with /O and /T
+    //  syntax, bad values record an error, and the O and T color keys
+    //  never touch them.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn env_var_sort_and_time_defaults () {
+        use crate::command_line::{SortDirection, SortOrder, TimeField};
+
+        let config = make_config (Some ("Sort=d;Time=c"));
+        assert_eq! (config.sort_default, Some ((SortOrder::Date, SortDirection::Ascending)));
+        assert_eq! (config.time_field_default, Some (TimeField::Creation));
+        assert! (!config.last_parse_result.has_issues());
+
+        let config = make_config (Some ("sort=-S"));
+        assert_eq! (config.sort_default, Some ((SortOrder::Size, SortDirection::Descending)));
+
+        let config = make_config (Some ("Sort=-Title"));
+        assert_eq! (config.sort_default, Some ((SortOrder::Title, SortDirection::Descending)));
+
+        assert! (make_config (Some ("Sort=x")).last_parse_result.has_issues());
+        assert! (make_config (Some ("Time=z")).last_parse_result.has_issues());
+
+        let config = make_config (Some ("O=Yellow;T=Red"));
+        assert_eq! (config.sort_default, None);
+        assert_eq! (config.time_field_default, None);
+        assert_eq! (config.attributes[Attribute::Owner as usize], FC_YELLOW);
+        assert_eq! (config.attributes[Attribute::Time as usize],  FC_RED);
+        assert! (!config.last_parse_result.has_issues());
+
+        let config = make_config (Some ("O=d;T=c"));
+        assert_eq! (config.sort_default, None);
+        assert_eq! (config.time_field_default, None);
+        assert! (config.last_parse_result.has_issues());
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  raw_colors_preserves_matching_fore_and_back
+    //
+    //  Verifies a Blue extension on an inherited Blue background is given
+    //  a contrasting background by default, but kept as-is with
+    //  raw_colors (/RawColors).
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn raw_colors_preserves_matching_fore_and_back () {
+        use crate::color::*;
+        use crate::file_info::FileInfo;
+
+        let mut config = make_config (Some (".xyz=Blue"));
+        config.attributes[Attribute::Default as usize] = FC_LIGHT_GREY | BC_BLUE;
+
+        let fi = FileInfo {
+            file_name:       std::ffi::OsString::from ("hidden.xyz"),
+            file_attributes: 0x20, // FILE_ATTRIBUTE_ARCHIVE
+            file_size:       100,
+            creation_time:   0,
+            last_write_time: 0,
+            last_access_time: 0,
+            reparse_tag:     0,
+            reparse_target:  String::new(),
+            streams:         Vec::new(),
+            ..Default::default()
+        };
+
+        let adjusted = config.get_display_style_for_file (&fi);
+        assert_eq! (adjusted.text_attr, FC_BLUE | BC_LIGHT_GREY);
+
+        config.raw_colors = true;
+        let raw = config.get_display_style_for_file (&fi);
+        assert_eq! (raw.text_attr, FC_BLUE | BC_BLUE);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  env_var_group_sorts
+    //
+    //  Verify groupsort:<cat>= records a per-category sort, and unknown
+    //  categories or keys record an error.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn env_var_group_sorts () {
+        use crate::command_line::{SortDirection, SortOrder};
+
+        let config = make_config (Some ("groupsort:img=-s;GroupSort:Doc=d"));
+        assert_eq! (config.group_sorts.get ("img"), Some (&(SortOrder::Size, SortDirection::Descending)));
+        assert_eq! (config.group_sorts.get ("doc"), Some (&(SortOrder::Date, SortDirection::Ascending)));
+        assert! (!config.group_sorts.contains_key ("bin"));
+        assert! (!config.last_parse_result.has_issues());
+
+        assert! (make_config (Some ("groupsort:video=d")).last_parse_result.has_issues());
+        assert! (make_config (Some ("groupsort:img=x")).last_parse_result.has_issues());
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  env_var_group_labels
+    //
+    //  Verify grouplabel:.ext= records a label with an optional color,
+    //  keyed by lowercase extension, and bad entries record an error.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn env_var_group_labels () {
+        let config = make_config (Some ("grouplabel:.cpp=C++ sources;GroupLabel:.H=Headers,LightCyan"));
+        assert_eq! (config.group_labels.get (".cpp"), Some (&("C++ sources".to_string(), None)));
+        assert_eq! (config.group_labels.get (".h"), Some (&("Headers".to_string(), Some (FC_LIGHT_CYAN))));
+        assert! (!config.last_parse_result.has_issues());
+
+        assert! (make_config (Some ("grouplabel:cpp=C++")).last_parse_result.has_issues());
+        assert! (make_config (Some ("grouplabel:.cpp=")).last_parse_result.has_issues());
+        assert! (make_config (Some ("grouplabel:.cpp=C++,Plaid")).last_parse_result.has_issues());
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  light_background_preset_replaces_dark_defaults
+    //
+    //  Verify /Light puts default text on a white background and swaps
+    //  bright foregrounds that wash out on white for dark ones.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn light_background_preset_replaces_dark_defaults () {
+        let mut config = Config::new();
+        config.light_background = true;
+        config.initialize_with_provider (FC_LIGHT_GREY, &MockEnvironmentProvider::new());
+
+        assert_eq! (config.attributes[Attribute::Default as usize],              FC_BLACK | BC_WHITE);
+        assert_eq! (config.attributes[Attribute::Size as usize],                 FC_BROWN | BC_WHITE);
+        assert_eq! (config.attributes[Attribute::InformationHighlight as usize], FC_BLACK | BC_WHITE);
+        assert_eq! (config.attributes[Attribute::Directory as usize],            FC_BLUE  | BC_WHITE);
+        assert_eq! (config.attributes[Attribute::Date as usize],                 FC_RED   | BC_WHITE);
+        assert_eq! (config.extension_colors[".cpp"], FC_GREEN);
+        assert_eq! (config.file_attr_colors[&0x4000].attr, FC_GREEN);
+
+        let dark = make_config (None);
+        assert_eq! (dark.attributes[Attribute::Default as usize], FC_LIGHT_GREY);
+        assert_eq! (dark.extension_colors[".cpp"], FC_LIGHT_GREEN);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  light_background_preset_yields_to_env_overrides
+    //
+    //  Verify RCDIR settings are applied on top of the /Light preset.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn light_background_preset_yields_to_env_overrides () {
+        let mut mock = MockEnvironmentProvider::new();
+        mock.set (RCDIR_ENV_VAR_NAME, "D=Yellow;.cpp=LightCyan");
+
+        let mut config = Config::new();
+        config.light_background = true;
+        config.initialize_with_provider (FC_LIGHT_GREY, &mock);
+
+        assert_eq! (config.attributes[Attribute::Date as usize] & FC_MASK, FC_YELLOW);
+        assert_eq! (config.extension_colors[".cpp"], FC_LIGHT_CYAN);
+        assert_eq! (config.attributes[Attribute::Default as usize], FC_BLACK | BC_WHITE);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  env_var_mode_sets_output_mode
+    //
+    //  Verify RCDIR=Mode=<style> sets the default listing style (case-
+    //  insensitively), and an unknown style records an error.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn env_var_mode_sets_output_mode () {
+        let config = make_config (Some ("Mode=tree"));
+        assert_eq! (config.output_mode, Some (OutputMode::Tree));
+        assert! (!config.last_parse_result.has_issues());
+
+        let config = make_config (Some ("W;Mode=Normal"));
+        assert_eq! (config.output_mode, Some (OutputMode::Normal));
+        assert_eq! (config.wide_listing, Some (true));
+
+        let config = make_config (Some ("Mode=json"));
+        assert_eq! (config.output_mode, None);
+        assert! (config.last_parse_result.has_issues());
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  glyphs_required_restores_suppressed_icons
+    //
+    //  Verify that with glyphs_required, entries whose icon is suppressed
+    //  (by extension, well-known directory, or category) fall back to the
+    //  default directory or file icon, and configured icons are kept.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn glyphs_required_restores_suppressed_icons () {
+        let mut cfg = make_config (Some (".txt=Red,;icons:dir=off"));
+        assert! (cfg.last_parse_result.errors.is_empty());
+        cfg.well_known_dir_icons.insert ("docs".to_string(), '\0');
+
+        let suppressed = cfg.get_display_style_for_file (&make_file ("notes.txt", 0x20, 0));
+        assert_eq! (suppressed.icon_code_point, None);
+
+        cfg.glyphs_required = true;
+
+        let style = cfg.get_display_style_for_file (&make_file ("notes.txt", 0x20, 0));
+        assert_eq! (style.icon_code_point, Some (cfg.icon_file_default));
+        assert! (!style.icon_suppressed);
+
+        for name in ["src", "docs"] {
+            let style = cfg.get_display_style_for_file (&make_file (name, FILE_ATTRIBUTE_DIRECTORY, 0));
+            assert_eq! (style.icon_code_point, Some (cfg.icon_directory_default), "{name}");
+            assert! (!style.icon_suppressed);
+        }
+
+        let rust_icon = cfg.extension_icons.get (".rs").copied();
+        let style     = cfg.get_display_style_for_file (&make_file ("main.rs", 0x20, 0));
+        assert_eq! (style.icon_code_point, rust_icon);
+    }
 }
\ No newline at end of file
@@ -5,6 +5,8 @@
 // Color changes are ANSI SGR (Select Graphic Rendition) sequences inline in
 // the buffer.
 // The entire buffer is flushed in one WriteConsoleW / WriteFile call.
+// Redirected output is always UTF-8; /Bom prefixes a file with a BOM so
+// editors like Notepad don't guess a legacy code page.
 
 use std::sync::Arc;
 
@@ -13,11 +15,14 @@
     STD_OUTPUT_HANDLE, ENABLE_VIRTUAL_TERMINAL_PROCESSING,
     CONSOLE_SCREEN_BUFFER_INFO,
 };
-use windows::Win32::Storage::FileSystem::WriteFile;
+use windows::Win32::Storage::FileSystem::{GetFileType, WriteFile, FILE_TYPE_DISK};
 
 use crate::ansi_codes;
+use crate::command_line::ColorMode;
 use crate::config::{Config, Attribute};
 use crate::ehm::AppError;
+use crate::environment_provider::{DefaultEnvironmentProvider, EnvironmentProvider};
+use crate::progress::ProgressIndicator;
 
 
 
@@ -26,6 +31,12 @@
 /// Initial buffer capacity: 10 MB (matches TCDir's s_kcchInitialBufferSize)
 const INITIAL_BUFFER_SIZE: usize = 10 * 1024 * 1024;
 
+/// Cross-tool convention (no-color.org): when present, disable color.
+pub const NO_COLOR_ENV_VAR_NAME: &str = "NO_COLOR";
+
+/// UTF-8 byte order mark written ahead of file output under /Bom.
+pub const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+
 
 
 
@@ -37,6 +48,9 @@ pub struct Console {
     console_width: u32,
     config:        Arc<Config>,
     prev_attr:     Option<u16>,
+    color_enabled: bool,
+    progress:      Option<Arc<ProgressIndicator>>,
+    bom_pending:   bool,
 }
 
 
@@ -90,14 +104,40 @@ pub fn initialize(config: Arc<Config>) -> Result<Self, AppError> {
             }
         }
 
-        Ok(Console {
+        let mut console = Console {
             buffer: String::with_capacity(INITIAL_BUFFER_SIZE),
             stdout_handle,
             is_redirected,
             console_width,
             config,
             prev_attr: None,
-        })
+            color_enabled: true,
+            progress: None,
+            bom_pending: false,
+        };
+
+        console.apply_no_color_env (&DefaultEnvironmentProvider);
+        Ok(console)
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  apply_no_color_env
+    //
+    //  Disable color if NO_COLOR is set (any value, even empty).  An
+    //  explicit --Color switch, applied afterwards via set_color_mode,
+    //  takes precedence.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn apply_no_color_env(&mut self, env: &dyn EnvironmentProvider) {
+        if env.get_env_var (NO_COLOR_ENV_VAR_NAME).is_some() {
+            self.color_enabled = false;
+        }
     }
 
 
@@ -153,6 +193,62 @@ pub fn config_arc(&self) -> Arc<Config> {
 
 
 
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  set_progress
+    //
+    //  Attach (or detach) a /Progress indicator whose stderr line is
+    //  erased before every flush.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn set_progress(&mut self, progress: Option<Arc<ProgressIndicator>>) {
+        self.progress = progress;
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  set_color_mode
+    //
+    //  Enable or disable ANSI color output.  Auto enables color only when
+    //  stdout is a console (not redirected to a file or pipe).
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn set_color_mode(&mut self, mode: ColorMode) {
+        self.color_enabled = match mode {
+            ColorMode::Always => true,
+            ColorMode::Never  => false,
+            ColorMode::Auto   => !self.is_redirected,
+        };
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  escapes_enabled
+    //
+    //  True if non-color escape sequences (cursor movement, screen
+    //  clearing) may be written: stdout is a console and color hasn't been
+    //  turned off by /NoColor, NO_COLOR, --Tsv or --Jsonl.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn escapes_enabled(&self) -> bool {
+        !self.is_redirected && self.color_enabled
+    }
+
+
+
+
+
     ////////////////////////////////////////////////////////////////////////////
     //
     //  set_color
@@ -166,7 +262,7 @@ pub fn config_arc(&self) -> Arc<Config> {
     ////////////////////////////////////////////////////////////////////////////
 
     pub fn set_color(&mut self, attr: u16) {
-        if self.prev_attr == Some(attr) {
+        if !self.color_enabled || self.prev_attr == Some(attr) {
             return;
         }
         self.prev_attr = Some(attr);
@@ -454,6 +550,26 @@ pub fn print_colorful_string(&mut self, text: &str) {
 
 
 
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  set_bom
+    //
+    //  Request a UTF-8 BOM ahead of the first write (/Bom).  Only applies
+    //  when stdout is redirected to a disk file; consoles and pipes are
+    //  left alone.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn set_bom(&mut self, enabled: bool) {
+        self.bom_pending = enabled
+            && self.is_redirected
+            && unsafe { GetFileType (self.stdout_handle) } == FILE_TYPE_DISK;
+    }
+
+
+
+
+
     ////////////////////////////////////////////////////////////////////////////
     //
     //  flush
@@ -473,6 +589,11 @@ pub fn flush(&mut self) -> Result<(), AppError> {
             return Ok(());
         }
 
+        // Erase the /Progress line so listing output starts at column 0
+        if let Some (progress) = &self.progress {
+            progress.clear();
+        }
+
         if !self.is_redirected {
             // Real console: convert to UTF-16 and use WriteConsoleW
             let wide: Vec<u16> = self.buffer.encode_utf16().collect();
@@ -489,6 +610,14 @@ pub fn flush(&mut self) -> Result<(), AppError> {
             // Redirected: write UTF-8 bytes via WriteFile
             let bytes = self.buffer.as_bytes();
             let mut written = 0u32;
+
+            if self.bom_pending {
+                unsafe {
+                    WriteFile(self.stdout_handle, Some(UTF8_BOM), Some(&mut written), None)?;
+                }
+                self.bom_pending = false;
+            }
+
             unsafe {
                 WriteFile(
                     self.stdout_handle,
@@ -543,6 +672,9 @@ pub(crate) fn new_for_testing (config: Arc<Config>) -> Console {
             console_width: 120,
             config,
             prev_attr:     None,
+            color_enabled: true,
+            progress:      None,
+            bom_pending:   false,
         }
     }
 
@@ -622,7 +754,9 @@ fn process_multiline_string(&mut self, text: &str, attr: u16) {
 impl Drop for Console {
     fn drop(&mut self) {
         // Append reset sequence and flush on drop
-        self.buffer.push_str(ansi_codes::RESET_ALL);
+        if self.color_enabled {
+            self.buffer.push_str(ansi_codes::RESET_ALL);
+        }
         let _ = self.flush();
     }
 }
@@ -634,6 +768,8 @@ fn drop(&mut self) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::environment_provider::MockEnvironmentProvider;
+    use crate::test_support::TempDir;
 
     /// Helper: create a Console for testing with default-initialized Config.
     fn make_test_console() -> Console {
@@ -1112,4 +1248,191 @@ fn process_multiline_string_resets_color_at_newlines() {
         let plain = strip_ansi (&buf);
         assert_eq! (plain, "line1\nline2");
     }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  escapes_disabled_when_redirected_or_colorless
+    //
+    //  Verify screen-control escapes need both a real console and color:
+    //  a redirected console never gets them, even with ColorMode::Always,
+    //  and ColorMode::Never turns them off on a console.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn escapes_disabled_when_redirected_or_colorless () {
+        let mut con = make_test_console();
+        assert! (!con.escapes_enabled());
+
+        con.set_color_mode (ColorMode::Always);
+        assert! (!con.escapes_enabled());
+
+        con.is_redirected = false;
+        assert! (con.escapes_enabled());
+
+        con.set_color_mode (ColorMode::Never);
+        assert! (!con.escapes_enabled());
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  color_mode_never_emits_no_escape_sequences
+    //
+    //  Verify ColorMode::Never suppresses all SGR sequences.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn color_mode_never_emits_no_escape_sequences () {
+        let mut con = make_test_console();
+        con.set_color_mode (ColorMode::Never);
+        con.color_puts ("{Error}bad{Information} news\nline2");
+        let buf = con.take_test_buffer();
+
+        assert! (!buf.contains ('\x1b'));
+        assert_eq! (buf, "bad news\nline2\n");
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  color_mode_auto_follows_redirection
+    //
+    //  Verify ColorMode::Auto disables color when output is redirected
+    //  (the test console is always redirected), while Always keeps it.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn color_mode_auto_follows_redirection () {
+        let mut con = make_test_console();
+        con.set_color_mode (ColorMode::Auto);
+        con.printf (0x0C, "red");
+        assert_eq! (con.take_test_buffer(), "red");
+
+        con.set_color_mode (ColorMode::Always);
+        con.printf (0x0C, "red");
+        assert! (con.take_test_buffer().contains ("\x1b["));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  no_color_env_disables_color
+    //
+    //  Verify NO_COLOR (even with an empty value) disables color output.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn no_color_env_disables_color () {
+        let mut mock = MockEnvironmentProvider::new();
+        mock.set (NO_COLOR_ENV_VAR_NAME, "");
+
+        let mut con = make_test_console();
+        con.apply_no_color_env (&mock);
+        con.color_puts ("{Error}bad{Information} news");
+
+        assert_eq! (con.take_test_buffer(), "bad news\n");
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  no_color_env_absent_keeps_color
+    //
+    //  Verify color stays on when NO_COLOR is not set.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn no_color_env_absent_keeps_color () {
+        let mut con = make_test_console();
+        con.apply_no_color_env (&MockEnvironmentProvider::new());
+        con.color_puts ("{Error}bad");
+
+        assert! (con.take_test_buffer().contains ('\x1b'));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  explicit_color_always_overrides_no_color_env
+    //
+    //  Verify an explicit --Color=Always applied after NO_COLOR re-enables
+    //  color (run() applies the switch after Console::initialize).
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn explicit_color_always_overrides_no_color_env () {
+        let mut mock = MockEnvironmentProvider::new();
+        mock.set (NO_COLOR_ENV_VAR_NAME, "1");
+
+        let mut con = make_test_console();
+        con.apply_no_color_env (&mock);
+        con.set_color_mode (ColorMode::Always);
+        con.color_puts ("{Error}bad");
+
+        assert! (con.take_test_buffer().contains ('\x1b'));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  bom_prefixes_file_output
+    //
+    //  Verify /Bom output redirected to a file begins with EF BB BF, is
+    //  followed by the UTF-8 text, and is written only once.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn bom_prefixes_file_output () {
+        use std::os::windows::io::AsRawHandle;
+
+        let temp = TempDir::new ("bom");
+        let path = temp.path().join ("out.txt");
+        let file = std::fs::File::create (&path).unwrap();
+
+        let mut console = make_test_console();
+        console.set_color_mode (ColorMode::Never);
+        console.stdout_handle = windows::Win32::Foundation::HANDLE (file.as_raw_handle());
+        console.set_bom (true);
+
+        console.write_raw ("Caf\u{e9} ");
+        console.flush().unwrap();
+        console.write_raw ("\u{f07b}\n");
+        console.flush().unwrap();
+        drop (console);
+        drop (file);
+
+        let bytes = std::fs::read (&path).unwrap();
+
+        assert_eq! (&bytes[..3], UTF8_BOM);
+        assert_eq! (std::str::from_utf8 (&bytes[3..]).unwrap(), "Caf\u{e9} \u{f07b}\n");
+    }
 }
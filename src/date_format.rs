@@ -0,0 +1,438 @@
+// date_format.rs — Custom date/time column patterns (/DateFmt)
+//
+// A /DateFmt pattern such as "yyyy-MM-dd HH:mm:ss" is split at its first
+// time token (h, H, m, s, t outside quotes): the part before goes to
+// GetDateFormatEx and the rest to GetTimeFormatEx, so both halves use the
+// documented Win32 picture syntax and keep their own column colors.  The
+// pattern is validated once at startup by formatting a sample time.
+
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::{FILETIME, SYSTEMTIME};
+use windows::Win32::Globalization::{GetDateFormatEx, GetTimeFormatEx, ENUM_DATE_FORMATS_FLAGS, TIME_FORMAT_FLAGS};
+use windows::Win32::System::Time::{FileTimeToSystemTime, SystemTimeToTzSpecificLocalTime};
+
+
+
+
+
+/// Width of the default "MM/dd/yyyy  hh:mm tt " date/time column.
+pub const DEFAULT_DATE_TIME_WIDTH: usize = 21;
+
+/// Width of the default "MM/dd/yyyy  " date part, before the time.
+pub const DEFAULT_DATE_PART_WIDTH: usize = 12;
+
+/// Width of the /T:both age suffix, "(364d ago)" plus the trailing space.
+pub const RELATIVE_AGE_WIDTH: usize = 11;
+
+/// Formatting buffer size (UTF-16 units), ample for any sane pattern.
+const FORMAT_BUFFER_LEN: usize = 256;
+
+/// Sample time used to validate patterns and size the column: a two-digit
+/// month and day, a PM hour, and nonzero seconds.
+const SAMPLE_TIME: SYSTEMTIME = SYSTEMTIME {
+    wYear:         2000,
+    wMonth:        12,
+    wDayOfWeek:    4,
+    wDay:          28,
+    wHour:         23,
+    wMinute:       59,
+    wSecond:       59,
+    wMilliseconds: 0,
+};
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  split_date_time_pattern
+//
+//  Split a pattern at its first unquoted time token (h, H, m, s, t).
+//  Returns (date part, time part); either may be empty.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn split_date_time_pattern(pattern: &str) -> (&str, &str) {
+    let mut in_quote = false;
+
+    for (i, ch) in pattern.char_indices() {
+        match ch {
+            '\''                                  => in_quote = !in_quote,
+            'h' | 'H' | 'm' | 's' | 't' if !in_quote => return (&pattern[..i], &pattern[i..]),
+            _                                     => {}
+        }
+    }
+
+    (pattern, "")
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  format_system_time
+//
+//  Format a local SYSTEMTIME with a /DateFmt pattern.  Returns the date
+//  and time halves, or None if Win32 rejects either half.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn format_system_time(st: &SYSTEMTIME, pattern: &str) -> Option<(String, String)> {
+    let (date_pattern, time_pattern) = split_date_time_pattern (pattern);
+
+    let date = if date_pattern.is_empty() { String::new() } else { format_date_part (st, date_pattern)? };
+    let time = if time_pattern.is_empty() { String::new() } else { format_time_part (st, time_pattern)? };
+
+    Some ((date, time))
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  filetime_to_local
+//
+//  Convert a FILETIME (as u64, UTC) to a local SYSTEMTIME.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn filetime_to_local(filetime_u64: u64) -> Option<SYSTEMTIME> {
+    let ft = FILETIME {
+        dwLowDateTime:  (filetime_u64 & 0xFFFF_FFFF) as u32,
+        dwHighDateTime: ((filetime_u64 >> 32) & 0xFFFF_FFFF) as u32,
+    };
+
+    let mut st       = SYSTEMTIME::default();
+    let mut st_local = SYSTEMTIME::default();
+
+    unsafe {
+        FileTimeToSystemTime (&ft, &mut st).ok()?;
+        SystemTimeToTzSpecificLocalTime (None, &st, &mut st_local).ok()?;
+    }
+
+    Some (st_local)
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  is_valid_pattern
+//
+//  True if the pattern formats successfully (checked once at startup).
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn is_valid_pattern(pattern: &str) -> bool {
+    !pattern.is_empty() && format_system_time (&SAMPLE_TIME, pattern).is_some()
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  column_width
+//
+//  Visual width of the date/time column including its trailing space:
+//  DEFAULT_DATE_TIME_WIDTH without /DateFmt, else the width of the sample
+//  time rendered with the pattern.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn column_width(pattern: Option<&str>) -> usize {
+    let Some (pattern) = pattern else {
+        return DEFAULT_DATE_TIME_WIDTH;
+    };
+
+    match format_system_time (&SAMPLE_TIME, pattern) {
+        Some ((date, time)) => date.chars().count() + time.chars().count() + 1,
+        None                => DEFAULT_DATE_TIME_WIDTH,
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  date_part_width
+//
+//  Visual width of the date half of the column (including the separator
+//  before the time), so headers can place "Time" over the time half.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn date_part_width(pattern: Option<&str>) -> usize {
+    let Some (pattern) = pattern else {
+        return DEFAULT_DATE_PART_WIDTH;
+    };
+
+    match format_system_time (&SAMPLE_TIME, pattern) {
+        Some ((date, _)) => date.chars().count(),
+        None             => DEFAULT_DATE_PART_WIDTH,
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  format_date_part
+//
+//  GetDateFormatEx with an explicit picture string (user default locale).
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn format_date_part(st: &SYSTEMTIME, pattern: &str) -> Option<String> {
+    let pattern_wide: Vec<u16> = pattern.encode_utf16().chain (Some (0)).collect();
+    let mut buf = [0u16; FORMAT_BUFFER_LEN];
+
+    let len = unsafe {
+        GetDateFormatEx (
+            PCWSTR (std::ptr::null()),
+            ENUM_DATE_FORMATS_FLAGS (0),
+            Some (st),
+            PCWSTR (pattern_wide.as_ptr()),
+            Some (&mut buf),
+            None,
+        )
+    };
+
+    (len > 0).then (|| String::from_utf16_lossy (&buf[..(len as usize - 1)]))
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  format_time_part
+//
+//  GetTimeFormatEx with an explicit picture string (user default locale).
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn format_time_part(st: &SYSTEMTIME, pattern: &str) -> Option<String> {
+    let pattern_wide: Vec<u16> = pattern.encode_utf16().chain (Some (0)).collect();
+    let mut buf = [0u16; FORMAT_BUFFER_LEN];
+
+    let len = unsafe {
+        GetTimeFormatEx (
+            PCWSTR (std::ptr::null()),
+            TIME_FORMAT_FLAGS (0),
+            Some (st),
+            PCWSTR (pattern_wide.as_ptr()),
+            Some (&mut buf),
+        )
+    };
+
+    (len > 0).then (|| String::from_utf16_lossy (&buf[..(len as usize - 1)]))
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  format_relative_age
+//
+//  The /T:both suffix: how long before `now` a FILETIME is, in the largest
+//  whole unit, e.g. "(3d ago)".  Times at or after `now` (clock skew,
+//  future timestamps) show as "(just now)".
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn format_relative_age(filetime_u64: u64, now: u64) -> String {
+    const TICKS_PER_SECOND: u64 = 10_000_000;   // FILETIME is in 100 ns units
+    const MINUTE: u64 = 60;
+    const HOUR:   u64 = 60 * MINUTE;
+    const DAY:    u64 = 24 * HOUR;
+    const YEAR:   u64 = 365 * DAY;
+
+    let seconds = now.saturating_sub (filetime_u64) / TICKS_PER_SECOND;
+
+    if seconds == 0 {
+        return "(just now)".to_string();
+    }
+
+    let (value, unit) = if seconds >= YEAR {
+        (seconds / YEAR, 'y')
+    } else if seconds >= DAY {
+        (seconds / DAY, 'd')
+    } else if seconds >= HOUR {
+        (seconds / HOUR, 'h')
+    } else if seconds >= MINUTE {
+        (seconds / MINUTE, 'm')
+    } else {
+        (seconds, 's')
+    };
+
+    format! ("({}{} ago)", value, unit)
+}
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2024-01-17 09:05:03 (a Wednesday).
+    const MORNING: SYSTEMTIME = SYSTEMTIME {
+        wYear:         2024,
+        wMonth:        1,
+        wDayOfWeek:    3,
+        wDay:          17,
+        wHour:         9,
+        wMinute:       5,
+        wSecond:       3,
+        wMilliseconds: 0,
+    };
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  split_at_first_time_token
+    //
+    //  Verify patterns split before the first h/H/m/s/t, ignoring quoted
+    //  text and the month token M.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn split_at_first_time_token() {
+        assert_eq! (split_date_time_pattern ("yyyy-MM-dd HH:mm:ss"), ("yyyy-MM-dd ", "HH:mm:ss"));
+        assert_eq! (split_date_time_pattern ("dd'at'hh"), ("dd'at'", "hh"));
+        assert_eq! (split_date_time_pattern ("yyyyMMdd"), ("yyyyMMdd", ""));
+        assert_eq! (split_date_time_pattern ("HH:mm"), ("", "HH:mm"));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  custom_pattern_changes_rendered_string
+    //
+    //  Verify an ISO-style pattern renders differently from the default
+    //  MM/dd/yyyy hh:mm tt layout, with each half formatted.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn custom_pattern_changes_rendered_string() {
+        let (date, time) = format_system_time (&MORNING, "yyyy-MM-dd HH:mm:ss").unwrap();
+        assert_eq! (date, "2024-01-17 ");
+        assert_eq! (time, "09:05:03");
+
+        let (date, time) = format_system_time (&MORNING, "dd.MM.yy").unwrap();
+        assert_eq! (date, "17.01.24");
+        assert_eq! (time, "");
+
+        let (default_date, _) = format_system_time (&MORNING, "MM/dd/yyyy  hh:mm tt").unwrap();
+        assert_ne! (default_date, "2024-01-17 ");
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  column_width_follows_pattern
+    //
+    //  Verify the default width is unchanged and custom patterns size the
+    //  column from the rendered sample plus the trailing space.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn column_width_follows_pattern() {
+        assert_eq! (column_width (None), DEFAULT_DATE_TIME_WIDTH);
+        assert_eq! (column_width (Some ("yyyy-MM-dd HH:mm:ss")), 20);
+        assert_eq! (column_width (Some ("yyyyMMdd")), 9);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  empty_pattern_is_invalid
+    //
+    //  Verify an empty pattern is rejected and a normal one accepted.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn empty_pattern_is_invalid() {
+        assert! (!is_valid_pattern (""));
+        assert! (is_valid_pattern ("yyyy-MM-dd HH:mm"));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  date_part_width_follows_pattern
+    //
+    //  Verify the date half width for the default layout and for patterns
+    //  with and without a date part.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn date_part_width_follows_pattern() {
+        assert_eq! (date_part_width (None), DEFAULT_DATE_PART_WIDTH);
+        assert_eq! (date_part_width (Some ("yyyy-MM-dd HH:mm:ss")), 11);
+        assert_eq! (date_part_width (Some ("HH:mm")), 0);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  relative_age_picks_largest_whole_unit
+    //
+    //  Verify ages truncate to the largest whole unit, future times show
+    //  as just now, and the longest common age fits the column.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn relative_age_picks_largest_whole_unit() {
+        const SECOND: u64 = 10_000_000;
+        const DAY:    u64 = 86_400 * SECOND;
+        let now = 133_500_000_000_000_000u64;
+
+        assert_eq! (format_relative_age (now - 45 * SECOND, now),        "(45s ago)");
+        assert_eq! (format_relative_age (now - 3 * DAY - SECOND, now),   "(3d ago)");
+        assert_eq! (format_relative_age (now - 800 * DAY, now),          "(2y ago)");
+        assert_eq! (format_relative_age (now + SECOND, now),             "(just now)");
+        assert! (format_relative_age (now - 364 * DAY, now).len() < RELATIVE_AGE_WIDTH);
+    }
+}
@@ -0,0 +1,283 @@
+// dir_diff.rs — /Diff: list entries missing from (or changed in) another directory
+//
+// The other directory is read once up front into a name index; the
+// listers then keep only entries whose name is not in the index, so the
+// difference goes through the normal sort/display/totals path unchanged.
+// Names compare case-insensitively, like the file system.  With /DiffMeta,
+// files present in both directories are also listed when their size or
+// last-write time differs.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::ehm::AppError;
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Name index of the /Diff reference directory.
+#[derive(Debug, Clone, Default)]
+pub struct DiffReference {
+    /// Lowercase name → (size, last-write FILETIME); None for directories
+    entries:          HashMap<String, Option<(u64, u64)>>,
+    compare_metadata: bool,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl DiffReference
+//
+//  Building the reference index and testing entries against it.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl DiffReference {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  new
+    //
+    //  Create an empty index.  `compare_metadata` (/DiffMeta) also treats
+    //  same-named files with a different size or write time as different.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn new(compare_metadata: bool) -> Self {
+        DiffReference { entries: HashMap::new(), compare_metadata }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  load
+    //
+    //  Index every entry of `dir` (not recursive).  A missing or unreadable
+    //  directory is a PathNotFound error.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn load(dir: &Path, compare_metadata: bool) -> Result<Self, AppError> {
+        use std::os::windows::fs::MetadataExt;
+
+        let read_dir = std::fs::read_dir (dir).map_err (|_| AppError::PathNotFound (dir.to_path_buf()))?;
+        let mut reference = Self::new (compare_metadata);
+
+        for entry in read_dir.flatten() {
+            let Ok (metadata) = entry.metadata() else {
+                continue;
+            };
+
+            let name = entry.file_name().to_string_lossy().into_owned();
+
+            if metadata.is_dir() {
+                reference.insert_directory (&name);
+            } else {
+                reference.insert_file (&name, metadata.file_size(), metadata.last_write_time());
+            }
+        }
+
+        Ok (reference)
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  insert_file
+    //
+    //  Add a file to the index.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn insert_file(&mut self, name: &str, size: u64, last_write_time: u64) {
+        self.entries.insert (name.to_lowercase(), Some ((size, last_write_time)));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  insert_directory
+    //
+    //  Add a directory to the index (matched by name only).
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn insert_directory(&mut self, name: &str) {
+        self.entries.insert (name.to_lowercase(), None);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  is_different
+    //
+    //  True if an entry of the listed directory should be shown: its name
+    //  is not in the reference directory, or (with /DiffMeta) both are
+    //  files and the size or last-write time differs.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn is_different(&self, name: &str, is_directory: bool, size: u64, last_write_time: u64) -> bool {
+        match self.entries.get (&name.to_lowercase()) {
+            None                   => true,
+            Some (None)            => false,
+            Some (Some (metadata)) => {
+                self.compare_metadata && !is_directory && *metadata != (size, last_write_time)
+            }
+        }
+    }
+}
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Synthetic directory contents: (name, is_directory, size, time).
+    const LEFT: &[(&str, bool, u64, u64)] = &[
+        ("readme.md",  false, 100, 10),
+        ("main.rs",    false, 200, 20),
+        ("lib.rs",     false, 300, 30),
+        ("src",        true,  0,   0),
+        ("build",      true,  0,   0),
+    ];
+
+    const RIGHT: &[(&str, bool, u64, u64)] = &[
+        ("README.md",  false, 100, 10),
+        ("main.rs",    false, 250, 20),
+        ("src",        true,  0,   0),
+        ("extra.txt",  false, 5,   50),
+    ];
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  index_of
+    //
+    //  Helper: build a reference index from synthetic contents.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn index_of(contents: &[(&str, bool, u64, u64)], compare_metadata: bool) -> DiffReference {
+        let mut reference = DiffReference::new (compare_metadata);
+
+        for &(name, is_directory, size, time) in contents {
+            if is_directory {
+                reference.insert_directory (name);
+            } else {
+                reference.insert_file (name, size, time);
+            }
+        }
+
+        reference
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  only_here
+    //
+    //  Helper: names in `here` that the index of `there` reports as
+    //  different.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn only_here(
+        here: &[(&'static str, bool, u64, u64)],
+        there: &[(&str, bool, u64, u64)],
+        compare_metadata: bool,
+    ) -> Vec<&'static str> {
+        let reference = index_of (there, compare_metadata);
+
+        here.iter()
+            .filter (|&&(name, is_directory, size, time)| reference.is_different (name, is_directory, size, time))
+            .map (|&(name, ..)| name)
+            .collect()
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  name_diff_lists_entries_missing_from_other
+    //
+    //  Verify a name-only diff keeps entries absent from the other side
+    //  (case-insensitively) and ignores size/time changes.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn name_diff_lists_entries_missing_from_other() {
+        assert_eq! (only_here (LEFT, RIGHT, false), vec!["lib.rs", "build"]);
+        assert_eq! (only_here (RIGHT, LEFT, false), vec!["extra.txt"]);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  metadata_diff_adds_changed_files
+    //
+    //  Verify /DiffMeta also lists same-named files whose size or time
+    //  differs, but never same-named directories.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn metadata_diff_adds_changed_files() {
+        assert_eq! (only_here (LEFT, RIGHT, true), vec!["main.rs", "lib.rs", "build"]);
+
+        let reference = index_of (RIGHT, true);
+        assert! (reference.is_different ("README.md", false, 100, 11));
+        assert! (!reference.is_different ("readme.MD", false, 100, 10));
+        assert! (!reference.is_different ("src", true, 0, 99));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  identical_directories_have_no_difference
+    //
+    //  Verify diffing contents against themselves yields nothing.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn identical_directories_have_no_difference() {
+        assert! (only_here (LEFT, LEFT, true).is_empty());
+    }
+}
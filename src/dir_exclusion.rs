@@ -0,0 +1,204 @@
+// dir_exclusion.rs — /XD directory-exclude pattern matching
+//
+// Decides whether recursion should skip a subdirectory.  A pattern with
+// no path separator matches a directory *name* at any depth (`/XD:obj`
+// prunes every `obj`).  A pattern with a separator matches the directory's
+// path relative to the listing root (`/XD:src\generated` prunes only that
+// one).  Each component is a glob (`*`, `?`, `[a-c]`) matched by
+// mask_grouper::Matcher; matching is case-insensitive, like the file system.
+
+use std::ffi::OsStr;
+use std::path::{Component, Path};
+
+use crate::mask_grouper::Matcher;
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  is_excluded_dir
+//
+//  True if the directory at `relative_path` (relative to the listing root,
+//  never empty) matches any of the /XD patterns.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn is_excluded_dir(patterns: &[String], relative_path: &Path) -> bool {
+    if patterns.is_empty() {
+        return false;
+    }
+
+    let path_parts: Vec<String> = relative_path.components()
+        .filter_map (|c| match c {
+            Component::Normal (part) => Some (part.to_string_lossy().to_string()),
+            _                        => None,
+        })
+        .collect();
+
+    let Some (name) = path_parts.last() else {
+        return false;
+    };
+
+    patterns.iter().any (|pattern| {
+        let pattern_parts = split_pattern (pattern);
+
+        match pattern_parts.len() {
+            0 => false,
+            1 => Matcher::new (pattern_parts[0]).matches (OsStr::new (name)),
+            _ => pattern_parts.len() == path_parts.len()
+                && pattern_parts.iter().zip (&path_parts).all (|(p, s)| Matcher::new (p).matches (OsStr::new (s))),
+        }
+    })
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  split_pattern
+//
+//  Split an /XD pattern into components on '\' or '/', dropping empty
+//  and "." components (so "src\gen\", ".\src\gen" and "src/gen" are the
+//  same pattern).
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn split_pattern(pattern: &str) -> Vec<&str> {
+    pattern.split (['\\', '/'])
+        .filter (|part| !part.is_empty() && *part != ".")
+        .collect()
+}
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  patterns
+    //
+    //  Helper: build an owned pattern list.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn patterns(list: &[&str]) -> Vec<String> {
+        list.iter().map (|s| s.to_string()).collect()
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  name_pattern_matches_at_any_depth
+    //
+    //  Verify a separator-free pattern prunes every directory with that
+    //  name, regardless of depth or case.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn name_pattern_matches_at_any_depth() {
+        let xd = patterns (&["generated"]);
+
+        assert! (is_excluded_dir (&xd, Path::new ("generated")));
+        assert! (is_excluded_dir (&xd, Path::new ("src\\generated")));
+        assert! (is_excluded_dir (&xd, Path::new ("lib\\deep\\Generated")));
+        assert! (!is_excluded_dir (&xd, Path::new ("src")));
+        assert! (!is_excluded_dir (&xd, Path::new ("generated_old")));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  path_pattern_matches_only_that_path
+    //
+    //  Verify a pattern with a separator prunes only the directory at that
+    //  relative path, not same-named directories elsewhere.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn path_pattern_matches_only_that_path() {
+        let xd = patterns (&["src\\generated"]);
+
+        assert! (is_excluded_dir (&xd, Path::new ("src\\generated")));
+        assert! (is_excluded_dir (&xd, Path::new ("SRC\\Generated")));
+        assert! (!is_excluded_dir (&xd, Path::new ("generated")));
+        assert! (!is_excluded_dir (&xd, Path::new ("lib\\generated")));
+        assert! (!is_excluded_dir (&xd, Path::new ("other\\src\\generated")));
+        assert! (!is_excluded_dir (&xd, Path::new ("src")));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  path_pattern_separator_forms_are_equivalent
+    //
+    //  Verify forward slashes, a leading ".\" and a trailing separator do
+    //  not change what a path pattern matches.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn path_pattern_separator_forms_are_equivalent() {
+        for pattern in ["src/generated", ".\\src\\generated", "src\\generated\\"] {
+            let xd = patterns (&[pattern]);
+            assert! (is_excluded_dir (&xd, Path::new ("src\\generated")), "{}", pattern);
+            assert! (!is_excluded_dir (&xd, Path::new ("lib\\generated")), "{}", pattern);
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  wildcards_match_within_components
+    //
+    //  Verify '*' and '?' glob within a single path component.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn wildcards_match_within_components() {
+        assert! (is_excluded_dir (&patterns (&["*.tmp"]), Path::new ("a\\build.tmp")));
+        assert! (is_excluded_dir (&patterns (&["obj?"]), Path::new ("obj1")));
+        assert! (!is_excluded_dir (&patterns (&["obj?"]), Path::new ("obj")));
+        assert! (is_excluded_dir (&patterns (&["src\\*"]), Path::new ("src\\anything")));
+        assert! (!is_excluded_dir (&patterns (&["src\\*"]), Path::new ("src\\a\\b")));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  no_patterns_excludes_nothing
+    //
+    //  Verify an empty pattern list never excludes.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn no_patterns_excludes_nothing() {
+        assert! (!is_excluded_dir (&[], Path::new ("anything")));
+    }
+}
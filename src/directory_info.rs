@@ -33,6 +33,7 @@ pub enum DirectoryStatus {
 pub struct DirectoryInfo {
     pub matches:             Vec<FileInfo>,
     pub dir_path:            PathBuf,
+    pub relative_path:       PathBuf,
     pub file_specs:          Vec<String>,
     pub largest_file_size:   u64,
     pub largest_file_name:   usize,
@@ -41,6 +42,8 @@ pub struct DirectoryInfo {
     pub stream_count:        u32,
     pub bytes_used:          u64,
     pub stream_bytes_used:   u64,
+    /// Every entry in the directory, matching or not (only for /ShowFiltered)
+    pub entries_scanned:     u32,
 
     // Multithreading support
     pub status:              DirectoryStatus,
@@ -78,6 +81,7 @@ pub fn new(dir_path: PathBuf, file_spec: String) -> Self {
         DirectoryInfo {
             matches:                 Vec::new(),
             dir_path,
+            relative_path:           PathBuf::new(),
             file_specs:              vec![file_spec],
             largest_file_size:       0,
             largest_file_name:       0,
@@ -86,6 +90,7 @@ pub fn new(dir_path: PathBuf, file_spec: String) -> Self {
             stream_count:            0,
             bytes_used:              0,
             stream_bytes_used:       0,
+            entries_scanned:         0,
             status:                  DirectoryStatus::Waiting,
             error:                   None,
             children:                Vec::new(),
@@ -111,6 +116,7 @@ pub fn new_multi(dir_path: PathBuf, file_specs: Vec<String>) -> Self {
         DirectoryInfo {
             matches:                 Vec::new(),
             dir_path,
+            relative_path:           PathBuf::new(),
             file_specs,
             largest_file_size:       0,
             largest_file_name:       0,
@@ -119,6 +125,7 @@ pub fn new_multi(dir_path: PathBuf, file_specs: Vec<String>) -> Self {
             stream_count:            0,
             bytes_used:              0,
             stream_bytes_used:       0,
+            entries_scanned:         0,
             status:                  DirectoryStatus::Waiting,
             error:                   None,
             children:                Vec::new(),
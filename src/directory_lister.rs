@@ -5,10 +5,10 @@
 // Core enumeration loop: FindFirstFileW/FindNextFileW, attribute filtering,
 // match collection, size/count tracking, stream collection delegation.
 
-use std::ffi::OsStr;
-use std::os::windows::ffi::OsStrExt;
+use std::collections::HashSet;
+use std::ffi::{OsStr, OsString};
+use std::os::windows::ffi::{OsStrExt, OsStringExt};
 use std::path::Path;
-use std::sync::Arc;
 
 use windows::Win32::Storage::FileSystem::{
     FindFirstFileW, FindNextFileW, WIN32_FIND_DATAW,
@@ -16,10 +16,20 @@
 
 use crate::command_line::CommandLine;
 use crate::config::Config;
+use crate::dir_exclusion::is_excluded_dir;
 use crate::directory_info::DirectoryInfo;
-use crate::file_info::{FileInfo, FindHandle, FILE_ATTRIBUTE_DIRECTORY};
+use crate::encoding;
+use crate::file_comparator;
+use crate::file_id;
+use crate::file_type;
+use crate::file_info::{FileInfo, FindHandle, FILE_ATTRIBUTE_DIRECTORY, FILE_ATTRIBUTE_REPARSE_POINT};
+use crate::hashing;
 use crate::listing_totals::ListingTotals;
+use crate::mask_grouper::{self, Matcher};
+use crate::on_disk_size;
+use crate::owner;
 use crate::streams;
+use crate::version_info;
 
 
 
@@ -44,7 +54,62 @@ pub fn collect_matching_files(
     di: &mut DirectoryInfo,
     cmd: &CommandLine,
     totals: &mut ListingTotals,
-    _config: &Arc<Config>,
+    config: &Config,
+) {
+    collect_matches (dir_path, file_spec, di, cmd, totals, config, None);
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  list_directory_into
+//
+//  Collect the matches for dir_path/file_spec into `di` and sort them in
+//  listing order.  This is the one enumeration path behind both
+//  list_directory and the single-threaded listing.  With `seen` (/Dedupe),
+//  entries whose (case-insensitive) name is already there are skipped and
+//  every entry added is recorded, so sharing one set across the specs for
+//  a directory lists each entry once even when the masks overlap.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn list_directory_into(
+    dir_path: &Path,
+    file_spec: &OsStr,
+    di: &mut DirectoryInfo,
+    cmd: &CommandLine,
+    totals: &mut ListingTotals,
+    config: &Config,
+    seen: Option<&mut HashSet<String>>,
+) {
+    collect_matches (dir_path, file_spec, di, cmd, totals, config, seen);
+    file_comparator::sort_files (&mut di.matches, cmd, false);
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  collect_matches
+//
+//  Shared enumeration loop behind collect_matching_files and
+//  list_directory_into.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn collect_matches(
+    dir_path: &Path,
+    file_spec: &OsStr,
+    di: &mut DirectoryInfo,
+    cmd: &CommandLine,
+    totals: &mut ListingTotals,
+    _config: &Config,
+    mut seen: Option<&mut HashSet<String>>,
 ) {
     // Build the search path: dir_path/file_spec
     let mut search_path = dir_path.to_path_buf();
@@ -56,6 +121,7 @@ pub fn collect_matching_files(
     let mut wfd = WIN32_FIND_DATAW::default();
 
     // FindFirstFileW
+    crate::run_stats::RUN_STATS.record_directory_opened();
     let handle = unsafe { FindFirstFileW(windows::core::PCWSTR(search_wide.as_ptr()), &mut wfd) };
 
     let handle = match handle {
@@ -64,18 +130,25 @@ pub fn collect_matching_files(
     };
 
     let _find_handle = FindHandle(handle);
+    let literal = literal_name_filter(file_spec, cmd);
 
     loop {
-        // Skip "." and ".." entries
-        if !is_dots(&wfd.cFileName) {
-            // Apply attribute filters: required attrs must all be present,
-            // excluded attrs must all be absent
-            let attrs = wfd.dwFileAttributes;
-            let required_ok = (attrs & cmd.attrs_required) == cmd.attrs_required;
-            let excluded_ok = (attrs & cmd.attrs_excluded) == 0;
-
-            if required_ok && excluded_ok {
-                add_match_to_list(&wfd, di, totals, cmd);
+        // Skip "." and ".." entries (unless /ShowDots); apply /A attribute filters
+        if !is_skipped_dots(&wfd.cFileName, cmd) {
+            crate::run_stats::RUN_STATS.record_file_enumerated();
+
+            // /Dedupe: skip names an earlier spec already listed (checked
+            // last so only names that actually matched are recorded)
+            let matched = passes_literal_filter(&wfd, literal.as_ref())
+                && passes_entry_filters(&wfd, cmd)
+                && passes_owner_filter(dir_path, &wfd, cmd)
+                && seen.as_deref_mut().is_none_or(|seen| {
+                    let name_len = wfd.cFileName.iter().position(|&c| c == 0).unwrap_or(0);
+                    seen.insert(OsString::from_wide(&wfd.cFileName[..name_len]).to_string_lossy().to_lowercase())
+                });
+
+            if matched {
+                add_match_to_list(&wfd, file_spec, di, totals, cmd);
             }
         }
 
@@ -86,10 +159,52 @@ pub fn collect_matching_files(
         }
     }
 
+    // Count every entry, matching or not, for "showing N of M" (/ShowFiltered)
+    if cmd.show_filtered {
+        di.entries_scanned = count_directory_entries(dir_path);
+    }
+
     // Enumerate alternate data streams if --streams enabled
     if cmd.show_streams {
         streams::enumerate_streams(di, totals);
     }
+
+    // Keep only stream-bearing entries if --HasStreams enabled
+    if cmd.has_streams {
+        streams::retain_stream_bearing(di, totals, cmd.tree.unwrap_or(false));
+    }
+
+    // Hash file contents if --Hash enabled
+    if let Some(algorithm) = cmd.hash_algorithm {
+        hashing::hash_matches(di, algorithm);
+    }
+
+    // Read NTFS file IDs if --FileId enabled (opens a handle per entry)
+    if cmd.show_file_id {
+        file_id::read_file_ids(di, &file_id::DefaultFileIdProvider);
+    }
+
+    // Guess text encodings if --Encoding enabled (reads each local text file)
+    if cmd.show_encoding {
+        encoding::read_encodings(di);
+    }
+
+    // Read PE version resources if --VerInfo enabled (opens each .exe/.dll/.sys)
+    if cmd.show_version {
+        version_info::read_version_info(di, &version_info::DefaultVersionInfoProvider);
+    }
+
+    // Look up shell type descriptions if --Desc enabled (once per extension per run)
+    if cmd.show_file_type {
+        file_type::read_file_types(di, file_type::default_cache());
+    }
+
+    // Swap in allocated sizes of sparse/compressed files if --OnDisk enabled;
+    // the logical sizes were already added to the totals
+    if cmd.on_disk {
+        let delta = on_disk_size::read_on_disk_sizes(di, &on_disk_size::DefaultOnDiskSizeProvider);
+        totals.file_bytes = totals.file_bytes.saturating_add_signed(-delta);
+    }
 }
 
 
@@ -98,62 +213,91 @@ pub fn collect_matching_files(
 
 ////////////////////////////////////////////////////////////////////////////////
 //
-//  add_match_to_list
+//  list_directory
 //
-//  Add a matched file entry to the DirectoryInfo.  Tracks directory/file
-//  counts, sizes, and widest filename.
+//  Enumerate and sort the entries matching dir_path/file_spec through the
+//  same list_directory_into a listing uses (filters, streams, hashes, sort
+//  order), but return them instead of displaying anything.  For
+//  programmatic use of the crate and for tests that assert on results
+//  directly.
 //
-//  Port of: CDirectoryLister::AddMatchToList
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn list_directory(dir_path: &Path, file_spec: &OsStr, cmd: &CommandLine, config: &Config) -> Vec<FileInfo> {
+    let mut di     = DirectoryInfo::new (dir_path.to_path_buf(), file_spec.to_string_lossy().into_owned());
+    let mut totals = ListingTotals::default();
+
+    list_directory_into (dir_path, file_spec, &mut di, cmd, &mut totals, config, None);
+
+    di.matches
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  count_matching_entries
+//
+//  Count entries matching dir_path/file_spec and the entry filters,
+//  optionally across all subdirectories.  Used by --Count: no FileInfo is
+//  built and no reparse targets are resolved.  Streams are only looked up
+//  under /HasStreams, so the count matches the entries
+//  collect_matching_files would have kept.
 //
 ////////////////////////////////////////////////////////////////////////////////
 
-fn add_match_to_list(
-    wfd: &WIN32_FIND_DATAW,
-    di: &mut DirectoryInfo,
-    totals: &mut ListingTotals,
-    cmd: &CommandLine,
-) {
-    let mut file_entry = FileInfo::from_find_data(wfd);
+pub fn count_matching_entries(dir_path: &Path, file_spec: &OsStr, cmd: &CommandLine, recursive: bool) -> u64 {
+    count_matching_entries_under(dir_path, Path::new(""), file_spec, cmd, recursive)
+}
 
-    // Resolve reparse target (symlink/junction/AppExecLink) — empty string if not applicable
-    file_entry.reparse_target = crate::reparse_resolver::resolve_reparse_target (&di.dir_path, &file_entry);
 
-    // Track filename length for wide listing
-    let file_name_len = if cmd.wide_listing {
-        let name_len = wfd.cFileName.iter().position(|&c| c == 0).unwrap_or(0);
-        if (wfd.dwFileAttributes & FILE_ATTRIBUTE_DIRECTORY) != 0 {
-            // Directories in wide listing get brackets: [dirname]
-            name_len + 2
-        } else {
-            name_len
-        }
-    } else {
-        0
-    };
 
-    if (wfd.dwFileAttributes & FILE_ATTRIBUTE_DIRECTORY) != 0 {
-        // Directory match
-        di.subdirectory_count += 1;
-    } else {
-        // File match — track sizes
-        let file_size = file_entry.file_size;
 
-        if file_size > di.largest_file_size {
-            di.largest_file_size = file_size;
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  count_matching_entries_under
+//
+//  Recursive worker for count_matching_entries.  relative_path is
+//  dir_path relative to the listing root, for /XD path matching.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn count_matching_entries_under(dir_path: &Path, relative_path: &Path, file_spec: &OsStr, cmd: &CommandLine, recursive: bool) -> u64 {
+    let mut count = 0;
+    let literal = literal_name_filter(file_spec, cmd);
+
+    for_each_find_data(&dir_path.join(file_spec), |wfd| {
+        if !is_skipped_dots(&wfd.cFileName, cmd) && passes_literal_filter(wfd, literal.as_ref()) && passes_entry_filters(wfd, cmd) && passes_owner_filter(dir_path, wfd, cmd) && passes_stream_filter(dir_path, wfd, cmd) {
+            count += 1;
         }
+    });
 
-        di.bytes_used += file_size;
-        di.file_count += 1;
+    if recursive {
+        let mut subdirs = Vec::new();
 
-        totals.file_bytes += file_size;
-        totals.file_count += 1;
-    }
+        for_each_find_data(&dir_path.join("*"), |wfd| {
+            if (wfd.dwFileAttributes & FILE_ATTRIBUTE_DIRECTORY) != 0
+                && !is_dots(&wfd.cFileName)
+                && passes_reparse_filter(wfd.dwFileAttributes, cmd)
+            {
+                let name_len = wfd.cFileName.iter().position(|&c| c == 0).unwrap_or(0);
+                subdirs.push(OsString::from_wide(&wfd.cFileName[..name_len]));
+            }
+        });
 
-    if cmd.wide_listing && file_name_len > di.largest_file_name {
-        di.largest_file_name = file_name_len;
+        for subdir in subdirs {
+            let subdir_relative_path = relative_path.join(&subdir);
+
+            if !is_excluded_dir(&cmd.exclude_dirs, &subdir_relative_path) {
+                count += count_matching_entries_under(&dir_path.join(&subdir), &subdir_relative_path, file_spec, cmd, true);
+            }
+        }
     }
 
-    di.matches.push(file_entry);
+    count
 }
 
 
@@ -162,99 +306,1043 @@ fn add_match_to_list(
 
 ////////////////////////////////////////////////////////////////////////////////
 //
-//  is_dots
-//
-//  Check if a filename is "." or "..".
+//  count_directory_entries
 //
-//  Port of: CDirectoryLister::IsDots
+//  Count every entry in dir_path except "." and "..", ignoring masks and
+//  filters.  The denominator of /ShowFiltered's "showing N of M".
 //
 ////////////////////////////////////////////////////////////////////////////////
 
-fn is_dots(filename: &[u16]) -> bool {
-    if filename[0] == b'.' as u16 {
-        if filename[1] == 0 {
-            return true; // "."
+pub(crate) fn count_directory_entries(dir_path: &Path) -> u32 {
+    let mut count = 0;
+
+    for_each_find_data(&dir_path.join("*"), |wfd| {
+        if !is_dots(&wfd.cFileName) {
+            count += 1;
         }
-        if filename[1] == b'.' as u16 && filename[2] == 0 {
-            return true; // ".."
+    });
+
+    count
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  for_each_find_data
+//
+//  Run FindFirstFileW/FindNextFileW over search_path, calling visit for
+//  every entry (including "." and "..").  Does nothing if nothing matches.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn for_each_find_data(search_path: &Path, mut visit: impl FnMut(&WIN32_FIND_DATAW)) {
+    let search_wide: Vec<u16> = search_path.as_os_str().encode_wide().chain(Some(0)).collect();
+
+    let mut wfd = WIN32_FIND_DATAW::default();
+    crate::run_stats::RUN_STATS.record_directory_opened();
+    let handle = unsafe { FindFirstFileW(windows::core::PCWSTR(search_wide.as_ptr()), &mut wfd) };
+
+    let handle = match handle {
+        Ok(h) if !h.is_invalid() => h,
+        _ => return,
+    };
+
+    let _find_handle = FindHandle(handle);
+
+    loop {
+        visit(&wfd);
+
+        let success = unsafe { FindNextFileW(handle, &mut wfd) };
+        if success.is_err() {
+            break;
         }
     }
-    false
 }
 
 
 
 
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+////////////////////////////////////////////////////////////////////////////////
+//
+//  literal_name_filter
+//
+//  Under /Literal, a spec with no * or ? names one entry exactly.  The
+//  returned matcher keeps only entries whose long name equals the spec,
+//  so '[' and ']' are plain characters and FindFirstFileW's 8.3 short-
+//  name matches are dropped.  None when every search result is kept.
+//
+////////////////////////////////////////////////////////////////////////////////
 
-    ////////////////////////////////////////////////////////////////////////////
-    //
-    //  is_dots_single_dot
-    //
-    //  Verify "." is detected as a dot entry.
-    //
-    ////////////////////////////////////////////////////////////////////////////
+pub(crate) fn literal_name_filter(file_spec: &OsStr, cmd: &CommandLine) -> Option<Matcher> {
+    let spec = file_spec.to_str()?;
 
-    #[test]
-    fn is_dots_single_dot() {
-        let name = [b'.' as u16, 0, 0, 0];
-        assert!(is_dots(&name));
-    }
+    (cmd.literal && !mask_grouper::has_wildcards(spec)).then(|| Matcher::literal(spec))
+}
 
 
 
 
 
-    ////////////////////////////////////////////////////////////////////////////
-    //
-    //  is_dots_double_dot
-    //
-    //  Verify ".." is detected as a dot entry.
-    //
-    ////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////
+//
+//  passes_literal_filter
+//
+//  Apply a literal_name_filter result to a find result.
+//
+////////////////////////////////////////////////////////////////////////////////
 
-    #[test]
-    fn is_dots_double_dot() {
-        let name = [b'.' as u16, b'.' as u16, 0, 0];
-        assert!(is_dots(&name));
-    }
+pub(crate) fn passes_literal_filter(wfd: &WIN32_FIND_DATAW, filter: Option<&Matcher>) -> bool {
+    filter.is_none_or(|matcher| {
+        let name_len = wfd.cFileName.iter().position(|&c| c == 0).unwrap_or(0);
+        matcher.matches(&OsString::from_wide(&wfd.cFileName[..name_len]))
+    })
+}
 
 
 
 
 
-    ////////////////////////////////////////////////////////////////////////////
-    //
-    //  is_dots_regular_name
-    //
-    //  Verify a regular filename is not detected as a dot entry.
-    //
-    ////////////////////////////////////////////////////////////////////////////
+////////////////////////////////////////////////////////////////////////////////
+//
+//  passes_entry_filters
+//
+//  Apply every per-entry filter (/A attributes, /NoReparse, /Newer,
+//  /Older, /Diff, /Contains, /XSZ) to a find result.
+//
+////////////////////////////////////////////////////////////////////////////////
 
-    #[test]
-    fn is_dots_regular_name() {
-        let name = [b'f' as u16, b'o' as u16, b'o' as u16, 0];
-        assert!(!is_dots(&name));
+pub(crate) fn passes_entry_filters(wfd: &WIN32_FIND_DATAW, cmd: &CommandLine) -> bool {
+    let last_write_time = ((wfd.ftLastWriteTime.dwHighDateTime as u64) << 32)
+                        | (wfd.ftLastWriteTime.dwLowDateTime as u64);
+
+    passes_attribute_filter(wfd.dwFileAttributes, cmd)
+        && passes_reparse_filter(wfd.dwFileAttributes, cmd)
+        && passes_time_filter(last_write_time, cmd)
+        && passes_diff_filter(wfd, last_write_time, cmd)
+        && passes_contains_filter(wfd, cmd)
+        && passes_size_exclusion(wfd, cmd)
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  passes_size_exclusion
+//
+//  Apply /XSZ: drop files whose size falls in any excluded band.
+//  Directories are never excluded by size.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn passes_size_exclusion(wfd: &WIN32_FIND_DATAW, cmd: &CommandLine) -> bool {
+    if cmd.exclude_sizes.is_empty() || (wfd.dwFileAttributes & FILE_ATTRIBUTE_DIRECTORY) != 0 {
+        return true;
     }
 
+    let size = ((wfd.nFileSizeHigh as u64) << 32) | (wfd.nFileSizeLow as u64);
 
+    !cmd.exclude_sizes.iter().any(|band| band.contains(size))
+}
 
 
 
-    ////////////////////////////////////////////////////////////////////////////
-    //
-    //  is_dots_dotfile
-    //
-    //  Verify a dotfile (e.g. ".git") is not detected as a dot entry.
-    //
-    ////////////////////////////////////////////////////////////////////////////
 
-    #[test]
-    fn is_dots_dotfile() {
-        let name = [b'.' as u16, b'g' as u16, b'i' as u16, b't' as u16, 0];
-        assert!(!is_dots(&name));
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  passes_contains_filter
+//
+//  Apply /Contains: the name must contain the text, ignoring case.  Glob
+//  characters in the text have no special meaning.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn passes_contains_filter(wfd: &WIN32_FIND_DATAW, cmd: &CommandLine) -> bool {
+    let Some(text) = &cmd.contains_text else {
+        return true;
+    };
+
+    let name_len = wfd.cFileName.iter().position(|&c| c == 0).unwrap_or(wfd.cFileName.len());
+    let name = String::from_utf16_lossy(&wfd.cFileName[..name_len]);
+
+    // The text was lowercased when /Contains was parsed
+    name.to_lowercase().contains(text.as_str())
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  passes_diff_filter
+//
+//  Apply /Diff: keep only entries missing from (or, with /DiffMeta,
+//  changed in) the reference directory.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn passes_diff_filter(wfd: &WIN32_FIND_DATAW, last_write_time: u64, cmd: &CommandLine) -> bool {
+    let Some(reference) = &cmd.diff_reference else {
+        return true;
+    };
+
+    let name_len = wfd.cFileName.iter().position(|&c| c == 0).unwrap_or(wfd.cFileName.len());
+    let name = OsString::from_wide(&wfd.cFileName[..name_len]);
+    let size = ((wfd.nFileSizeHigh as u64) << 32) | (wfd.nFileSizeLow as u64);
+    let is_directory = (wfd.dwFileAttributes & FILE_ATTRIBUTE_DIRECTORY) != 0;
+
+    reference.is_different(&name.to_string_lossy(), is_directory, size, last_write_time)
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  passes_time_filter
+//
+//  Apply /Newer and /Older: the entry must be strictly newer (older)
+//  than the reference file's last-write time, like find -newer.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn passes_time_filter(last_write_time: u64, cmd: &CommandLine) -> bool {
+    let newer_ok = cmd.newer_than.is_none_or(|t| last_write_time > t);
+    let older_ok = cmd.older_than.is_none_or(|t| last_write_time < t);
+
+    newer_ok && older_ok
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  passes_attribute_filter
+//
+//  Apply /A filters: required attrs must all be present, excluded attrs
+//  must all be absent.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn passes_attribute_filter(attrs: u32, cmd: &CommandLine) -> bool {
+    let required_ok = (attrs & cmd.attrs_required) == cmd.attrs_required;
+    let excluded_ok = (attrs & cmd.attrs_excluded) == 0;
+
+    required_ok && excluded_ok
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  passes_reparse_filter
+//
+//  Apply /NoReparse: drop symlinks, junctions, and cloud placeholders.
+//  The recursive walks use it too, so they never descend into a
+//  reparse-point directory under /NoReparse.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(crate) fn passes_reparse_filter(attrs: u32, cmd: &CommandLine) -> bool {
+    !cmd.no_reparse || (attrs & FILE_ATTRIBUTE_REPARSE_POINT) == 0
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  passes_owner_filter
+//
+//  Apply /Mine: keep entries whose owner SID is the current user's.
+//  Reads each entry's security descriptor, so it runs after the cheaper
+//  filters.  Directories are kept in tree mode, which needs them for
+//  structure.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(crate) fn passes_owner_filter(dir_path: &Path, wfd: &WIN32_FIND_DATAW, cmd: &CommandLine) -> bool {
+    if !cmd.mine {
+        return true;
+    }
+
+    if cmd.tree.unwrap_or(false) && (wfd.dwFileAttributes & FILE_ATTRIBUTE_DIRECTORY) != 0 {
+        return true;
+    }
+
+    let name_len = wfd.cFileName.iter().position(|&c| c == 0).unwrap_or(wfd.cFileName.len());
+    let path = dir_path.join(OsString::from_wide(&wfd.cFileName[..name_len]));
+
+    owner::is_owned_by(&owner::DefaultOwnerSidProvider, &path, owner::current_user_sid())
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  passes_stream_filter
+//
+//  Apply /HasStreams while counting: keep files with at least one
+//  alternate data stream.  Directories never carry streams, so as in
+//  streams::retain_stream_bearing they are dropped except in tree mode.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn passes_stream_filter(dir_path: &Path, wfd: &WIN32_FIND_DATAW, cmd: &CommandLine) -> bool {
+    if !cmd.has_streams {
+        return true;
+    }
+
+    if (wfd.dwFileAttributes & FILE_ATTRIBUTE_DIRECTORY) != 0 {
+        return cmd.tree.unwrap_or(false);
+    }
+
+    let name_len = wfd.cFileName.iter().position(|&c| c == 0).unwrap_or(wfd.cFileName.len());
+    let path = dir_path.join(OsString::from_wide(&wfd.cFileName[..name_len]));
+
+    !streams::enumerate_file_streams(path.as_os_str()).is_empty()
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  add_match_to_list
+//
+//  Add a matched file entry to the DirectoryInfo.  Tracks directory/file
+//  counts, sizes, and widest filename.
+//
+//  Port of: CDirectoryLister::AddMatchToList
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn add_match_to_list(
+    wfd: &WIN32_FIND_DATAW,
+    file_spec: &OsStr,
+    di: &mut DirectoryInfo,
+    totals: &mut ListingTotals,
+    cmd: &CommandLine,
+) {
+    let mut file_entry = FileInfo::from_find_data(wfd);
+
+    // Resolve reparse target (symlink/junction/AppExecLink) — empty string if not applicable
+    file_entry.reparse_target = crate::reparse_resolver::resolve_reparse_target (&di.dir_path, &file_entry, cmd.show_target);
+
+    // Remember which spec matched for /ShowMask
+    if cmd.show_mask {
+        file_entry.matched_spec = Some(file_spec.to_string_lossy().into_owned());
+    }
+
+    // Track filename length for wide listing
+    let file_name_len = if cmd.wide_listing {
+        let name_len = wfd.cFileName.iter().position(|&c| c == 0).unwrap_or(0);
+        if (wfd.dwFileAttributes & FILE_ATTRIBUTE_DIRECTORY) != 0 {
+            // Directories in wide listing get brackets: [dirname]
+            name_len + 2
+        } else {
+            name_len
+        }
+    } else {
+        0
+    };
+
+    if (wfd.dwFileAttributes & FILE_ATTRIBUTE_DIRECTORY) != 0 {
+        // Directory match
+        di.subdirectory_count += 1;
+    } else {
+        // File match — track sizes
+        let file_size = file_entry.file_size;
+
+        if file_size > di.largest_file_size {
+            di.largest_file_size = file_size;
+        }
+
+        di.bytes_used += file_size;
+        di.file_count += 1;
+
+        totals.file_bytes += file_size;
+        totals.file_count += 1;
+    }
+
+    // Link inventory for the recursive summary
+    totals.count_reparse_point(file_entry.file_attributes, file_entry.reparse_tag);
+
+    if cmd.wide_listing && file_name_len > di.largest_file_name {
+        di.largest_file_name = file_name_len;
+    }
+
+    di.matches.push(file_entry);
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  is_skipped_dots
+//
+//  Check if a filename is "." or ".." and should be left out of the
+//  listing.  /ShowDots lists them as entries; recursion enumerates
+//  subdirectories separately and never descends into them.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(crate) fn is_skipped_dots(filename: &[u16], cmd: &CommandLine) -> bool {
+    !cmd.show_dots && is_dots(filename)
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  is_dots
+//
+//  Check if a filename is "." or "..".
+//
+//  Port of: CDirectoryLister::IsDots
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn is_dots(filename: &[u16]) -> bool {
+    if filename[0] == b'.' as u16 {
+        if filename[1] == 0 {
+            return true; // "."
+        }
+        if filename[1] == b'.' as u16 && filename[2] == 0 {
+            return true; // ".."
+        }
+    }
+    false
+}
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::test_support::TempDir;
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  is_dots_single_dot
+    //
+    //  Verify "." is detected as a dot entry.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn is_dots_single_dot() {
+        let name = [b'.' as u16, 0, 0, 0];
+        assert!(is_dots(&name));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  is_dots_double_dot
+    //
+    //  Verify ".." is detected as a dot entry.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn is_dots_double_dot() {
+        let name = [b'.' as u16, b'.' as u16, 0, 0];
+        assert!(is_dots(&name));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  is_dots_regular_name
+    //
+    //  Verify a regular filename is not detected as a dot entry.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn is_dots_regular_name() {
+        let name = [b'f' as u16, b'o' as u16, b'o' as u16, 0];
+        assert!(!is_dots(&name));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  is_dots_dotfile
+    //
+    //  Verify a dotfile (e.g. ".git") is not detected as a dot entry.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn is_dots_dotfile() {
+        let name = [b'.' as u16, b'g' as u16, b'i' as u16, b't' as u16, 0];
+        assert!(!is_dots(&name));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  make_count_fixture
+    //
+    //  Creates a unique temp directory containing a.txt, b.txt, c.log, and
+    //  sub\d.txt, sub\e.log, removed when the returned guard drops.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn make_count_fixture(tag: &str) -> TempDir {
+        let fixture = TempDir::new(&format!("count_{}", tag));
+        let root = fixture.path();
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+
+        for name in ["a.txt", "b.txt", "c.log", "sub\\d.txt", "sub\\e.log"] {
+            std::fs::write(root.join(name), b"x").unwrap();
+        }
+
+        fixture
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  count_matches_collected_entries
+    //
+    //  Verify count_matching_entries equals the number of FileInfo entries
+    //  collect_matching_files produces, for a mask and attribute filter.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn count_matches_collected_entries() {
+        let fixture = make_count_fixture("flat");
+        let root = fixture.path();
+        let config = Arc::new(Config::new());
+
+        for args in [vec![], vec!["/a:-d"], vec!["/a:d"]] {
+            for spec in ["*", "*.txt"] {
+                let cmd = CommandLine::parse_from(args.clone()).unwrap();
+                let mut di = DirectoryInfo::new(root.to_path_buf(), spec.to_string());
+                let mut totals = ListingTotals::default();
+                collect_matching_files(root, OsStr::new(spec), &mut di, &cmd, &mut totals, &config);
+
+                let count = count_matching_entries(root, OsStr::new(spec), &cmd, false);
+                assert_eq!(count, di.matches.len() as u64, "args {:?}, spec {}", args, spec);
+            }
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  count_recursive_includes_subdirectories
+    //
+    //  Verify recursive counting adds matches from subdirectories.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn count_recursive_includes_subdirectories() {
+        let fixture = make_count_fixture("recursive");
+        let root = fixture.path();
+        let cmd = CommandLine::parse_from(Vec::<&str>::new()).unwrap();
+
+        assert_eq!(count_matching_entries(root, OsStr::new("*.txt"), &cmd, false), 2);
+        assert_eq!(count_matching_entries(root, OsStr::new("*.txt"), &cmd, true), 3);
+        assert_eq!(count_matching_entries(root, OsStr::new("*"), &cmd, true), 6); // 5 files + "sub"
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  count_recursive_skips_excluded_directories
+    //
+    //  Verify /XD prunes by name anywhere, but a path pattern only prunes
+    //  the directory at that relative path.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn count_recursive_skips_excluded_directories() {
+        let fixture = make_count_fixture("exclude");
+        let root = fixture.path();
+        std::fs::create_dir_all(root.join("other\\sub")).unwrap();
+        std::fs::write(root.join("other\\sub\\f.txt"), b"x").unwrap();
+
+        let by_name = CommandLine::parse_from(["/XD:sub"]).unwrap();
+        assert_eq!(count_matching_entries(root, OsStr::new("*.txt"), &by_name, true), 2);
+
+        let by_path = CommandLine::parse_from(["/XD:other\\sub"]).unwrap();
+        assert_eq!(count_matching_entries(root, OsStr::new("*.txt"), &by_path, true), 3);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  count_has_streams_keeps_stream_bearing_files
+    //
+    //  Verify /HasStreams counts only files with alternate data streams,
+    //  agreeing with the entries collect_matching_files keeps.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn count_has_streams_keeps_stream_bearing_files() {
+        let fixture = make_count_fixture("streams");
+        let root = fixture.path();
+        std::fs::write(root.join("a.txt:note"), b"tag").unwrap();
+        std::fs::write(root.join("sub\\e.log:note"), b"tag").unwrap();
+
+        let cmd = CommandLine::parse_from(["/HasStreams"]).unwrap();
+        let config = Config::new();
+        let mut di = DirectoryInfo::new(root.to_path_buf(), "*".to_string());
+        let mut totals = ListingTotals::default();
+        collect_matching_files(root, OsStr::new("*"), &mut di, &cmd, &mut totals, &config);
+
+        assert_eq!(di.matches.len(), 1);
+        assert_eq!(count_matching_entries(root, OsStr::new("*"), &cmd, false), 1);
+        assert_eq!(count_matching_entries(root, OsStr::new("*"), &cmd, true), 2);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  time_filter_compares_against_reference_times
+    //
+    //  Verify /Newer keeps only strictly newer entries, /Older only
+    //  strictly older ones, and both together form an open interval.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn time_filter_compares_against_reference_times() {
+        const REFERENCE: u64 = 133_500_000_000_000_000;
+
+        let mut cmd = CommandLine::default();
+        assert!(passes_time_filter(REFERENCE, &cmd));
+
+        cmd.newer_than = Some(REFERENCE);
+        assert!(passes_time_filter(REFERENCE + 1, &cmd));
+        assert!(!passes_time_filter(REFERENCE, &cmd));
+        assert!(!passes_time_filter(REFERENCE - 1, &cmd));
+
+        cmd.newer_than = None;
+        cmd.older_than = Some(REFERENCE);
+        assert!(passes_time_filter(REFERENCE - 1, &cmd));
+        assert!(!passes_time_filter(REFERENCE, &cmd));
+
+        cmd.newer_than = Some(REFERENCE - 10);
+        assert!(passes_time_filter(REFERENCE - 5, &cmd));
+        assert!(!passes_time_filter(REFERENCE - 10, &cmd));
+        assert!(!passes_time_filter(REFERENCE + 5, &cmd));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  recent_window_includes_only_entries_inside_window
+    //
+    //  Verify /Recent:7d keeps an entry written just inside the window and
+    //  drops one written just outside it.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn recent_window_includes_only_entries_inside_window() {
+        const NOW: u64 = 133_500_000_000_000_000;
+
+        let mut cmd = CommandLine::parse_from(["/Recent:7d"]).unwrap();
+        let window = cmd.recent_window.unwrap();
+        cmd.apply_recent_window(NOW);
+
+        assert!(passes_time_filter(NOW, &cmd));
+        assert!(passes_time_filter(NOW - window + 1, &cmd));
+        assert!(!passes_time_filter(NOW - window, &cmd));
+        assert!(!passes_time_filter(NOW - window - 1, &cmd));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  list_directory_returns_sorted_matches
+    //
+    //  Verify list_directory returns the filtered, sorted entries of a
+    //  temp directory: directories first, /O order honored, masks and
+    //  attribute filters applied.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn list_directory_returns_sorted_matches() {
+        let temp = TempDir::new("list_api");
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+        std::fs::write(root.join("b.txt"), b"1234567890").unwrap();
+        std::fs::write(root.join("a.txt"), b"123").unwrap();
+        std::fs::write(root.join("c.log"), b"12345").unwrap();
+
+        let config = Config::new();
+        let names = |args: &[&str], spec: &str| -> Vec<String> {
+            let cmd = CommandLine::parse_from(args.iter().copied()).unwrap();
+            list_directory(root, OsStr::new(spec), &cmd, &config)
+                .iter()
+                .map(|fi| fi.file_name.to_string_lossy().into_owned())
+                .collect()
+        };
+
+        assert_eq!(names(&["/on"], "*"), vec!["sub", "a.txt", "b.txt", "c.log"]);
+        assert_eq!(names(&["/o-s"], "*"), vec!["sub", "b.txt", "c.log", "a.txt"]);
+        assert_eq!(names(&["/on"], "*.txt"), vec!["a.txt", "b.txt"]);
+        assert_eq!(names(&["/a:-d", "/on"], "*"), vec!["a.txt", "b.txt", "c.log"]);
+
+        let cmd = CommandLine::parse_from(["/on"]).unwrap();
+        let entries = list_directory(root, OsStr::new("*.txt"), &cmd, &config);
+        assert_eq!(entries[1].file_size, 10);
+        assert!(!entries[1].is_directory());
+
+        assert!(list_directory(root, OsStr::new("*.none"), &cmd, &config).is_empty());
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  show_mask_records_matching_spec
+    //
+    //  Verify /ShowMask stamps each collected entry with the spec that
+    //  matched it, and leaves it unset otherwise.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn show_mask_records_matching_spec() {
+        let fixture = make_count_fixture("mask");
+        let root = fixture.path();
+        let config = Arc::new(Config::new());
+
+        for (args, expected) in [(vec!["/ShowMask"], Some("*.log")), (vec![], None)] {
+            let cmd = CommandLine::parse_from(args).unwrap();
+            let mut di = DirectoryInfo::new(root.to_path_buf(), "*.log".to_string());
+            let mut totals = ListingTotals::default();
+            collect_matching_files(root, OsStr::new("*.log"), &mut di, &cmd, &mut totals, &config);
+
+            assert_eq!(di.matches.len(), 1);
+            assert_eq!(di.matches[0].matched_spec.as_deref(), expected);
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  contains_filter_matches_substring_ignoring_case
+    //
+    //  Verify /Contains:report keeps names containing the text in any
+    //  case, treats glob characters literally, and passes all without it.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn contains_filter_matches_substring_ignoring_case() {
+        let find_data = |name: &str| {
+            let mut wfd = WIN32_FIND_DATAW::default();
+            for (i, ch) in name.encode_utf16().enumerate() {
+                wfd.cFileName[i] = ch;
+            }
+            wfd
+        };
+
+        let cmd = CommandLine::parse_from(["/Contains:report"]).unwrap();
+        assert!(passes_contains_filter(&find_data("monthly_report_2024.xlsx"), &cmd));
+        assert!(passes_contains_filter(&find_data("REPORT.txt"), &cmd));
+        assert!(!passes_contains_filter(&find_data("monthly_summary.xlsx"), &cmd));
+
+        let cmd = CommandLine::parse_from(["/Contains:a*b"]).unwrap();
+        assert!(passes_contains_filter(&find_data("xa*by"), &cmd));
+        assert!(!passes_contains_filter(&find_data("axxb"), &cmd));
+
+        assert!(passes_contains_filter(&find_data("anything"), &CommandLine::default()));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  contains_composes_with_recursion
+    //
+    //  Verify /Contains filters the entries counted across subdirectories.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn contains_composes_with_recursion() {
+        let fixture = make_count_fixture("contains");
+        let root = fixture.path();
+
+        let cmd = CommandLine::parse_from(["/S", "/Contains:.LOG"]).unwrap();
+        assert_eq!(count_matching_entries(root, OsStr::new("*"), &cmd, true), 2);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  size_exclusion_drops_zero_byte_files
+    //
+    //  Verify /XSZ:0-0 drops empty files, keeps non-empty ones, and never
+    //  drops directories.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn size_exclusion_drops_zero_byte_files() {
+        let temp = TempDir::new("xsz");
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+        std::fs::write(root.join("empty.txt"), b"").unwrap();
+        std::fs::write(root.join("one.txt"), b"1").unwrap();
+
+        let config = Arc::new(Config::new());
+        let collect = |args: &[&str]| -> Vec<String> {
+            let cmd = CommandLine::parse_from(args.iter().copied()).unwrap();
+            let mut di = DirectoryInfo::new(root.to_path_buf(), "*".to_string());
+            let mut totals = ListingTotals::default();
+            collect_matching_files(root, OsStr::new("*"), &mut di, &cmd, &mut totals, &config);
+
+            let mut names: Vec<String> = di.matches.iter().map(|fi| fi.file_name.to_string_lossy().into_owned()).collect();
+            names.sort();
+            names
+        };
+
+        assert_eq!(collect(&[]), ["empty.txt", "one.txt", "sub"]);
+        assert_eq!(collect(&["/XSZ:0-0"]), ["one.txt", "sub"]);
+        assert_eq!(collect(&["/XSZ:1-1K"]), ["empty.txt", "sub"]);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  show_dots_lists_dot_entries_without_recursing
+    //
+    //  Verify "." and ".." are listed (as directories) only under /ShowDots,
+    //  and that recursion never descends into them.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn show_dots_lists_dot_entries_without_recursing() {
+        let fixture = make_count_fixture("dots");
+        let root = fixture.path();
+        let config = Arc::new(Config::new());
+
+        let collect = |args: &[&str]| -> Vec<FileInfo> {
+            let cmd = CommandLine::parse_from(args.iter().copied()).unwrap();
+            list_directory(root, OsStr::new("*"), &cmd, &config)
+        };
+
+        let plain = collect(&[]);
+        assert!(!plain.iter().any(|fi| fi.file_name == "." || fi.file_name == ".."));
+
+        let dotted = collect(&["/ShowDots"]);
+        assert_eq!(dotted.len(), plain.len() + 2);
+        for dots in [".", ".."] {
+            let entry = dotted.iter().find(|fi| fi.file_name == dots).expect(dots);
+            assert!(entry.is_directory(), "{}", dots);
+        }
+
+        // 5 files + "sub", plus "." and ".." in both root and sub
+        let cmd = CommandLine::parse_from(["/ShowDots"]).unwrap();
+        assert_eq!(count_matching_entries(root, OsStr::new("*"), &cmd, true), 10);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  show_filtered_counts_every_scanned_entry
+    //
+    //  Verify /ShowFiltered records every entry in the directory, so a
+    //  restrictive mask leaves the scanned total above the matched count.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn show_filtered_counts_every_scanned_entry() {
+        let fixture = make_count_fixture("filtered");
+        let root = fixture.path();
+        let config = Arc::new(Config::new());
+        let cmd = CommandLine::parse_from(["/ShowFiltered"]).unwrap();
+
+        let mut di = DirectoryInfo::new(root.to_path_buf(), "*.log".to_string());
+        let mut totals = ListingTotals::default();
+        collect_matching_files(root, OsStr::new("*.log"), &mut di, &cmd, &mut totals, &config);
+
+        // c.log of a.txt, b.txt, c.log, and sub
+        assert_eq!(di.matches.len(), 1);
+        assert_eq!(di.entries_scanned, 4);
+        assert!(di.entries_scanned as usize > di.matches.len());
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  literal_matches_bracketed_name_exactly
+    //
+    //  Verify /Literal lists file[1].txt as that exact name, never its
+    //  8.3 alias or the file1.txt a character class would select, and
+    //  that specs with * or ? still glob.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn literal_matches_bracketed_name_exactly() {
+        let temp = TempDir::new("literal");
+        let root = temp.path();
+        for name in ["file[1].txt", "file1.txt", "a_rather_long_name.txt"] {
+            std::fs::write(root.join(name), b"x").unwrap();
+        }
+
+        let config = Config::new();
+        let names = |args: &[&str], spec: &str| -> Vec<String> {
+            let cmd = CommandLine::parse_from(args.iter().copied()).unwrap();
+            list_directory(root, OsStr::new(spec), &cmd, &config)
+                .iter()
+                .map(|fi| fi.file_name.to_string_lossy().into_owned())
+                .collect()
+        };
+
+        assert_eq!(names(&["/Literal"], "file[1].txt"), vec!["file[1].txt"]);
+        assert_eq!(names(&["/Literal"], "FILE[1].TXT"), vec!["file[1].txt"]);
+        assert!(names(&["/Literal"], "ARATHE~1.TXT").is_empty());
+        let mut globbed = names(&["/Literal"], "file*.txt");
+        globbed.sort();
+        assert_eq!(globbed, vec!["file1.txt", "file[1].txt"]);
+
+        let cmd = CommandLine::parse_from(["/Literal"]).unwrap();
+        assert_eq!(count_matching_entries(root, OsStr::new("file[1].txt"), &cmd, false), 1);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  no_reparse_omits_reparse_points
+    //
+    //  Verify /NoReparse drops reparse-point entries (a junction here) from
+    //  the listing and from recursion, and keeps them without the flag.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn no_reparse_omits_reparse_points() {
+        let mut wfd = WIN32_FIND_DATAW { dwFileAttributes: FILE_ATTRIBUTE_DIRECTORY | FILE_ATTRIBUTE_REPARSE_POINT, ..Default::default() };
+        let no_reparse = CommandLine::parse_from(["/NoReparse"]).unwrap();
+        assert!(passes_entry_filters(&wfd, &CommandLine::default()));
+        assert!(!passes_entry_filters(&wfd, &no_reparse));
+
+        wfd.dwFileAttributes = FILE_ATTRIBUTE_DIRECTORY;
+        assert!(passes_entry_filters(&wfd, &no_reparse));
+
+        let temp = TempDir::new("no_reparse");
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("real")).unwrap();
+        std::fs::write(root.join("real\\data.txt"), b"x").unwrap();
+
+        let created = std::process::Command::new("cmd")
+            .args(["/C", "mklink", "/J"])
+            .arg(root.join("link"))
+            .arg(root.join("real"))
+            .output()
+            .is_ok_and(|output| output.status.success());
+        assert!(created, "mklink /J failed");
+
+        let config = Config::new();
+        let names = |cmd: &CommandLine| -> Vec<String> {
+            list_directory(root, OsStr::new("*"), cmd, &config)
+                .iter()
+                .map(|fi| fi.file_name.to_string_lossy().into_owned())
+                .collect()
+        };
+
+        let plain = CommandLine::parse_from(["/on"]).unwrap();
+        assert_eq!(names(&plain), vec!["link", "real"]);
+        assert_eq!(names(&CommandLine::parse_from(["/on", "/NoReparse"]).unwrap()), vec!["real"]);
+
+        assert_eq!(count_matching_entries(root, OsStr::new("*.txt"), &plain, true), 2);
+        assert_eq!(count_matching_entries(root, OsStr::new("*.txt"), &no_reparse, true), 1);
     }
 }
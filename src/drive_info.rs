@@ -2,8 +2,11 @@
 //
 // Port of: DriveInfo.h, DriveInfo.cpp → CDriveInfo
 //
-// Retrieves volume label, filesystem name, drive type, and UNC info
-// using Win32 APIs: GetVolumeInformationW, GetDriveTypeW, WNetGetConnectionW.
+// Retrieves volume label, serial number, filesystem name, capacity, drive
+// type, and UNC info using Win32 APIs: GetVolumeInformationW,
+// GetDiskFreeSpaceExW, GetDriveTypeW, WNetGetConnectionW.  Volumes mounted
+// without a drive letter are reached through their \\?\Volume{GUID}\ path,
+// which the Win32 volume APIs accept as a root directly.
 
 use std::path::{Path, PathBuf};
 
@@ -52,6 +55,8 @@ pub struct DriveInfo {
     pub volume_type:     u32,
     pub is_unc_path:     bool,
     pub remote_name:     String,
+    pub volume_serial:   Option<u32>,
+    pub total_bytes:     Option<u64>,
 }
 
 
@@ -87,9 +92,12 @@ pub fn new(dir_path: &Path) -> Result<Self, AppError> {
             volume_type:     DRIVE_UNKNOWN,
             is_unc_path:     false,
             remote_name:     String::new(),
+            volume_serial:   None,
+            total_bytes:     None,
         };
 
         info.initialize_volume_info(dir_path);
+        info.initialize_capacity_info();
         info.initialize_unc_info();
 
         Ok(info)
@@ -115,6 +123,40 @@ pub fn volume_description(&self) -> &str {
 
 
 
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  volume_serial_string
+    //
+    //  Format the volume serial number as "XXXX-XXXX" (matching dir), or
+    //  None if the serial number could not be retrieved.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn volume_serial_string(&self) -> Option<String> {
+        self.volume_serial.map (|serial| format! ("{:04X}-{:04X}", serial >> 16, serial & 0xFFFF))
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  volume_guid_root
+    //
+    //  The \\?\Volume{GUID}\ root when the listing is on a volume reached
+    //  by its GUID path rather than a drive letter.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn volume_guid_root(&self) -> Option<String> {
+        volume_guid_root(&self.root_path.to_string_lossy())
+    }
+
+
+
+
+
     ////////////////////////////////////////////////////////////////////////////
     //
     //  is_ntfs
@@ -161,6 +203,12 @@ fn initialize_volume_info(&mut self, dir_path: &Path) {
         // Get the root path (e.g., "C:\")
         // Check if it has a drive letter
         let dir_str = dir_path.to_string_lossy();
+
+        // Verbatim drive paths (\\?\C:\...) still have a drive letter
+        let dir_str = dir_str.strip_prefix("\\\\?\\")
+            .filter(|rest| rest.as_bytes().get(1) == Some(&b':'))
+            .unwrap_or(&dir_str);
+
         let has_drive_letter = dir_str.len() >= 2
             && dir_str.as_bytes()[1] == b':'
             && dir_str.as_bytes()[0].is_ascii_alphabetic();
@@ -169,15 +217,11 @@ fn initialize_volume_info(&mut self, dir_path: &Path) {
             // Local drive — extract root path "X:\"
             let drive_letter = dir_str.as_bytes()[0] as char;
             self.root_path = PathBuf::from(format!("{}:\\", drive_letter));
-
-            // Get drive type
-            if let Ok(root_wide) = U16CString::from_str(self.root_path.to_string_lossy()) {
-                self.volume_type = unsafe {
-                    windows::Win32::Storage::FileSystem::GetDriveTypeW(
-                        windows::core::PCWSTR(root_wide.as_ptr()),
-                    )
-                };
-            }
+            self.volume_type = drive_type(&self.root_path);
+        } else if let Some(guid_root) = volume_guid_root(dir_str) {
+            // Volume without a drive letter — its GUID path is the root
+            self.root_path = PathBuf::from(guid_root);
+            self.volume_type = drive_type(&self.root_path);
         } else {
             // No drive letter → UNC path
             self.is_unc_path = true;
@@ -195,12 +239,13 @@ fn initialize_volume_info(&mut self, dir_path: &Path) {
         if let Ok(root_wide) = U16CString::from_str(&*root_str) {
             let mut volume_name_buf = [0u16; 261];
             let mut fs_name_buf = [0u16; 261];
+            let mut serial = 0u32;
 
             let success = unsafe {
                 windows::Win32::Storage::FileSystem::GetVolumeInformationW(
                     windows::core::PCWSTR(root_wide.as_ptr()),
                     Some(&mut volume_name_buf),
-                    None,
+                    Some(&mut serial),
                     None,
                     None,
                     Some(&mut fs_name_buf),
@@ -215,6 +260,8 @@ fn initialize_volume_info(&mut self, dir_path: &Path) {
                 // Extract filesystem name
                 let fs_len = fs_name_buf.iter().position(|&c| c == 0).unwrap_or(fs_name_buf.len());
                 self.file_system_name = String::from_utf16_lossy(&fs_name_buf[..fs_len]);
+
+                self.volume_serial = Some(serial);
             }
         }
     }
@@ -223,6 +270,41 @@ fn initialize_volume_info(&mut self, dir_path: &Path) {
 
 
 
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  initialize_capacity_info
+    //
+    //  Query the total size of the volume.  Left as None when the root
+    //  is not queryable (e.g., some UNC shares deny access at the root).
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn initialize_capacity_info(&mut self) {
+        let root_str = self.root_path.to_string_lossy();
+        let Ok(root_wide) = U16CString::from_str(&*root_str) else {
+            return;
+        };
+
+        let mut total_bytes = 0u64;
+
+        let success = unsafe {
+            windows::Win32::Storage::FileSystem::GetDiskFreeSpaceExW(
+                windows::core::PCWSTR(root_wide.as_ptr()),
+                None,
+                Some(&mut total_bytes),
+                None,
+            )
+        };
+
+        if success.is_ok() && total_bytes > 0 {
+            self.total_bytes = Some(total_bytes);
+        }
+    }
+
+
+
+
+
     ////////////////////////////////////////////////////////////////////////////
     //
     //  initialize_unc_info
@@ -288,3 +370,91 @@ fn initialize_unc_info(&mut self) {
         }
     }
 }
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  drive_type
+//
+//  GetDriveTypeW for a root path ("C:\" or "\\?\Volume{GUID}\").
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn drive_type(root: &Path) -> u32 {
+    let Ok(root_wide) = U16CString::from_str(root.to_string_lossy()) else {
+        return DRIVE_UNKNOWN;
+    };
+
+    unsafe {
+        windows::Win32::Storage::FileSystem::GetDriveTypeW(
+            windows::core::PCWSTR(root_wide.as_ptr()),
+        )
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  volume_guid_root
+//
+//  If `path` is on a volume GUID path (\\?\Volume{GUID}\... or the
+//  \\.\ device form), return its root with a trailing backslash, as the
+//  volume APIs require.  Matching is case-insensitive.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn volume_guid_root(path: &str) -> Option<String> {
+    const VOLUME_PREFIX: &str = "Volume{";
+
+    let rest = path.strip_prefix("\\\\?\\").or_else(|| path.strip_prefix("\\\\.\\"))?;
+    let head = rest.get(..VOLUME_PREFIX.len())?;
+    if !head.eq_ignore_ascii_case(VOLUME_PREFIX) {
+        return None;
+    }
+
+    let close = rest.find('}')?;
+    if !matches!(rest.as_bytes().get(close + 1), None | Some(b'\\')) {
+        return None;
+    }
+
+    Some(format!("\\\\?\\{}\\", &rest[..=close]))
+}
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  volume_guid_root_extracts_root
+    //
+    //  Verify GUID paths in either prefix form yield the \\?\Volume{GUID}\
+    //  root, and drive, UNC and malformed paths yield None.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn volume_guid_root_extracts_root() {
+        const ROOT: &str = "\\\\?\\Volume{26a21bda-a627-11d7-9931-806e6f6e6963}\\";
+
+        assert_eq!(volume_guid_root("\\\\?\\Volume{26a21bda-a627-11d7-9931-806e6f6e6963}\\Users\\me").as_deref(), Some(ROOT));
+        assert_eq!(volume_guid_root("\\\\?\\Volume{26a21bda-a627-11d7-9931-806e6f6e6963}").as_deref(), Some(ROOT));
+        assert_eq!(volume_guid_root("\\\\.\\volume{26a21bda-a627-11d7-9931-806e6f6e6963}\\").as_deref(),
+                   Some("\\\\?\\volume{26a21bda-a627-11d7-9931-806e6f6e6963}\\"));
+
+        assert_eq!(volume_guid_root("C:\\Users"), None);
+        assert_eq!(volume_guid_root("\\\\server\\share"), None);
+        assert_eq!(volume_guid_root("\\\\?\\C:\\Users"), None);
+        assert_eq!(volume_guid_root("\\\\?\\Volume{unterminated\\x"), None);
+    }
+}
@@ -0,0 +1,351 @@
+// drive_usage.rs — /DrivePct: how much of the drive each top-level directory uses
+//
+// After a recursive listing, each immediate subdirectory of the listing
+// root is sized (every file beneath it, whatever the mask) and shown as a
+// percentage of the drive's total capacity with a small gauge, largest
+// first, to find what is filling a disk.  Reparse-point directories are
+// not followed, so junctions and mounted folders aren't counted against
+// this drive, and /XD exclusions are honored at every depth.
+
+use std::ffi::OsString;
+use std::os::windows::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+
+use crate::command_line::CommandLine;
+use crate::console::Console;
+use crate::dir_exclusion;
+use crate::drive_info::DriveInfo;
+use crate::file_info::FILE_ATTRIBUTE_REPARSE_POINT;
+use crate::results_displayer::format_number_with_separators;
+
+
+
+
+
+/// Cells in the /DrivePct gauge.
+pub const DRIVE_GAUGE_WIDTH: usize = 10;
+
+/// Share of the drive at which the gauge turns from the size color to the
+/// error color.
+const DRIVE_GAUGE_ALERT_PERCENT: f64 = 25.0;
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// A top-level directory and the bytes of every file beneath it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectoryUsage {
+    pub name:  OsString,
+    pub bytes: u64,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  is_reparse_point
+//
+//  True if the metadata has FILE_ATTRIBUTE_REPARSE_POINT set.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn is_reparse_point(metadata: &std::fs::Metadata) -> bool {
+    metadata.file_attributes() & FILE_ATTRIBUTE_REPARSE_POINT != 0
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  subtree_bytes
+//
+//  Total size of every file under `dir`, recursively.  `relative_path` is
+//  `dir` relative to the listing root, so nested /XD patterns match the
+//  same way they do in the listing.  Unreadable entries, reparse-point
+//  directories, and excluded directories contribute nothing.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn subtree_bytes(dir: &Path, relative_path: &Path, cmd: &CommandLine) -> u64 {
+    let Ok (read_dir) = std::fs::read_dir (dir) else {
+        return 0;
+    };
+
+    let mut bytes = 0u64;
+
+    for entry in read_dir.flatten() {
+        let Ok (metadata) = entry.metadata() else {
+            continue;
+        };
+
+        if metadata.is_dir() {
+            let child_relative = relative_path.join (entry.file_name());
+
+            if !is_reparse_point (&metadata) && !dir_exclusion::is_excluded_dir (&cmd.exclude_dirs, &child_relative) {
+                bytes += subtree_bytes (&entry.path(), &child_relative, cmd);
+            }
+        } else {
+            bytes += metadata.file_size();
+        }
+    }
+
+    bytes
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  top_level_usage
+//
+//  Size each immediate subdirectory of `root` (skipping reparse points and
+//  /XD exclusions), largest first; ties keep name order.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn top_level_usage(root: &Path, cmd: &CommandLine) -> Vec<DirectoryUsage> {
+    let Ok (read_dir) = std::fs::read_dir (root) else {
+        return Vec::new();
+    };
+
+    let mut usage: Vec<DirectoryUsage> = read_dir
+        .flatten()
+        .filter (|entry| entry.metadata().is_ok_and (|m| m.is_dir() && !is_reparse_point (&m)))
+        .filter_map (|entry| {
+            let relative_path = PathBuf::from (entry.file_name());
+
+            if dir_exclusion::is_excluded_dir (&cmd.exclude_dirs, &relative_path) {
+                return None;
+            }
+
+            let bytes = subtree_bytes (&entry.path(), &relative_path, cmd);
+            Some (DirectoryUsage { name: entry.file_name(), bytes })
+        })
+        .collect();
+
+    usage.sort_by (|a, b| b.bytes.cmp (&a.bytes).then_with (|| a.name.cmp (&b.name)));
+    usage
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  drive_percentage
+//
+//  `bytes` as a percentage of the drive's capacity; an unknown (zero)
+//  capacity yields 0.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn drive_percentage(bytes: u64, capacity: u64) -> f64 {
+    if capacity == 0 {
+        return 0.0;
+    }
+
+    bytes as f64 * 100.0 / capacity as f64
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  gauge_cells
+//
+//  Filled gauge cells for a percentage.  Any non-zero share shows at
+//  least one cell, and the gauge never overflows.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn gauge_cells(percent: f64) -> usize {
+    let cells = (percent * DRIVE_GAUGE_WIDTH as f64 / 100.0).ceil();
+    (cells.max (0.0) as usize).min (DRIVE_GAUGE_WIDTH)
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  display_drive_usage
+//
+//  Write the /DrivePct section: one line per top-level directory with a
+//  gauge, its share of the drive, and its size.  Nothing is written when
+//  the drive's capacity is unknown (e.g. some network shares).
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn display_drive_usage(console: &mut Console, drive_info: &DriveInfo, usage: &[DirectoryUsage]) {
+    let Some (capacity) = drive_info.total_bytes else {
+        console.color_puts ("\n{Information} Drive capacity unavailable; no drive percentages shown.");
+        return;
+    };
+
+    if usage.is_empty() {
+        return;
+    }
+
+    let size_width = usage.iter().map (|u| format_number_with_separators (u.bytes).len()).max().unwrap_or (0);
+
+    console.color_puts (&format! (
+        "\n{{Information}} Share of drive capacity ({{InformationHighlight}}{}{{Information}} bytes):",
+        format_number_with_separators (capacity),
+    ));
+
+    for dir in usage {
+        let percent = drive_percentage (dir.bytes, capacity);
+        let filled  = gauge_cells (percent);
+        let color   = if percent >= DRIVE_GAUGE_ALERT_PERCENT { "Error" } else { "Size" };
+
+        console.color_puts (&format! (
+            "{{Information}}   [{{{}}}{}{{Information}}{}] {{Size}}{:>5.1}%  {:>size_width$}{{Information}}  {{Directory}}{}",
+            color,
+            "#".repeat (filled),
+            ".".repeat (DRIVE_GAUGE_WIDTH - filled),
+            percent,
+            format_number_with_separators (dir.bytes),
+            dir.name.to_string_lossy(),
+        ));
+    }
+}
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    const GB: u64 = 1 << 30;
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  percentages_use_drive_capacity
+    //
+    //  Verify sizes are divided by the drive's total capacity (not by the
+    //  listed bytes), and an unknown capacity gives 0.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn percentages_use_drive_capacity() {
+        let capacity = 500 * GB;
+
+        assert_eq! (drive_percentage (125 * GB, capacity), 25.0);
+        assert_eq! (drive_percentage (5 * GB, capacity),   1.0);
+        assert_eq! (drive_percentage (capacity, capacity), 100.0);
+        assert_eq! (drive_percentage (GB, 0),              0.0);
+
+        let listed = [125 * GB, 5 * GB];
+        let sum: f64 = listed.iter().map (|&b| drive_percentage (b, capacity)).sum();
+        assert! ((sum - 26.0).abs() < 1e-9, "{}", sum);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  gauge_cells_round_up_and_clamp
+    //
+    //  Verify empty is empty, any share shows a cell, and the gauge is
+    //  full at 100% without overflowing.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn gauge_cells_round_up_and_clamp() {
+        assert_eq! (gauge_cells (0.0),   0);
+        assert_eq! (gauge_cells (0.01),  1);
+        assert_eq! (gauge_cells (25.0),  3);
+        assert_eq! (gauge_cells (100.0), DRIVE_GAUGE_WIDTH);
+        assert_eq! (gauge_cells (150.0), DRIVE_GAUGE_WIDTH);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  top_level_usage_sizes_subtrees
+    //
+    //  Verify each top-level directory's size includes nested files, root
+    //  files are not attributed to any directory, /XD skips a directory,
+    //  and the largest comes first.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn top_level_usage_sizes_subtrees() {
+        let temp = TempDir::new ("drive_pct");
+        let root = temp.path();
+        std::fs::create_dir_all (root.join ("big\\nested")).unwrap();
+        std::fs::create_dir_all (root.join ("small")).unwrap();
+        std::fs::create_dir_all (root.join ("skipped")).unwrap();
+        std::fs::write (root.join ("big\\a.bin"),         vec![0u8; 300]).unwrap();
+        std::fs::write (root.join ("big\\nested\\b.bin"), vec![0u8; 200]).unwrap();
+        std::fs::write (root.join ("small\\c.bin"),       vec![0u8; 40]).unwrap();
+        std::fs::write (root.join ("skipped\\d.bin"),     vec![0u8; 900]).unwrap();
+        std::fs::write (root.join ("root.bin"),           vec![0u8; 1000]).unwrap();
+
+        let cmd   = CommandLine::parse_from (["/S", "/DrivePct", "/XD:skipped"]).unwrap();
+        let usage = top_level_usage (root, &cmd);
+
+        assert_eq! (usage, vec![
+            DirectoryUsage { name: OsString::from ("big"),   bytes: 500 },
+            DirectoryUsage { name: OsString::from ("small"), bytes: 40 },
+        ]);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  top_level_usage_honors_nested_exclusions
+    //
+    //  Verify /XD applies below the top level too: a bare name skips the
+    //  directory at any depth, and a relative path skips only that one.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn top_level_usage_honors_nested_exclusions() {
+        let temp = TempDir::new ("drive_pct_nested");
+        let root = temp.path();
+        std::fs::create_dir_all (root.join ("src\\web\\node_modules\\pkg")).unwrap();
+        std::fs::create_dir_all (root.join ("src\\obj")).unwrap();
+        std::fs::create_dir_all (root.join ("tools\\obj")).unwrap();
+        std::fs::write (root.join ("src\\web\\app.js"),                    vec![0u8; 100]).unwrap();
+        std::fs::write (root.join ("src\\web\\node_modules\\pkg\\lib.js"), vec![0u8; 5000]).unwrap();
+        std::fs::write (root.join ("src\\obj\\a.obj"),                     vec![0u8; 700]).unwrap();
+        std::fs::write (root.join ("tools\\obj\\b.obj"),                   vec![0u8; 60]).unwrap();
+
+        let cmd   = CommandLine::parse_from (["/S", "/DrivePct", "/XD:node_modules", "/XD:src\\obj"]).unwrap();
+        let usage = top_level_usage (root, &cmd);
+
+        assert_eq! (usage, vec![
+            DirectoryUsage { name: OsString::from ("src"),   bytes: 100 },
+            DirectoryUsage { name: OsString::from ("tools"), bytes: 60 },
+        ]);
+    }
+}
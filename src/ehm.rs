@@ -25,6 +25,13 @@ pub enum AppError {
 
     /// Path does not exist
     PathNotFound(PathBuf),
+
+    /// Nothing matched under /FailOnEmpty (non-zero exit, no message)
+    NoMatches,
+
+    /// A directory group failed under /ErrorSummary (already reported;
+    /// non-zero exit, no message)
+    GroupsFailed,
 }
 
 
@@ -48,6 +55,8 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             AppError::PathNotFound(path) => {
                 write!(f, "Error:   {} does not exist", path.display())
             }
+            AppError::NoMatches => Ok(()),
+            AppError::GroupsFailed => Ok(()),
         }
     }
 }
@@ -0,0 +1,314 @@
+// encoding.rs — Text encoding guesses for the /Encoding column
+//
+// Peeks at the first few KB of a text file (one with an extension in
+// TEXT_EXTENSIONS) and guesses its encoding: a byte order mark decides
+// UTF-8/UTF-16 outright, otherwise pure 7-bit bytes are ASCII, valid UTF-8
+// is UTF-8, and anything else without NULs is the ANSI code page.  NUL
+// bytes without a UTF-16 BOM still mean binary, which gets no guess.
+// Binary extensions and cloud-only placeholders are never opened.  Reading
+// means opening every eligible file, so it is opt-in.
+
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+use crate::directory_info::DirectoryInfo;
+use crate::hashing;
+
+
+
+
+
+/// Width of the /Encoding column: the longest label plus the trailing space.
+pub const ENCODING_COLUMN_WIDTH: usize = 10;
+
+/// Bytes read from the start of each file for the guess.
+const SNIFF_LEN: u64 = 4096;
+
+const UTF8_BOM:    &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// Extensions of files stored as text, the only ones /Encoding reads.
+const TEXT_EXTENSIONS: &[&str] = &[
+    // Source code
+    "asm", "c", "c++", "cpp", "cxx", "h", "hpp", "hxx", "rc", "cs", "csx",
+    "js", "mjs", "cjs", "jsx", "ts", "tsx", "html", "htm", "xhtml", "css",
+    "scss", "sass", "less", "vue", "svelte", "py", "pyw", "rs", "java", "go",
+    "rb", "erb", "fs", "fsx", "fsi", "lua", "pl", "pm", "php", "hs", "dart",
+    "kt", "kts", "swift", "scala", "sc", "sbt", "clj", "cljs", "cljc", "ex",
+    "exs", "erl", "groovy", "gradle", "jl", "r", "rmd", "elm", "sql",
+
+    // Scripts
+    "bash", "bat", "cmd", "ps1", "psd1", "psm1", "ps1xml", "sh", "zsh", "fish",
+
+    // Config and data
+    "xml", "xsd", "xsl", "xslt", "dtd", "resx", "xaml", "manifest", "json",
+    "ipynb", "toml", "yml", "yaml", "ini", "cfg", "conf", "config",
+    "properties", "reg", "csv", "tsv", "svg", "sln", "vcxproj", "vcproj",
+    "csproj", "fsproj", "code-workspace", "dockerfile", "dockerignore", "tf",
+    "tfvars", "bicep", "lock", "pem",
+
+    // Documents and logs
+    "txt", "text", "md", "markdown", "rst", "me", "1st", "eml", "log", "wrn",
+    "err",
+];
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// A guessed text encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncoding {
+    Ascii,      // Only 7-bit bytes
+    Utf8,       // Valid UTF-8 with at least one multi-byte sequence
+    Utf8Bom,    // EF BB BF
+    Utf16Le,    // FF FE
+    Utf16Be,    // FE FF
+    Ansi,       // 8-bit text that isn't UTF-8 (legacy code page)
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl TextEncoding
+//
+//  Column labels.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl TextEncoding {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  label
+    //
+    //  The text shown in the /Encoding column.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TextEncoding::Ascii   => "ASCII",
+            TextEncoding::Utf8    => "UTF-8",
+            TextEncoding::Utf8Bom => "UTF-8 BOM",
+            TextEncoding::Utf16Le => "UTF-16LE",
+            TextEncoding::Utf16Be => "UTF-16BE",
+            TextEncoding::Ansi    => "ANSI",
+        }
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  detect_encoding
+//
+//  Guess the encoding of a file from its first bytes.  None for an empty
+//  sample or one that looks binary.  A multi-byte sequence cut off at the
+//  end of the sample still counts as UTF-8.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn detect_encoding(bytes: &[u8]) -> Option<TextEncoding> {
+    if bytes.starts_with (UTF8_BOM) {
+        return Some (TextEncoding::Utf8Bom);
+    }
+    if bytes.starts_with (UTF16LE_BOM) {
+        return Some (TextEncoding::Utf16Le);
+    }
+    if bytes.starts_with (UTF16BE_BOM) {
+        return Some (TextEncoding::Utf16Be);
+    }
+
+    if bytes.is_empty() || bytes.contains (&0) {
+        return None;
+    }
+
+    if bytes.is_ascii() {
+        return Some (TextEncoding::Ascii);
+    }
+
+    match std::str::from_utf8 (bytes) {
+        Ok (_)                             => Some (TextEncoding::Utf8),
+        Err (e) if e.error_len().is_none() => Some (TextEncoding::Utf8),
+        Err (_)                            => Some (TextEncoding::Ansi),
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  is_text_candidate
+//
+//  True if the file's extension is one of TEXT_EXTENSIONS.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn is_text_candidate(file_name: &OsStr) -> bool {
+    Path::new (file_name)
+        .extension()
+        .is_some_and (|ext| TEXT_EXTENSIONS.iter().any (|text| ext.eq_ignore_ascii_case (text)))
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  read_sample
+//
+//  The first SNIFF_LEN bytes of a file (fewer if it is shorter).
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn read_sample(path: &Path) -> io::Result<Vec<u8>> {
+    let mut sample = Vec::new();
+    File::open (path)?.take (SNIFF_LEN).read_to_end (&mut sample)?;
+    Ok (sample)
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  read_encodings
+//
+//  Fill file_info.encoding for every text file in a DirectoryInfo.
+//  Directories, other extensions, cloud-only placeholders (which reading
+//  would download), and unreadable files stay None.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn read_encodings(di: &mut DirectoryInfo) {
+    for file_info in &mut di.matches {
+        if !hashing::is_hashable (file_info) || !is_text_candidate (&file_info.file_name) {
+            continue;
+        }
+
+        file_info.encoding = read_sample (&di.dir_path.join (&file_info.file_name))
+            .ok()
+            .and_then (|sample| detect_encoding (&sample));
+    }
+}
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS;
+    use crate::file_info::FILE_ATTRIBUTE_ARCHIVE;
+    use crate::test_support::{make_file, TempDir};
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  detect_encoding_uses_bom_first
+    //
+    //  Verify each BOM decides the encoding regardless of what follows,
+    //  including NULs in UTF-16 text.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn detect_encoding_uses_bom_first() {
+        assert_eq! (detect_encoding (b"\xEF\xBB\xBFhello"), Some (TextEncoding::Utf8Bom));
+        assert_eq! (detect_encoding (b"\xFF\xFEh\0i\0"),    Some (TextEncoding::Utf16Le));
+        assert_eq! (detect_encoding (b"\xFE\xFF\0h\0i"),    Some (TextEncoding::Utf16Be));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  detect_encoding_without_bom
+    //
+    //  Verify the heuristic: 7-bit is ASCII, valid (or truncated) UTF-8 is
+    //  UTF-8, other 8-bit text is ANSI, and NULs or no bytes give no guess.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn detect_encoding_without_bom() {
+        assert_eq! (detect_encoding (b"fn main() {}\r\n"),      Some (TextEncoding::Ascii));
+        assert_eq! (detect_encoding ("caf\u{e9} \u{2192}".as_bytes()), Some (TextEncoding::Utf8));
+        assert_eq! (detect_encoding (b"arrow \xE2\x86"),         Some (TextEncoding::Utf8));
+        assert_eq! (detect_encoding (b"caf\xE9 cr\xE8me"),       Some (TextEncoding::Ansi));
+        assert_eq! (detect_encoding (b"MZ\x90\0\x03\0"),         None);
+        assert_eq! (detect_encoding (b""),                       None);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  labels_fit_column
+    //
+    //  Every label plus its trailing space fits ENCODING_COLUMN_WIDTH.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn labels_fit_column() {
+        let all = [
+            TextEncoding::Ascii, TextEncoding::Utf8, TextEncoding::Utf8Bom,
+            TextEncoding::Utf16Le, TextEncoding::Utf16Be, TextEncoding::Ansi,
+        ];
+
+        assert_eq! (all.iter().map (|e| e.label().len() + 1).max(), Some (ENCODING_COLUMN_WIDTH));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  read_encodings_reads_only_local_text_files
+    //
+    //  Verify a text file gets a guess, while a binary extension and a
+    //  cloud-only placeholder are left unread even though both hold text.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn read_encodings_reads_only_local_text_files() {
+        let temp = TempDir::new ("encoding");
+        for name in ["notes.txt", "tool.exe", "cloud.txt"] {
+            std::fs::write (temp.path().join (name), b"plain text").unwrap();
+        }
+
+        let mut di = DirectoryInfo::new (temp.path().to_path_buf(), "*".to_string());
+        di.matches.push (make_file ("notes.txt", FILE_ATTRIBUTE_ARCHIVE, 10));
+        di.matches.push (make_file ("tool.exe",  FILE_ATTRIBUTE_ARCHIVE, 10));
+        di.matches.push (make_file ("cloud.txt", FILE_ATTRIBUTE_ARCHIVE | FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS.0, 10));
+
+        read_encodings (&mut di);
+
+        assert_eq! (di.matches[0].encoding, Some (TextEncoding::Ascii));
+        assert_eq! (di.matches[1].encoding, None);
+        assert_eq! (di.matches[2].encoding, None);
+    }
+}
@@ -6,14 +6,45 @@
 // before files. Only the primary sort attribute respects reverse direction;
 // tiebreakers always use ascending order.
 //
+// Under /Group:ext with no explicit file sort, each extension group that
+// belongs to a known file-type category (images, documents, binaries) is
+// re-sorted by that category's key; RCDIR groupsort:<cat>= overrides it.
+//
+// /O:title sorts on a normalized name (leading symbols and articles
+// stripped) with the raw name as the tie-break.
+//
 // Performance: sort keys (wide strings for name/extension) are pre-computed
 // once per file before sorting, avoiding O(n log n) repeated allocations.
+// Very large directories are sorted in parallel chunks when /M is active,
+// then merged; the comparator is shared so the result is identical.
 
 use std::cmp::Ordering;
 use std::os::windows::ffi::OsStrExt;
+use std::thread;
 
 use crate::command_line::{CommandLine, SortOrder, SortDirection, TimeField};
+use crate::config::Config;
 use crate::file_info::{FileInfo, FILE_ATTRIBUTE_DIRECTORY};
+use crate::work_queue::WorkQueue;
+
+
+
+
+
+/// Minimum number of entries before the parallel sort path is used.
+/// Below this, thread startup costs more than the sort itself.
+pub const PARALLEL_SORT_THRESHOLD: usize = 50_000;
+
+/// Leading articles /O:title skips, longest first so "An" wins over "A".
+const TITLE_ARTICLES: [&str; 3] = ["the", "an", "a"];
+
+/// /Group:ext sort categories: name (as in RCDIR groupsort:<name>=),
+/// member extensions, and the built-in sort key for their groups.
+pub const GROUP_SORT_CATEGORIES: [(&str, &[&str], SortOrder); 3] = [
+    ("img", &[".bmp", ".gif", ".heic", ".ico", ".jpeg", ".jpg", ".png", ".raw", ".svg", ".tif", ".tiff", ".webp"], SortOrder::Date),
+    ("doc", &[".csv", ".doc", ".docx", ".md", ".odt", ".pdf", ".ppt", ".pptx", ".rtf", ".txt", ".xls", ".xlsx"], SortOrder::Name),
+    ("bin", &[".a", ".bin", ".dll", ".exe", ".lib", ".msi", ".o", ".obj", ".pdb", ".so", ".sys"],                SortOrder::Size),
+];
 
 
 
@@ -28,6 +59,7 @@
 struct SortKey {
     name_wide:        Vec<u16>,
     ext_wide:         Vec<u16>,
+    title_wide:       Vec<u16>,   // Empty unless /O:title is in use
     is_dir:           bool,
     file_size:        u64,
     creation_time:    u64,
@@ -52,11 +84,12 @@ impl SortKey {
     //
     //  from_file_info
     //
-    //  Pre-compute sort key fields from a FileInfo entry.
+    //  Pre-compute sort key fields from a FileInfo entry.  The title key
+    //  is only built when a /O:title sort needs it.
     //
     ////////////////////////////////////////////////////////////////////////////
 
-    fn from_file_info(f: &FileInfo) -> Self {
+    fn from_file_info(f: &FileInfo, with_title: bool) -> Self {
         let name_wide: Vec<u16> = f.file_name.encode_wide().chain (Some (0)).collect();
 
         // Extract extension for pre-computation
@@ -64,9 +97,16 @@ fn from_file_info(f: &FileInfo) -> Self {
         let ext_str  = name_str.rfind ('.').map (|i| &name_str[i..]).unwrap_or ("");
         let ext_wide: Vec<u16> = std::ffi::OsStr::new (ext_str).encode_wide().chain (Some (0)).collect();
 
+        let title_wide: Vec<u16> = if with_title {
+            title_sort_name (&name_str).encode_utf16().chain (Some (0)).collect()
+        } else {
+            Vec::new()
+        };
+
         SortKey {
             name_wide,
             ext_wide,
+            title_wide,
             is_dir:           (f.file_attributes & FILE_ATTRIBUTE_DIRECTORY) != 0,
             file_size:        f.file_size,
             creation_time:    f.creation_time,
@@ -86,6 +126,8 @@ fn from_file_info(f: &FileInfo) -> Self {
 //
 //  Sort a slice of FileInfo entries according to the CommandLine sort
 //  preferences.  Pre-computes sort keys to avoid per-comparison allocations.
+//  /Mark always interleaves directories with files (they are marked
+//  instead of grouped).
 //
 //  Port of: std::sort with FileComparator.
 //
@@ -96,14 +138,20 @@ pub fn sort_files(matches: &mut [FileInfo], cmd: &CommandLine, interleaved_sort:
         return;
     }
 
+    let interleaved_sort = interleaved_sort || cmd.mark_dirs;
+
     // Pre-compute sort keys (name/extension wide strings, sizes, times)
+    let with_title = uses_title_sort (cmd);
     let keys: Vec<SortKey> = matches.iter()
-        .map (SortKey::from_file_info)
+        .map (|f| SortKey::from_file_info (f, with_title))
         .collect();
 
     // Sort indices using pre-computed keys
-    let mut indices: Vec<usize> = (0..matches.len()).collect();
-    indices.sort_by (|&a, &b| compare_keyed (&keys[a], &keys[b], cmd, interleaved_sort));
+    let indices = if cmd.multi_threaded && matches.len() >= PARALLEL_SORT_THRESHOLD {
+        sort_indices_parallel (&keys, cmd, interleaved_sort)
+    } else {
+        sort_indices_sequential (&keys, cmd, interleaved_sort)
+    };
 
     // Apply the permutation in-place
     apply_permutation (matches, indices);
@@ -113,6 +161,197 @@ pub fn sort_files(matches: &mut [FileInfo], cmd: &CommandLine, interleaved_sort:
 
 
 
+////////////////////////////////////////////////////////////////////////////////
+//
+//  sort_group
+//
+//  Stable re-sort of one /Group:ext section: `indices` are positions in
+//  `matches`, ordered by `key` with the usual tiebreaker chain.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn sort_group(matches: &[FileInfo], indices: &mut [usize], key: (SortOrder, SortDirection), cmd: &CommandLine) {
+    let with_title = key.0 == SortOrder::Title;
+    let keys: Vec<SortKey> = indices.iter()
+        .map (|&idx| SortKey::from_file_info (&matches[idx], with_title))
+        .collect();
+
+    let mut order: Vec<usize> = (0..indices.len()).collect();
+    order.sort_by (|&a, &b| compare_chain (&keys[a], &keys[b], cmd, key));
+
+    let sorted: Vec<usize> = order.iter().map (|&pos| indices[pos]).collect();
+    indices.copy_from_slice (&sorted);
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  group_sort_category
+//
+//  The GROUP_SORT_CATEGORIES name for a lowercase extension (".jpg"),
+//  or None if it isn't in any category.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn group_sort_category(extension: &str) -> Option<&'static str> {
+    GROUP_SORT_CATEGORIES.iter()
+        .find (|(_, extensions, _)| extensions.contains (&extension))
+        .map (|&(name, _, _)| name)
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  group_sort_key
+//
+//  Sort key for an extension's /Group:ext section: the configured
+//  groupsort:<cat>= override, else the category default (ascending).
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn group_sort_key(config: &Config, extension: &str) -> Option<(SortOrder, SortDirection)> {
+    let category = group_sort_category (extension)?;
+
+    if let Some (&key) = config.group_sorts.get (category) {
+        return Some (key);
+    }
+
+    GROUP_SORT_CATEGORIES.iter()
+        .find (|(name, _, _)| *name == category)
+        .map (|&(_, _, order)| (order, SortDirection::Ascending))
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  sort_indices_sequential
+//
+//  Stable sort of the index permutation on the calling thread.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn sort_indices_sequential(keys: &[SortKey], cmd: &CommandLine, interleaved_sort: bool) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..keys.len()).collect();
+    indices.sort_by (|&a, &b| compare_keyed (&keys[a], &keys[b], cmd, interleaved_sort));
+    indices
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  sort_indices_parallel
+//
+//  Parallel merge sort of the index permutation.  The indices are split
+//  into contiguous chunks which worker threads pull from a WorkQueue and
+//  stable-sort independently; the sorted runs are then merged pairwise.
+//  Both the chunk sorts and the merge are stable, so the result matches
+//  sort_indices_sequential exactly.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn sort_indices_parallel(keys: &[SortKey], cmd: &CommandLine, interleaved_sort: bool) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..keys.len()).collect();
+
+    let num_threads = thread::available_parallelism()
+        .map (|n| n.get())
+        .unwrap_or (1)
+        .max (1);
+
+    if num_threads == 1 {
+        return sort_indices_sequential (keys, cmd, interleaved_sort);
+    }
+
+    let chunk_size = keys.len().div_ceil (num_threads);
+
+    // Sort each chunk on a worker thread
+    {
+        let work_queue: WorkQueue<&mut [usize]> = WorkQueue::new();
+
+        for chunk in indices.chunks_mut (chunk_size) {
+            work_queue.push (chunk);
+        }
+        work_queue.set_done();
+
+        thread::scope (|scope| {
+            for _ in 0..num_threads {
+                scope.spawn (|| {
+                    while let Some (chunk) = work_queue.pop() {
+                        chunk.sort_by (|&a, &b| compare_keyed (&keys[a], &keys[b], cmd, interleaved_sort));
+                    }
+                });
+            }
+        });
+    }
+
+    // Merge sorted runs pairwise until a single run remains
+    let mut runs: Vec<Vec<usize>> = indices.chunks (chunk_size).map (|c| c.to_vec()).collect();
+
+    while runs.len() > 1 {
+        let mut merged = Vec::with_capacity (runs.len().div_ceil (2));
+        let mut iter   = runs.into_iter();
+
+        while let Some (left) = iter.next() {
+            match iter.next() {
+                Some (right) => merged.push (merge_runs (&left, &right, keys, cmd, interleaved_sort)),
+                None         => merged.push (left),
+            }
+        }
+
+        runs = merged;
+    }
+
+    runs.pop().unwrap_or_default()
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  merge_runs
+//
+//  Stable merge of two sorted index runs.  Ties take from the left run,
+//  preserving the original relative order of equal elements.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn merge_runs(left: &[usize], right: &[usize], keys: &[SortKey], cmd: &CommandLine, interleaved_sort: bool) -> Vec<usize> {
+    let mut out = Vec::with_capacity (left.len() + right.len());
+    let mut l   = 0;
+    let mut r   = 0;
+
+    while l < left.len() && r < right.len() {
+        if compare_keyed (&keys[right[r]], &keys[left[l]], cmd, interleaved_sort) == Ordering::Less {
+            out.push (right[r]);
+            r += 1;
+        } else {
+            out.push (left[l]);
+            l += 1;
+        }
+    }
+
+    out.extend_from_slice (&left[l..]);
+    out.extend_from_slice (&right[r..]);
+    out
+}
+
+
+
+
+
 ////////////////////////////////////////////////////////////////////////////////
 //
 //  apply_permutation
@@ -153,7 +392,8 @@ fn apply_permutation<T>(slice: &mut [T], perm: Vec<usize>) {
 //  compare_keyed
 //
 //  Compare two pre-computed SortKeys.  Directories always sort before
-//  files, then walks the sort_preference tiebreaker chain.
+//  files, then walks the sort_preference tiebreaker chain.  /ODir and
+//  /OFile replace the primary key within their partition.
 //
 //  Port of: FileComparator::operator()
 //
@@ -165,13 +405,33 @@ fn compare_keyed(lhs: &SortKey, rhs: &SortKey, cmd: &CommandLine, interleaved_so
         return if lhs.is_dir { Ordering::Less } else { Ordering::Greater };
     }
 
-    // Walk the sort_preference chain
-    for (idx, sort_attr) in cmd.sort_preference.iter().enumerate() {
+    compare_chain (lhs, rhs, cmd, partition_sort_key (lhs.is_dir, cmd, interleaved_sort))
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  compare_chain
+//
+//  Walk the sort_preference tiebreaker chain, led by the given primary
+//  key.  Only the primary key honours the direction.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn compare_chain(lhs: &SortKey, rhs: &SortKey, cmd: &CommandLine, (primary, direction): (SortOrder, SortDirection)) -> Ordering {
+    let chain = std::iter::once (primary).chain (cmd.sort_preference[1..].iter().copied());
+
+    for (idx, sort_attr) in chain.enumerate() {
         let cmp = match sort_attr {
             SortOrder::Default | SortOrder::Name => compare_name_wide (&lhs.name_wide, &rhs.name_wide),
             SortOrder::Date      => compare_date_keyed (lhs, rhs, cmd.time_field),
             SortOrder::Extension => compare_name_wide (&lhs.ext_wide, &rhs.ext_wide),
             SortOrder::Size      => lhs.file_size.cmp (&rhs.file_size),
+            SortOrder::Title     => compare_name_wide (&lhs.title_wide, &rhs.title_wide)
+                .then_with (|| compare_name_wide (&lhs.name_wide, &rhs.name_wide)),
         };
 
         if cmp == Ordering::Equal {
@@ -179,7 +439,7 @@ fn compare_keyed(lhs: &SortKey, rhs: &SortKey, cmd: &CommandLine, interleaved_so
         }
 
         // Only reverse the primary sort attribute (idx == 0), not tiebreakers
-        if idx == 0 && cmd.sort_direction == SortDirection::Descending {
+        if idx == 0 && direction == SortDirection::Descending {
             return cmp.reverse();
         }
 
@@ -193,6 +453,94 @@ fn compare_keyed(lhs: &SortKey, rhs: &SortKey, cmd: &CommandLine, interleaved_so
 
 
 
+////////////////////////////////////////////////////////////////////////////////
+//
+//  partition_sort_key
+//
+//  Primary sort key and direction for the directory or file partition:
+//  /ODir or /OFile if given, else /O.  Interleaved (tree) sorting mixes
+//  the partitions, so it always uses /O.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn partition_sort_key(is_dir: bool, cmd: &CommandLine, interleaved_sort: bool) -> (SortOrder, SortDirection) {
+    let override_key = match (interleaved_sort, is_dir) {
+        (true, _)      => None,
+        (false, true)  => cmd.dir_sort,
+        (false, false) => cmd.file_sort,
+    };
+
+    override_key.unwrap_or ((cmd.sort_preference[0], cmd.sort_direction))
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  uses_title_sort
+//
+//  True when /O, /ODir or /OFile selects the title key.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn uses_title_sort(cmd: &CommandLine) -> bool {
+    cmd.sort_preference[0] == SortOrder::Title
+        || cmd.dir_sort.is_some_and (|(order, _)| order == SortOrder::Title)
+        || cmd.file_sort.is_some_and (|(order, _)| order == SortOrder::Title)
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  title_sort_name
+//
+//  Normalize a name for /O:title: drop leading non-alphanumeric
+//  characters, then a leading "The", "An" or "A" followed by a
+//  separator, so "The Matrix.mkv" and "[A] Beach.jpg" sort as
+//  "Matrix.mkv" and "Beach.jpg".  The extension is kept, and an article
+//  that is the whole stem ("A.txt") is left alone.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(crate) fn title_sort_name(name: &str) -> String {
+    let is_symbol = |c: char| !c.is_alphanumeric();
+
+    let (stem, ext) = match name.rfind ('.') {
+        Some (idx) if idx > 0 => name.split_at (idx),
+        _                     => (name, ""),
+    };
+
+    let mut title = stem.trim_start_matches (is_symbol);
+
+    for article in TITLE_ARTICLES {
+        let Some (head) = title.get (..article.len()) else { continue };
+        let tail        = &title[article.len()..];
+
+        if head.eq_ignore_ascii_case (article) && tail.starts_with (is_symbol) {
+            let rest = tail.trim_start_matches (is_symbol);
+            if !rest.is_empty() {
+                title = rest;
+            }
+            break;
+        }
+    }
+
+    if title.is_empty() {
+        title = stem;
+    }
+
+    format! ("{}{}", title, ext)
+}
+
+
+
+
+
 ////////////////////////////////////////////////////////////////////////////////
 //
 //  compare_name_wide
@@ -243,33 +591,7 @@ fn compare_date_keyed(lhs: &SortKey, rhs: &SortKey, time_field: TimeField) -> Or
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::ffi::OsString;
-
-    ////////////////////////////////////////////////////////////////////////////
-    //
-    //  make_file
-    //
-    //  Creates a FileInfo with the given name, attributes, and size.
-    //
-    ////////////////////////////////////////////////////////////////////////////
-
-    fn make_file(name: &str, attrs: u32, size: u64) -> FileInfo {
-        FileInfo {
-            file_name:       OsString::from(name),
-            file_attributes: attrs,
-            file_size:       size,
-            creation_time:   0,
-            last_write_time: 0,
-            last_access_time: 0,
-            reparse_tag:     0,
-            reparse_target:  String::new(),
-            streams:         Vec::new(),
-        }
-    }
-
-
-
-
+    use crate::test_support::make_file;
 
     ////////////////////////////////////////////////////////////////////////////
     //
@@ -568,4 +890,218 @@ fn permutation_cycle() {
         apply_permutation (&mut data, vec![1, 2, 0, 3]);
         assert_eq!(data, ['b', 'c', 'a', 'd']);
     }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parallel_sort_matches_sequential
+    //
+    //  Verify the parallel merge sort produces exactly the same order as
+    //  the sequential sort for a large shuffled input with duplicate keys.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    #[allow(clippy::field_reassign_with_default)]
+    fn parallel_sort_matches_sequential () {
+        let count = PARALLEL_SORT_THRESHOLD + 1234;
+
+        // Deterministic shuffle via a simple LCG; sizes repeat so tiebreakers matter
+        let mut seed: u64 = 0x2545_F491_4F6C_DD1D;
+        let mut files: Vec<FileInfo> = (0..count)
+            .map (|i| {
+                seed = seed.wrapping_mul (6_364_136_223_846_793_005).wrapping_add (1_442_695_040_888_963_407);
+                let attrs = if seed.is_multiple_of (17) { FILE_ATTRIBUTE_DIRECTORY } else { 0x20 };
+                make_file (&format! ("file{:08}.{}", seed % 100_000, i % 7), attrs, (seed >> 32) % 500)
+            })
+            .collect();
+
+        for i in (1..files.len()).rev() {
+            seed = seed.wrapping_mul (6_364_136_223_846_793_005).wrapping_add (1_442_695_040_888_963_407);
+            files.swap (i, (seed >> 33) as usize % (i + 1));
+        }
+
+        for sort_order in [SortOrder::Name, SortOrder::Size, SortOrder::Extension] {
+            let mut cmd = CommandLine::default();
+            cmd.sort_order         = sort_order;
+            cmd.sort_preference[0] = sort_order;
+
+            let keys: Vec<SortKey> = files.iter().map (|f| SortKey::from_file_info (f, false)).collect();
+
+            let sequential = sort_indices_sequential (&keys, &cmd, false);
+            let parallel   = sort_indices_parallel (&keys, &cmd, false);
+
+            assert_eq! (sequential, parallel);
+        }
+    }
+
+
+
+
+
+    // =========================================================================
+    //  Per-partition sort tests (/ODir, /OFile)
+    // =========================================================================
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  partition_files
+    //
+    //  Helper: two directories and three files whose name order differs
+    //  from their size order.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn partition_files() -> Vec<FileInfo> {
+        vec![
+            make_file ("c.txt", 0x20, 100),
+            make_file ("zeta",  FILE_ATTRIBUTE_DIRECTORY, 5),
+            make_file ("a.txt", 0x20, 300),
+            make_file ("alpha", FILE_ATTRIBUTE_DIRECTORY, 9),
+            make_file ("b.txt", 0x20, 200),
+        ]
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  names_of
+    //
+    //  Helper: file names in their current order.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn names_of(files: &[FileInfo]) -> Vec<String> {
+        files.iter().map (|f| f.file_name.to_string_lossy().into_owned()).collect()
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  each_partition_uses_its_own_key
+    //
+    //  Verify /ODir and /OFile sort directories and files by different
+    //  keys, with directories still listed first.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    #[allow(clippy::field_reassign_with_default)]
+    fn each_partition_uses_its_own_key () {
+        let mut cmd = CommandLine::default();
+        cmd.dir_sort  = Some ((SortOrder::Size, SortDirection::Descending));
+        cmd.file_sort = Some ((SortOrder::Name, SortDirection::Ascending));
+
+        let mut files = partition_files();
+        sort_files (&mut files, &cmd, false);
+        assert_eq! (names_of (&files), vec!["alpha", "zeta", "a.txt", "b.txt", "c.txt"]);
+
+        cmd.dir_sort  = Some ((SortOrder::Name, SortDirection::Ascending));
+        cmd.file_sort = Some ((SortOrder::Size, SortDirection::Descending));
+
+        let mut files = partition_files();
+        sort_files (&mut files, &cmd, false);
+        assert_eq! (names_of (&files), vec!["alpha", "zeta", "a.txt", "b.txt", "c.txt"]);
+
+        cmd.file_sort = Some ((SortOrder::Size, SortDirection::Ascending));
+
+        let mut files = partition_files();
+        sort_files (&mut files, &cmd, false);
+        assert_eq! (names_of (&files), vec!["alpha", "zeta", "c.txt", "b.txt", "a.txt"]);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  missing_partition_key_falls_back_to_sort_order
+    //
+    //  Verify a partition without its own key uses /O (and its direction).
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    #[allow(clippy::field_reassign_with_default)]
+    fn missing_partition_key_falls_back_to_sort_order () {
+        let mut cmd = CommandLine::default();
+        cmd.sort_order         = SortOrder::Name;
+        cmd.sort_direction     = SortDirection::Descending;
+        cmd.sort_preference[0] = SortOrder::Name;
+        cmd.file_sort          = Some ((SortOrder::Size, SortDirection::Ascending));
+
+        let mut files = partition_files();
+        sort_files (&mut files, &cmd, false);
+        assert_eq! (names_of (&files), vec!["zeta", "alpha", "c.txt", "b.txt", "a.txt"]);
+
+        cmd.file_sort = None;
+        cmd.dir_sort  = Some ((SortOrder::Size, SortDirection::Ascending));
+
+        let mut files = partition_files();
+        sort_files (&mut files, &cmd, false);
+        assert_eq! (names_of (&files), vec!["zeta", "alpha", "c.txt", "b.txt", "a.txt"]);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  title_sort_name_strips_articles_and_symbols
+    //
+    //  Verifies leading symbols and articles are dropped while the
+    //  extension and article-only stems are kept.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn title_sort_name_strips_articles_and_symbols () {
+        assert_eq! (title_sort_name ("The Matrix.mkv"),        "Matrix.mkv");
+        assert_eq! (title_sort_name ("the.matrix.1999.mkv"),   "matrix.1999.mkv");
+        assert_eq! (title_sort_name ("[A] Beach.jpg"),         "Beach.jpg");
+        assert_eq! (title_sort_name ("An Affair.avi"),         "Affair.avi");
+        assert_eq! (title_sort_name ("Theory.txt"),            "Theory.txt");
+        assert_eq! (title_sort_name ("A.txt"),                 "A.txt");
+        assert_eq! (title_sort_name ("___.txt"),               "___.txt");
+        assert_eq! (title_sort_name (".gitignore"),            "gitignore");
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  sort_by_title_ignores_leading_article
+    //
+    //  Verifies /O:title files "The Matrix.mkv" under M, not T, with the
+    //  raw name breaking the tie against "Matrix.mkv".
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn sort_by_title_ignores_leading_article () {
+        let cmd = CommandLine::parse_from (["/O:title"]).unwrap();
+        let mut files = vec![
+            make_file ("Tron.mkv",        0x20, 100),
+            make_file ("The Matrix.mkv",  0x20, 100),
+            make_file ("Alien.mkv",       0x20, 100),
+            make_file ("Matrix.mkv",      0x20, 100),
+            make_file ("_Memento.mkv",    0x20, 100),
+        ];
+        sort_files (&mut files, &cmd, false);
+
+        assert_eq! (names_of (&files), ["Alien.mkv", "Matrix.mkv", "The Matrix.mkv", "_Memento.mkv", "Tron.mkv"]);
+    }
 }
@@ -0,0 +1,208 @@
+// file_id.rs — NTFS file IDs for the /FileId column
+//
+// Every NTFS file has a 64-bit file index (nFileIndexHigh/Low from
+// GetFileInformationByHandle) that is shared by all hard links to it and
+// survives renames and moves within the volume.  Reading it means opening
+// a handle per entry, so it is opt-in.  The lookup goes through the
+// FileIdProvider trait so tests can substitute a mock.
+
+use std::ffi::OsStr;
+use std::os::windows::ffi::OsStrExt;
+use std::path::Path;
+
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::CloseHandle;
+use windows::Win32::Storage::FileSystem::{
+    CreateFileW, GetFileInformationByHandle, BY_HANDLE_FILE_INFORMATION,
+    FILE_FLAG_BACKUP_SEMANTICS, FILE_SHARE_DELETE, FILE_SHARE_READ,
+    FILE_SHARE_WRITE, OPEN_EXISTING,
+};
+
+use crate::directory_info::DirectoryInfo;
+
+
+
+
+
+/// Width of the /FileId column: 16 hex digits plus the trailing space.
+pub const FILE_ID_COLUMN_WIDTH: usize = 17;
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  FileIdProvider
+//
+//  Source of file IDs.  The default implementation opens the file; tests
+//  supply a mock.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub trait FileIdProvider {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  file_id
+    //
+    //  The 64-bit file index of the file at `path`, or None if it can't
+    //  be opened.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn file_id(&self, path: &Path) -> Option<u64>;
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Reads file IDs with CreateFileW + GetFileInformationByHandle.
+pub struct DefaultFileIdProvider;
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl FileIdProvider for DefaultFileIdProvider
+//
+//  Opens the entry with no access rights (attributes only) and backup
+//  semantics so directories can be opened too.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl FileIdProvider for DefaultFileIdProvider {
+    fn file_id(&self, path: &Path) -> Option<u64> {
+        let wide: Vec<u16> = OsStr::new (path).encode_wide().chain (Some (0)).collect();
+
+        let handle = unsafe {
+            CreateFileW (
+                PCWSTR (wide.as_ptr()),
+                0,
+                FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE,
+                None,
+                OPEN_EXISTING,
+                FILE_FLAG_BACKUP_SEMANTICS,
+                None,
+            )
+        }.ok()?;
+
+        let mut info = BY_HANDLE_FILE_INFORMATION::default();
+        let result   = unsafe { GetFileInformationByHandle (handle, &mut info) };
+        let _        = unsafe { CloseHandle (handle) };
+
+        result.ok()?;
+        Some (((info.nFileIndexHigh as u64) << 32) | info.nFileIndexLow as u64)
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  read_file_ids
+//
+//  Fill file_info.file_id for every entry in a DirectoryInfo.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn read_file_ids(di: &mut DirectoryInfo, provider: &dyn FileIdProvider) {
+    for file_info in &mut di.matches {
+        file_info.file_id = provider.file_id (&di.dir_path.join (&file_info.file_name));
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  format_file_id
+//
+//  The /FileId cell: 16 zero-padded uppercase hex digits.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn format_file_id(file_id: u64) -> String {
+    format! ("{:016X}", file_id)
+}
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    use crate::file_info::FILE_ATTRIBUTE_ARCHIVE;
+    use crate::test_support::make_file;
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  MockFileIdProvider
+    //
+    //  Maps full paths to IDs, as the volume would for hard links.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    struct MockFileIdProvider {
+        ids: HashMap<PathBuf, u64>,
+    }
+
+    impl FileIdProvider for MockFileIdProvider {
+        fn file_id(&self, path: &Path) -> Option<u64> {
+            self.ids.get (path).copied()
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  hard_links_share_file_id
+    //
+    //  Verify two names linked to the same file get the same ID, a
+    //  different file gets its own, and an unreadable entry gets None.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn hard_links_share_file_id() {
+        let dir = PathBuf::from ("C:\\data");
+        let provider = MockFileIdProvider {
+            ids: HashMap::from ([
+                (dir.join ("report.txt"),      0x0001_0000_0000_002A),
+                (dir.join ("report-link.txt"), 0x0001_0000_0000_002A),
+                (dir.join ("other.txt"),       0x0001_0000_0000_002B),
+            ]),
+        };
+
+        let mut di = DirectoryInfo::new (dir, "*".to_string());
+        for name in ["report.txt", "report-link.txt", "other.txt", "locked.txt"] {
+            di.matches.push (make_file (name, FILE_ATTRIBUTE_ARCHIVE, 10));
+        }
+
+        read_file_ids (&mut di, &provider);
+
+        assert_eq! (di.matches[0].file_id, Some (0x0001_0000_0000_002A));
+        assert_eq! (di.matches[0].file_id, di.matches[1].file_id);
+        assert_ne! (di.matches[0].file_id, di.matches[2].file_id);
+        assert_eq! (di.matches[3].file_id, None);
+
+        assert_eq! (format_file_id (di.matches[0].file_id.unwrap()), "000100000000002A");
+        assert_eq! (format_file_id (0).len() + 1, FILE_ID_COLUMN_WIDTH);
+    }
+}
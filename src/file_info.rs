@@ -10,6 +10,8 @@
     FindClose, WIN32_FIND_DATAW,
 };
 
+use crate::encoding::TextEncoding;
+
 
 
 
@@ -27,6 +29,16 @@
 pub const FILE_ATTRIBUTE_REPARSE_POINT: u32 = 0x0000_0400;
 pub const FILE_ATTRIBUTE_SPARSE_FILE:   u32 = 0x0000_0200;
 
+// Rare flags, shown only by /Attr:full
+pub const FILE_ATTRIBUTE_OFFLINE:               u32 = 0x0000_1000;
+pub const FILE_ATTRIBUTE_NOT_CONTENT_INDEXED:   u32 = 0x0000_2000;
+pub const FILE_ATTRIBUTE_INTEGRITY_STREAM:      u32 = 0x0000_8000;
+pub const FILE_ATTRIBUTE_NO_SCRUB_DATA:         u32 = 0x0002_0000;
+pub const FILE_ATTRIBUTE_RECALL_ON_OPEN:        u32 = 0x0004_0000;
+pub const FILE_ATTRIBUTE_PINNED:                u32 = 0x0008_0000;
+pub const FILE_ATTRIBUTE_UNPINNED:              u32 = 0x0010_0000;
+pub const FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS: u32 = 0x0040_0000;
+
 pub const IO_REPARSE_TAG_SYMLINK:       u32 = 0xA000_000C;
 pub const IO_REPARSE_TAG_MOUNT_POINT:   u32 = 0xA000_0003;
 
@@ -56,6 +68,140 @@
 
 
 
+/// Extra attribute letters appended to the column by /Attr:full, using the
+/// same letters as /A.  'O' is the cloud-only composite (offline or
+/// recall-on-open/recall-on-data-access).
+pub const FULL_ATTRIBUTE_EXTRAS: [(u32, char); 6] = [
+    (FILE_ATTRIBUTE_NOT_CONTENT_INDEXED, 'X'),
+    (FILE_ATTRIBUTE_INTEGRITY_STREAM,    'I'),
+    (FILE_ATTRIBUTE_NO_SCRUB_DATA,       'B'),
+    (FILE_ATTRIBUTE_OFFLINE | FILE_ATTRIBUTE_RECALL_ON_OPEN | FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS, 'O'),
+    (FILE_ATTRIBUTE_UNPINNED,            'L'),
+    (FILE_ATTRIBUTE_PINNED,              'V'),
+];
+
+
+
+
+
+/// `ls`-style letters for /AttrStyle:ls: a leading directory marker, then
+/// the same flags as FILE_ATTRIBUTE_MAP in lowercase, with reparse points
+/// shown as links.
+pub const LS_ATTRIBUTE_MAP: [(u32, char); 10] = [
+    (FILE_ATTRIBUTE_DIRECTORY,     'd'),
+    (FILE_ATTRIBUTE_READONLY,      'r'),
+    (FILE_ATTRIBUTE_HIDDEN,        'h'),
+    (FILE_ATTRIBUTE_SYSTEM,        's'),
+    (FILE_ATTRIBUTE_ARCHIVE,       'a'),
+    (FILE_ATTRIBUTE_TEMPORARY,     't'),
+    (FILE_ATTRIBUTE_ENCRYPTED,     'e'),
+    (FILE_ATTRIBUTE_COMPRESSED,    'c'),
+    (FILE_ATTRIBUTE_REPARSE_POINT, 'l'),
+    (FILE_ATTRIBUTE_SPARSE_FILE,   '0'),
+];
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Attribute letter scheme (/AttrStyle).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AttributeStyle {
+    #[default]
+    Dir,        // FILE_ATTRIBUTE_MAP — dir-compatible (default)
+    Ls,         // LS_ATTRIBUTE_MAP — ls-like lowercase letters
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Layout of the attribute column: the letter scheme plus the /Attr:full
+/// extras, and whether /Attr:auto blanks it for ordinary entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AttributeColumn {
+    pub style: AttributeStyle,
+    pub full:  bool,
+    pub auto:  bool,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl AttributeColumn
+//
+//  The (flag, letter) entries the column shows, and its width.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl AttributeColumn {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  entries
+    //
+    //  The style's base map, plus FULL_ATTRIBUTE_EXTRAS under /Attr:full.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn entries(self) -> impl Iterator<Item = &'static (u32, char)> {
+        let base: &'static [(u32, char)] = match self.style {
+            AttributeStyle::Dir => &FILE_ATTRIBUTE_MAP,
+            AttributeStyle::Ls  => &LS_ATTRIBUTE_MAP,
+        };
+        let extras: &'static [(u32, char)] = if self.full { &FULL_ATTRIBUTE_EXTRAS } else { &[] };
+
+        base.iter().chain (extras)
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  width
+    //
+    //  Width of the column: one character per entry.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn width(self) -> usize {
+        self.entries().count()
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  is_notable
+    //
+    //  True if the entry carries a flag this column shows other than
+    //  archive or the /AttrStyle:ls directory marker.  /Attr:auto blanks
+    //  the column for entries where this is false.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn is_notable(self, file_attributes: u32) -> bool {
+        self.entries()
+            .filter (|&&(flag, _)| flag != FILE_ATTRIBUTE_ARCHIVE && flag != FILE_ATTRIBUTE_DIRECTORY)
+            .any (|&(flag, _)| (file_attributes & flag) != 0)
+    }
+}
+
+
+
+
+
 ////////////////////////////////////////////////////////////////////////////////
 
 /// RAII wrapper for Win32 find handles (FindFirstFile/FindNextFile).
@@ -134,7 +280,7 @@ pub struct StreamInfo {
 /// Port of: FileInfo (extends WIN32_FIND_DATA)
 ///
 /// Holds all information about a single file entry from FindFirstFile/FindNextFile.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct FileInfo {
     pub file_name:       OsString,
     pub file_attributes: u32,
@@ -145,6 +291,13 @@ pub struct FileInfo {
     pub reparse_tag:     u32,    // dwReserved0 — reparse tag for cloud/symlink detection
     pub reparse_target:  String, // Resolved symlink/junction target path (empty if not applicable)
     pub streams:         Vec<StreamInfo>,
+    pub hash:            Option<String>, // /Hash digest as lowercase hex (None if not computed)
+    pub matched_spec:    Option<String>, // /ShowMask: the file spec this entry matched (None unless requested)
+    pub file_id:         Option<u64>,    // /FileId: NTFS file index (None unless requested)
+    pub encoding:        Option<TextEncoding>, // /Encoding: guessed text encoding (None unless requested, or not text)
+    pub version:         Option<String>, // /VerInfo: PE file version (None unless requested, or no version resource)
+    pub on_disk_size:    Option<u64>,    // /OnDisk: allocated size of a sparse or compressed file (None unless requested)
+    pub type_name:       Option<String>, // /Desc: shell file type description (None unless requested)
 }
 
 
@@ -195,6 +348,7 @@ pub fn from_find_data(wfd: &WIN32_FIND_DATAW) -> Self {
             reparse_tag: wfd.dwReserved0,
             reparse_target: String::new(),
             streams: Vec::new(),
+            ..Default::default()
         }
     }
 
@@ -218,6 +372,40 @@ pub fn is_directory(&self) -> bool {
 
 
 
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  displayed_size
+    //
+    //  The size shown and totaled: the on-disk size when /OnDisk read one,
+    //  otherwise the logical size.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn displayed_size(&self) -> u64 {
+        self.on_disk_size.unwrap_or (self.file_size)
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  is_size_on_disk_different
+    //
+    //  True if /OnDisk found an allocated size that differs from the
+    //  logical size (the size column marks these).
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn is_size_on_disk_different(&self) -> bool {
+        self.on_disk_size.is_some_and (|size| size != self.file_size)
+    }
+
+
+
+
+
     ////////////////////////////////////////////////////////////////////////////
     //
     //  is_hidden
@@ -346,6 +534,7 @@ fn is_directory_flag() {
             reparse_tag:     0,
             reparse_target:  String::new(),
             streams:         Vec::new(),
+            ..Default::default()
         };
         assert!(fi.is_directory());
     }
@@ -374,6 +563,7 @@ fn is_dot_dir() {
             reparse_tag:     0,
             reparse_target:  String::new(),
             streams:         Vec::new(),
+            ..Default::default()
         };
         assert!(fi.is_dot_dir());
     }
@@ -0,0 +1,346 @@
+// file_type.rs — Shell file type descriptions for the /Desc column
+//
+// Explorer's "Type" column comes from the registered file associations:
+// SHGetFileInfoW with SHGFI_TYPENAME turns ".docx" into "Microsoft Word
+// Document".  With SHGFI_USEFILEATTRIBUTES the shell answers from the
+// extension alone, without touching the file, so one lookup per extension
+// is enough; FileTypeCache remembers each answer for the whole run.  The
+// lookup goes through the FileTypeProvider trait so tests can substitute
+// a mock.
+
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+use windows::core::PCWSTR;
+use windows::Win32::Storage::FileSystem::{FILE_ATTRIBUTE_DIRECTORY, FILE_ATTRIBUTE_NORMAL};
+use windows::Win32::UI::Shell::{SHGetFileInfoW, SHFILEINFOW, SHGFI_TYPENAME, SHGFI_USEFILEATTRIBUTES};
+
+use crate::directory_info::DirectoryInfo;
+
+
+
+
+
+/// Width of the /Desc column including its trailing space.  Longer
+/// descriptions are cut so the names stay aligned.
+pub const FILE_TYPE_COLUMN_WIDTH: usize = 28;
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  FileTypeProvider
+//
+//  Source of type descriptions.  The default implementation asks the
+//  shell; tests supply a mock.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub trait FileTypeProvider {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  type_name
+    //
+    //  The registered description for an extension (".docx", or "" for
+    //  none), or for a directory, or None if the shell has none.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn type_name(&self, extension: &str, is_directory: bool) -> Option<String>;
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Reads type descriptions with SHGetFileInfoW(SHGFI_TYPENAME).
+pub struct DefaultFileTypeProvider;
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl FileTypeProvider for DefaultFileTypeProvider
+//
+//  Queries a made-up name with the extension; SHGFI_USEFILEATTRIBUTES
+//  keeps the shell from looking for it on disk.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl FileTypeProvider for DefaultFileTypeProvider {
+    fn type_name(&self, extension: &str, is_directory: bool) -> Option<String> {
+        let name = format! ("file{}", extension);
+        let wide: Vec<u16> = name.encode_utf16().chain (Some (0)).collect();
+        let attributes = if is_directory { FILE_ATTRIBUTE_DIRECTORY } else { FILE_ATTRIBUTE_NORMAL };
+
+        let mut info = SHFILEINFOW::default();
+        let result = unsafe {
+            SHGetFileInfoW (
+                PCWSTR (wide.as_ptr()),
+                attributes,
+                Some (&mut info),
+                std::mem::size_of::<SHFILEINFOW>() as u32,
+                SHGFI_TYPENAME | SHGFI_USEFILEATTRIBUTES,
+            )
+        };
+
+        if result == 0 {
+            return None;
+        }
+
+        let len = info.szTypeName.iter().position (|&c| c == 0).unwrap_or (info.szTypeName.len());
+        let type_name = String::from_utf16_lossy (&info.szTypeName[..len]);
+        (!type_name.is_empty()).then_some (type_name)
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Per-extension memo of type descriptions, shared across directories and
+/// lister threads.  Keys are lowercase extensions; directories share one
+/// entry.
+pub struct FileTypeCache<P: FileTypeProvider> {
+    provider: P,
+    names:    Mutex<HashMap<(bool, String), Option<String>>>,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl FileTypeCache
+//
+//  Cached lookups through a FileTypeProvider.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl<P: FileTypeProvider> FileTypeCache<P> {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  new
+    //
+    //  Create an empty cache over a provider.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn new(provider: P) -> Self {
+        FileTypeCache { provider, names: Mutex::new (HashMap::new()) }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  type_name
+    //
+    //  The description for a file name, asking the provider only the first
+    //  time its extension (or a directory) is seen.  Types the shell
+    //  doesn't know show the extension itself, or nothing if there is none.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn type_name(&self, file_name: &OsStr, is_directory: bool) -> String {
+        let extension = if is_directory {
+            String::new()
+        } else {
+            Path::new (file_name)
+                .extension()
+                .map (|ext| format! (".{}", ext.to_string_lossy().to_lowercase()))
+                .unwrap_or_default()
+        };
+
+        let mut names = self.names.lock().unwrap();
+        let resolved  = names
+            .entry ((is_directory, extension.clone()))
+            .or_insert_with (|| self.provider.type_name (&extension, is_directory));
+
+        resolved.clone().unwrap_or (extension)
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  default_cache
+//
+//  The process-wide cache over the shell, so each extension is resolved
+//  once per run however many directories contain it.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn default_cache() -> &'static FileTypeCache<DefaultFileTypeProvider> {
+    static CACHE: OnceLock<FileTypeCache<DefaultFileTypeProvider>> = OnceLock::new();
+    CACHE.get_or_init (|| FileTypeCache::new (DefaultFileTypeProvider))
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  read_file_types
+//
+//  Fill file_info.type_name for every entry in a DirectoryInfo.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn read_file_types<P: FileTypeProvider>(di: &mut DirectoryInfo, cache: &FileTypeCache<P>) {
+    for file_info in &mut di.matches {
+        file_info.type_name = Some (cache.type_name (&file_info.file_name, file_info.is_directory()));
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  format_file_type
+//
+//  The /Desc cell text: the description, cut to fit the column.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn format_file_type(type_name: &str) -> String {
+    type_name.chars().take (FILE_TYPE_COLUMN_WIDTH - 1).collect()
+}
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::path::PathBuf;
+
+    use crate::file_info::FILE_ATTRIBUTE_ARCHIVE;
+    use crate::test_support::make_file;
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  MockFileTypeProvider
+    //
+    //  Knows a fixed set of extensions; every lookup is recorded so tests
+    //  can check how often the shell would have been asked.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    struct MockFileTypeProvider {
+        known:   HashMap<&'static str, &'static str>,
+        queried: RefCell<Vec<(String, bool)>>,
+    }
+
+    impl FileTypeProvider for MockFileTypeProvider {
+        fn type_name(&self, extension: &str, is_directory: bool) -> Option<String> {
+            self.queried.borrow_mut().push ((extension.to_string(), is_directory));
+            if is_directory {
+                return Some ("File folder".to_string());
+            }
+            self.known.get (extension).map (|name| name.to_string())
+        }
+    }
+
+    fn mock_provider() -> MockFileTypeProvider {
+        MockFileTypeProvider {
+            known: HashMap::from ([
+                (".docx", "Microsoft Word Document"),
+                (".txt",  "Text Document"),
+            ]),
+            queried: RefCell::new (Vec::new()),
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  cache_asks_once_per_extension
+    //
+    //  Verify repeated extensions (in any case) hit the provider once,
+    //  across directories, and directories share a single lookup.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn cache_asks_once_per_extension() {
+        use crate::file_info::FILE_ATTRIBUTE_DIRECTORY;
+
+        let cache = FileTypeCache::new (mock_provider());
+
+        let mut di = DirectoryInfo::new (PathBuf::from ("C:\\docs"), "*".to_string());
+        for name in ["a.docx", "b.DOCX", "notes.txt", "c.docx"] {
+            di.matches.push (make_file (name, FILE_ATTRIBUTE_ARCHIVE, 10));
+        }
+        di.matches.push (make_file ("old", FILE_ATTRIBUTE_DIRECTORY, 10));
+        read_file_types (&mut di, &cache);
+
+        let mut other = DirectoryInfo::new (PathBuf::from ("C:\\more"), "*".to_string());
+        other.matches.push (make_file ("d.Docx", FILE_ATTRIBUTE_ARCHIVE, 10));
+        other.matches.push (make_file ("new", FILE_ATTRIBUTE_DIRECTORY, 10));
+        read_file_types (&mut other, &cache);
+
+        assert_eq! (di.matches[0].type_name.as_deref(), Some ("Microsoft Word Document"));
+        assert_eq! (di.matches[1].type_name.as_deref(), Some ("Microsoft Word Document"));
+        assert_eq! (di.matches[2].type_name.as_deref(), Some ("Text Document"));
+        assert_eq! (di.matches[4].type_name.as_deref(), Some ("File folder"));
+        assert_eq! (other.matches[0].type_name.as_deref(), Some ("Microsoft Word Document"));
+
+        assert_eq! (*cache.provider.queried.borrow(), vec![
+            (".docx".to_string(), false),
+            (".txt".to_string(),  false),
+            (String::new(),       true),
+        ]);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  unknown_types_show_the_extension
+    //
+    //  Verify an unregistered extension shows itself (and is still only
+    //  asked for once), and a name without an extension shows nothing.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn unknown_types_show_the_extension() {
+        let cache = FileTypeCache::new (mock_provider());
+
+        assert_eq! (cache.type_name (OsStr::new ("data.xyz"), false), ".xyz");
+        assert_eq! (cache.type_name (OsStr::new ("more.XYZ"), false), ".xyz");
+        assert_eq! (cache.type_name (OsStr::new ("Makefile"), false), "");
+        assert_eq! (cache.provider.queried.borrow().len(), 2);
+
+        assert_eq! (format_file_type ("Microsoft Excel Macro-Enabled Worksheet").chars().count(), FILE_TYPE_COLUMN_WIDTH - 1);
+    }
+}
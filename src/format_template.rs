@@ -0,0 +1,371 @@
+// format_template.rs — Per-entry output templates (/Fmt)
+//
+// A /Fmt template such as "{size:>12} {name}" is literal text with named
+// placeholders.  Each placeholder may carry a width and alignment after a
+// colon ('<' left, '>' right, left by default), and "{{" / "}}" stand for
+// literal braces.  The template is parsed once at startup, so an unknown
+// placeholder or a malformed spec is reported before anything is listed;
+// the TemplateDisplayer fills in the values per entry.
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// A value a /Fmt template can show for each entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placeholder {
+    Name,
+    Size,
+    Date,
+    Time,
+    Attrs,
+    Owner,
+    Cloud,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl Placeholder
+//
+//  Placeholder names.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl Placeholder {
+
+    /// Every placeholder, in the order listed in errors and help.
+    pub const ALL: [Placeholder; 7] = [
+        Placeholder::Name,
+        Placeholder::Size,
+        Placeholder::Date,
+        Placeholder::Time,
+        Placeholder::Attrs,
+        Placeholder::Owner,
+        Placeholder::Cloud,
+    ];
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  name
+    //
+    //  The placeholder as written in a template.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Placeholder::Name  => "name",
+            Placeholder::Size  => "size",
+            Placeholder::Date  => "date",
+            Placeholder::Time  => "time",
+            Placeholder::Attrs => "attrs",
+            Placeholder::Owner => "owner",
+            Placeholder::Cloud => "cloud",
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse
+    //
+    //  Look up a placeholder by name (case-insensitive).
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find (|p| p.name().eq_ignore_ascii_case (name))
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Alignment of a value within its field width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// One placeholder with its optional width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Field {
+    pub placeholder: Placeholder,
+    pub align:       Align,
+    pub width:       usize,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl Field
+//
+//  Padding a value to the field width.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl Field {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  pad
+    //
+    //  Pad `value` to the field width (by character count).  Longer values
+    //  are never truncated.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn pad(&self, value: &str) -> String {
+        match self.align {
+            Align::Left  => format! ("{:<width$}", value, width = self.width),
+            Align::Right => format! ("{:>width$}", value, width = self.width),
+        }
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// A piece of a parsed template: fixed text or a placeholder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    Literal(String),
+    Field(Field),
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// A parsed /Fmt template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatTemplate {
+    pub segments: Vec<Segment>,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl FormatTemplate
+//
+//  Template parsing and placeholder queries.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl FormatTemplate {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse
+    //
+    //  Split a template into literal text and fields.  The error names the
+    //  offending placeholder or brace.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn parse(template: &str) -> Result<Self, String> {
+        let mut segments = Vec::new();
+        let mut literal  = String::new();
+        let mut chars    = template.chars().peekable();
+
+        while let Some (ch) = chars.next() {
+            match ch {
+                '{' if chars.peek() == Some (&'{') => { chars.next(); literal.push ('{'); }
+                '}' if chars.peek() == Some (&'}') => { chars.next(); literal.push ('}'); }
+                '}' => return Err ("unmatched '}' (use '}}' for a literal brace)".to_string()),
+                '{' => {
+                    let mut body = String::new();
+                    loop {
+                        match chars.next() {
+                            Some ('}') => break,
+                            Some (c)   => body.push (c),
+                            None       => return Err (format! ("unterminated placeholder '{{{}'", body)),
+                        }
+                    }
+
+                    if !literal.is_empty() {
+                        segments.push (Segment::Literal (std::mem::take (&mut literal)));
+                    }
+                    segments.push (Segment::Field (Self::parse_field (&body)?));
+                }
+                _ => literal.push (ch),
+            }
+        }
+
+        if !literal.is_empty() {
+            segments.push (Segment::Literal (literal));
+        }
+
+        Ok (FormatTemplate { segments })
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_field
+    //
+    //  Parse "name" or "name:[<|>]width" from inside a placeholder.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn parse_field(body: &str) -> Result<Field, String> {
+        let (name, spec) = match body.split_once (':') {
+            Some ((name, spec)) => (name, Some (spec)),
+            None                => (body, None),
+        };
+
+        let placeholder = Placeholder::parse (name.trim()).ok_or_else (|| {
+            let known: Vec<&str> = Placeholder::ALL.iter().map (|p| p.name()).collect();
+            format! ("unknown placeholder '{{{}}}' (use {})", name, known.join (", "))
+        })?;
+
+        let Some (spec) = spec else {
+            return Ok (Field { placeholder, align: Align::Left, width: 0 });
+        };
+
+        let (align, digits) = match spec.as_bytes().first() {
+            Some (b'<') => (Align::Left,  &spec[1..]),
+            Some (b'>') => (Align::Right, &spec[1..]),
+            _           => (Align::Left,  spec),
+        };
+
+        let width = digits.parse::<usize>().map_err (|_| {
+            format! ("invalid width '{}' in '{{{}}}' (use e.g. {{{}:>12}})", spec, body, name)
+        })?;
+
+        Ok (Field { placeholder, align, width })
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  uses
+    //
+    //  True if the template shows `placeholder` anywhere, so costly values
+    //  (owner, cloud status) are only looked up when needed.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn uses(&self, placeholder: Placeholder) -> bool {
+        self.segments.iter().any (|s| matches! (s, Segment::Field (f) if f.placeholder == placeholder))
+    }
+}
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_splits_literals_and_fields
+    //
+    //  Verify widths, alignment, escaped braces, and case-insensitive
+    //  names parse into the expected segments.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_splits_literals_and_fields() {
+        let template = FormatTemplate::parse ("{{{SIZE:>12}}} {name:<8}|{attrs}").unwrap();
+
+        assert_eq! (template.segments, vec![
+            Segment::Literal ("{".to_string()),
+            Segment::Field (Field { placeholder: Placeholder::Size, align: Align::Right, width: 12 }),
+            Segment::Literal ("} ".to_string()),
+            Segment::Field (Field { placeholder: Placeholder::Name, align: Align::Left, width: 8 }),
+            Segment::Literal ("|".to_string()),
+            Segment::Field (Field { placeholder: Placeholder::Attrs, align: Align::Left, width: 0 }),
+        ]);
+
+        assert! (template.uses (Placeholder::Size));
+        assert! (!template.uses (Placeholder::Owner));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_rejects_bad_templates
+    //
+    //  Verify unknown placeholders, bad widths, and stray braces are errors.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_rejects_bad_templates() {
+        let error = FormatTemplate::parse ("{name} {bogus}").unwrap_err();
+        assert! (error.contains ("unknown placeholder '{bogus}'"), "{error}");
+
+        assert! (FormatTemplate::parse ("{size:>wide}").is_err());
+        assert! (FormatTemplate::parse ("{name").is_err());
+        assert! (FormatTemplate::parse ("name}").is_err());
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  field_pads_by_alignment
+    //
+    //  Verify left/right padding and that long values are not truncated.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn field_pads_by_alignment() {
+        let right = Field { placeholder: Placeholder::Size, align: Align::Right, width: 6 };
+        let left  = Field { placeholder: Placeholder::Name, align: Align::Left,  width: 6 };
+
+        assert_eq! (right.pad ("42"), "    42");
+        assert_eq! (left.pad ("a.txt"), "a.txt ");
+        assert_eq! (left.pad ("longer.txt"), "longer.txt");
+    }
+}
@@ -0,0 +1,662 @@
+// hashing.rs — File content hashing for the /Hash column
+//
+// CRC-32 (IEEE 802.3, as used by zip/PNG) and SHA-256 (FIPS 180-4),
+// implemented here since the crate has no hashing dependency.  Hashing
+// reads file contents, so it is opt-in: directories and cloud-only
+// placeholders (which would trigger a download) are skipped.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use windows::Win32::Storage::FileSystem::{
+    FILE_ATTRIBUTE_OFFLINE,
+    FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS,
+    FILE_ATTRIBUTE_RECALL_ON_OPEN,
+};
+
+use crate::directory_info::DirectoryInfo;
+use crate::file_info::FileInfo;
+
+
+
+
+
+/// Read buffer size for hashing file contents.
+const HASH_READ_BUFFER_SIZE: usize = 64 * 1024;
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Hash algorithm selected with /Hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    Crc32,
+    Sha256,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl HashAlgorithm
+//
+//  Parsing and column metrics.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl HashAlgorithm {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse
+    //
+    //  Parse a /Hash value (case-insensitive): crc32 or sha256.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn parse(value: &str) -> Option<Self> {
+        if value.eq_ignore_ascii_case ("crc32") {
+            Some (HashAlgorithm::Crc32)
+        } else if value.eq_ignore_ascii_case ("sha256") {
+            Some (HashAlgorithm::Sha256)
+        } else {
+            None
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  hex_len
+    //
+    //  Number of hex digits in a digest (the hash column's text width).
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn hex_len(self) -> usize {
+        match self {
+            HashAlgorithm::Crc32  => 8,
+            HashAlgorithm::Sha256 => 64,
+        }
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Incremental hasher over either algorithm.
+enum Hasher {
+    Crc32 (Crc32),
+    Sha256 (Box<Sha256>),
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl Hasher
+//
+//  Dispatch update/finish to the selected algorithm.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl Hasher {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  new
+    //
+    //  Create a fresh hasher for the algorithm.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn new(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::Crc32  => Hasher::Crc32 (Crc32::new()),
+            HashAlgorithm::Sha256 => Hasher::Sha256 (Box::new (Sha256::new())),
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  update
+    //
+    //  Feed more bytes.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Crc32 (h)  => h.update (data),
+            Hasher::Sha256 (h) => h.update (data),
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  finish_hex
+    //
+    //  Finalize and return the digest as lowercase hex.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn finish_hex(self) -> String {
+        match self {
+            Hasher::Crc32 (h)  => format! ("{:08x}", h.finish()),
+            Hasher::Sha256 (h) => h.finish().iter().map (|b| format! ("{:02x}", b)).collect(),
+        }
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  hash_bytes
+//
+//  Hash an in-memory buffer, returning lowercase hex.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn hash_bytes(data: &[u8], algorithm: HashAlgorithm) -> String {
+    let mut hasher = Hasher::new (algorithm);
+    hasher.update (data);
+    hasher.finish_hex()
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  hash_file
+//
+//  Hash a file's contents, returning lowercase hex, or None if the file
+//  cannot be opened or read (locked, access denied, ...).
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn hash_file(path: &Path, algorithm: HashAlgorithm) -> Option<String> {
+    let mut file   = File::open (path).ok()?;
+    let mut hasher = Hasher::new (algorithm);
+    let mut buffer = vec![0u8; HASH_READ_BUFFER_SIZE];
+
+    loop {
+        let read = file.read (&mut buffer).ok()?;
+        if read == 0 {
+            break;
+        }
+        hasher.update (&buffer[..read]);
+    }
+
+    Some (hasher.finish_hex())
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  is_hashable
+//
+//  True for regular files whose contents are available locally.
+//  Directories and cloud-only placeholders are skipped.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn is_hashable(file_info: &FileInfo) -> bool {
+    !file_info.is_directory() && !is_cloud_only (file_info)
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  is_cloud_only
+//
+//  True for a placeholder whose contents would have to be downloaded
+//  (or recalled from offline storage) before they could be read.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn is_cloud_only(file_info: &FileInfo) -> bool {
+    let cloud_only = FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS.0
+                   | FILE_ATTRIBUTE_RECALL_ON_OPEN.0
+                   | FILE_ATTRIBUTE_OFFLINE.0;
+
+    (file_info.file_attributes & cloud_only) != 0
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  hash_matches
+//
+//  Compute the hash of every hashable entry in a DirectoryInfo and store
+//  it in file_info.hash.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn hash_matches(di: &mut DirectoryInfo, algorithm: HashAlgorithm) {
+    for file_info in &mut di.matches {
+        if is_hashable (file_info) {
+            file_info.hash = hash_file (&di.dir_path.join (&file_info.file_name), algorithm);
+        }
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// CRC-32 lookup table for the reflected IEEE polynomial 0xEDB88320.
+const CRC32_TABLE: [u32; 256] = build_crc32_table();
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  build_crc32_table
+//
+//  Compile-time generation of CRC32_TABLE.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+const fn build_crc32_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+
+        while bit < 8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+
+        table[i] = crc;
+        i += 1;
+    }
+
+    table
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Incremental CRC-32 (IEEE).
+struct Crc32 {
+    crc: u32,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl Crc32
+//
+//  Table-driven CRC-32.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl Crc32 {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  new
+    //
+    //  Start a new CRC (initial value all ones).
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn new() -> Self {
+        Crc32 { crc: 0xFFFF_FFFF }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  update
+    //
+    //  Fold bytes into the running CRC.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            let index = ((self.crc ^ byte as u32) & 0xFF) as usize;
+            self.crc = (self.crc >> 8) ^ CRC32_TABLE[index];
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  finish
+    //
+    //  Final CRC value (running value inverted).
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn finish(&self) -> u32 {
+        !self.crc
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// SHA-256 round constants (first 32 bits of the fractional parts of the
+/// cube roots of the first 64 primes).
+const SHA256_K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// SHA-256 initial hash values.
+const SHA256_H0: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Incremental SHA-256.
+struct Sha256 {
+    state:     [u32; 8],
+    block:     [u8; 64],
+    block_len: usize,
+    total_len: u64,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl Sha256
+//
+//  Block buffering, compression, and padding.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl Sha256 {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  new
+    //
+    //  Start a new digest.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn new() -> Self {
+        Sha256 { state: SHA256_H0, block: [0; 64], block_len: 0, total_len: 0 }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  update
+    //
+    //  Buffer bytes, compressing each full 64-byte block.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn update(&mut self, mut data: &[u8]) {
+        self.total_len += data.len() as u64;
+
+        while !data.is_empty() {
+            let take = (64 - self.block_len).min (data.len());
+            self.block[self.block_len..self.block_len + take].copy_from_slice (&data[..take]);
+            self.block_len += take;
+            data = &data[take..];
+
+            if self.block_len == 64 {
+                let block = self.block;
+                self.compress (&block);
+                self.block_len = 0;
+            }
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  finish
+    //
+    //  Append the 0x80 terminator, zero padding, and 64-bit big-endian bit
+    //  length, then return the 32-byte digest.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn finish(mut self) -> [u8; 32] {
+        let bit_len = self.total_len.wrapping_mul (8);
+
+        self.update (&[0x80]);
+        while self.block_len != 56 {
+            self.update (&[0]);
+        }
+        self.update (&bit_len.to_be_bytes());
+
+        let mut digest = [0u8; 32];
+        for (chunk, word) in digest.as_chunks_mut::<4>().0.iter_mut().zip (self.state) {
+            *chunk = word.to_be_bytes();
+        }
+        digest
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  compress
+    //
+    //  Process one 64-byte block (FIPS 180-4 §6.2.2).
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn compress(&mut self, block: &[u8; 64]) {
+        let mut w = [0u32; 64];
+
+        for (i, chunk) in block.as_chunks::<4>().0.iter().enumerate() {
+            w[i] = u32::from_be_bytes (*chunk);
+        }
+
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right (7) ^ w[i - 15].rotate_right (18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right (17) ^ w[i - 2].rotate_right (19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16].wrapping_add (s0).wrapping_add (w[i - 7]).wrapping_add (s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = self.state;
+
+        for i in 0..64 {
+            let s1    = e.rotate_right (6) ^ e.rotate_right (11) ^ e.rotate_right (25);
+            let ch    = (e & f) ^ (!e & g);
+            let temp1 = h.wrapping_add (s1).wrapping_add (ch).wrapping_add (SHA256_K[i]).wrapping_add (w[i]);
+            let s0    = a.rotate_right (2) ^ a.rotate_right (13) ^ a.rotate_right (22);
+            let maj   = (a & b) ^ (a & c) ^ (b & c);
+            let temp2 = s0.wrapping_add (maj);
+
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add (temp1);
+            d = c;
+            c = b;
+            b = a;
+            a = temp1.wrapping_add (temp2);
+        }
+
+        for (state, value) in self.state.iter_mut().zip ([a, b, c, d, e, f, g, h]) {
+            *state = state.wrapping_add (value);
+        }
+    }
+}
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  crc32_known_vectors
+    //
+    //  Verify the standard CRC-32 check value and the empty input.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn crc32_known_vectors() {
+        assert_eq! (hash_bytes (b"123456789", HashAlgorithm::Crc32), "cbf43926");
+        assert_eq! (hash_bytes (b"", HashAlgorithm::Crc32), "00000000");
+        assert_eq! (hash_bytes (b"The quick brown fox jumps over the lazy dog", HashAlgorithm::Crc32), "414fa339");
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  sha256_known_vectors
+    //
+    //  Verify FIPS 180-4 example digests, including a two-block message.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn sha256_known_vectors() {
+        assert_eq! (
+            hash_bytes (b"", HashAlgorithm::Sha256),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        );
+        assert_eq! (
+            hash_bytes (b"abc", HashAlgorithm::Sha256),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+        );
+        assert_eq! (
+            hash_bytes (b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq", HashAlgorithm::Sha256),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+        );
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  sha256_incremental_matches_one_shot
+    //
+    //  Verify feeding data in odd-sized pieces gives the same digest.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn sha256_incremental_matches_one_shot() {
+        let data: Vec<u8> = (0..1000u32).map (|i| (i * 31) as u8).collect();
+
+        let mut hasher = Hasher::new (HashAlgorithm::Sha256);
+        for piece in data.chunks (37) {
+            hasher.update (piece);
+        }
+
+        assert_eq! (hasher.finish_hex(), hash_bytes (&data, HashAlgorithm::Sha256));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  parse_accepts_known_algorithms
+    //
+    //  Verify case-insensitive parsing and column widths.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn parse_accepts_known_algorithms() {
+        assert_eq! (HashAlgorithm::parse ("CRC32"), Some (HashAlgorithm::Crc32));
+        assert_eq! (HashAlgorithm::parse ("sha256"), Some (HashAlgorithm::Sha256));
+        assert_eq! (HashAlgorithm::parse ("md5"), None);
+
+        assert_eq! (HashAlgorithm::Crc32.hex_len(), hash_bytes (b"x", HashAlgorithm::Crc32).len());
+        assert_eq! (HashAlgorithm::Sha256.hex_len(), hash_bytes (b"x", HashAlgorithm::Sha256).len());
+    }
+}
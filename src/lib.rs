@@ -16,18 +16,24 @@
 pub mod perf_timer;
 pub mod file_comparator;
 pub mod directory_lister;
+pub mod dir_exclusion;
+pub mod dir_diff;
+pub mod date_format;
+pub mod format_template;
 pub mod multi_threaded_lister;
 pub mod work_queue;
 pub mod results_displayer;
 pub mod cloud_status;
 pub mod streams;
 pub mod reparse_resolver;
+pub mod run_stats;
 pub mod owner;
 pub mod usage;
 pub mod icon_mapping;
 pub mod nerd_font_detector;
 pub mod file_attribute_map;
 pub mod tree_connector_state;
+pub mod age_tier;
 pub mod alias_types;
 pub mod profile_path_resolver;
 pub mod profile_file_manager;
@@ -35,11 +41,26 @@
 pub mod tui_widgets;
 pub mod alias_manager;
 pub mod path_ellipsis;
+pub mod watch;
+pub mod progress;
+pub mod hashing;
+pub mod file_id;
+pub mod version_info;
+pub mod encoding;
+pub mod since_last;
+pub mod archive;
+pub mod drive_usage;
+pub mod on_disk_size;
+pub mod file_type;
 
+#[cfg(test)]
+mod test_support;
 
 
 
 
+
+use std::collections::{HashMap, HashSet};
 use std::ffi::{OsStr, OsString};
 use std::os::windows::ffi::OsStringExt;
 use std::path::{Path, PathBuf};
@@ -66,6 +87,16 @@ pub fn run() -> Result<(), AppError> {
 
     let (cmd, cfg, icons_active) = initialize()?;
     let mut console = console::Console::initialize (Arc::clone (&cfg))?;
+    if let Some (mode) = cmd.color_mode {
+        console.set_color_mode (mode);
+    }
+
+    // --Tsv and --Jsonl are for pipelines: never emit escape sequences
+    if cmd.tsv_listing || cmd.jsonl_listing {
+        console.set_color_mode (command_line::ColorMode::Never);
+    }
+
+    console.set_bom (cmd.bom);
 
     if process_info_switches (&mut console, &cmd, icons_active)? {
         return Ok(());
@@ -78,14 +109,31 @@ pub fn run() -> Result<(), AppError> {
 
     let cmd = Arc::new (cmd);
     let groups = build_mask_groups (&cmd);
-    let mut totals = listing_totals::ListingTotals::default();
+
+    if cmd.count_only {
+        return run_count_mode (&groups, &cmd, console);
+    }
+
+    if cmd.watch {
+        return run_watch_mode (&groups, &cmd, &cfg, console, icons_active);
+    }
+
+    let mut totals             = listing_totals::ListingTotals::default();
+    let mut last_volume_serial = None;
+
+    // The listing loop is the enumeration phase timed for /P and /Stats throughput
+    let mut enumeration_timer = perf_timer::PerfTimer::new();
+    enumeration_timer.start();
 
     for group in &groups {
-        console = process_directory_group (group, &cmd, &cfg, console, &mut totals, icons_active);
+        console = process_directory_group (group, &cmd, &cfg, console, &mut totals, &mut last_volume_serial, icons_active);
     }
 
-    finalize (&mut console, &cmd, &mut timer)?;
-    Ok(())
+    enumeration_timer.stop();
+
+    finalize (&mut console, &cmd, &totals, &mut timer, &enumeration_timer)?;
+    check_failed_groups (&cmd, &totals)?;
+    check_empty_listing (&cmd, &totals)
 }
 
 
@@ -107,7 +155,8 @@ fn initialize() -> Result<(command_line::CommandLine, Arc<config::Config>, bool)
 
     // Initialize config regardless of parse result — needed for help display
     let mut cfg = config::Config::new();
-    cfg.initialize (0x07); // default: LightGrey on Black
+    cfg.light_background = cmd.as_ref().is_ok_and (|c| c.light);
+    cfg.initialize (0x07); // default: LightGrey on Black (Black on White with /Light)
 
     let mut cmd = match cmd {
         Ok (c) => c,
@@ -126,8 +175,20 @@ fn initialize() -> Result<(command_line::CommandLine, Arc<config::Config>, bool)
     };
 
     cmd.apply_config_defaults (&cfg);
+    cmd.expand_paths (&environment_provider::DefaultEnvironmentProvider)?;
+    cmd.resolve_time_references()?;
+    cmd.resolve_diff_reference()?;
+
+    // /SinceLast: read the previous run's marker before anything is listed
+    if cmd.since_last {
+        cmd.since_last_run = since_last::FileMarkerStore::from_environment (&environment_provider::DefaultEnvironmentProvider)
+            .map (|store| since_last::SinceLastRun::begin (&store, &since_last::SystemClock));
+    }
+
+    cfg.raw_colors = cmd.raw_colors;
 
     let icons_active = resolve_icons (&cmd, &cfg);
+    cfg.glyphs_required = icons_active && cmd.color_mode == Some (command_line::ColorMode::Never);
 
     let cfg = Arc::new (cfg);
     Ok ((cmd, cfg, icons_active))
@@ -163,6 +224,22 @@ fn process_info_switches(
         return Ok (true);
     }
 
+    if cmd.config_errors_json {
+        let json = console.config().validate_environment_variable().to_json();
+        console.set_color_mode (command_line::ColorMode::Never);
+        console.write_raw (&json);
+        console.flush()?;
+        return Ok (true);
+    }
+
+    if cmd.config_json {
+        let json = console.config().to_json();
+        console.set_color_mode (command_line::ColorMode::Never);
+        console.write_raw (&json);
+        console.flush()?;
+        return Ok (true);
+    }
+
     if cmd.show_config {
         usage::display_config_file_help (console, cmd.switch_prefix);
         console.flush()?;
@@ -182,11 +259,82 @@ fn process_info_switches(
 
 
 
+////////////////////////////////////////////////////////////////////////////////
+//
+//  run_watch_mode
+//
+//  /Watch: list all groups, then re-list them whenever any of the
+//  directories changes.  Runs until the user presses Ctrl-C.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn run_watch_mode(
+    groups: &[(PathBuf, Vec<OsString>)],
+    cmd: &Arc<command_line::CommandLine>,
+    cfg: &Arc<config::Config>,
+    console: console::Console,
+    icons_active: bool,
+) -> Result<(), AppError> {
+    let dirs: Vec<PathBuf> = groups.iter().map (|(dir, _)| dir.clone()).collect();
+    let recursive = cmd.recurse || cmd.tree.unwrap_or (false);
+
+    watch::run (console, &dirs, recursive, |mut console| {
+        let mut totals             = listing_totals::ListingTotals::default();
+        let mut last_volume_serial = None;
+
+        for group in groups {
+            console = process_directory_group (group, cmd, cfg, console, &mut totals, &mut last_volume_serial, icons_active);
+        }
+
+        if cmd.error_summary {
+            display_error_summary (&mut console, &totals);
+        }
+
+        console
+    })
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  run_count_mode
+//
+//  --Count: print only the number of matching entries across all groups
+//  (and subdirectories with /S or --Tree).  Nothing is enriched, sorted,
+//  or displayed; missing directories contribute 0.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn run_count_mode(
+    groups: &[(PathBuf, Vec<OsString>)],
+    cmd: &command_line::CommandLine,
+    mut console: console::Console,
+) -> Result<(), AppError> {
+    let recursive = cmd.recurse || cmd.tree.unwrap_or (false);
+
+    let count: u64 = groups.iter()
+        .flat_map (|(dir, specs)| specs.iter().map (move |spec| (dir, spec)))
+        .map (|(dir, spec)| directory_lister::count_matching_entries (dir, spec, cmd, recursive))
+        .sum();
+
+    console.set_color_mode (command_line::ColorMode::Never);
+    console.write_raw (&format! ("{}\n", count));
+    console.flush()
+}
+
+
+
+
+
 ////////////////////////////////////////////////////////////////////////////////
 //
 //  build_mask_groups
 //
-//  Build mask list (defaulting to "*") and group by target directory.
+//  Build mask list (defaulting to "*") and group by target directory,
+//  rooted at the /Base (or DefaultPath=) directory when one is set.
 //
 ////////////////////////////////////////////////////////////////////////////////
 
@@ -197,7 +345,7 @@ fn build_mask_groups(cmd: &command_line::CommandLine) -> Vec<(PathBuf, Vec<OsStr
         cmd.masks.clone()
     };
 
-    mask_grouper::group_masks_by_directory (&masks)
+    mask_grouper::group_masks_by_directory (&masks, cmd.base_path.as_deref().map (Path::new))
 }
 
 
@@ -210,38 +358,160 @@ fn build_mask_groups(cmd: &command_line::CommandLine) -> Vec<(PathBuf, Vec<OsStr
 //
 //  Process a single (directory, file_specs) group: validate the path, get
 //  drive info, create a displayer, and dispatch to MT or ST processing.
+//  A file argument arrives here as (parent dir, file name), so it lists
+//  just that file; only a missing parent directory is reported as an error.
+//  `last_volume_serial` is the volume of the previous group: consecutive
+//  groups on the same volume share its drive header.  With /DerefArg a
+//  symlinked or junctioned directory argument is swapped for its target.
 //  Returns the console recovered from the displayer.
 //
 ////////////////////////////////////////////////////////////////////////////////
 
 fn process_directory_group(
+    group: &(PathBuf, Vec<OsString>),
+    cmd: &Arc<command_line::CommandLine>,
+    cfg: &Arc<config::Config>,
+    console: console::Console,
+    totals: &mut listing_totals::ListingTotals,
+    last_volume_serial: &mut Option<u32>,
+    icons_active: bool,
+) -> console::Console {
+    process_directory_group_with (group, cmd, cfg, console, totals, last_volume_serial, icons_active, &SystemGroupProbe)
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Per-group volume and link lookups made by process_directory_group.
+/// Lets tests supply a known volume or link instead of real ones.
+trait GroupProbe {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  drive_info
+    //
+    //  Returns the drive and volume information for a directory.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn drive_info(&self, dir_path: &Path) -> Result<drive_info::DriveInfo, AppError>;
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  linked_target
+    //
+    //  Returns the directory a linked directory argument resolves to.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn linked_target(&self, dir_path: &Path) -> Option<PathBuf>;
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Production GroupProbe that queries the real volume.
+struct SystemGroupProbe;
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl GroupProbe for SystemGroupProbe
+//
+//  Delegates to the Win32-backed lookups.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl GroupProbe for SystemGroupProbe {
+    fn drive_info(&self, dir_path: &Path) -> Result<drive_info::DriveInfo, AppError> {
+        drive_info::DriveInfo::new (dir_path)
+    }
+
+    fn linked_target(&self, dir_path: &Path) -> Option<PathBuf> {
+        resolve_linked_argument (dir_path)
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  process_directory_group_with
+//
+//  process_directory_group with the volume and link lookups supplied by
+//  `probe`.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+#[allow(clippy::too_many_arguments)]
+fn process_directory_group_with(
     group: &(PathBuf, Vec<OsString>),
     cmd: &Arc<command_line::CommandLine>,
     cfg: &Arc<config::Config>,
     mut console: console::Console,
     totals: &mut listing_totals::ListingTotals,
+    last_volume_serial: &mut Option<u32>,
     icons_active: bool,
+    probe: &dyn GroupProbe,
 ) -> console::Console {
     let (dir_path, file_specs) = group;
 
-    // Validate directory exists
-    if !dir_path.exists() || !dir_path.is_dir() {
-        console.color_printf (&format! (
-            "{{Error}}Error:   {{InformationHighlight}}{}{{Error}} does not exist\n",
-            dir_path.display(),
-        ));
+    // /DerefArg: list a linked directory argument's target rather than the link
+    let link_target = if cmd.deref_arg { probe.linked_target (dir_path) } else { None };
+
+    // Validate directory exists (reported at the end instead under /ErrorSummary)
+    if link_target.is_none() && (!dir_path.exists() || !dir_path.is_dir()) {
+        if !cmd.error_summary {
+            console.color_printf (&format! (
+                "{{Error}}Error:   {{InformationHighlight}}{}{{Error}} does not exist\n",
+                dir_path.display(),
+            ));
+        }
+        totals.group_errors.push (listing_totals::GroupError { path: dir_path.clone(), reason: "does not exist" });
         return console;
     }
 
-    console.puts (config::Attribute::Default, "");
+    // TSV, JSON Lines, /Brief and /Fmt output have no headers, so no leading blank line either
+    let has_headers = !cmd.tsv_listing && !cmd.jsonl_listing && !cmd.brief && cmd.format_template.is_none();
+    if has_headers {
+        console.puts (config::Attribute::Default, "");
+    }
+
+    let dir_path = match &link_target {
+        Some (target) => {
+            if has_headers {
+                console.color_printf (&format! (
+                    "{{Information}}Following link {{InformationHighlight}}{}{{Information}} to {{InformationHighlight}}{}\n",
+                    dir_path.display(),
+                    target.display(),
+                ));
+            }
+            target
+        }
+        None => dir_path,
+    };
 
-    let drive_info = match drive_info::DriveInfo::new (dir_path) {
+    let drive_info = match probe.drive_info (dir_path) {
         Ok (di) => di,
         Err(_) => {
-            console.color_printf (&format! (
-                "{{Error}}Error:   Unable to get drive info for {{InformationHighlight}}{}\n",
-                dir_path.display(),
-            ));
+            if !cmd.error_summary {
+                console.color_printf (&format! (
+                    "{{Error}}Error:   Unable to get drive info for {{InformationHighlight}}{}\n",
+                    dir_path.display(),
+                ));
+            }
+            totals.group_errors.push (listing_totals::GroupError { path: dir_path.clone(), reason: "unable to get drive info" });
             return console;
         }
     };
@@ -254,12 +524,24 @@ fn process_directory_group(
         icons_active,
     );
 
+    // Same volume as the previous group: its drive header already covers this one
+    if drive_info.volume_serial.is_some() && drive_info.volume_serial == *last_volume_serial {
+        displayer.skip_drive_header();
+    }
+    *last_volume_serial = drive_info.volume_serial;
+
     if cmd.tree.unwrap_or (false) || (cmd.multi_threaded && cmd.recurse) {
         process_multi_threaded (&drive_info, dir_path, file_specs, cmd, cfg, &mut displayer, totals);
     } else {
         process_single_threaded (&drive_info, dir_path, file_specs, cmd, cfg, &mut displayer, totals);
     }
 
+    // /DrivePct: size the top-level directories once the listing is done
+    if cmd.drive_pct {
+        let usage = drive_usage::top_level_usage (dir_path, cmd);
+        drive_usage::display_drive_usage (displayer.console_mut(), &drive_info, &usage);
+    }
+
     displayer.into_console()
 }
 
@@ -267,6 +549,32 @@ fn process_directory_group(
 
 
 
+////////////////////////////////////////////////////////////////////////////////
+//
+//  resolve_linked_argument
+//
+//  /DerefArg: if a directory argument is itself a reparse point (symlink
+//  or junction), the directory it finally resolves to; None for an
+//  ordinary directory or a link that can't be resolved.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn resolve_linked_argument(dir_path: &Path) -> Option<PathBuf> {
+    use std::os::windows::fs::MetadataExt;
+
+    let metadata = std::fs::symlink_metadata (dir_path).ok()?;
+    if metadata.file_attributes() & file_info::FILE_ATTRIBUTE_REPARSE_POINT == 0 {
+        return None;
+    }
+
+    let target = std::fs::canonicalize (dir_path).ok()?;
+    target.is_dir().then (|| mask_grouper::strip_extended_length_prefix (target))
+}
+
+
+
+
+
 ////////////////////////////////////////////////////////////////////////////////
 //
 //  process_multi_threaded
@@ -284,21 +592,34 @@ fn process_multi_threaded(
     displayer: &mut results_displayer::Displayer,
     totals: &mut listing_totals::ListingTotals,
 ) {
+    // /Progress: status line on stderr, erased before each stdout flush
+    let progress = progress::ProgressIndicator::new_if_enabled (cmd).map (Arc::new);
+    displayer.console_mut().set_progress (progress.clone());
+
     let mut mt_lister = multi_threaded_lister::MultiThreadedLister::new (
         Arc::clone (cmd),
         Arc::clone (cfg),
+        progress.clone(),
     );
 
     mt_lister.process (drive_info, dir_path, file_specs, displayer, totals);
 
-    // Build a summary DirectoryInfo for the recursive summary display
-    let spec_strings: Vec<String> = file_specs.iter()
-        .map (|s| s.to_string_lossy().to_string())
-        .collect();
-    let summary_di = directory_info::DirectoryInfo::new_multi (dir_path.to_path_buf(), spec_strings);
+    if let Some (progress) = &progress {
+        progress.finish();
+    }
+    displayer.console_mut().set_progress (None);
 
-    use results_displayer::ResultsDisplayer;
-    displayer.display_recursive_summary (&summary_di, totals);
+    // Build a summary DirectoryInfo for the recursive summary display
+    // (skipped under /NoSummary)
+    if !cmd.no_summary {
+        let spec_strings: Vec<String> = file_specs.iter()
+            .map (|s| s.to_string_lossy().to_string())
+            .collect();
+        let summary_di = directory_info::DirectoryInfo::new_multi (dir_path.to_path_buf(), spec_strings);
+
+        use results_displayer::ResultsDisplayer;
+        displayer.display_recursive_summary (&summary_di, totals);
+    }
 
     mt_lister.stop_workers();
 }
@@ -312,7 +633,9 @@ fn process_multi_threaded(
 //  process_single_threaded
 //
 //  Single-threaded listing: enumerate, sort, display each file spec, with
-//  optional recursion into subdirectories.
+//  optional recursion into subdirectories.  With /Dedupe, the names listed
+//  in each directory are remembered across specs so overlapping masks
+//  don't list an entry twice.
 //
 ////////////////////////////////////////////////////////////////////////////////
 
@@ -327,37 +650,41 @@ fn process_single_threaded(
 ) {
     use results_displayer::{ResultsDisplayer, DirectoryLevel};
 
+    let mut seen_by_dir: HashMap<PathBuf, HashSet<String>> = HashMap::new();
+
     for file_spec in file_specs {
+        let zip_path = dir_path.join (file_spec);
+
+        if cmd.in_zip && archive::is_zip_file (&zip_path) {
+            display_zip_contents (drive_info, &zip_path, cmd, displayer, totals);
+            continue;
+        }
+
         let spec_str = file_spec.to_string_lossy().to_string();
         let mut di = directory_info::DirectoryInfo::new (dir_path.to_path_buf(), spec_str);
 
-        directory_lister::collect_matching_files (
-            dir_path,
-            file_spec.as_os_str(),
-            &mut di,
-            cmd,
-            totals,
-            cfg,
-        );
+        list_spec_matches (dir_path, file_spec.as_os_str(), &mut di, cmd, cfg, totals, &mut seen_by_dir);
 
         totals.directory_count += di.subdirectory_count;
 
-        file_comparator::sort_files (&mut di.matches, cmd, false);
-
         displayer.display_results (drive_info, &di, DirectoryLevel::Initial);
 
         if cmd.recurse {
             recurse_into_subdirectories (
                 drive_info,
                 dir_path,
+                Path::new (""),
                 file_spec.as_os_str(),
                 cmd,
                 cfg,
                 totals,
                 displayer,
+                &mut seen_by_dir,
             );
 
-            displayer.display_recursive_summary (&di, totals);
+            if !cmd.no_summary {
+                displayer.display_recursive_summary (&di, totals);
+            }
         }
     }
 }
@@ -366,32 +693,250 @@ fn process_single_threaded(
 
 
 
+////////////////////////////////////////////////////////////////////////////////
+//
+//  list_spec_matches
+//
+//  Collect and sort one spec's matches for a directory, skipping names an
+//  earlier spec already listed there when /Dedupe is on.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn list_spec_matches(
+    dir_path: &Path,
+    file_spec: &OsStr,
+    di: &mut directory_info::DirectoryInfo,
+    cmd: &command_line::CommandLine,
+    cfg: &config::Config,
+    totals: &mut listing_totals::ListingTotals,
+    seen_by_dir: &mut HashMap<PathBuf, HashSet<String>>,
+) {
+    let seen = if cmd.dedupe { Some (seen_by_dir.entry (dir_path.to_path_buf()).or_default()) } else { None };
+    directory_lister::list_directory_into (dir_path, file_spec, di, cmd, totals, cfg, seen);
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  display_zip_contents
+//
+//  /InZip: list a .zip archive's entries as if the archive were a
+//  directory.  An unreadable archive is reported and skipped.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn display_zip_contents(
+    drive_info: &drive_info::DriveInfo,
+    zip_path: &Path,
+    cmd: &command_line::CommandLine,
+    displayer: &mut results_displayer::Displayer,
+    totals: &mut listing_totals::ListingTotals,
+) {
+    use results_displayer::{ResultsDisplayer, DirectoryLevel};
+
+    let entries = match archive::read_zip_file (zip_path) {
+        Ok (entries) => entries,
+        Err (e)      => {
+            displayer.console_mut().color_printf (&format! (
+                "{{Error}}Error:   Unable to read {{InformationHighlight}}{}{{Error}}: {}\n",
+                zip_path.display(),
+                e,
+            ));
+            return;
+        }
+    };
+
+    let mut di = archive::build_directory_info (zip_path, entries);
+
+    totals.file_count      += di.file_count;
+    totals.file_bytes      += di.bytes_used;
+    totals.directory_count += di.subdirectory_count;
+
+    file_comparator::sort_files (&mut di.matches, cmd, false);
+
+    displayer.display_results (drive_info, &di, DirectoryLevel::Initial);
+}
+
+
+
+
+
 ////////////////////////////////////////////////////////////////////////////////
 //
 //  finalize
 //
-//  Display any RCDIR env var parsing errors, flush output, and optionally
-//  show performance timing.
+//  Display the /ErrorSummary and any RCDIR env var parsing errors, flush
+//  output, update the /SinceLast marker, and optionally show performance
+//  timing and enumeration throughput.
 //
 ////////////////////////////////////////////////////////////////////////////////
 
 fn finalize(
     console: &mut console::Console,
     cmd: &command_line::CommandLine,
+    totals: &listing_totals::ListingTotals,
     timer: &mut perf_timer::PerfTimer,
+    enumeration_timer: &perf_timer::PerfTimer,
 ) -> Result<(), AppError> {
-    // Display any config file or RCDIR env var parsing errors at end of output
-    usage::display_config_file_issues (console, cmd.switch_prefix, true);
-    usage::display_env_var_issues (console, cmd.switch_prefix, true);
+    if cmd.error_summary {
+        display_error_summary (console, totals);
+    }
+
+    // Display any config file or RCDIR env var parsing errors at end of output (not under /Quiet)
+    if !cmd.quiet {
+        usage::display_config_file_issues (console, cmd.switch_prefix, true);
+        usage::display_env_var_issues (console, cmd.switch_prefix, true);
+    }
     console.flush()?;
 
+    // Record this run for the next /SinceLast; a marker we can't write
+    // only costs the next run its highlights
+    if let Some (run) = cmd.since_last_run {
+        let store = since_last::FileMarkerStore::from_environment (&environment_provider::DefaultEnvironmentProvider);
+        let _ = store.map (|store| run.finish (&store));
+    }
+
+    let files_enumerated = run_stats::RUN_STATS.snapshot().files_enumerated;
+    let throughput       = perf_timer::format_throughput_line (files_enumerated, enumeration_timer.elapsed_ms());
+
+    // I/O counters go to stderr so piped listings stay clean (/Stats)
+    if cmd.show_stats {
+        run_stats::write_report();
+        if let Some (line) = &throughput {
+            eprint! ("{}", line);
+        }
+    }
+
     // Performance timer output — spec A.11: "RCDir time elapsed:  X.XX msec\n"
     if cmd.perf_timer {
         timer.stop();
         console.printf_attr (config::Attribute::Default, &format! ("RCDir time elapsed:  {:.2} msec\n", timer.elapsed_ms()));
+        if let Some (line) = &throughput {
+            console.printf_attr (config::Attribute::Default, line);
+        }
         console.flush()?;
     }
 
+    // /Status goes last, on stderr, after everything else has been flushed
+    if cmd.status_line {
+        eprintln! ("{}", format_status_line (totals));
+    }
+
+    Ok(())
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  format_status_line
+//
+//  /Status: the run's totals on one line, e.g. "42 files, 3 dirs,
+//  12.3 MB, 0 errors", where errors are directory groups that could not
+//  be listed.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn format_status_line(totals: &listing_totals::ListingTotals) -> String {
+    let plural = |count: usize, one: &str, many: &str| {
+        format! ("{} {}", results_displayer::format_number_with_separators (count as u64), if count == 1 { one } else { many })
+    };
+
+    format! (
+        "{}, {}, {}, {}",
+        plural (totals.file_count as usize, "file", "files"),
+        plural (totals.directory_count as usize, "dir", "dirs"),
+        results_displayer::format_abbreviated_size (totals.total_bytes()).trim(),
+        plural (totals.group_errors.len(), "error", "errors"),
+    )
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  display_error_summary
+//
+//  /ErrorSummary: list every directory group that could not be listed,
+//  together, after the listings.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn display_error_summary(
+    console: &mut console::Console,
+    totals: &listing_totals::ListingTotals,
+) {
+    if totals.group_errors.is_empty() {
+        return;
+    }
+
+    let count = totals.group_errors.len();
+    console.color_printf (&format! (
+        "\n{{Error}}{} {} could not be listed:\n",
+        count,
+        if count == 1 { "directory" } else { "directories" },
+    ));
+
+    for error in &totals.group_errors {
+        console.color_printf (&format! (
+            "{{Error}}   {{InformationHighlight}}{}{{Error}}: {}\n",
+            error.path.display(),
+            error.reason,
+        ));
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  check_failed_groups
+//
+//  /ErrorSummary: fail the run (non-zero exit) when any directory group
+//  could not be listed.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn check_failed_groups(
+    cmd: &command_line::CommandLine,
+    totals: &listing_totals::ListingTotals,
+) -> Result<(), AppError> {
+    if cmd.error_summary && !totals.group_errors.is_empty() {
+        return Err (AppError::GroupsFailed);
+    }
+
+    Ok(())
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  check_empty_listing
+//
+//  /FailOnEmpty: fail the run (non-zero exit) when nothing matched.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn check_empty_listing(
+    cmd: &command_line::CommandLine,
+    totals: &listing_totals::ListingTotals,
+) -> Result<(), AppError> {
+    if cmd.empty_policy == command_line::EmptyMatchPolicy::Fail && totals.matched_nothing() {
+        return Err (AppError::NoMatches);
+    }
+
     Ok(())
 }
 
@@ -403,19 +948,24 @@ fn finalize(
 //
 //  recurse_into_subdirectories
 //
-//  Recurse into subdirectories applying the same file spec.
+//  Recurse into subdirectories applying the same file spec.  The
+//  accumulated `relative_path` (from the listing root) is matched against
+//  /XD patterns so path-based exclusions prune only that one directory.
 //  Port of: CDirectoryLister::RecurseIntoSubdirectories
 //
 ////////////////////////////////////////////////////////////////////////////////
 
+#[allow(clippy::too_many_arguments)]
 fn recurse_into_subdirectories(
     drive_info: &drive_info::DriveInfo,
     dir_path: &Path,
+    relative_path: &Path,
     file_spec: &OsStr,
     cmd: &Arc<command_line::CommandLine>,
     cfg: &Arc<config::Config>,
     totals: &mut listing_totals::ListingTotals,
     displayer: &mut results_displayer::Displayer,
+    seen_by_dir: &mut HashMap<PathBuf, HashSet<String>>,
 ) {
     use std::os::windows::ffi::OsStrExt;
     use windows::Win32::Storage::FileSystem::{FindFirstFileW, FindNextFileW, WIN32_FIND_DATAW};
@@ -428,6 +978,7 @@ fn recurse_into_subdirectories(
     let search_wide: Vec<u16> = search_path.as_os_str().encode_wide().chain(Some(0)).collect();
 
     let mut wfd = WIN32_FIND_DATAW::default();
+    run_stats::RUN_STATS.record_directory_opened();
     let handle = unsafe { FindFirstFileW(windows::core::PCWSTR(search_wide.as_ptr()), &mut wfd) };
     let handle = match handle {
         Ok(h) if !h.is_invalid() => h,
@@ -442,38 +993,39 @@ fn recurse_into_subdirectories(
             let name = std::ffi::OsString::from_wide(&wfd.cFileName[..name_len]);
             let name_str = name.to_string_lossy();
 
-            if name_str != "." && name_str != ".." {
+            let subdir_relative_path = relative_path.join (&name);
+
+            if name_str != "." && name_str != ".."
+                && directory_lister::passes_reparse_filter (wfd.dwFileAttributes, cmd)
+                && !dir_exclusion::is_excluded_dir (&cmd.exclude_dirs, &subdir_relative_path)
+            {
                 let subdir_path = dir_path.join(&name);
                 let spec_str = file_spec.to_string_lossy().to_string();
                 let mut di = directory_info::DirectoryInfo::new(subdir_path.clone(), spec_str);
+                di.relative_path = subdir_relative_path.clone();
 
-                // Enumerate matching files in subdirectory
-                directory_lister::collect_matching_files(
-                    &subdir_path,
-                    file_spec,
-                    &mut di,
-                    cmd,
-                    totals,
-                    cfg,
-                );
+                // Enumerate and sort matching files in subdirectory
+                list_spec_matches (&subdir_path, file_spec, &mut di, cmd, cfg, totals, seen_by_dir);
 
                 totals.directory_count += di.subdirectory_count;
 
-                // Sort results
-                file_comparator::sort_files(&mut di.matches, cmd, false);
-
-                // Display results (Subdirectory level — skips empty dirs)
-                displayer.display_results(drive_info, &di, DirectoryLevel::Subdirectory);
+                // Display results (Subdirectory level — skips empty dirs;
+                // /CollapseEmpty also omits file-less dirs and counts them)
+                if !(cmd.collapse_empty && totals.omit_if_empty(&di)) {
+                    displayer.display_results(drive_info, &di, DirectoryLevel::Subdirectory);
+                }
 
                 // Continue recursion depth-first
                 recurse_into_subdirectories(
                     drive_info,
                     &subdir_path,
+                    &subdir_relative_path,
                     file_spec,
                     cmd,
                     cfg,
                     totals,
                     displayer,
+                    seen_by_dir,
                 );
             }
         }
@@ -494,16 +1046,62 @@ fn recurse_into_subdirectories(
 //  resolve_icons
 //
 //  Determine whether icons should be active.  Priority cascade:
-//    1. CLI flag (/Icons or /Icons-)  — always wins
-//    2. RCDIR env var (Icons / Icons-) — second priority
-//    3. Auto-detect via NerdFontDetector — third priority
+//    1. /Icons:force                   — always on, even over a later /Icons-
+//    2. CLI flag (/Icons or /Icons-)  — wins over everything below
+//    3. RCDIR env var (Icons / Icons-) — second priority
+//    4. Auto-detect via NerdFontDetector — third priority
+//  Only auto-detection needs a valid console handle, so the CLI flags
+//  still apply when stdout is redirected.
 //
 //  Port of: CreateDisplayer() icon activation in TCDirCore/TCDir.cpp
 //
 ////////////////////////////////////////////////////////////////////////////////
 
 pub fn resolve_icons(cmd: &command_line::CommandLine, cfg: &config::Config) -> bool {
-    // CLI flag always wins
+    let console_handle = unsafe {
+        windows::Win32::System::Console::GetStdHandle (
+            windows::Win32::System::Console::STD_OUTPUT_HANDLE,
+        )
+    };
+
+    let console_handle = match console_handle {
+        Ok(h) if !h.is_invalid() => Some (h),
+        _ => None,
+    };
+
+    resolve_icons_with (cmd, cfg, console_handle, |handle| {
+        let prober = nerd_font_detector::DefaultFontProber;
+        let env    = environment_provider::DefaultEnvironmentProvider;
+
+        nerd_font_detector::detect (handle, &env, &prober)
+    })
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  resolve_icons_with
+//
+//  resolve_icons with the console handle and detection injected, so the
+//  cascade can be tested without a real console.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn resolve_icons_with(
+    cmd: &command_line::CommandLine,
+    cfg: &config::Config,
+    console_handle: Option<windows::Win32::Foundation::HANDLE>,
+    detect: impl FnOnce (windows::Win32::Foundation::HANDLE) -> nerd_font_detector::DetectionResult,
+) -> bool {
+    // /Icons:force always wins
+    if cmd.icons_forced {
+        return true;
+    }
+
+    // CLI flag wins over the env var and detection
     if let Some(cli_icons) = cmd.icons {
         return cli_icons;
     }
@@ -514,21 +1112,581 @@ pub fn resolve_icons(cmd: &command_line::CommandLine, cfg: &config::Config) -> b
     }
 
     // Auto-detect: probe console font / enumerate system fonts
-    let console_handle = unsafe {
-        windows::Win32::System::Console::GetStdHandle (
-            windows::Win32::System::Console::STD_OUTPUT_HANDLE,
-        )
+    let Some (console_handle) = console_handle else {
+        return false;
     };
 
-    let console_handle = match console_handle {
-        Ok(h) if !h.is_invalid() => h,
-        _ => return false,
-    };
+    matches!(detect (console_handle), nerd_font_detector::DetectionResult::Detected)
+}
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  render_single_threaded
+    //
+    //  Helper: run the single-threaded listing over `root` into a test
+    //  console and return the plain-text output.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn render_single_threaded (root: &Path, args: &[&str]) -> String {
+        render_single_threaded_specs (root, &["*"], args)
+    }
+
+    fn render_single_threaded_specs (root: &Path, specs: &[&str], args: &[&str]) -> String {
+        let mut cfg = config::Config::new();
+        cfg.initialize (0x07);
+        let cfg = Arc::new (cfg);
+
+        let mut console = console::Console::new_for_testing (Arc::clone (&cfg));
+        console.set_color_mode (command_line::ColorMode::Never);
+
+        let cmd        = Arc::new (command_line::CommandLine::parse_from (args.iter().copied()).unwrap());
+        let drive_info = drive_info::DriveInfo::new (root).unwrap();
+        let mut displayer = results_displayer::Displayer::new (console, Arc::clone (&cmd), Arc::clone (&cfg), false);
+        let mut totals    = listing_totals::ListingTotals::default();
+
+        let file_specs: Vec<OsString> = specs.iter().map (OsString::from).collect();
+        process_single_threaded (&drive_info, root, &file_specs, &cmd, &cfg, &mut displayer, &mut totals);
+
+        displayer.into_console().take_test_buffer()
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  no_summary_keeps_recursion_but_drops_summary
+    //
+    //  Verify /S /NoSummary still lists subdirectory contents but omits
+    //  the closing "Total files listed" block that plain /S prints.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn no_summary_keeps_recursion_but_drops_summary() {
+        let temp = TempDir::new ("no_summary");
+        let root = temp.path();
+        std::fs::create_dir_all (root.join ("sub")).unwrap();
+        std::fs::write (root.join ("top.txt"), b"1").unwrap();
+        std::fs::write (root.join ("sub\\nested.txt"), b"22").unwrap();
+
+        let with_summary    = render_single_threaded (root, &["/S", "/NoCloud"]);
+        let without_summary = render_single_threaded (root, &["/S", "/NoCloud", "/NoSummary"]);
+
+        assert! (with_summary.contains ("Total files listed"));
+        assert! (without_summary.contains ("nested.txt"));
+        assert! (without_summary.contains ("top.txt"));
+        assert! (!without_summary.contains ("Total files listed"));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  quiet_suppresses_env_var_issue_report
+    //
+    //  Verify finalize prints the RCDIR issue block for an invalid value,
+    //  and prints nothing under /Quiet.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn quiet_suppresses_env_var_issue_report () {
+        let mut mock = environment_provider::MockEnvironmentProvider::new();
+        mock.set (config::RCDIR_ENV_VAR_NAME, "/W");
+
+        let mut cfg = config::Config::new();
+        cfg.initialize_with_provider (0x07, &mock);
+        let cfg = Arc::new (cfg);
+        assert! (cfg.validate_environment_variable().has_issues());
+
+        let render = |args: &[&str]| -> String {
+            let mut console = console::Console::new_for_testing (Arc::clone (&cfg));
+            console.set_color_mode (command_line::ColorMode::Never);
+
+            let cmd       = command_line::CommandLine::parse_from (args.iter().copied()).unwrap();
+            let mut timer = perf_timer::PerfTimer::new();
+            finalize (&mut console, &cmd, &listing_totals::ListingTotals::default(), &mut timer, &perf_timer::PerfTimer::new()).unwrap();
+            console.take_test_buffer()
+        };
+
+        assert! (render (&[]).contains ("There are some problems"));
+        assert_eq! (render (&["/Quiet"]), "");
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  stats_count_run_operations
+    //
+    //  Verify a /S /Streams /Owner run over a small tree bumps the shared
+    //  counters by at least the work it had to do.  Other tests may run
+    //  concurrently, so only lower bounds are checked.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn stats_count_run_operations() {
+        let temp = TempDir::new ("stats");
+        let root = temp.path();
+        std::fs::create_dir_all (root.join ("sub")).unwrap();
+        std::fs::write (root.join ("a.txt"), b"1").unwrap();
+        std::fs::write (root.join ("b.txt"), b"2").unwrap();
+        std::fs::write (root.join ("sub\\c.txt"), b"3").unwrap();
+
+        let before = run_stats::RUN_STATS.snapshot();
+        render_single_threaded (root, &["/S", "/Streams", "/Owner", "/NoCloud"]);
+        let delta = run_stats::RUN_STATS.snapshot().since (&before);
+
+        // Root and sub are each searched for matches, plus one subdirectory scan per level
+        assert! (delta.directories_opened  >= 4, "{delta:?}");
+        assert! (delta.files_enumerated    >= 4, "{delta:?}");  // a.txt, b.txt, sub, c.txt
+        assert! (delta.stream_enumerations >= 3, "{delta:?}");  // files only
+        assert! (delta.owner_lookups       >= 4, "{delta:?}");
+    }
+
+
+
 
-    let prober = nerd_font_detector::DefaultFontProber;
-    let env    = environment_provider::DefaultEnvironmentProvider;
 
-    let result = nerd_font_detector::detect (console_handle, &env, &prober);
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  icons_force_ignores_invalid_console_handle
+    //
+    //  Verify /Icons:force and plain /Icons produce icons without a valid
+    //  console handle, while auto-detection alone falls back to off.
+    //
+    ////////////////////////////////////////////////////////////////////////////
 
-    matches!(result, nerd_font_detector::DetectionResult::Detected)
+    #[test]
+    fn icons_force_ignores_invalid_console_handle() {
+        let cfg = config::Config::new();
+        let never_detect = |_| -> nerd_font_detector::DetectionResult {
+            panic! ("detection must not run without a console handle")
+        };
+
+        let forced = command_line::CommandLine::parse_from (["/Icons:force", "/Icons-"]).unwrap();
+        assert! (resolve_icons_with (&forced, &cfg, None, never_detect));
+
+        let cli = command_line::CommandLine::parse_from (["/Icons"]).unwrap();
+        assert! (resolve_icons_with (&cli, &cfg, None, never_detect));
+
+        let auto = command_line::CommandLine::parse_from (std::iter::empty::<&str>()).unwrap();
+        assert! (!resolve_icons_with (&auto, &cfg, None, never_detect));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  tree_dirs_lists_only_directories
+    //
+    //  Verify /Tree:dirs renders every directory node but no files, while
+    //  the files are still counted in the totals.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn tree_dirs_lists_only_directories() {
+        let temp = TempDir::new ("tree_dirs");
+        let root = temp.path();
+        std::fs::create_dir_all (root.join ("src\\deep")).unwrap();
+        std::fs::write (root.join ("top.txt"), b"1").unwrap();
+        std::fs::write (root.join ("src\\main.rs"), b"22").unwrap();
+        std::fs::write (root.join ("src\\deep\\leaf.txt"), b"333").unwrap();
+
+        let mut cfg = config::Config::new();
+        cfg.initialize (0x07);
+        let cfg = Arc::new (cfg);
+
+        let mut console = console::Console::new_for_testing (Arc::clone (&cfg));
+        console.set_color_mode (command_line::ColorMode::Never);
+
+        let cmd        = Arc::new (command_line::CommandLine::parse_from (["/Tree:dirs", "/NoCloud"]).unwrap());
+        let drive_info = drive_info::DriveInfo::new (root).unwrap();
+        let mut displayer = results_displayer::Displayer::new (console, Arc::clone (&cmd), Arc::clone (&cfg), false);
+        let mut totals    = listing_totals::ListingTotals::default();
+
+        process_multi_threaded (&drive_info, root, &[OsString::from ("*")], &cmd, &cfg, &mut displayer, &mut totals);
+        let output = displayer.into_console().take_test_buffer();
+
+        assert! (output.contains ("src"));
+        assert! (output.contains ("deep"));
+        for file in ["top.txt", "main.rs", "leaf.txt"] {
+            assert! (!output.contains (file), "{file} should not be listed:\n{output}");
+        }
+        assert_eq! (totals.file_count, 3);
+        assert_eq! (totals.file_bytes, 6);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  dedupe_lists_overlapping_matches_once
+    //
+    //  Verify a file matched by both *.txt and *.t* is listed twice by
+    //  default but once per directory with /Dedupe, including under /S.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn dedupe_lists_overlapping_matches_once() {
+        let temp = TempDir::new ("dedupe");
+        let root = temp.path();
+        std::fs::create_dir_all (root.join ("sub")).unwrap();
+        std::fs::write (root.join ("alpha.txt"), b"1").unwrap();
+        std::fs::write (root.join ("beta.tmp"), b"22").unwrap();
+        std::fs::write (root.join ("sub\\gamma.txt"), b"333").unwrap();
+
+        let specs = ["*.txt", "*.t*"];
+
+        let plain = render_single_threaded_specs (root, &specs, &["/S", "/NoCloud"]);
+        assert_eq! (plain.matches ("alpha.txt").count(), 2, "{plain}");
+        assert_eq! (plain.matches ("gamma.txt").count(), 2, "{plain}");
+
+        let deduped = render_single_threaded_specs (root, &specs, &["/S", "/NoCloud", "/Dedupe"]);
+        assert_eq! (deduped.matches ("alpha.txt").count(), 1, "{deduped}");
+        assert_eq! (deduped.matches ("beta.tmp").count(), 1, "{deduped}");
+        assert_eq! (deduped.matches ("gamma.txt").count(), 1, "{deduped}");
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  FakeGroupProbe
+    //
+    //  Reports the real volume for a path, but with a fixed serial number,
+    //  and resolves only the listed (link, target) pairs.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    struct FakeGroupProbe {
+        volume_serial: Option<u32>,
+        links:         Vec<(PathBuf, PathBuf)>,
+    }
+
+    impl GroupProbe for FakeGroupProbe {
+        fn drive_info (&self, dir_path: &Path) -> Result<drive_info::DriveInfo, AppError> {
+            Ok (drive_info::DriveInfo { volume_serial: self.volume_serial, ..drive_info::DriveInfo::new (dir_path)? })
+        }
+
+        fn linked_target (&self, dir_path: &Path) -> Option<PathBuf> {
+            self.links.iter().find (|(link, _)| link == dir_path).map (|(_, target)| target.clone())
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  same_volume_groups_share_drive_header
+    //
+    //  Verify two directories on the same drive print the volume header
+    //  once but a "Directory of" header for each.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn same_volume_groups_share_drive_header() {
+        let temp = TempDir::new ("shared_header");
+        let root = temp.path();
+        std::fs::create_dir_all (root.join ("one")).unwrap();
+        std::fs::create_dir_all (root.join ("two")).unwrap();
+        std::fs::write (root.join ("one\\a.txt"), b"1").unwrap();
+        std::fs::write (root.join ("two\\b.txt"), b"22").unwrap();
+
+        let mut cfg = config::Config::new();
+        cfg.initialize (0x07);
+        let cfg = Arc::new (cfg);
+
+        let mut console = console::Console::new_for_testing (Arc::clone (&cfg));
+        console.set_color_mode (command_line::ColorMode::Never);
+
+        let cmd    = Arc::new (command_line::CommandLine::parse_from (["/NoCloud"]).unwrap());
+        let probe  = FakeGroupProbe { volume_serial: Some (0x1234_ABCD), links: Vec::new() };
+        let groups = [
+            (root.join ("one"), vec![OsString::from ("*")]),
+            (root.join ("two"), vec![OsString::from ("*")]),
+        ];
+
+        let mut totals             = listing_totals::ListingTotals::default();
+        let mut last_volume_serial = None;
+        for group in &groups {
+            console = process_directory_group_with (group, &cmd, &cfg, console, &mut totals, &mut last_volume_serial, false, &probe);
+        }
+
+        let output = console.take_test_buffer();
+        assert_eq! (output.matches (" Directory of ").count(), 2, "{output}");
+        assert_eq! (output.matches (" Volume Serial Number is 1234-ABCD").count(), 1, "{output}");
+        assert_eq! (last_volume_serial, Some (0x1234_ABCD));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  empty_match_policies
+    //
+    //  Verify a mask that matches nothing reports "No files matching" and
+    //  succeeds by default, fails silently with /FailOnEmpty, and succeeds
+    //  silently with /IgnoreEmpty; a mask that matches never fails.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn empty_match_policies() {
+        let temp = TempDir::new ("empty_policy");
+        let root = temp.path();
+        std::fs::write (root.join ("a.txt"), b"1").unwrap();
+
+        let mut cfg = config::Config::new();
+        cfg.initialize (0x07);
+        let cfg = Arc::new (cfg);
+
+        let list = |spec: &str, args: &[&str]| -> (String, Result<(), AppError>) {
+            let mut console = console::Console::new_for_testing (Arc::clone (&cfg));
+            console.set_color_mode (command_line::ColorMode::Never);
+
+            let mut all_args = vec!["/NoCloud"];
+            all_args.extend_from_slice (args);
+            let cmd = Arc::new (command_line::CommandLine::parse_from (all_args).unwrap());
+
+            let group                  = (root.to_path_buf(), vec![OsString::from (spec)]);
+            let mut totals             = listing_totals::ListingTotals::default();
+            let mut last_volume_serial = None;
+            let mut console = process_directory_group (&group, &cmd, &cfg, console, &mut totals, &mut last_volume_serial, false);
+
+            (console.take_test_buffer(), check_empty_listing (&cmd, &totals))
+        };
+
+        let (output, result) = list ("*.zzz", &[]);
+        assert! (output.contains ("No files matching '*.zzz' found."), "{output}");
+        assert! (result.is_ok());
+
+        let (output, result) = list ("*.zzz", &["/FailOnEmpty"]);
+        assert! (!output.contains ("No files matching"), "{output}");
+        assert! (matches! (result, Err (AppError::NoMatches)));
+        assert_eq! (AppError::NoMatches.to_string(), "");
+
+        let (output, result) = list ("*.zzz", &["/IgnoreEmpty"]);
+        assert! (!output.contains ("No files matching"), "{output}");
+        assert! (result.is_ok());
+
+        let (output, result) = list ("*.txt", &["/FailOnEmpty"]);
+        assert! (output.contains ("a.txt"), "{output}");
+        assert! (result.is_ok());
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  mode_env_entry_selects_displayer
+    //
+    //  Verify RCDIR=Mode=tree picks the tree displayer when the command
+    //  line names no listing style, and /W on the command line still wins.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn mode_env_entry_selects_displayer() {
+        use environment_provider::MockEnvironmentProvider;
+        use results_displayer::Displayer;
+
+        let mut env = MockEnvironmentProvider::new();
+        env.set ("RCDIR", "Mode=tree");
+
+        let mut cfg = config::Config::new();
+        cfg.initialize_with_provider (0x07, &env);
+        let cfg = Arc::new (cfg);
+
+        let select = |args: &[&str]| -> Displayer {
+            let mut cmd = command_line::CommandLine::parse_from (args.iter().copied()).unwrap();
+            cmd.apply_config_defaults (&cfg);
+
+            let console = console::Console::new_for_testing (Arc::clone (&cfg));
+            Displayer::new (console, Arc::new (cmd), Arc::clone (&cfg), false)
+        };
+
+        assert! (matches! (select (&[]),      Displayer::Tree (_)));
+        assert! (matches! (select (&["/W"]),  Displayer::Wide (_)));
+        assert! (matches! (select (&["/B"]),  Displayer::Bare (_)));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  error_summary_combines_failed_groups
+    //
+    //  Verify two missing directories are reported together in one summary
+    //  after the listing (not inline) under /ErrorSummary and fail the
+    //  run, while the default reports them inline and still succeeds.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn error_summary_combines_failed_groups() {
+        let temp    = TempDir::new ("error_summary");
+        let missing = [temp.path().join ("gone1"), temp.path().join ("gone2")];
+
+        let mut cfg = config::Config::new();
+        cfg.initialize (0x07);
+        let cfg = Arc::new (cfg);
+
+        let list = |args: &[&str]| -> (String, String, Result<(), AppError>) {
+            let mut console = console::Console::new_for_testing (Arc::clone (&cfg));
+            console.set_color_mode (command_line::ColorMode::Never);
+
+            let cmd                    = Arc::new (command_line::CommandLine::parse_from (args.iter().copied()).unwrap());
+            let mut totals             = listing_totals::ListingTotals::default();
+            let mut last_volume_serial = None;
+
+            for dir in &missing {
+                let group = (dir.clone(), vec![OsString::from ("*")]);
+                console = process_directory_group (&group, &cmd, &cfg, console, &mut totals, &mut last_volume_serial, false);
+            }
+            let listing = console.take_test_buffer();
+
+            if cmd.error_summary {
+                display_error_summary (&mut console, &totals);
+            }
+
+            (listing, console.take_test_buffer(), check_failed_groups (&cmd, &totals))
+        };
+
+        let (listing, summary, result) = list (&["/ErrorSummary"]);
+        assert! (!listing.contains ("does not exist"), "{listing}");
+        assert! (summary.contains ("2 directories could not be listed:"), "{summary}");
+        for dir in &missing {
+            assert! (summary.contains (&format! ("{}: does not exist", dir.display())), "{summary}");
+        }
+        assert! (matches! (result, Err (AppError::GroupsFailed)));
+        assert_eq! (AppError::GroupsFailed.to_string(), "");
+
+        let (listing, summary, result) = list (&[]);
+        assert_eq! (listing.matches ("does not exist").count(), 2, "{listing}");
+        assert_eq! (summary, "");
+        assert! (result.is_ok());
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  status_line_reflects_accumulated_totals
+    //
+    //  Verify /Status reports the files, directories, bytes, and failed
+    //  groups summed across every directory group, and /Status- turns it
+    //  back off.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn status_line_reflects_accumulated_totals () {
+        let mut totals = listing_totals::ListingTotals::default();
+        assert_eq! (format_status_line (&totals), "0 files, 0 dirs, 0 B, 0 errors");
+
+        let first = listing_totals::ListingTotals { file_count: 40, directory_count: 2, file_bytes: 12_000_000, ..Default::default() };
+        let second = listing_totals::ListingTotals {
+            file_count:      2,
+            directory_count: 1,
+            file_bytes:      897_485,
+            group_errors:    vec![listing_totals::GroupError { path: PathBuf::from ("Z:\\gone"), reason: "does not exist" }],
+            ..Default::default()
+        };
+        totals.add (&first);
+        totals.add (&second);
+
+        assert_eq! (format_status_line (&totals), "42 files, 3 dirs, 12.3 MB, 1 error");
+
+        assert! (command_line::CommandLine::parse_from (["/Status"]).unwrap().status_line);
+        assert! (!command_line::CommandLine::parse_from (["/Status", "/Status-"]).unwrap().status_line);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  deref_arg_lists_link_target
+    //
+    //  Verify /DerefArg lists a linked directory argument as its target,
+    //  with a note naming both, that without the flag the link path is
+    //  taken as given, and that an ordinary directory doesn't resolve.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn deref_arg_lists_link_target () {
+        let temp = TempDir::new ("deref_arg");
+        let real = temp.path().join ("real");
+        let link = temp.path().join ("link");
+        std::fs::create_dir_all (&real).unwrap();
+        std::fs::write (real.join ("data.txt"), b"x").unwrap();
+
+        let mut cfg = config::Config::new();
+        cfg.initialize (0x07);
+        let cfg = Arc::new (cfg);
+
+        let probe = FakeGroupProbe { volume_serial: None, links: vec![(link.clone(), real.clone())] };
+        let list  = |args: &[&str]| -> String {
+            let mut console = console::Console::new_for_testing (Arc::clone (&cfg));
+            console.set_color_mode (command_line::ColorMode::Never);
+
+            let cmd                    = Arc::new (command_line::CommandLine::parse_from (args.iter().copied()).unwrap());
+            let group                  = (link.clone(), vec![OsString::from ("*")]);
+            let mut totals             = listing_totals::ListingTotals::default();
+            let mut last_volume_serial = None;
+            console = process_directory_group_with (&group, &cmd, &cfg, console, &mut totals, &mut last_volume_serial, false, &probe);
+            console.take_test_buffer()
+        };
+
+        let followed = list (&["/NoCloud", "/DerefArg"]);
+        assert! (followed.contains (&format! ("Following link {} to {}", link.display(), real.display())), "{followed}");
+        assert! (followed.contains (&format! (" Directory of {}", real.display())), "{followed}");
+        assert! (followed.contains ("data.txt"), "{followed}");
+
+        // Without the flag the link is taken as given, and this one doesn't exist
+        let plain = list (&["/NoCloud"]);
+        assert! (!plain.contains ("Following link"), "{plain}");
+        assert! (plain.contains ("does not exist"), "{plain}");
+
+        assert_eq! (resolve_linked_argument (&real), None);
+    }
 }
@@ -2,15 +2,41 @@
 //
 // Port of: ListingTotals.h → SListingTotals
 
-/// Aggregates file/directory/stream counts and sizes.
+use std::path::PathBuf;
+
+use crate::directory_info::DirectoryInfo;
+use crate::file_info::{FILE_ATTRIBUTE_REPARSE_POINT, IO_REPARSE_TAG_MOUNT_POINT, IO_REPARSE_TAG_SYMLINK};
+
+
+
+
+
+
+/// Aggregates file/directory/stream/link counts and sizes.
 /// Port of: SListingTotals
 #[derive(Debug, Clone, Default)]
 pub struct ListingTotals {
-    pub file_count:       u32,
-    pub directory_count:  u32,
-    pub file_bytes:       u64,
-    pub stream_count:     u32,
-    pub stream_bytes:     u64,
+    pub file_count:          u32,
+    pub directory_count:     u32,
+    pub file_bytes:          u64,
+    pub stream_count:        u32,
+    pub stream_bytes:        u64,
+    pub empty_dirs_omitted:  u32,  // Subdirectories hidden by /CollapseEmpty
+    pub symlink_count:       u32,  // Matched entries tagged IO_REPARSE_TAG_SYMLINK
+    pub junction_count:      u32,  // Matched entries tagged IO_REPARSE_TAG_MOUNT_POINT
+    pub other_reparse_count: u32,  // Any other reparse point (cloud placeholders, AppExecLinks, ...)
+    pub group_errors:        Vec<GroupError>,  // Directory groups that could not be listed (/ErrorSummary)
+}
+
+
+
+
+
+/// A directory group that could not be listed, and why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupError {
+    pub path:   PathBuf,
+    pub reason: &'static str,
 }
 
 
@@ -53,11 +79,114 @@ pub fn new() -> Self {
     ////////////////////////////////////////////////////////////////////////////
 
     pub fn add(&mut self, other: &ListingTotals) {
-        self.file_count      += other.file_count;
-        self.directory_count += other.directory_count;
-        self.file_bytes      += other.file_bytes;
-        self.stream_count    += other.stream_count;
-        self.stream_bytes    += other.stream_bytes;
+        self.file_count          += other.file_count;
+        self.directory_count     += other.directory_count;
+        self.file_bytes          += other.file_bytes;
+        self.stream_count        += other.stream_count;
+        self.stream_bytes        += other.stream_bytes;
+        self.empty_dirs_omitted  += other.empty_dirs_omitted;
+        self.symlink_count       += other.symlink_count;
+        self.junction_count      += other.junction_count;
+        self.other_reparse_count += other.other_reparse_count;
+        self.group_errors.extend_from_slice (&other.group_errors);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  matched_nothing
+    //
+    //  True if no file or directory matched anywhere in the listing
+    //  (/FailOnEmpty).
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn matched_nothing(&self) -> bool {
+        self.file_count == 0 && self.directory_count == 0
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  count_reparse_point
+    //
+    //  Count a matched entry toward the link totals by its reparse tag.
+    //  Entries without FILE_ATTRIBUTE_REPARSE_POINT are ignored (their tag
+    //  is undefined).
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn count_reparse_point(&mut self, file_attributes: u32, reparse_tag: u32) {
+        if (file_attributes & FILE_ATTRIBUTE_REPARSE_POINT) == 0 {
+            return;
+        }
+
+        match reparse_tag {
+            IO_REPARSE_TAG_SYMLINK     => self.symlink_count       += 1,
+            IO_REPARSE_TAG_MOUNT_POINT => self.junction_count      += 1,
+            _                          => self.other_reparse_count += 1,
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  reparse_point_count
+    //
+    //  Symlinks, junctions, and other reparse points together.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn reparse_point_count(&self) -> u32 {
+        self.symlink_count + self.junction_count + self.other_reparse_count
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  total_bytes
+    //
+    //  File data plus alternate stream data: the space the listing uses.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn total_bytes(&self) -> u64 {
+        self.file_bytes + self.stream_bytes
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  omit_if_empty
+    //
+    //  For /CollapseEmpty: if a subdirectory has no matching files (nothing
+    //  at all, or only subdirectory entries), count it as omitted and
+    //  return true so the caller skips its header, listing, and summary.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn omit_if_empty(&mut self, di: &DirectoryInfo) -> bool {
+        if di.file_count > 0 {
+            return false;
+        }
+
+        self.empty_dirs_omitted += 1;
+        true
     }
 }
 
@@ -99,13 +228,90 @@ fn default_is_zero() {
 
     #[test]
     fn add_accumulates() {
-        let mut a = ListingTotals { file_count: 3, directory_count: 1, file_bytes: 1000, stream_count: 0, stream_bytes: 0 };
-        let b = ListingTotals { file_count: 5, directory_count: 2, file_bytes: 2000, stream_count: 1, stream_bytes: 100 };
+        let mut a = ListingTotals { file_count: 3, directory_count: 1, file_bytes: 1000, stream_count: 0, stream_bytes: 0, empty_dirs_omitted: 2, ..Default::default() };
+        let b = ListingTotals { file_count: 5, directory_count: 2, file_bytes: 2000, stream_count: 1, stream_bytes: 100, empty_dirs_omitted: 3, ..Default::default() };
         a.add(&b);
         assert_eq!(a.file_count, 8);
         assert_eq!(a.directory_count, 3);
         assert_eq!(a.file_bytes, 3000);
         assert_eq!(a.stream_count, 1);
         assert_eq!(a.stream_bytes, 100);
+        assert_eq!(a.empty_dirs_omitted, 5);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  omit_if_empty_counts_directories_without_files
+    //
+    //  Verifies directories with no matches, or only subdirectory matches,
+    //  are omitted and counted, and a directory with a file is kept.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn omit_if_empty_counts_directories_without_files() {
+        let mut totals = ListingTotals::new();
+
+        let empty = DirectoryInfo::new(std::path::PathBuf::from("C:\\a"), "*".into());
+        assert!(totals.omit_if_empty(&empty));
+
+        let mut only_subdirs = DirectoryInfo::new(std::path::PathBuf::from("C:\\b"), "*".into());
+        only_subdirs.subdirectory_count = 2;
+        assert!(totals.omit_if_empty(&only_subdirs));
+
+        let mut with_file = DirectoryInfo::new(std::path::PathBuf::from("C:\\c"), "*".into());
+        with_file.file_count = 1;
+        assert!(!totals.omit_if_empty(&with_file));
+
+        assert_eq!(totals.empty_dirs_omitted, 2);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  count_reparse_point_sorts_by_tag
+    //
+    //  Verifies a synthetic set of entries lands in the symlink, junction,
+    //  and other counters, that plain entries (even with a stale tag) are
+    //  ignored, and that add carries the counters.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn count_reparse_point_sorts_by_tag() {
+        const IO_REPARSE_TAG_APPEXECLINK: u32 = 0x8000_001B;
+        const IO_REPARSE_TAG_CLOUD_6:     u32 = 0x9000_601A;
+
+        let entries = [
+            (FILE_ATTRIBUTE_REPARSE_POINT,          IO_REPARSE_TAG_SYMLINK),
+            (FILE_ATTRIBUTE_REPARSE_POINT | 0x0010, IO_REPARSE_TAG_SYMLINK),
+            (FILE_ATTRIBUTE_REPARSE_POINT | 0x0010, IO_REPARSE_TAG_MOUNT_POINT),
+            (FILE_ATTRIBUTE_REPARSE_POINT,          IO_REPARSE_TAG_APPEXECLINK),
+            (FILE_ATTRIBUTE_REPARSE_POINT,          IO_REPARSE_TAG_CLOUD_6),
+            (0x0020,                                IO_REPARSE_TAG_SYMLINK),
+            (0x0010,                                0),
+        ];
+
+        let mut totals = ListingTotals::new();
+        for (attributes, tag) in entries {
+            totals.count_reparse_point(attributes, tag);
+        }
+
+        assert_eq!(totals.symlink_count, 2);
+        assert_eq!(totals.junction_count, 1);
+        assert_eq!(totals.other_reparse_count, 2);
+        assert_eq!(totals.reparse_point_count(), 5);
+
+        let mut sum = ListingTotals::new();
+        sum.add(&totals);
+        sum.add(&totals);
+        assert_eq!(sum.reparse_point_count(), 10);
     }
 }
@@ -17,7 +17,10 @@
 
 fn main() {
     if let Err(e) = rcdir::run() {
-        eprintln!("{}", e);
+        let msg = e.to_string();
+        if !msg.is_empty() {
+            eprintln!("{}", msg);
+        }
         process::exit(1);
     }
 }
@@ -5,10 +5,15 @@
 // Groups command-line file masks by their directory component. Pure masks
 // (no path separator) are combined under CWD. Directory-qualified masks
 // are grouped by their normalized directory path (case-insensitive).
+// Matcher compiles a glob mask once for repeated name tests.
+// expand_path resolves %VAR% and a leading ~ before any of that happens.
 
-use std::ffi::OsString;
+use std::ffi::{OsStr, OsString};
 use std::path::{Path, PathBuf};
 
+use crate::ehm::AppError;
+use crate::environment_provider::EnvironmentProvider;
+
 
 
 
@@ -107,6 +112,72 @@ pub fn is_pure_mask(mask: &str) -> bool {
 
 
 
+////////////////////////////////////////////////////////////////////////////////
+//
+//  has_wildcards
+//
+//  True if a mask contains a * or ? wildcard.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn has_wildcards(mask: &str) -> bool {
+    mask.contains (['*', '?'])
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  expand_path
+//
+//  Expand a path argument the way cmd and PowerShell users expect to
+//  type it: each %VAR% is replaced by that environment variable, and a
+//  leading ~ (alone or before a separator) by USERPROFILE.  A lone '%'
+//  with no closing '%' is kept literally; a variable that isn't defined
+//  is an error rather than a silently wrong path.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn expand_path(path: &str, provider: &dyn EnvironmentProvider) -> Result<String, AppError> {
+    let lookup = |name: &str| {
+        provider.get_env_var (name).ok_or_else (|| AppError::InvalidArg (
+            format! ("Environment variable '{}' in '{}' is not defined", name, path)
+        ))
+    };
+
+    let mut expanded = String::with_capacity (path.len());
+    let mut rest     = path;
+
+    if let Some (after) = rest.strip_prefix ('~')
+        && (after.is_empty() || after.starts_with (['\\', '/']))
+    {
+        expanded.push_str (&lookup ("USERPROFILE")?);
+        rest = after;
+    }
+
+    while let Some (start) = rest.find ('%') {
+        let Some (len) = rest[start + 1..].find ('%') else { break };
+        let name = &rest[start + 1..start + 1 + len];
+
+        expanded.push_str (&rest[..start]);
+        if name.is_empty() {
+            expanded.push ('%');
+        } else {
+            expanded.push_str (&lookup (name)?);
+        }
+        rest = &rest[start + len + 2..];
+    }
+
+    expanded.push_str (rest);
+    Ok (expanded)
+}
+
+
+
+
+
 ////////////////////////////////////////////////////////////////////////////////
 //
 //  strip_extended_length_prefix
@@ -116,7 +187,7 @@ pub fn is_pure_mask(mask: &str) -> bool {
 //
 ////////////////////////////////////////////////////////////////////////////////
 
-fn strip_extended_length_prefix(path: PathBuf) -> PathBuf {
+pub(crate) fn strip_extended_length_prefix(path: PathBuf) -> PathBuf {
     let s = path.to_string_lossy();
     if let Some (stripped) = s.strip_prefix (r"\\?\") {
         PathBuf::from (stripped)
@@ -276,13 +347,16 @@ fn add_mask_to_groups(
 //
 //  group_masks_by_directory
 //
-//  Group command-line masks by their target directory.
+//  Group command-line masks by their target directory.  A base directory
+//  (/Base or DefaultPath=) stands in for the current directory, so bare
+//  and relative masks resolve under it; a relative base is itself taken
+//  relative to the current directory.
 //  Port of: CMaskGrouper::GroupMasksByDirectory
 //
 ////////////////////////////////////////////////////////////////////////////////
 
-pub fn group_masks_by_directory(masks: &[OsString]) -> Vec<MaskGroup> {
-    group_masks_by_directory_with_fs (masks, &DefaultFileSystemQuery)
+pub fn group_masks_by_directory(masks: &[OsString], base: Option<&Path>) -> Vec<MaskGroup> {
+    group_masks_by_directory_with_fs (masks, base, &DefaultFileSystemQuery)
 }
 
 
@@ -297,8 +371,12 @@ pub fn group_masks_by_directory(masks: &[OsString]) -> Vec<MaskGroup> {
 //
 ////////////////////////////////////////////////////////////////////////////////
 
-fn group_masks_by_directory_with_fs(masks: &[OsString], fs: &dyn FileSystemQuery) -> Vec<MaskGroup> {
+fn group_masks_by_directory_with_fs(masks: &[OsString], base: Option<&Path>, fs: &dyn FileSystemQuery) -> Vec<MaskGroup> {
     let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let cwd = match base {
+        Some (base) => cwd.join (base),
+        None        => cwd,
+    };
     group_masks_with_cwd_and_fs (masks, &cwd, fs)
 }
 
@@ -338,6 +416,272 @@ fn group_masks_with_cwd_and_fs(masks: &[OsString], cwd: &Path, fs: &dyn FileSyst
 
 
 
+/// One compiled element of a glob pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum GlobToken {
+    /// A single literal character (already case-folded when insensitive)
+    Literal (char),
+    /// `?` — exactly one character
+    AnyOne,
+    /// `*` — any run of characters, including none
+    AnyRun,
+    /// `[a-c]` / `[!a-c]` — one character in (or not in) the ranges
+    Class { ranges: Vec<(char, char)>, negated: bool },
+}
+
+
+
+
+
+/// A glob mask compiled once and matched against many names.
+///
+/// Supports `*`, `?` and character classes (`[abc]`, `[a-c]`, `[!a-c]`).
+/// A `[` with no closing `]` is a literal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matcher {
+    tokens:           Vec<GlobToken>,
+    case_insensitive: bool,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl Matcher
+//
+//  Compiling a glob and testing names against it.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl Matcher {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  new
+    //
+    //  Compile a case-insensitive matcher, like the file system.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn new(pattern: &str) -> Self {
+        Self::with_case_sensitivity (pattern, false)
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  with_case_sensitivity
+    //
+    //  Compile a matcher that compares characters exactly when
+    //  `case_sensitive` is set, else case-insensitively.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn with_case_sensitivity(pattern: &str, case_sensitive: bool) -> Self {
+        let case_insensitive = !case_sensitive;
+        let chars: Vec<char> = pattern.chars().collect();
+        let mut tokens = Vec::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            match chars[i] {
+                '*' => {
+                    // Collapse runs of '*' — they match the same thing
+                    if tokens.last() != Some (&GlobToken::AnyRun) {
+                        tokens.push (GlobToken::AnyRun);
+                    }
+                    i += 1;
+                }
+                '?' => {
+                    tokens.push (GlobToken::AnyOne);
+                    i += 1;
+                }
+                '[' => match parse_class (&chars, i + 1) {
+                    Some ((token, next)) => {
+                        tokens.push (token);
+                        i = next;
+                    }
+                    None => {
+                        tokens.push (GlobToken::Literal ('['));
+                        i += 1;
+                    }
+                },
+                ch => {
+                    tokens.push (GlobToken::Literal (if case_insensitive { fold_case (ch) } else { ch }));
+                    i += 1;
+                }
+            }
+        }
+
+        Matcher { tokens, case_insensitive }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  literal
+    //
+    //  Compile an exact, case-insensitive name: every character, including
+    //  '[', ']', '*' and '?', matches only itself (/Literal).
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn literal(name: &str) -> Self {
+        Matcher {
+            tokens:           name.chars().map (|ch| GlobToken::Literal (fold_case (ch))).collect(),
+            case_insensitive: true,
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  matches
+    //
+    //  True if the whole of `name` matches the pattern.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn matches(&self, name: &OsStr) -> bool {
+        let text: Vec<char> = name.to_string_lossy().chars().collect();
+
+        // Iterative matcher with single-star backtracking
+        let (mut p, mut t) = (0, 0);
+        let mut star: Option<(usize, usize)> = None;
+
+        while t < text.len() {
+            if p < self.tokens.len() && self.token_matches (&self.tokens[p], text[t]) {
+                p += 1;
+                t += 1;
+            } else if p < self.tokens.len() && self.tokens[p] == GlobToken::AnyRun {
+                star = Some ((p, t));
+                p += 1;
+            } else if let Some ((star_p, star_t)) = star {
+                p = star_p + 1;
+                t = star_t + 1;
+                star = Some ((star_p, star_t + 1));
+            } else {
+                return false;
+            }
+        }
+
+        self.tokens[p..].iter().all (|token| *token == GlobToken::AnyRun)
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  token_matches
+    //
+    //  True if a single-character token accepts `ch`.  `*` never matches
+    //  here; the caller handles it.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn token_matches(&self, token: &GlobToken, ch: char) -> bool {
+        match token {
+            GlobToken::AnyOne                  => true,
+            GlobToken::AnyRun                  => false,
+            GlobToken::Literal (lit)           => {
+                *lit == if self.case_insensitive { fold_case (ch) } else { ch }
+            }
+            GlobToken::Class { ranges, negated } => {
+                let in_ranges = |c: char| ranges.iter().any (|&(lo, hi)| lo <= c && c <= hi);
+
+                let found = if self.case_insensitive {
+                    in_ranges (ch)
+                        || ch.to_lowercase().any (in_ranges)
+                        || ch.to_uppercase().any (in_ranges)
+                } else {
+                    in_ranges (ch)
+                };
+
+                found != *negated
+            }
+        }
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  parse_class
+//
+//  Parse a character class whose body starts at `start` (just past '[').
+//  Returns the token and the index after ']', or None if unterminated.
+//  A leading '!' or '^' negates; a ']' right after the opener is literal.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn parse_class(chars: &[char], start: usize) -> Option<(GlobToken, usize)> {
+    let mut i = start;
+    let negated = matches! (chars.get (i), Some ('!') | Some ('^'));
+
+    if negated {
+        i += 1;
+    }
+
+    let body_start = i;
+    let mut ranges = Vec::new();
+
+    while i < chars.len() {
+        let ch = chars[i];
+
+        if ch == ']' && i > body_start {
+            return Some ((GlobToken::Class { ranges, negated }, i + 1));
+        }
+
+        if chars.get (i + 1) == Some (&'-') && i + 2 < chars.len() && chars[i + 2] != ']' {
+            let (lo, hi) = (ch, chars[i + 2]);
+            ranges.push (if lo <= hi { (lo, hi) } else { (hi, lo) });
+            i += 3;
+        } else {
+            ranges.push ((ch, ch));
+            i += 1;
+        }
+    }
+
+    None
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  fold_case
+//
+//  Simple one-to-one lowercase mapping, so '?' still consumes exactly
+//  one character of the original name.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn fold_case(ch: char) -> char {
+    ch.to_lowercase().next().unwrap_or (ch)
+}
+
+
+
+
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -888,4 +1232,303 @@ fn is_pure_mask_absolute_path_returns_false() {
         assert! (!is_pure_mask (r"C:\foo\*.cpp"));
         assert! (!is_pure_mask (r"D:\file.txt"));
     }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  qualified_file_argument_lists_single_file_in_parent
+    //
+    //  Verifies a path naming an existing file (not a directory) is listed
+    //  as a one-entry spec in its parent directory, like `dir file.txt`.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn qualified_file_argument_lists_single_file_in_parent () {
+        let cwd = PathBuf::from (r"C:\Work");
+        let fs = MockFileSystemQuery::new().with_dir (Path::new (r"C:\path\to"));
+        let masks = vec![OsString::from (r"C:\path\to\file.txt")];
+
+        let groups = group_masks_with_cwd_and_fs (&masks, &cwd, &fs);
+
+        assert_eq! (groups.len(), 1);
+        assert_eq! (groups[0].0, PathBuf::from (r"C:\path\to"));
+        assert_eq! (groups[0].1, vec![OsString::from ("file.txt")]);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  pure_file_argument_lists_single_file_in_cwd
+    //
+    //  Verifies a bare filename (no wildcards, not a directory) is listed
+    //  as a one-entry spec in the current directory.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn pure_file_argument_lists_single_file_in_cwd () {
+        let cwd = PathBuf::from (r"C:\Work");
+        let fs = MockFileSystemQuery::new().with_dir (&cwd);
+        let masks = vec![OsString::from ("file.txt")];
+
+        let groups = group_masks_with_cwd_and_fs (&masks, &cwd, &fs);
+
+        assert_eq! (groups.len(), 1);
+        assert_eq! (groups[0].0, cwd);
+        assert_eq! (groups[0].1, vec![OsString::from ("file.txt")]);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  missing_path_keeps_nonexistent_parent
+    //
+    //  Verifies a path under a missing directory keeps that directory as
+    //  the group path, so process_directory_group reports it as missing.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn missing_path_keeps_nonexistent_parent () {
+        let cwd = PathBuf::from (r"C:\Work");
+        let fs = MockFileSystemQuery::new();
+        let masks = vec![OsString::from (r"C:\missing\file.txt")];
+
+        let groups = group_masks_with_cwd_and_fs (&masks, &cwd, &fs);
+
+        assert_eq! (groups.len(), 1);
+        assert_eq! (groups[0].0, PathBuf::from (r"C:\missing"));
+        assert! (!fs.is_dir (&groups[0].0));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  matcher_question_mark_matches_one_char
+    //
+    //  Verify '?' consumes exactly one character.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn matcher_question_mark_matches_one_char() {
+        let m = Matcher::new ("file?.txt");
+
+        assert! (m.matches (OsStr::new ("file1.txt")));
+        assert! (m.matches (OsStr::new ("fileA.txt")));
+        assert! (!m.matches (OsStr::new ("file.txt")));
+        assert! (!m.matches (OsStr::new ("file12.txt")));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  matcher_star_matches_any_run
+    //
+    //  Verify '*' matches empty and non-empty runs, anywhere in the mask.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn matcher_star_matches_any_run() {
+        let m = Matcher::new ("*.rs");
+
+        assert! (m.matches (OsStr::new ("main.rs")));
+        assert! (m.matches (OsStr::new (".rs")));
+        assert! (!m.matches (OsStr::new ("main.rsx")));
+
+        let m = Matcher::new ("a*b*c");
+        assert! (m.matches (OsStr::new ("abc")));
+        assert! (m.matches (OsStr::new ("a-b-b-c")));
+        assert! (!m.matches (OsStr::new ("a-c-b")));
+
+        assert! (Matcher::new ("**").matches (OsStr::new ("")));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  matcher_character_class
+    //
+    //  Verify [a-c] ranges, explicit sets, negation, and that an
+    //  unterminated '[' is a literal.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn matcher_character_class() {
+        let m = Matcher::new ("[a-c]*.log");
+
+        assert! (m.matches (OsStr::new ("alpha.log")));
+        assert! (m.matches (OsStr::new ("c.log")));
+        assert! (!m.matches (OsStr::new ("delta.log")));
+
+        assert! (Matcher::new ("v[13x]").matches (OsStr::new ("vx")));
+        assert! (!Matcher::new ("v[13x]").matches (OsStr::new ("v2")));
+
+        assert! (Matcher::new ("[!a-c]*").matches (OsStr::new ("delta")));
+        assert! (!Matcher::new ("[!a-c]*").matches (OsStr::new ("beta")));
+
+        assert! (Matcher::new ("a[b").matches (OsStr::new ("a[b")));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  matcher_case_sensitivity
+    //
+    //  Verify the default is case-insensitive (literals and classes) and
+    //  that a case-sensitive matcher compares exactly.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn matcher_case_sensitivity() {
+        assert! (Matcher::new ("README.*").matches (OsStr::new ("readme.md")));
+        assert! (Matcher::new ("[A-C]x").matches (OsStr::new ("bX")));
+
+        let exact = Matcher::with_case_sensitivity ("README.*", true);
+        assert! (exact.matches (OsStr::new ("README.md")));
+        assert! (!exact.matches (OsStr::new ("readme.md")));
+        assert! (!Matcher::with_case_sensitivity ("[A-C]x", true).matches (OsStr::new ("bx")));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  base_directory_replaces_cwd_for_bare_masks
+    //
+    //  Verifies a configured base directory (/Base, DefaultPath=) is listed
+    //  instead of the current directory, and bare directory names resolve
+    //  under it.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn base_directory_replaces_cwd_for_bare_masks() {
+        let base = PathBuf::from (r"C:\Projects\app");
+        let fs = MockFileSystemQuery::new()
+            .with_dir (&base.join ("src"));
+
+        let groups = group_masks_by_directory_with_fs (&[OsString::from ("*")], Some (&base), &fs);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].0, base);
+        assert_eq!(groups[0].1, vec![OsString::from ("*")]);
+
+        let groups = group_masks_by_directory_with_fs (&[OsString::from ("src")], Some (&base), &fs);
+        assert_eq!(groups[0].0, base.join ("src"));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  expand_path_resolves_variables_and_tilde
+    //
+    //  Verify %TEMP%\foo and ~\Documents expand through the provider, that
+    //  lookups are per-variable, and that stray '%' and mid-path '~' stay
+    //  literal.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn expand_path_resolves_variables_and_tilde() {
+        use crate::environment_provider::MockEnvironmentProvider;
+
+        let mut env = MockEnvironmentProvider::new();
+        env.set ("TEMP",        "C:\\Users\\me\\AppData\\Local\\Temp");
+        env.set ("USERPROFILE", "C:\\Users\\me");
+        env.set ("DRIVE",       "D:");
+
+        assert_eq! (expand_path ("%TEMP%\\foo", &env).unwrap(),          "C:\\Users\\me\\AppData\\Local\\Temp\\foo");
+        assert_eq! (expand_path ("~\\Documents\\*.md", &env).unwrap(),   "C:\\Users\\me\\Documents\\*.md");
+        assert_eq! (expand_path ("~", &env).unwrap(),                     "C:\\Users\\me");
+        assert_eq! (expand_path ("%DRIVE%\\%USERPROFILE%", &env).unwrap(), "D:\\C:\\Users\\me");
+        assert_eq! (expand_path ("*.rs", &env).unwrap(),                  "*.rs");
+        assert_eq! (expand_path ("100%.txt", &env).unwrap(),              "100%.txt");
+        assert_eq! (expand_path ("~backup\\a~b", &env).unwrap(),          "~backup\\a~b");
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  expand_path_unknown_variable_errors
+    //
+    //  Verify an undefined %VAR%, or ~ without USERPROFILE, is an error
+    //  naming the variable.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn expand_path_unknown_variable_errors() {
+        use crate::environment_provider::MockEnvironmentProvider;
+
+        let env = MockEnvironmentProvider::new();
+
+        match expand_path ("%NOPE%\\foo", &env) {
+            Err (AppError::InvalidArg (msg)) => assert! (msg.contains ("NOPE"), "{msg}"),
+            other => panic! ("expected InvalidArg, got {other:?}"),
+        }
+        assert! (expand_path ("~\\Documents", &env).is_err());
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  literal_matcher_ignores_glob_syntax
+    //
+    //  Verify a literal matcher treats [1] as characters where the glob
+    //  matcher sees a class, still ignoring case.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn literal_matcher_ignores_glob_syntax() {
+        let glob    = Matcher::new ("file[1].txt");
+        let literal = Matcher::literal ("file[1].txt");
+
+        assert! (glob.matches (OsStr::new ("file1.txt")));
+        assert! (!glob.matches (OsStr::new ("file[1].txt")));
+
+        assert! (literal.matches (OsStr::new ("file[1].txt")));
+        assert! (literal.matches (OsStr::new ("FILE[1].TXT")));
+        assert! (!literal.matches (OsStr::new ("file1.txt")));
+        assert! (!Matcher::literal ("a*").matches (OsStr::new ("abc")));
+
+        assert! (has_wildcards ("*.rs") && has_wildcards ("a?c"));
+        assert! (!has_wildcards ("file[1].txt"));
+    }
 }
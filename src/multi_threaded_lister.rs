@@ -7,7 +7,7 @@
 
 use std::collections::HashMap;
 use std::collections::HashSet;
-use std::ffi::OsString;
+use std::ffi::{OsStr, OsString};
 use std::os::windows::ffi::{OsStrExt, OsStringExt};
 use std::path::Path;
 use std::sync::{Arc, Condvar, Mutex, Weak};
@@ -20,13 +20,26 @@
 
 use crate::command_line::CommandLine;
 use crate::config::Config;
+use crate::dir_exclusion::is_excluded_dir;
+use crate::directory_lister::{
+    count_directory_entries, is_skipped_dots, literal_name_filter, passes_entry_filters, passes_literal_filter,
+    passes_owner_filter, passes_reparse_filter,
+};
 use crate::directory_info::{DirectoryInfo, DirectoryStatus};
 use crate::drive_info::DriveInfo;
 use crate::file_comparator;
+use crate::encoding;
+use crate::file_id;
+use crate::file_type;
 use crate::file_info::{FileInfo, FindHandle, FILE_ATTRIBUTE_DIRECTORY, FILE_ATTRIBUTE_REPARSE_POINT};
+use crate::hashing::{self, HashAlgorithm};
 use crate::listing_totals::ListingTotals;
+use crate::on_disk_size;
+use crate::progress::ProgressIndicator;
 use crate::results_displayer::{DirectoryLevel, Displayer, ResultsDisplayer, TreeDisplayer};
+use crate::streams;
 use crate::tree_connector_state::TreeConnectorState;
+use crate::version_info;
 use crate::work_queue::WorkQueue;
 
 
@@ -69,11 +82,12 @@ impl MultiThreadedLister {
     //
     //  new
     //
-    //  Create a new multi-threaded lister and spawn worker threads.
+    //  Create a new multi-threaded lister and spawn worker threads.  If a
+    //  progress indicator is given, workers report each finished directory.
     //
     ////////////////////////////////////////////////////////////////////////////
 
-    pub fn new(cmd: Arc<CommandLine>, config: Arc<Config>) -> Self {
+    pub fn new(cmd: Arc<CommandLine>, config: Arc<Config>, progress: Option<Arc<ProgressIndicator>>) -> Self {
         let work_queue          = Arc::new (WorkQueue::new());
         let stop                = Arc::new (AtomicBool::new (false));
         let tree_pruning_active = Arc::new (AtomicBool::new (false));
@@ -91,9 +105,10 @@ pub fn new(cmd: Arc<CommandLine>, config: Arc<Config>) -> Self {
             let tpa = Arc::clone (&tree_pruning_active);
             let c   = Arc::clone (&cmd);
             let cf  = Arc::clone (&config);
+            let pr  = progress.clone();
 
             workers.push(thread::spawn(move || {
-                worker_thread_func (&wq, &st, &tpa, &c, &cf);
+                worker_thread_func (&wq, &st, &tpa, &c, &cf, pr.as_deref());
             }));
         }
 
@@ -222,7 +237,14 @@ fn print_directory_tree(
             let mut di = node.0.lock().unwrap();
 
             file_comparator::sort_files(&mut di.matches, &self.cmd, false);
-            displayer.display_results(drive_info, &di, level);
+
+            let omitted = self.cmd.collapse_empty
+                && level == DirectoryLevel::Subdirectory
+                && totals.omit_if_empty(&di);
+
+            if !omitted {
+                displayer.display_results(drive_info, &di, level);
+            }
             accumulate_totals(&di, totals);
         }
 
@@ -308,7 +330,7 @@ fn print_directory_tree_mode (
         }
 
         // Display entries interleaved with directory recursion
-        self.display_tree_entries (node, drive_info, tree_displayer, totals, tree_state);
+        self.display_tree_entries (node, drive_info, tree_displayer, level, totals, tree_state);
 
         // Flush trailing output
         let _ = tree_displayer.console_mut().flush();
@@ -336,6 +358,8 @@ fn print_directory_tree_mode (
     //  Display each visible entry interleaved with directory recursion.
     //  Builds a child lookup map from lowercase filename to child WorkItem,
     //  then iterates entries determining last-entry status for connectors.
+    //  Files are skipped where the /DirsFirst:tree policy collapses the
+    //  directory to its name, and everywhere under /Tree:dirs.
     //
     //  Port of: CMultiThreadedLister::DisplayTreeEntries
     //
@@ -346,6 +370,7 @@ fn display_tree_entries (
         node: &WorkItem,
         drive_info: &DriveInfo,
         tree_displayer: &mut TreeDisplayer,
+        level: DirectoryLevel,
         totals: &mut ListingTotals,
         tree_state: &mut TreeConnectorState,
     ) {
@@ -371,6 +396,9 @@ fn display_tree_entries (
 
         let pruning = self.pruning_active();
 
+        let has_subdirectories = entries.iter().any (|e| (e.file_attributes & FILE_ATTRIBUTE_DIRECTORY) != 0);
+        let show_files = self.cmd.tree_files.shows_files (level == DirectoryLevel::Initial, has_subdirectories);
+
         for (i, entry) in entries.iter().enumerate() {
             if self.stop_requested() {
                 return;
@@ -378,6 +406,11 @@ fn display_tree_entries (
 
             let is_dir = (entry.file_attributes & FILE_ATTRIBUTE_DIRECTORY) != 0;
 
+            // Collapsed directory (/DirsFirst:tree, /Tree:dirs): subdirectories only
+            if !is_dir && !show_files {
+                continue;
+            }
+
             // Tree pruning: if this directory has no descendant matches, skip it
             if pruning && is_dir {
                 let lower_name = entry.file_name.to_string_lossy().to_lowercase();
@@ -395,7 +428,7 @@ fn display_tree_entries (
             }
 
             // Determine if this is the last visible entry (look-ahead)
-            let is_last = self.is_last_visible_entry (&entries, i, &child_map);
+            let is_last = self.is_last_visible_entry (&entries, i, &child_map, show_files);
 
             tree_displayer.display_single_entry (entry, tree_state, is_last, i);
 
@@ -487,8 +520,8 @@ fn recurse_into_child_directory (
     //  is_last_visible_entry
     //
     //  Look ahead from the current entry to determine if it is the last
-    //  visible entry.  Without tree pruning active, every entry after
-    //  the current one is visible.
+    //  visible entry.  Without tree pruning active, every directory after
+    //  the current one is visible, and every file unless show_files is off.
     //
     //  Port of: CMultiThreadedLister::IsLastVisibleEntry
     //
@@ -499,6 +532,7 @@ fn is_last_visible_entry (
         entries: &[FileInfo],
         current_idx: usize,
         child_map: &HashMap<String, WorkItem>,
+        show_files: bool,
     ) -> bool {
         let pruning = self.pruning_active();
 
@@ -507,8 +541,11 @@ fn is_last_visible_entry (
             let next_is_dir = (next_entry.file_attributes & FILE_ATTRIBUTE_DIRECTORY) != 0;
 
             if !next_is_dir {
-                // A file entry is always visible
-                return false;
+                // A file entry is visible unless this directory is collapsed
+                if show_files {
+                    return false;
+                }
+                continue;
             }
 
             // Directory entry: if pruning is not active, it's always visible
@@ -604,6 +641,7 @@ fn worker_thread_func(
     tree_pruning_active: &AtomicBool,
     cmd: &CommandLine,
     _config: &Config,
+    progress: Option<&ProgressIndicator>,
 ) {
     while !stop.load(Ordering::Acquire) {
         let item = match work_queue.pop() {
@@ -612,6 +650,10 @@ fn worker_thread_func(
         };
 
         enumerate_directory_node (&item, work_queue, stop, tree_pruning_active, cmd);
+
+        if let Some (progress) = progress {
+            progress.directory_scanned();
+        }
     }
 }
 
@@ -714,6 +756,35 @@ fn perform_enumeration(
 ) -> Result<(), String> {
     enumerate_matching_files (node, stop, cmd)?;
 
+    if cmd.show_streams {
+        enumerate_matching_streams (node, stop, cmd);
+    }
+
+    if let Some (algorithm) = cmd.hash_algorithm {
+        hash_matching_files (node, stop, algorithm);
+    }
+
+    if cmd.show_file_id && !stop.load (Ordering::Acquire) {
+        file_id::read_file_ids (&mut node.0.lock().unwrap(), &file_id::DefaultFileIdProvider);
+    }
+
+    if cmd.show_encoding && !stop.load (Ordering::Acquire) {
+        encoding::read_encodings (&mut node.0.lock().unwrap());
+    }
+
+    if cmd.show_version && !stop.load (Ordering::Acquire) {
+        version_info::read_version_info (&mut node.0.lock().unwrap(), &version_info::DefaultVersionInfoProvider);
+    }
+
+    if cmd.show_file_type && !stop.load (Ordering::Acquire) {
+        file_type::read_file_types (&mut node.0.lock().unwrap(), file_type::default_cache());
+    }
+
+    // Totals come from bytes_used when the node is consumed, so the delta isn't needed here
+    if cmd.on_disk && !stop.load (Ordering::Acquire) {
+        on_disk_size::read_on_disk_sizes (&mut node.0.lock().unwrap(), &on_disk_size::DefaultOnDiskSizeProvider);
+    }
+
     if cmd.recurse || cmd.tree.unwrap_or (false) {
         enumerate_subdirectories (node, work_queue, stop, tree_pruning_active, cmd)?;
     }
@@ -756,17 +827,21 @@ fn enumerate_matching_files(
         let search_wide: Vec<u16> = search_path.as_os_str().encode_wide().chain(Some(0)).collect();
 
         let mut wfd = WIN32_FIND_DATAW::default();
+        crate::run_stats::RUN_STATS.record_directory_opened();
         let handle = unsafe { FindFirstFileW(windows::core::PCWSTR(search_wide.as_ptr()), &mut wfd) };
         let handle = match handle {
             Ok(h) if !h.is_invalid() => h,
             _ => continue,
         };
         let _find_handle = FindHandle(handle);
+        let literal = literal_name_filter(OsStr::new(spec), cmd);
 
         loop {
             if stop.load(Ordering::Acquire) { break; }
 
-            if !is_dots(&wfd.cFileName) {
+            if !is_skipped_dots(&wfd.cFileName, cmd) && passes_literal_filter(&wfd, literal.as_ref()) {
+                crate::run_stats::RUN_STATS.record_file_enumerated();
+
                 // Dedup across multiple file specs
                 let name_len = wfd.cFileName.iter().position(|&c| c == 0).unwrap_or(0);
                 let lower_name = OsString::from_wide(&wfd.cFileName[..name_len])
@@ -775,14 +850,13 @@ fn enumerate_matching_files(
                 if !seen.contains(&lower_name) {
                     seen.insert(lower_name);
 
-                    let attrs = wfd.dwFileAttributes;
-                    let required_ok = (attrs & cmd.attrs_required) == cmd.attrs_required;
-                    let excluded_ok = (attrs & cmd.attrs_excluded) == 0;
-
-                    if required_ok && excluded_ok {
+                    if passes_entry_filters(&wfd, cmd) && passes_owner_filter(&dir_path, &wfd, cmd) {
                         let mut file_entry = FileInfo::from_find_data(&wfd);
                         let dir_path = { node.0.lock().unwrap().dir_path.clone() };
-                        file_entry.reparse_target = crate::reparse_resolver::resolve_reparse_target (&dir_path, &file_entry);
+                        file_entry.reparse_target = crate::reparse_resolver::resolve_reparse_target (&dir_path, &file_entry, cmd.show_target);
+                        if cmd.show_mask {
+                            file_entry.matched_spec = Some(spec.clone());
+                        }
                         let mut di = node.0.lock().unwrap();
                         add_match_to_list(&wfd, file_entry, &mut di, cmd);
                     }
@@ -794,6 +868,12 @@ fn enumerate_matching_files(
         }
     }
 
+    // Count every entry, matching or not, for "showing N of M" (/ShowFiltered)
+    if cmd.show_filtered {
+        let scanned = count_directory_entries(&dir_path);
+        node.0.lock().unwrap().entries_scanned = scanned;
+    }
+
     Ok(())
 }
 
@@ -801,6 +881,72 @@ fn enumerate_matching_files(
 
 
 
+////////////////////////////////////////////////////////////////////////////////
+//
+//  enumerate_matching_streams
+//
+//  Enumerate alternate data streams for the node's matches and apply the
+//  /HasStreams filter.  Totals are accumulated later from the node's
+//  counts, so the per-call totals here are scratch.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn enumerate_matching_streams(node: &WorkItem, stop: &AtomicBool, cmd: &CommandLine) {
+    if stop.load (Ordering::Acquire) {
+        return;
+    }
+
+    let mut di = node.0.lock().unwrap();
+    let mut scratch = ListingTotals::default();
+
+    streams::enumerate_streams (&mut di, &mut scratch);
+
+    if cmd.has_streams {
+        streams::retain_stream_bearing (&mut di, &mut scratch, cmd.tree.unwrap_or (false));
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  hash_matching_files
+//
+//  Compute /Hash digests for a node's matches on this worker thread.  The
+//  matches are moved out of the node so file reads happen without holding
+//  its lock; the node isn't marked done until they are put back.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn hash_matching_files(node: &WorkItem, stop: &AtomicBool, algorithm: HashAlgorithm) {
+    if stop.load (Ordering::Acquire) {
+        return;
+    }
+
+    let (dir_path, mut matches) = {
+        let mut di = node.0.lock().unwrap();
+        (di.dir_path.clone(), std::mem::take (&mut di.matches))
+    };
+
+    for file_info in &mut matches {
+        if stop.load (Ordering::Acquire) {
+            break;
+        }
+
+        if hashing::is_hashable (file_info) {
+            file_info.hash = hashing::hash_file (&dir_path.join (&file_info.file_name), algorithm);
+        }
+    }
+
+    node.0.lock().unwrap().matches = matches;
+}
+
+
+
+
+
 ////////////////////////////////////////////////////////////////////////////////
 //
 //  enumerate_subdirectories
@@ -818,9 +964,9 @@ fn enumerate_subdirectories(
     tree_pruning_active: &AtomicBool,
     cmd: &CommandLine,
 ) -> Result<(), String> {
-    let (dir_path, file_specs) = {
+    let (dir_path, file_specs, relative_path) = {
         let di = node.0.lock().unwrap();
-        (di.dir_path.clone(), di.file_specs.clone())
+        (di.dir_path.clone(), di.file_specs.clone(), di.relative_path.clone())
     };
 
     //
@@ -847,6 +993,7 @@ fn enumerate_subdirectories(
     let search_wide: Vec<u16> = search_path.as_os_str().encode_wide().chain(Some(0)).collect();
 
     let mut wfd = WIN32_FIND_DATAW::default();
+    crate::run_stats::RUN_STATS.record_directory_opened();
     let handle = unsafe { FindFirstFileW(windows::core::PCWSTR(search_wide.as_ptr()), &mut wfd) };
     let handle = match handle {
         Ok(h) if !h.is_invalid() => h,
@@ -859,27 +1006,33 @@ fn enumerate_subdirectories(
 
         if !is_dots(&wfd.cFileName)
             && (wfd.dwFileAttributes & FILE_ATTRIBUTE_DIRECTORY) != 0
+            && passes_reparse_filter(wfd.dwFileAttributes, cmd)
         {
             let name_len = wfd.cFileName.iter().position(|&c| c == 0).unwrap_or(0);
             let name = OsString::from_wide(&wfd.cFileName[..name_len]);
             let subdir_path = dir_path.join(&name);
+            let child_relative_path = relative_path.join (&name);
 
-            let mut child_di = DirectoryInfo::new_multi (subdir_path, file_specs.clone());
+            // /XD: the directory is still listed, but never recursed into
+            if !is_excluded_dir (&cmd.exclude_dirs, &child_relative_path) {
+                let mut child_di = DirectoryInfo::new_multi (subdir_path, file_specs.clone());
+                child_di.relative_path = child_relative_path;
 
-            // Set parent weak reference for tree pruning propagation
-            if tree_pruning_active.load (Ordering::Acquire) {
-                child_di.parent = Some (Arc::downgrade (node));
-            }
+                // Set parent weak reference for tree pruning propagation
+                if tree_pruning_active.load (Ordering::Acquire) {
+                    child_di.parent = Some (Arc::downgrade (node));
+                }
 
-            let child_node: WorkItem = Arc::new ((Mutex::new (child_di), Condvar::new()));
+                let child_node: WorkItem = Arc::new ((Mutex::new (child_di), Condvar::new()));
 
-            // Add child to parent's children list
-            {
-                let mut di = node.0.lock().unwrap();
-                di.children.push(Arc::clone(&child_node));
-            }
+                // Add child to parent's children list
+                {
+                    let mut di = node.0.lock().unwrap();
+                    di.children.push(Arc::clone(&child_node));
+                }
 
-            work_queue.push(Arc::clone (&child_node));
+                work_queue.push(Arc::clone (&child_node));
+            }
 
             //
             // In tree mode, add every directory to matches so the tree
@@ -894,7 +1047,7 @@ fn enumerate_subdirectories(
                     seen_dirs.insert (lower_name);
                     let mut file_entry = FileInfo::from_find_data (&wfd);
                     let dir_path = { node.0.lock().unwrap().dir_path.clone() };
-                    file_entry.reparse_target = crate::reparse_resolver::resolve_reparse_target (&dir_path, &file_entry);
+                    file_entry.reparse_target = crate::reparse_resolver::resolve_reparse_target (&dir_path, &file_entry, cmd.show_target);
                     let mut di = node.0.lock().unwrap();
                     add_match_to_list (&wfd, file_entry, &mut di, cmd);
                 }
@@ -1002,6 +1155,10 @@ fn accumulate_totals(di: &DirectoryInfo, totals: &mut ListingTotals) {
     totals.stream_count    += di.stream_count;
     totals.stream_bytes    += di.stream_bytes_used;
     totals.directory_count += di.subdirectory_count;
+
+    for fi in &di.matches {
+        totals.count_reparse_point(fi.file_attributes, fi.reparse_tag);
+    }
 }
 
 
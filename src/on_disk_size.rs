@@ -0,0 +1,214 @@
+// on_disk_size.rs — Allocated sizes of sparse and compressed files (/OnDisk)
+//
+// A sparse or NTFS-compressed file can report a logical size far larger
+// than the space it really takes.  /OnDisk asks GetCompressedFileSizeW for
+// the allocated size of those files (others allocate what they report, so
+// they aren't queried) and the listing shows and totals that instead,
+// marking sizes that differ.  The lookup goes through the
+// OnDiskSizeProvider trait so tests can substitute a mock.
+
+use std::ffi::OsStr;
+use std::os::windows::ffi::OsStrExt;
+use std::path::Path;
+
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::{GetLastError, NO_ERROR};
+use windows::Win32::Storage::FileSystem::{GetCompressedFileSizeW, INVALID_FILE_SIZE};
+
+use crate::directory_info::DirectoryInfo;
+use crate::file_info::{FILE_ATTRIBUTE_COMPRESSED, FILE_ATTRIBUTE_SPARSE_FILE};
+
+
+
+
+
+/// Shown in place of the size column's leading space when the on-disk
+/// size differs from the logical size.
+pub const ON_DISK_MARKER: char = '~';
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  OnDiskSizeProvider
+//
+//  Source of allocated sizes.  The default implementation asks the file
+//  system; tests supply a mock.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub trait OnDiskSizeProvider {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  on_disk_size
+    //
+    //  The bytes allocated to the file at `path`, or None if it can't be
+    //  queried.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn on_disk_size(&self, path: &Path) -> Option<u64>;
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Reads allocated sizes with GetCompressedFileSizeW.
+pub struct DefaultOnDiskSizeProvider;
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl OnDiskSizeProvider for DefaultOnDiskSizeProvider
+//
+//  INVALID_FILE_SIZE in the low DWORD is only a failure if GetLastError
+//  says so; it is also a valid low half of a large size.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl OnDiskSizeProvider for DefaultOnDiskSizeProvider {
+    fn on_disk_size(&self, path: &Path) -> Option<u64> {
+        let wide: Vec<u16> = OsStr::new (path).encode_wide().chain (Some (0)).collect();
+
+        let mut high = 0u32;
+        let low = unsafe { GetCompressedFileSizeW (PCWSTR (wide.as_ptr()), Some (&mut high)) };
+
+        if low == INVALID_FILE_SIZE && unsafe { GetLastError() } != NO_ERROR {
+            return None;
+        }
+
+        Some (((high as u64) << 32) | low as u64)
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  read_on_disk_sizes
+//
+//  Fill file_info.on_disk_size for every sparse or compressed file in a
+//  DirectoryInfo and move its bytes_used (and largest_file_size) from
+//  logical to on-disk sizes.  Returns the logical bytes replaced minus
+//  the on-disk bytes that replaced them, for callers that have already
+//  added the logical sizes to their totals.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn read_on_disk_sizes(di: &mut DirectoryInfo, provider: &dyn OnDiskSizeProvider) -> i64 {
+    let mut delta = 0i64;
+
+    for file_info in &mut di.matches {
+        if file_info.is_directory()
+            || file_info.file_attributes & (FILE_ATTRIBUTE_SPARSE_FILE | FILE_ATTRIBUTE_COMPRESSED) == 0
+        {
+            continue;
+        }
+
+        let Some (on_disk) = provider.on_disk_size (&di.dir_path.join (&file_info.file_name)) else {
+            continue;
+        };
+
+        file_info.on_disk_size = Some (on_disk);
+        delta += file_info.file_size as i64 - on_disk as i64;
+    }
+
+    di.bytes_used = di.bytes_used.saturating_add_signed (-delta);
+    di.largest_file_size = di.matches.iter()
+        .filter (|fi| !fi.is_directory())
+        .map (|fi| fi.displayed_size())
+        .max()
+        .unwrap_or (0);
+
+    delta
+}
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    use crate::file_info::FILE_ATTRIBUTE_ARCHIVE;
+    use crate::test_support::make_file;
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  MockOnDiskSizeProvider
+    //
+    //  Maps full paths to allocated sizes; every lookup is recorded so
+    //  tests can check which files were queried.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    struct MockOnDiskSizeProvider {
+        sizes:   HashMap<PathBuf, u64>,
+        queried: std::cell::RefCell<Vec<PathBuf>>,
+    }
+
+    impl OnDiskSizeProvider for MockOnDiskSizeProvider {
+        fn on_disk_size(&self, path: &Path) -> Option<u64> {
+            self.queried.borrow_mut().push (path.to_path_buf());
+            self.sizes.get (path).copied()
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  sparse_and_compressed_files_use_on_disk_size
+    //
+    //  Verify only sparse and compressed files are queried, their on-disk
+    //  sizes replace the logical ones in bytes_used and largest_file_size,
+    //  and the returned delta is what the totals must drop by.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn sparse_and_compressed_files_use_on_disk_size() {
+        let dir = PathBuf::from ("C:\\vm");
+        let provider = MockOnDiskSizeProvider {
+            sizes: HashMap::from ([
+                (dir.join ("disk.vhdx"), 4_096),
+                (dir.join ("logs.txt"),  300),
+            ]),
+            queried: Default::default(),
+        };
+
+        let mut di = DirectoryInfo::new (dir.clone(), "*".to_string());
+        di.matches.push (make_file ("disk.vhdx",  FILE_ATTRIBUTE_SPARSE_FILE, 10_000_000));
+        di.matches.push (make_file ("logs.txt",   FILE_ATTRIBUTE_COMPRESSED,  1_000));
+        di.matches.push (make_file ("notes.txt",  FILE_ATTRIBUTE_ARCHIVE,     500));
+        di.bytes_used        = 10_001_500;
+        di.largest_file_size = 10_000_000;
+
+        let delta = read_on_disk_sizes (&mut di, &provider);
+
+        assert_eq! (di.matches[0].on_disk_size, Some (4_096));
+        assert_eq! (di.matches[1].on_disk_size, Some (300));
+        assert_eq! (di.matches[2].on_disk_size, None);
+        assert_eq! (di.bytes_used, 4_096 + 300 + 500);
+        assert_eq! (delta, 10_001_500 - (4_096 + 300 + 500));
+        assert_eq! (di.largest_file_size, 4_096);
+
+        assert_eq! (*provider.queried.borrow(), vec![dir.join ("disk.vhdx"), dir.join ("logs.txt")]);
+    }
+}
@@ -3,10 +3,18 @@
 // Port of: ResultsDisplayerNormal.cpp → GetFileOwner(), GetFileOwners()
 //
 // Uses GetNamedSecurityInfoW to get the file's security descriptor,
-// then LookupAccountSidW to resolve the SID to DOMAIN\User.
+// then LookupAccountSidW to resolve the SID to DOMAIN\User, or
+// ConvertSidToStringSidW for the raw SID string (/Owner:sid).  /Mine
+// compares owner SID strings against the process token's user SID, so
+// no account names are resolved.
 
 use std::ffi::OsStr;
 use std::os::windows::ffi::OsStrExt;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use windows::Win32::Foundation::{LocalFree, HLOCAL};
+use windows::Win32::Security::PSID;
 
 use crate::directory_info::DirectoryInfo;
 
@@ -24,15 +32,65 @@
 ////////////////////////////////////////////////////////////////////////////////
 
 pub fn get_file_owner(file_path: &OsStr) -> String {
-    use windows::Win32::Security::{
-        LookupAccountSidW,
-        OWNER_SECURITY_INFORMATION, PSECURITY_DESCRIPTOR, PSID,
-        SID_NAME_USE,
-    };
+    lookup_owner(file_path, account_name_for_sid)
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  get_file_owner_sid
+//
+//  Get the owner of a single file as a raw SID string ("S-1-5-...").
+//  Never contacts a domain controller, so it is fast and cannot hang on
+//  disconnected machines (/Owner:sid).
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn get_file_owner_sid(file_path: &OsStr) -> String {
+    lookup_owner(file_path, sid_to_string)
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  lookup_owner
+//
+//  Read the owner SID from a file's security descriptor and format it
+//  with `format_sid`.  Returns "Unknown" if either step fails.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn lookup_owner(file_path: &OsStr, format_sid: fn(PSID) -> Option<String>) -> String {
+    read_owner(file_path, format_sid).unwrap_or_else(|| "Unknown".to_string())
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  read_owner
+//
+//  Read the owner SID from a file's security descriptor and format it
+//  with `format_sid`, or None if either step fails.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn read_owner(file_path: &OsStr, format_sid: fn(PSID) -> Option<String>) -> Option<String> {
+    use windows::Win32::Security::{OWNER_SECURITY_INFORMATION, PSECURITY_DESCRIPTOR};
     use windows::Win32::Security::Authorization::{
         GetNamedSecurityInfoW, SE_FILE_OBJECT,
     };
-    use windows::Win32::Foundation::{LocalFree, HLOCAL, ERROR_SUCCESS};
+    use windows::Win32::Foundation::ERROR_SUCCESS;
+
+    crate::run_stats::RUN_STATS.record_owner_lookup();
 
     let path_wide: Vec<u16> = file_path.encode_wide().chain(Some(0)).collect();
 
@@ -54,9 +112,34 @@ pub fn get_file_owner(file_path: &OsStr) -> String {
     };
 
     if result != ERROR_SUCCESS {
-        return "Unknown".to_string();
+        return None;
     }
 
+    // The SID points into the security descriptor, so format before freeing it
+    let owner = format_sid(p_sid_owner);
+
+    if !p_sd.0.is_null() {
+        unsafe { let _ = LocalFree(Some(HLOCAL(p_sd.0))); }
+    }
+
+    owner
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  account_name_for_sid
+//
+//  Resolve a SID to "DOMAIN\User" (or just "User") via LookupAccountSidW.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn account_name_for_sid(sid: PSID) -> Option<String> {
+    use windows::Win32::Security::{LookupAccountSidW, SID_NAME_USE};
+
     // Look up the account name for the SID
     let mut name_buf = [0u16; 256];
     let mut domain_buf = [0u16; 256];
@@ -67,7 +150,7 @@ pub fn get_file_owner(file_path: &OsStr) -> String {
     let success = unsafe {
         LookupAccountSidW(
             windows::core::PCWSTR::null(),
-            p_sid_owner,
+            sid,
             Some(windows::core::PWSTR(name_buf.as_mut_ptr())),
             &mut cch_name,
             Some(windows::core::PWSTR(domain_buf.as_mut_ptr())),
@@ -76,13 +159,8 @@ pub fn get_file_owner(file_path: &OsStr) -> String {
         )
     };
 
-    // Free the security descriptor
-    if !p_sd.0.is_null() {
-        unsafe { let _ = LocalFree(Some(HLOCAL(p_sd.0))); }
-    }
-
     if success.is_err() {
-        return "Unknown".to_string();
+        return None;
     }
 
     let name = String::from_utf16_lossy(&name_buf[..cch_name as usize]);
@@ -90,9 +168,149 @@ pub fn get_file_owner(file_path: &OsStr) -> String {
 
     // Format as DOMAIN\User or just User if domain is empty
     if !domain.is_empty() {
-        format!("{}\\{}", domain, name)
+        Some(format!("{}\\{}", domain, name))
     } else {
-        name
+        Some(name)
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  sid_to_string
+//
+//  Format a SID as its "S-1-..." string via ConvertSidToStringSidW.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn sid_to_string(sid: PSID) -> Option<String> {
+    use windows::Win32::Security::Authorization::ConvertSidToStringSidW;
+
+    let mut string_sid = windows::core::PWSTR::null();
+    unsafe { ConvertSidToStringSidW(sid, &mut string_sid) }.ok()?;
+
+    let text = unsafe { string_sid.to_string() }.ok();
+    unsafe { let _ = LocalFree(Some(HLOCAL(string_sid.0 as *mut _))); }
+
+    text
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  current_user_sid
+//
+//  The SID string of the user the process runs as, read once from the
+//  process token.  None if the token can't be queried.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn current_user_sid() -> Option<&'static str> {
+    static USER_SID: OnceLock<Option<String>> = OnceLock::new();
+
+    USER_SID.get_or_init(read_token_user_sid).as_deref()
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  read_token_user_sid
+//
+//  Query TokenUser from the process token and format its SID.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn read_token_user_sid() -> Option<String> {
+    use windows::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows::Win32::Security::{GetTokenInformation, TokenUser, TOKEN_QUERY, TOKEN_USER};
+    use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+    let mut token = HANDLE::default();
+    unsafe { OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) }.ok()?;
+
+    // TOKEN_USER is followed by the variable-length SID it points to, so
+    // size the buffer from the first call (u64 keeps it pointer-aligned)
+    let mut cb_needed = 0u32;
+    let _ = unsafe { GetTokenInformation(token, TokenUser, None, 0, &mut cb_needed) };
+
+    let mut buffer = vec![0u64; (cb_needed as usize).div_ceil(8)];
+    let result = unsafe {
+        GetTokenInformation(
+            token,
+            TokenUser,
+            Some(buffer.as_mut_ptr() as *mut _),
+            cb_needed,
+            &mut cb_needed,
+        )
+    };
+    let _ = unsafe { CloseHandle(token) };
+
+    result.ok()?;
+
+    let token_user = unsafe { &*(buffer.as_ptr() as *const TOKEN_USER) };
+    sid_to_string(token_user.User.Sid)
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  OwnerSidProvider
+//
+//  Source of file owner SIDs for /Mine.  The default implementation reads
+//  the security descriptor; tests supply a mock.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub trait OwnerSidProvider {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  owner_sid
+    //
+    //  The owner SID string of the file at `path`, or None if its
+    //  security descriptor can't be read.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn owner_sid(&self, path: &Path) -> Option<String>;
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Reads owner SIDs with GetNamedSecurityInfoW.
+pub struct DefaultOwnerSidProvider;
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl OwnerSidProvider for DefaultOwnerSidProvider
+//
+//  Same lookup as /Owner:sid, without the "Unknown" fallback.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl OwnerSidProvider for DefaultOwnerSidProvider {
+    fn owner_sid(&self, path: &Path) -> Option<String> {
+        read_owner(path.as_os_str(), sid_to_string)
     }
 }
 
@@ -100,23 +318,45 @@ pub fn get_file_owner(file_path: &OsStr) -> String {
 
 
 
+////////////////////////////////////////////////////////////////////////////////
+//
+//  is_owned_by
+//
+//  /Mine test: true if the file at `path` is owned by `user_sid`.  An
+//  unreadable owner, or no user SID at all, never matches.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn is_owned_by(provider: &dyn OwnerSidProvider, path: &Path, user_sid: Option<&str>) -> bool {
+    user_sid.is_some_and(|user_sid| {
+        provider.owner_sid(path).is_some_and(|owner| owner.eq_ignore_ascii_case(user_sid))
+    })
+}
+
+
+
+
+
 ////////////////////////////////////////////////////////////////////////////////
 //
 //  get_file_owners
 //
-//  Get owners for all files in a DirectoryInfo.
+//  Get owners for all files in a DirectoryInfo, as account names or (with
+//  /Owner:sid) raw SID strings.
 //  Port of: CResultsDisplayerNormal::GetFileOwners
 //  Returns (owners_vec, max_owner_length) for column alignment.
 //
 ////////////////////////////////////////////////////////////////////////////////
 
-pub fn get_file_owners(di: &DirectoryInfo) -> (Vec<String>, usize) {
+pub fn get_file_owners(di: &DirectoryInfo, as_sid: bool) -> (Vec<String>, usize) {
+    let lookup = if as_sid { get_file_owner_sid } else { get_file_owner };
+
     let mut owners = Vec::with_capacity(di.matches.len());
     let mut max_len = 0usize;
 
     for fi in &di.matches {
         let full_path = di.dir_path.join(&fi.file_name);
-        let owner = get_file_owner(full_path.as_os_str());
+        let owner = lookup(full_path.as_os_str());
         max_len = max_len.max(owner.len());
         owners.push(owner);
     }
@@ -169,4 +409,107 @@ fn get_owner_of_nonexistent_file() {
         let owner = get_file_owner(path.as_os_str());
         assert_eq!(owner, "Unknown");
     }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  sid_to_string_formats_known_sid
+    //
+    //  A BUILTIN\Administrators SID byte pattern formats to S-1-5-32-544.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn sid_to_string_formats_known_sid() {
+        // Revision 1, 2 sub-authorities, NT authority (5), RIDs 32 and 544
+        let mut bytes: [u8; 16] = [
+            1, 2,
+            0, 0, 0, 0, 0, 5,
+            32, 0, 0, 0,
+            0x20, 0x02, 0, 0,
+        ];
+        let sid = PSID(bytes.as_mut_ptr() as *mut _);
+        assert_eq!(sid_to_string(sid).as_deref(), Some("S-1-5-32-544"));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  MockOwnerSidProvider
+    //
+    //  Maps full paths to owner SID strings.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    struct MockOwnerSidProvider {
+        owners: std::collections::HashMap<std::path::PathBuf, &'static str>,
+    }
+
+    impl OwnerSidProvider for MockOwnerSidProvider {
+        fn owner_sid(&self, path: &Path) -> Option<String> {
+            self.owners.get(path).map(|sid| sid.to_string())
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  mine_filter_keeps_only_current_user
+    //
+    //  Only entries whose owner SID matches the user SID pass; another
+    //  user's file, an unreadable owner, and a missing user SID never do.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn mine_filter_keeps_only_current_user() {
+        const ME: &str    = "S-1-5-21-1000-2000-3000-1001";
+        const OTHER: &str = "S-1-5-21-1000-2000-3000-1002";
+
+        let dir = Path::new("C:\\shared");
+        let provider = MockOwnerSidProvider {
+            owners: std::collections::HashMap::from([
+                (dir.join("mine.txt"),   ME),
+                (dir.join("theirs.txt"), OTHER),
+                (dir.join("admin.log"),  "S-1-5-32-544"),
+                (dir.join("notes"),      ME),
+            ]),
+        };
+
+        let names = ["mine.txt", "theirs.txt", "admin.log", "notes", "locked.txt"];
+        let kept: Vec<&str> = names.iter().copied()
+            .filter(|name| is_owned_by(&provider, &dir.join(name), Some(ME)))
+            .collect();
+
+        assert_eq!(kept, ["mine.txt", "notes"]);
+        assert!(is_owned_by(&provider, &dir.join("mine.txt"), Some(&ME.to_lowercase())));
+        assert!(!is_owned_by(&provider, &dir.join("mine.txt"), None));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  current_user_sid_is_a_sid_string
+    //
+    //  The process token's user SID formats as "S-1-...".
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn current_user_sid_is_a_sid_string() {
+        let sid = current_user_sid().expect("process token should have a user SID");
+        assert!(sid.starts_with("S-1-"), "{sid}");
+    }
 }
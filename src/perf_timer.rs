@@ -3,9 +3,15 @@
 // Port of: PerfTimer.h, PerfTimer.cpp
 // Uses std::time::Instant for cross-platform high-resolution timing
 // (Instant uses QueryPerformanceCounter internally on Windows).
+//
+// /P and /Stats also report enumeration throughput: entries enumerated
+// divided by the time spent listing, a handy figure when comparing single-
+// and multi-threaded runs over the same tree.
 
 use std::time::Instant;
 
+use crate::results_displayer::format_number_with_separators;
+
 
 
 
@@ -125,6 +131,52 @@ pub fn elapsed_ms(&self) -> f64 {
 
 
 
+////////////////////////////////////////////////////////////////////////////////
+//
+//  throughput_per_second
+//
+//  Entries per second for `count` entries enumerated in `elapsed_ms`.
+//  None when no time was measured.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn throughput_per_second(count: u64, elapsed_ms: f64) -> Option<f64> {
+    if elapsed_ms <= 0.0 {
+        return None;
+    }
+
+    Some (count as f64 * 1000.0 / elapsed_ms)
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  format_throughput_line
+//
+//  The /P and /Stats throughput line, e.g.
+//  "RCDir throughput:    12,345 files/sec (1,234 files in 99.96 msec)".
+//  None when no time was measured.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn format_throughput_line(count: u64, elapsed_ms: f64) -> Option<String> {
+    let rate = throughput_per_second (count, elapsed_ms)?;
+
+    Some (format! (
+        "RCDir throughput:    {} files/sec ({} files in {:.2} msec)\n",
+        format_number_with_separators (rate.round() as u64),
+        format_number_with_separators (count),
+        elapsed_ms,
+    ))
+}
+
+
+
+
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -190,4 +242,46 @@ fn timer_still_running_returns_positive() {
         let ms = t.elapsed_ms();
         assert!(ms > 0.0);
     }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  throughput_divides_count_by_duration
+    //
+    //  Verify files/sec from mocked counts and durations, and that a zero
+    //  duration gives no figure.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn throughput_divides_count_by_duration() {
+        assert_eq!(throughput_per_second(1_000, 500.0), Some(2_000.0));
+        assert_eq!(throughput_per_second(250, 1_000.0), Some(250.0));
+        assert_eq!(throughput_per_second(0, 10.0), Some(0.0));
+        assert_eq!(throughput_per_second(1_000, 0.0), None);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  throughput_line_groups_and_rounds
+    //
+    //  Verify the reported line rounds the rate and groups both counts.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn throughput_line_groups_and_rounds() {
+        assert_eq!(
+            format_throughput_line(12_345, 3_000.0).as_deref(),
+            Some("RCDir throughput:    4,115 files/sec (12,345 files in 3000.00 msec)\n")
+        );
+        assert_eq!(format_throughput_line(5, 0.0), None);
+    }
 }
@@ -0,0 +1,394 @@
+// progress.rs — Directory-scan progress indicator on stderr (/Progress)
+//
+// Multi-threaded recursive listings over slow shares can run for a long
+// time before the summary appears.  With /Progress, worker threads report
+// each scanned directory and a single status line ("Scanning: 1,234
+// directories...") is redrawn in place on stderr, throttled so the
+// counter doesn't flood the console.  stdout is never touched, and the
+// line is erased before each stdout flush so listing output isn't mixed
+// into it.  Suppressed when stderr is not a console.
+
+use std::io::Write;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use windows::Win32::System::Console::{GetConsoleMode, GetStdHandle, CONSOLE_MODE, STD_ERROR_HANDLE};
+
+use crate::command_line::CommandLine;
+use crate::results_displayer::format_number_with_separators;
+
+
+
+
+
+/// Minimum time between progress line redraws.
+pub const PROGRESS_UPDATE_INTERVAL: Duration = Duration::from_millis (100);
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Rate limiter for progress redraws: allows the first update immediately,
+/// then at most one per interval.
+pub struct ProgressThrottle {
+    interval: Duration,
+    last:     Option<Instant>,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl ProgressThrottle
+//
+//  Interval-based update gating.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl ProgressThrottle {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  new
+    //
+    //  Create a throttle that allows one update per `interval`.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn new(interval: Duration) -> Self {
+        ProgressThrottle { interval, last: None }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  should_update
+    //
+    //  True (and records `now`) if no update has happened yet or at least
+    //  one interval has passed since the last one.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn should_update(&mut self, now: Instant) -> bool {
+        let due = match self.last {
+            None       => true,
+            Some (last) => now.saturating_duration_since (last) >= self.interval,
+        };
+
+        if due {
+            self.last = Some (now);
+        }
+
+        due
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Mutable line state, guarded so redraws and clears never interleave.
+struct ProgressLineState {
+    throttle:    ProgressThrottle,
+    visible_len: usize,
+    finished:    bool,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Shared progress indicator, updated from worker threads.
+pub struct ProgressIndicator {
+    scanned: AtomicU64,
+    state:   Mutex<ProgressLineState>,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl ProgressIndicator
+//
+//  Counting, throttled redraw, and clearing of the stderr status line.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl ProgressIndicator {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  new
+    //
+    //  Create an indicator with the default update interval.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn new() -> Self {
+        ProgressIndicator {
+            scanned: AtomicU64::new (0),
+            state:   Mutex::new (ProgressLineState {
+                throttle:    ProgressThrottle::new (PROGRESS_UPDATE_INTERVAL),
+                visible_len: 0,
+                finished:    false,
+            }),
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  new_if_enabled
+    //
+    //  Create an indicator only if /Progress was given and stderr is a
+    //  console (a redirected stderr would just collect carriage returns).
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn new_if_enabled(cmd: &CommandLine) -> Option<Self> {
+        if cmd.show_progress && stderr_is_console() {
+            Some (Self::new())
+        } else {
+            None
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  directory_scanned
+    //
+    //  Count one finished directory and redraw the line if the throttle
+    //  allows.  Skips the redraw rather than blocking if another thread
+    //  holds the line.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn directory_scanned(&self) {
+        let scanned = self.scanned.fetch_add (1, Ordering::Relaxed) + 1;
+
+        let Ok (mut state) = self.state.try_lock() else {
+            return;
+        };
+
+        if state.finished || !state.throttle.should_update (Instant::now()) {
+            return;
+        }
+
+        let line = format_progress (scanned);
+        let mut stderr = std::io::stderr().lock();
+        let _ = write! (stderr, "\r{}", line);
+        let _ = stderr.flush();
+        state.visible_len = line.len();
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  clear
+    //
+    //  Erase the status line (if drawn).  The next update redraws it.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        erase_line (&mut state);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  finish
+    //
+    //  Erase the status line for good; later updates are ignored.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn finish(&self) {
+        let mut state = self.state.lock().unwrap();
+        erase_line (&mut state);
+        state.finished = true;
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl Default for ProgressIndicator
+//
+//  Same as ProgressIndicator::new().
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl Default for ProgressIndicator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  format_progress
+//
+//  The status line text for a given count of scanned directories.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn format_progress(scanned: u64) -> String {
+    let noun = if scanned == 1 { "directory" } else { "directories" };
+    format! ("Scanning: {} {}...", format_number_with_separators (scanned), noun)
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  erase_line
+//
+//  Overwrite the visible status line with spaces and return the cursor
+//  to column 0.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn erase_line(state: &mut ProgressLineState) {
+    if state.visible_len == 0 {
+        return;
+    }
+
+    let mut stderr = std::io::stderr().lock();
+    let _ = write! (stderr, "\r{:width$}\r", "", width = state.visible_len);
+    let _ = stderr.flush();
+    state.visible_len = 0;
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  stderr_is_console
+//
+//  True if stderr is attached to a console (not redirected).
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn stderr_is_console() -> bool {
+    let Ok (handle) = (unsafe { GetStdHandle (STD_ERROR_HANDLE) }) else {
+        return false;
+    };
+
+    let mut mode = CONSOLE_MODE (0);
+    unsafe { GetConsoleMode (handle, &mut mode) }.is_ok()
+}
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  throttle_allows_first_then_one_per_interval
+    //
+    //  Verify the first update is immediate, updates inside the interval
+    //  are suppressed, and the next one after the interval goes through.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn throttle_allows_first_then_one_per_interval() {
+        let start = Instant::now();
+        let mut throttle = ProgressThrottle::new (Duration::from_millis (100));
+
+        assert! (throttle.should_update (start));
+        assert! (!throttle.should_update (start + Duration::from_millis (10)));
+        assert! (!throttle.should_update (start + Duration::from_millis (99)));
+        assert! (throttle.should_update (start + Duration::from_millis (100)));
+        assert! (!throttle.should_update (start + Duration::from_millis (150)));
+        assert! (throttle.should_update (start + Duration::from_millis (250)));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  progress_line_formats_count_with_separators
+    //
+    //  Verify the status line text, singular/plural, and separators.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn progress_line_formats_count_with_separators() {
+        assert_eq! (format_progress (1), "Scanning: 1 directory...");
+        assert_eq! (format_progress (42), "Scanning: 42 directories...");
+        assert_eq! (
+            format_progress (12_345),
+            format! ("Scanning: {} directories...", format_number_with_separators (12_345)),
+        );
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  finished_indicator_ignores_updates
+    //
+    //  Verify updates after finish() still count but draw nothing.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn finished_indicator_ignores_updates() {
+        let progress = ProgressIndicator::new();
+        progress.finish();
+        progress.directory_scanned();
+
+        assert_eq! (progress.scanned.load (Ordering::Relaxed), 1);
+        assert_eq! (progress.state.lock().unwrap().visible_len, 0);
+    }
+}
@@ -6,7 +6,7 @@
 
 use std::ffi::OsStr;
 use std::os::windows::ffi::OsStrExt;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use windows::Win32::Storage::FileSystem::{
     CreateFileW, FILE_FLAG_BACKUP_SEMANTICS, FILE_FLAG_OPEN_REPARSE_POINT,
@@ -14,6 +14,7 @@
 };
 use windows::Win32::System::IO::DeviceIoControl;
 
+use crate::drive_info::DriveInfo;
 use crate::file_info::FileInfo;
 
 
@@ -275,7 +276,9 @@ pub fn parse_app_exec_link_buffer (buffer: &[u8]) -> String {
 //
 //  resolve_reparse_target
 //
-//  Read the reparse data for a file and resolve its target path.
+//  Read the reparse data for a file and resolve its target path.  With
+//  `show_mount_volume` (/Target), mount point targets also carry the
+//  target volume's label (one extra volume lookup each).
 //  Returns empty string if:
 //    - The file is not a reparse point
 //    - The reparse tag is not supported (junction, symlink, AppExecLink)
@@ -284,7 +287,7 @@ pub fn parse_app_exec_link_buffer (buffer: &[u8]) -> String {
 //
 ////////////////////////////////////////////////////////////////////////////////
 
-pub fn resolve_reparse_target (dir_path: &Path, file_info: &FileInfo) -> String {
+pub fn resolve_reparse_target (dir_path: &Path, file_info: &FileInfo, show_mount_volume: bool) -> String {
     // Early exit: not a reparse point
     if (file_info.file_attributes & FILE_ATTRIBUTE_REPARSE_POINT) == 0 {
         return String::new();
@@ -299,6 +302,8 @@ pub fn resolve_reparse_target (dir_path: &Path, file_info: &FileInfo) -> String
         return String::new();
     }
 
+    crate::run_stats::RUN_STATS.record_reparse_resolution();
+
     // Build full path: dir_path + filename
     let full_path = dir_path.join (&file_info.file_name);
 
@@ -354,6 +359,11 @@ pub fn resolve_reparse_target (dir_path: &Path, file_info: &FileInfo) -> String
 
     // Dispatch to the appropriate parser
     match tag {
+        IO_REPARSE_TAG_MOUNT_POINT if show_mount_volume => {
+            let target = parse_junction_buffer (data);
+            let label  = lookup_volume_label (&target);
+            format_mount_target (&target, &label)
+        }
         IO_REPARSE_TAG_MOUNT_POINT => parse_junction_buffer (data),
         IO_REPARSE_TAG_SYMLINK     => parse_symlink_buffer (data),
         IO_REPARSE_TAG_APPEXECLINK => parse_app_exec_link_buffer (data),
@@ -365,6 +375,84 @@ pub fn resolve_reparse_target (dir_path: &Path, file_info: &FileInfo) -> String
 
 
 
+////////////////////////////////////////////////////////////////////////////////
+//
+//  format_mount_target
+//
+//  Decorate a resolved mount point target with its volume label.  A
+//  volume GUID target ("Volume{...}\") is replaced by the label since the
+//  GUID means nothing to a reader; a path target keeps the path.  An
+//  empty label (or target) leaves the target unchanged.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn format_mount_target (target: &str, volume_label: &str) -> String {
+    if target.is_empty() || volume_label.is_empty() {
+        return target.to_string();
+    }
+
+    if is_volume_guid_path (target) {
+        format! ("[Volume: {}]", volume_label)
+    } else {
+        format! ("{} [Volume: {}]", target, volume_label)
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  is_volume_guid_path
+//
+//  True if a (prefix-stripped) target names a volume by GUID rather than
+//  by drive letter or directory.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn is_volume_guid_path (target: &str) -> bool {
+    target.get (..7).is_some_and (|head| head.eq_ignore_ascii_case ("Volume{"))
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  lookup_volume_label
+//
+//  Volume label of the volume a mount target lives on, via DriveInfo.
+//  Volume GUID targets get their \\?\ prefix back so Win32 accepts them.
+//  Returns empty string if the target is empty or the lookup fails.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn lookup_volume_label (target: &str) -> String {
+    if target.is_empty() {
+        return String::new();
+    }
+
+    let volume_path = if is_volume_guid_path (target) {
+        let mut root = format! ("\\\\?\\{}", target);
+        if !root.ends_with ('\\') {
+            root.push ('\\');
+        }
+        PathBuf::from (root)
+    } else {
+        PathBuf::from (target)
+    };
+
+    DriveInfo::new (&volume_path)
+        .map (|info| info.volume_name)
+        .unwrap_or_default()
+}
+
+
+
+
+
 ////////////////////////////////////////////////////////////////////////////////
 //
 //  extract_utf16_string
@@ -714,4 +802,33 @@ fn appexeclink_empty_target_returns_empty() {
         let buf = build_app_exec_link_buffer (3, "pkg", "app", "");
         assert_eq! (parse_app_exec_link_buffer (&buf), "");
     }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  format_mount_target tests
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn mount_target_volume_guid_shows_label_only() {
+        assert_eq! (
+            format_mount_target ("Volume{1b3f2c4e-0000-0000-0000-100000000000}\\", "BACKUP"),
+            "[Volume: BACKUP]"
+        );
+    }
+
+    #[test]
+    fn mount_target_path_appends_label() {
+        assert_eq! (format_mount_target ("D:\\Data", "BACKUP"), "D:\\Data [Volume: BACKUP]");
+    }
+
+    #[test]
+    fn mount_target_without_label_is_unchanged() {
+        assert_eq! (format_mount_target ("D:\\Data", ""), "D:\\Data");
+        assert_eq! (format_mount_target ("", "BACKUP"), "");
+    }
 }
@@ -4,13 +4,15 @@
 
 use std::sync::Arc;
 
-use crate::command_line::CommandLine;
-use crate::config::Config;
+use crate::command_line::{CommandLine, SizeFormat};
+use crate::config::{Attribute, Config};
 use crate::console::Console;
 use crate::directory_info::DirectoryInfo;
 use crate::drive_info::DriveInfo;
+use crate::file_info::FileInfo;
 use crate::listing_totals::ListingTotals;
 
+use super::common::{format_abbreviated_size, format_compact_size};
 use super::{DirectoryLevel, ResultsDisplayer};
 
 
@@ -27,6 +29,8 @@ pub struct BareDisplayer {
     cmd:          Arc<CommandLine>,
     config:       Arc<Config>,
     icons_active: bool,
+    /// Next /B:numbered index; runs on across directories under /S
+    next_index:   usize,
 }
 
 
@@ -52,7 +56,7 @@ impl BareDisplayer {
     ////////////////////////////////////////////////////////////////////////////
 
     pub fn new(console: Console, cmd: Arc<CommandLine>, config: Arc<Config>, icons_active: bool) -> Self {
-        BareDisplayer { console, cmd, config, icons_active }
+        BareDisplayer { console, cmd, config, icons_active, next_index: 1 }
     }
 
 
@@ -112,10 +116,26 @@ impl ResultsDisplayer for BareDisplayer {
     ////////////////////////////////////////////////////////////////////////////
 
     fn display_results(&mut self, _drive_info: &DriveInfo, dir_info: &DirectoryInfo, _level: DirectoryLevel) {
+        let index_width = numbered_index_width (self.next_index, dir_info.matches.len());
+
         for fi in &dir_info.matches {
             let style = self.config.get_display_style_for_file (fi);
             let text_attr = style.text_attr;
 
+            // Running index before everything else (/B:numbered)
+            if self.cmd.bare_numbered {
+                let info_attr = self.config.attributes[Attribute::Information as usize];
+                self.console.writef (info_attr, format_args! ("{:>width$}: ", self.next_index, width = index_width));
+                self.next_index += 1;
+            }
+
+            // Size before the name (/B:size)
+            if self.cmd.bare_sizes {
+                let size_attr = self.config.attributes[Attribute::Size as usize];
+                let size_str  = format_bare_size (fi, self.cmd.resolved_size_format());
+                self.console.writef (size_attr, format_args! ("{} ", size_str));
+            }
+
             // Icon glyph before filename (when icons are active)
             if self.icons_active {
                 if let Some(icon) = style.icon_code_point {
@@ -165,6 +185,47 @@ fn display_recursive_summary(&mut self, _dir_info: &DirectoryInfo, _totals: &Lis
 
 
 
+////////////////////////////////////////////////////////////////////////////////
+//
+//  numbered_index_width
+//
+//  Width of the /B:numbered index for a batch of `count` entries starting
+//  at `first`: the digits in the batch's last index, so a single
+//  directory's numbers all line up with its total.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn numbered_index_width(first: usize, count: usize) -> usize {
+    (first + count.saturating_sub (1)).to_string().len()
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  format_bare_size
+//
+//  Size field for /B:size: exact bytes with no separators (so `sort -n`
+//  works), or abbreviated with --Size=Auto.  Directories show 0.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn format_bare_size(fi: &FileInfo, size_format: SizeFormat) -> String {
+    let size = if fi.is_directory() { 0 } else { fi.displayed_size() };
+
+    match size_format {
+        SizeFormat::Auto    => format_abbreviated_size (size),
+        SizeFormat::Compact => format_compact_size (size).trim_start().to_string(),
+        _                   => size.to_string(),
+    }
+}
+
+
+
+
+
 ////////////////////////////////////////////////////////////////////////////////
 //
 //  console_printf_line
@@ -176,3 +237,146 @@ fn display_recursive_summary(&mut self, _dir_info: &DirectoryInfo, _totals: &Lis
 fn console_printf_line(console: &mut Console, attr: u16, text: &str) {
     console.writef_line (attr, format_args! ("{}", text));
 }
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::file_info::{FILE_ATTRIBUTE_ARCHIVE, FILE_ATTRIBUTE_DIRECTORY};
+    use crate::test_support::make_file;
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  render_bare
+    //
+    //  Renders a directory's matches through BareDisplayer as plain text.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn render_bare(args: &[&str], files: Vec<FileInfo>) -> String {
+        let mut cfg = Config::new();
+        cfg.initialize (0x07);
+        let cfg = Arc::new (cfg);
+
+        let mut console = Console::new_for_testing (Arc::clone (&cfg));
+        console.set_color_mode (crate::command_line::ColorMode::Never);
+
+        let cmd = Arc::new (CommandLine::parse_from (args.iter().copied()).unwrap());
+        let mut di = DirectoryInfo::new (std::path::PathBuf::from ("C:\\test"), "*".to_string());
+        di.matches = files;
+
+        let drive_info = DriveInfo {
+            unc_path:         std::path::PathBuf::new(),
+            root_path:        std::path::PathBuf::from ("C:\\"),
+            volume_name:      String::new(),
+            file_system_name: "NTFS".to_string(),
+            volume_type:      crate::drive_info::DRIVE_FIXED,
+            is_unc_path:      false,
+            remote_name:      String::new(),
+            volume_serial:    None,
+            total_bytes:      None,
+        };
+
+        let mut displayer = BareDisplayer::new (console, cmd, cfg, false);
+        displayer.display_results (&drive_info, &di, DirectoryLevel::Initial);
+        displayer.console_mut().take_test_buffer()
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  bare_size_precedes_name
+    //
+    //  Verify /B:size prints "size name" per line with exact bytes and no
+    //  thousands separators, and directories show 0.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn bare_size_precedes_name() {
+        let output = render_bare (&["/B:size"], vec![
+            make_file ("big.bin", FILE_ATTRIBUTE_ARCHIVE, 1_234_567),
+            make_file ("src",     FILE_ATTRIBUTE_DIRECTORY, 4096),
+        ]);
+
+        assert_eq! (output, "1234567 big.bin\n0 src\n");
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  bare_size_respects_auto_format
+    //
+    //  Verify --Size=Auto abbreviates the leading size.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn bare_size_respects_auto_format() {
+        let output = render_bare (&["/B:size", "--Size=Auto"], vec![
+            make_file ("big.bin", FILE_ATTRIBUTE_ARCHIVE, 1_048_576),
+        ]);
+
+        assert_eq! (output, format! ("{} big.bin\n", format_abbreviated_size (1_048_576)));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  bare_without_size_is_name_only
+    //
+    //  Verify plain /B output is unchanged.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn bare_without_size_is_name_only() {
+        let output = render_bare (&["/B"], vec![make_file ("big.bin", FILE_ATTRIBUTE_ARCHIVE, 10)]);
+        assert_eq! (output, "big.bin\n");
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  bare_numbered_is_contiguous_and_padded
+    //
+    //  Verify /B:numbered numbers 12 entries 1..12 with no gaps and pads
+    //  the single-digit indexes to the width of the total.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn bare_numbered_is_contiguous_and_padded() {
+        let files = (0..12).map (|i| make_file (&format! ("file{:02}.txt", i), FILE_ATTRIBUTE_ARCHIVE, 1)).collect();
+        let output = render_bare (&["/B:numbered"], files);
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq! (lines.len(), 12);
+        assert_eq! (lines[0],  " 1: file00.txt");
+        assert_eq! (lines[8],  " 9: file08.txt");
+        assert_eq! (lines[9],  "10: file09.txt");
+        assert_eq! (lines[11], "12: file11.txt");
+
+        for (i, line) in lines.iter().enumerate() {
+            let (index, _) = line.split_once (": ").unwrap();
+            assert_eq! (index.len(), 2);
+            assert_eq! (index.trim().parse::<usize>().unwrap(), i + 1);
+        }
+    }
+}
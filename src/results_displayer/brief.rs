@@ -0,0 +1,235 @@
+// brief.rs — One-line-per-directory inventory displayer (/Brief)
+//
+// Replaces each directory's full section with a single summary line:
+// "path  N files  M bytes".  Intended for quick /S inventories of a tree,
+// so there are no drive headers, column rows, or recursive footers.
+
+use std::sync::Arc;
+
+use crate::config::{Attribute, Config};
+use crate::console::Console;
+use crate::directory_info::DirectoryInfo;
+use crate::drive_info::DriveInfo;
+use crate::listing_totals::ListingTotals;
+
+use super::common::format_number_with_separators;
+use super::{DirectoryLevel, ResultsDisplayer};
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Brief displayer — one summary line per visited directory.
+pub struct BriefDisplayer {
+    console: Console,
+    config:  Arc<Config>,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl BriefDisplayer
+//
+//  Brief displayer construction and console access.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl BriefDisplayer {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  new
+    //
+    //  Create a new BriefDisplayer.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn new(console: Console, config: Arc<Config>) -> Self {
+        BriefDisplayer { console, config }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  into_console
+    //
+    //  Consume the displayer and return the Console.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn into_console(self) -> Console {
+        self.console
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  console_mut
+    //
+    //  Get a mutable reference to the console.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn console_mut(&mut self) -> &mut Console {
+        &mut self.console
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl ResultsDisplayer for BriefDisplayer
+//
+//  One line per directory; no recursive summary.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl ResultsDisplayer for BriefDisplayer {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  display_results
+    //
+    //  Write "path  N files  M bytes": the path in Information, the counts
+    //  in Size.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn display_results(&mut self, _drive_info: &DriveInfo, dir_info: &DirectoryInfo, _level: DirectoryLevel) {
+        let info_attr = self.config.attributes[Attribute::Information as usize];
+        let size_attr = self.config.attributes[Attribute::Size as usize];
+
+        let files_word = if dir_info.file_count == 1 { "file" } else { "files" };
+        let bytes_word = if dir_info.bytes_used == 1 { "byte" } else { "bytes" };
+
+        self.console.writef (info_attr, format_args! ("{}  ", dir_info.dir_path.display()));
+        self.console.writef (size_attr, format_args! ("{}", dir_info.file_count));
+        self.console.writef (info_attr, format_args! (" {}  ", files_word));
+        self.console.writef (size_attr, format_args! ("{}", format_number_with_separators (dir_info.bytes_used)));
+        self.console.writef_line (info_attr, format_args! (" {}", bytes_word));
+
+        let _ = self.console.flush();
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  display_recursive_summary
+    //
+    //  Brief mode doesn't display a recursive summary.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn display_recursive_summary(&mut self, _dir_info: &DirectoryInfo, _totals: &ListingTotals) {
+        // No summary in brief mode
+    }
+}
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    use crate::command_line::ColorMode;
+    use crate::drive_info::DRIVE_FIXED;
+    use crate::file_info::FILE_ATTRIBUTE_ARCHIVE;
+    use crate::test_support::make_file;
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  make_dir_info
+    //
+    //  Creates a DirectoryInfo holding files of the given sizes.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn make_dir_info(path: &str, sizes: &[u64]) -> DirectoryInfo {
+        let mut di = DirectoryInfo::new (PathBuf::from (path), "*".to_string());
+
+        for (i, &size) in sizes.iter().enumerate() {
+            di.matches.push (make_file (&format! ("file{}.txt", i), FILE_ATTRIBUTE_ARCHIVE, size));
+        }
+
+        di.file_count = sizes.len() as u32;
+        di.bytes_used = sizes.iter().sum();
+        di
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  each_directory_yields_one_line
+    //
+    //  Verify every visited directory, including an empty one, produces
+    //  exactly one "path  N files  M bytes" line.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn each_directory_yields_one_line() {
+        let mut cfg = Config::new();
+        cfg.initialize (0x07);
+        let cfg = Arc::new (cfg);
+
+        let mut console = Console::new_for_testing (Arc::clone (&cfg));
+        console.set_color_mode (ColorMode::Never);
+
+        let drive_info = DriveInfo {
+            unc_path:         PathBuf::new(),
+            root_path:        PathBuf::from ("C:\\"),
+            volume_name:      "TestVol".to_string(),
+            file_system_name: "NTFS".to_string(),
+            volume_type:      DRIVE_FIXED,
+            is_unc_path:      false,
+            remote_name:      String::new(),
+            volume_serial:    None,
+            total_bytes:      None,
+        };
+
+        let dirs = [
+            (make_dir_info ("C:\\Root",           &[1024, 512]), DirectoryLevel::Initial),
+            (make_dir_info ("C:\\Root\\src",      &[1]),         DirectoryLevel::Subdirectory),
+            (make_dir_info ("C:\\Root\\src\\old", &[]),          DirectoryLevel::Subdirectory),
+        ];
+
+        let mut displayer = BriefDisplayer::new (console, cfg);
+        for (di, level) in &dirs {
+            displayer.display_results (&drive_info, di, *level);
+        }
+
+        let output = displayer.console_mut().take_test_buffer();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq! (lines, vec![
+            "C:\\Root  2 files  1,536 bytes",
+            "C:\\Root\\src  1 file  1 byte",
+            "C:\\Root\\src\\old  0 files  0 bytes",
+        ]);
+    }
+}
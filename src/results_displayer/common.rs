@@ -2,12 +2,40 @@
 //
 // Port of: CResultsDisplayerWithHeaderAndFooter (header/footer/summary methods)
 
+use std::collections::BTreeMap;
+use std::path::{Component, Path};
+
 use crate::cloud_status::CloudStatus;
+use crate::color::{BC_MASK, FC_DARK_GREY};
+use crate::command_line::{CommandLine, SummaryVerbosity};
 use crate::config::{Attribute, Config};
 use crate::console::Console;
 use crate::directory_info::DirectoryInfo;
 use crate::drive_info::DriveInfo;
+use crate::file_info::FileInfo;
 use crate::listing_totals::ListingTotals;
+use crate::usage::LINE_HORIZONTAL;
+
+/// Separator between /Up:N breadcrumb segments.
+const BREADCRUMB_SEPARATOR: &str = " \u{203A} ";
+
+/// /V:2 size histogram bands: label and exclusive upper bound in bytes.
+const SIZE_HISTOGRAM_BANDS: [(&str, u64); 4] = [
+    ("< 1 KB",      1 << 10),
+    ("1 KB - 1 MB", 1 << 20),
+    ("1 MB - 1 GB", 1 << 30),
+    (">= 1 GB",     u64::MAX),
+];
+
+/// Widest /V:2 histogram bar, in characters.
+const HISTOGRAM_BAR_WIDTH: usize = 20;
+
+/// Width of a --Size=Compact value (see format_compact_size).
+pub const COMPACT_SIZE_WIDTH: usize = 5;
+
+/// /Mark prefix on directory names, plain ASCII so it survives without
+/// color or a Nerd Font.
+pub const DIRECTORY_MARKER: &str = "+ ";
 
 
 
@@ -30,6 +58,13 @@ pub(super) fn display_drive_header(console: &mut Console, drive_info: &DriveInfo
             drive_info.unc_path.display(),
             drive_info.volume_description(),
         ));
+    } else if let Some(guid_root) = drive_info.volume_guid_root() {
+        // Volume mounted without a drive letter
+        console.color_printf(&format!(
+            "{{Information}} Volume {{InformationHighlight}}{}{{Information}} is {{InformationHighlight}}{}{{Information}}",
+            guid_root,
+            drive_info.volume_description(),
+        ));
     } else {
         let drive_letter = drive_info.root_path.to_string_lossy()
             .chars().next().unwrap_or('?');
@@ -63,6 +98,21 @@ pub(super) fn display_drive_header(console: &mut Console, drive_info: &DriveInfo
         console.color_puts("{Information} Volume has no name");
     }
 
+    // Serial number and capacity (may be unavailable, e.g. on UNC shares)
+    if let Some(serial) = drive_info.volume_serial_string() {
+        console.color_printf(&format!(
+            "{{Information}} Volume Serial Number is {{InformationHighlight}}{}{{Information}}\n",
+            serial,
+        ));
+    }
+
+    if let Some(total_bytes) = drive_info.total_bytes {
+        console.color_printf(&format!(
+            "{{Information}} Volume size is {{InformationHighlight}}{}{{Information}} bytes\n",
+            format_number_with_separators(total_bytes),
+        ));
+    }
+
     console.color_puts("");
 }
 
@@ -90,6 +140,60 @@ pub(super) fn display_path_header(console: &mut Console, dir_info: &DirectoryInf
 
 
 
+////////////////////////////////////////////////////////////////////////////////
+//
+//  breadcrumb_segments
+//
+//  Split a directory path into the /Up:N breadcrumb: the directory itself
+//  plus up to `levels` ancestors, outermost first.  The drive ("C:") or
+//  UNC share counts as one segment.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn breadcrumb_segments(dir_path: &Path, levels: usize) -> Vec<String> {
+    let segments: Vec<String> = dir_path
+        .components()
+        .filter(|c| !matches!(c, Component::RootDir | Component::CurDir))
+        .map(|c| c.as_os_str().to_string_lossy().trim_end_matches('\\').to_string())
+        .collect();
+
+    let skip = segments.len().saturating_sub(levels + 1);
+    segments.into_iter().skip(skip).collect()
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  display_breadcrumb
+//
+//  Display the /Up:N breadcrumb line: path segments in the Directory
+//  color joined by separators in the SeparatorLine color.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn display_breadcrumb(console: &mut Console, dir_path: &Path, levels: usize) {
+    let directory_attr = console.config().attributes[Attribute::Directory as usize];
+    let separator_attr = console.config().attributes[Attribute::SeparatorLine as usize];
+
+    console.printf_attr(Attribute::Default, " ");
+
+    for (i, segment) in breadcrumb_segments(dir_path, levels).iter().enumerate() {
+        if i > 0 {
+            console.printf(separator_attr, BREADCRUMB_SEPARATOR);
+        }
+        console.printf(directory_attr, segment);
+    }
+
+    console.puts(Attribute::Default, "");
+}
+
+
+
+
+
 ////////////////////////////////////////////////////////////////////////////////
 //
 //  display_empty_directory_message
@@ -133,13 +237,7 @@ pub(super) fn display_empty_directory_message(console: &mut Console, dir_info: &
 ////////////////////////////////////////////////////////////////////////////////
 
 pub(super) fn display_cloud_status_symbol(console: &mut Console, config: &Config, status: CloudStatus, icons_active: bool) {
-    let attr = match status {
-        CloudStatus::None      => Attribute::Default,
-        CloudStatus::CloudOnly => Attribute::CloudStatusCloudOnly,
-        CloudStatus::Local     => Attribute::CloudStatusLocallyAvailable,
-        CloudStatus::Pinned    => Attribute::CloudStatusAlwaysLocallyAvailable,
-    };
-    let color = config.attributes[attr as usize];
+    let color = config.attributes[cloud_status_attribute (status) as usize];
 
     if icons_active {
         // NF glyph path — leading space + 2-col icon + trailing space (4 visual cols)
@@ -159,6 +257,95 @@ pub(super) fn display_cloud_status_symbol(console: &mut Console, config: &Config
 
 
 
+////////////////////////////////////////////////////////////////////////////////
+//
+//  cloud_status_attribute
+//
+//  The configured color for a cloud status.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn cloud_status_attribute(status: CloudStatus) -> Attribute {
+    match status {
+        CloudStatus::None      => Attribute::Default,
+        CloudStatus::CloudOnly => Attribute::CloudStatusCloudOnly,
+        CloudStatus::Local     => Attribute::CloudStatusLocallyAvailable,
+        CloudStatus::Pinned    => Attribute::CloudStatusAlwaysLocallyAvailable,
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  highlight_text_attr
+//
+//  Apply the --Highlight overlay: an entry carrying any of the chosen
+//  attributes, or (with /SinceLast) written since the previous run,
+//  renders in InformationHighlight instead of its normal extension/
+//  attribute color.  Otherwise a /Stale file is dimmed to dark grey on
+//  its usual background.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn highlight_text_attr(config: &Config, cmd: &CommandLine, file_info: &FileInfo, text_attr: u16) -> u16 {
+    let modified_since_last = cmd.since_last_run.is_some_and (|run| run.is_modified_since (file_info.last_write_time));
+
+    if cmd.highlight_attrs & file_info.file_attributes != 0 || modified_since_last {
+        config.attributes[Attribute::InformationHighlight as usize]
+    } else if cmd.is_stale (file_info) {
+        (text_attr & BC_MASK) | FC_DARK_GREY
+    } else {
+        text_attr
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  cloud_column_width
+//
+//  Visual width of the cloud status cell: 0 when the column is hidden
+//  (/NoCloud), 4 with icons (" {icon} "), 3 otherwise (" ● ").
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn cloud_column_width(show_cloud: bool, icons_active: bool) -> usize {
+    match (show_cloud, icons_active) {
+        (false, _)    => 0,
+        (true, true)  => 4,
+        (true, false) => 3,
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  cloud_column_shown
+//
+//  Whether a directory's listing has the cloud status column.  It stays
+//  (blank) outside a sync root to match TCDir's layout, unless /NoCloud
+//  drops it everywhere or /NoCloud:auto drops it where no sync root was
+//  detected.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn cloud_column_shown(cmd: &CommandLine, in_sync_root: bool) -> bool {
+    !cmd.no_cloud && (in_sync_root || !cmd.no_cloud_auto)
+}
+
+
+
+
+
 ////////////////////////////////////////////////////////////////////////////////
 //
 //  display_directory_summary
@@ -202,6 +389,154 @@ pub(super) fn display_directory_summary(console: &mut Console, di: &DirectoryInf
 
 
 
+////////////////////////////////////////////////////////////////////////////////
+//
+//  display_filtered_count
+//
+//  Display "Showing N of M entries" (/ShowFiltered): the matches listed
+//  versus every entry scanned in the directory.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn display_filtered_count(console: &mut Console, di: &DirectoryInfo) {
+    let entries_word = if di.entries_scanned == 1 { " entry" } else { " entries" };
+
+    console.color_puts(&format!(
+        "{{Information}} Showing {{InformationHighlight}}{}{{Information}} of {{InformationHighlight}}{}{{Information}}{}",
+        format_number_with_separators(di.matches.len() as u64),
+        format_number_with_separators(di.entries_scanned as u64),
+        entries_word,
+    ));
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  display_summary_details
+//
+//  The /V:2 additions to a directory summary: a per-extension breakdown,
+//  the oldest and newest files (by the /T time field), and a size
+//  histogram.  Directories are not counted.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn display_summary_details(console: &mut Console, cmd: &CommandLine, di: &DirectoryInfo) {
+    let files: Vec<&FileInfo> = di.matches.iter().filter(|fi| !fi.is_directory()).collect();
+    if files.is_empty() {
+        return;
+    }
+
+    // Per-extension breakdown
+    let breakdown   = extension_breakdown(&files);
+    let label_width = breakdown.iter().map(|(ext, _, _)| ext.len()).max().unwrap_or(0);
+
+    console.color_puts("\n{Information} By extension:");
+    for (ext, count, bytes) in &breakdown {
+        let files_word = if *count == 1 { " file using " } else { " files using " };
+        let bytes_word = if *bytes == 1 { " byte" } else { " bytes" };
+        console.color_puts(&format!(
+            "{{Information}}   {:<width$}  {{InformationHighlight}}{}{{Information}}{}{{InformationHighlight}}{}{{Information}}{}",
+            ext,
+            format_number_with_separators(*count as u64),
+            files_word,
+            format_number_with_separators(*bytes),
+            bytes_word,
+            width = label_width,
+        ));
+    }
+
+    // Oldest and newest by the displayed time field
+    let time_of = |fi: &FileInfo| super::normal::get_time_field_for_display(fi, cmd.time_field);
+    let oldest  = files.iter().min_by_key(|fi| time_of(fi));
+    let newest  = files.iter().max_by_key(|fi| time_of(fi));
+
+    for (label, fi) in [(" Oldest: ", oldest), (" Newest: ", newest)] {
+        if let Some(fi) = fi {
+            console.color_printf(&format!("{{Information}}{}", label));
+            super::normal::display_date_and_time(console, time_of(fi), cmd.date_format.as_deref(), None);
+            console.color_puts(&format!("{{Information}}{}", fi.file_name.to_string_lossy()));
+        }
+    }
+
+    // Size histogram
+    let histogram = size_histogram(&files);
+    let max_count = histogram.iter().copied().max().unwrap_or(0).max(1);
+    let label_width = SIZE_HISTOGRAM_BANDS.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+    let count_width = format_number_with_separators(max_count as u64).len();
+
+    console.color_puts("{Information} Size histogram:");
+    for ((label, _), count) in SIZE_HISTOGRAM_BANDS.iter().zip(histogram) {
+        let bar_len = (count as usize * HISTOGRAM_BAR_WIDTH).div_ceil(max_count as usize);
+        console.color_puts(&format!(
+            "{{Information}}   {:<label_width$}  {{InformationHighlight}}{:>count_width$}{{Information}}  {}",
+            label,
+            format_number_with_separators(count as u64),
+            "#".repeat(bar_len),
+        ));
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  extension_breakdown
+//
+//  Group files by lowercased extension ("(none)" when there is none):
+//  (extension, file count, bytes), sorted by extension.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn extension_breakdown(files: &[&FileInfo]) -> Vec<(String, u32, u64)> {
+    let mut by_extension: BTreeMap<String, (u32, u64)> = BTreeMap::new();
+
+    for fi in files {
+        let extension = Path::new(&fi.file_name).extension()
+            .map(|e| format!(".{}", e.to_string_lossy().to_lowercase()))
+            .unwrap_or_else(|| "(none)".to_string());
+
+        let entry = by_extension.entry(extension).or_default();
+        entry.0 += 1;
+        entry.1 += fi.displayed_size();
+    }
+
+    by_extension.into_iter().map(|(ext, (count, bytes))| (ext, count, bytes)).collect()
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  size_histogram
+//
+//  Count files in each SIZE_HISTOGRAM_BANDS band.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn size_histogram(files: &[&FileInfo]) -> [u32; SIZE_HISTOGRAM_BANDS.len()] {
+    let mut counts = [0u32; SIZE_HISTOGRAM_BANDS.len()];
+
+    for fi in files {
+        let band = SIZE_HISTOGRAM_BANDS.iter()
+            .position(|&(_, upper)| fi.displayed_size() < upper)
+            .unwrap_or(SIZE_HISTOGRAM_BANDS.len() - 1);
+        counts[band] += 1;
+    }
+
+    counts
+}
+
+
+
+
+
 ////////////////////////////////////////////////////////////////////////////////
 //
 //  display_volume_footer
@@ -297,7 +632,9 @@ fn display_footer_quota_info(console: &mut Console, free_bytes_available: u64) {
 //
 ////////////////////////////////////////////////////////////////////////////////
 
-pub(super) fn display_listing_summary(console: &mut Console, di: &DirectoryInfo, totals: &ListingTotals) {
+pub(super) fn display_listing_summary(console: &mut Console, di: &DirectoryInfo, totals: &ListingTotals, verbosity: SummaryVerbosity) {
+    display_separator_line(console);
+
     let max_count = totals.file_count.max(totals.directory_count);
     let max_digits = if max_count > 0 {
         let d = (max_count as f64).log10() as usize + 1;
@@ -332,9 +669,21 @@ pub(super) fn display_listing_summary(console: &mut Console, di: &DirectoryInfo,
             sbytes_word,
             width = max_digits,
         ));
+
+        console.color_printf(&format_byte_breakdown_line(totals, max_digits));
+    }
+
+    if totals.reparse_point_count() > 0 {
+        console.color_printf(&format_reparse_points_line(totals, max_digits));
     }
 
-    display_volume_footer(console, di);
+    if totals.empty_dirs_omitted > 0 {
+        console.color_printf(&format_omitted_line(totals.empty_dirs_omitted, max_digits));
+    }
+
+    if verbosity != SummaryVerbosity::TotalsOnly {
+        display_volume_footer(console, di);
+    }
 
     console.puts(Attribute::Default, "");
 }
@@ -343,6 +692,191 @@ pub(super) fn display_listing_summary(console: &mut Console, di: &DirectoryInfo,
 
 
 
+////////////////////////////////////////////////////////////////////////////////
+//
+//  format_byte_breakdown_line
+//
+//  The recursive summary's split of total bytes into file data and
+//  alternate stream data, shown when any streams were counted.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn format_byte_breakdown_line(totals: &ListingTotals, width: usize) -> String {
+    let bytes_word = if totals.total_bytes() == 1 { " byte total: " } else { " bytes total: " };
+
+    format!(
+        "{{InformationHighlight}}    {:>width$}{{Information}}{}{{InformationHighlight}}{}{{Information}} file data + {{InformationHighlight}}{}{{Information}} stream data\n",
+        format_number_with_separators(totals.total_bytes()),
+        bytes_word,
+        format_number_with_separators(totals.file_bytes),
+        format_number_with_separators(totals.stream_bytes),
+        width = width,
+    )
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  format_reparse_points_line
+//
+//  The recursive summary's link inventory ("N reparse points: 3
+//  symlinks, 2 junctions, 1 other"), listing only the non-zero kinds.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn format_reparse_points_line(totals: &ListingTotals, width: usize) -> String {
+    let total       = totals.reparse_point_count();
+    let points_word = if total == 1 { " reparse point: " } else { " reparse points: " };
+
+    let kinds: Vec<String> = [
+        (totals.symlink_count,       "symlink",  "symlinks"),
+        (totals.junction_count,      "junction", "junctions"),
+        (totals.other_reparse_count, "other",    "other"),
+    ]
+    .iter()
+    .filter(|(count, _, _)| *count > 0)
+    .map(|&(count, singular, plural)| {
+        format!(
+            "{{InformationHighlight}}{}{{Information}} {}",
+            format_number_with_separators(count as u64),
+            if count == 1 { singular } else { plural },
+        )
+    })
+    .collect();
+
+    format!(
+        "{{InformationHighlight}}    {:>width$}{{Information}}{}{}\n",
+        format_number_with_separators(total as u64),
+        points_word,
+        kinds.join(", "),
+        width = width,
+    )
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  format_omitted_line
+//
+//  The /CollapseEmpty summary line ("N empty directories omitted"),
+//  aligned with the other recursive totals.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn format_omitted_line(omitted: u32, width: usize) -> String {
+    let dirs_word = if omitted == 1 { " empty directory omitted" } else { " empty directories omitted" };
+
+    format!(
+        "{{InformationHighlight}}    {:>width$}{{Information}}{}\n",
+        format_number_with_separators(omitted as u64),
+        dirs_word,
+        width = width,
+    )
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  format_stream_count
+//
+//  The /HasStreams per-file suffix (" (N streams)") shown after the
+//  filename.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn format_stream_count(count: usize) -> String {
+    let streams_word = if count == 1 { "stream" } else { "streams" };
+
+    format!(" ({} {})", format_number_with_separators(count as u64), streams_word)
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  format_name_suffix
+//
+//  Text written after the filename in the Information color: the
+//  /HasStreams stream count and the /ShowMask matched spec.  Empty when
+//  neither applies, so the name line renders exactly as before.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn format_name_suffix(cmd: &CommandLine, file_info: &FileInfo) -> String {
+    let mut suffix = String::new();
+
+    if cmd.has_streams && !file_info.streams.is_empty() {
+        suffix.push_str(&format_stream_count(file_info.streams.len()));
+    }
+
+    if cmd.show_mask
+        && let Some(spec) = &file_info.matched_spec
+    {
+        suffix.push_str(&format!("  [{}]", spec));
+    }
+
+    suffix
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  separator_rule
+//
+//  Build the section separator rule from the SepChar/SepWidth settings.
+//  Returns None when neither is configured, so default output stays
+//  identical to TCDir.  The character defaults to LINE_HORIZONTAL and
+//  the width to the console width.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn separator_rule(config: &Config, console_width: usize) -> Option<String> {
+    if config.separator_char.is_none() && config.separator_width.is_none() {
+        return None;
+    }
+
+    let ch    = config.separator_char.unwrap_or(LINE_HORIZONTAL);
+    let width = config.separator_width.unwrap_or(console_width);
+
+    Some(std::iter::repeat_n(ch, width).collect())
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  display_separator_line
+//
+//  Draw the configured separator rule (if any) in the SeparatorLine color.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn display_separator_line(console: &mut Console) {
+    if let Some(rule) = separator_rule(console.config(), console.width() as usize) {
+        console.puts(Attribute::SeparatorLine, &rule);
+    }
+}
+
+
+
+
+
 ////////////////////////////////////////////////////////////////////////////////
 //
 //  get_string_length_of_max_file_size
@@ -503,13 +1037,61 @@ pub fn format_abbreviated_size (cb_size: u64) -> String {
     //   >=100  → ###   (e.g., " 976 KB")
     let suffix = SUFFIXES[idx_suffix];
 
-    if value < 10.0 {
-        format! ("{:>4.2} {:<2}", value, suffix)
-    } else if value < 100.0 {
-        format! ("{:>4.1} {:<2}", value, suffix)
-    } else {
-        format! ("{:>4.0} {:<2}", value, suffix)
+    if value < 10.0 {
+        format! ("{:>4.2} {:<2}", value, suffix)
+    } else if value < 100.0 {
+        format! ("{:>4.1} {:<2}", value, suffix)
+    } else {
+        format! ("{:>4.0} {:<2}", value, suffix)
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  format_compact_size
+//
+//  Dense size for --Size=Compact: 1024-based with a one-letter unit,
+//  right-justified in COMPACT_SIZE_WIDTH (5) characters so it fits beside
+//  wide-listing names and in tree rows.  Values that would round to 1000
+//  of a unit move up to the next one.
+//
+//  Range                 Example
+//  0-999                 "  999"
+//  1000-102348           " 1.0K", " 1.2K", "99.9K"
+//  102349-1023487        " 100K", " 999K"
+//  1023488+              " 1.0M", "12.3G", "16.0E"
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn format_compact_size (cb_size: u64) -> String {
+
+    static SUFFIXES: &[char] = &['K', 'M', 'G', 'T', 'P', 'E'];
+
+
+
+    if cb_size < 1000 {
+        return format! ("{:>width$}", cb_size, width = COMPACT_SIZE_WIDTH);
+    }
+
+    let mut value      = cb_size as f64 / 1024.0;
+    let mut idx_suffix = 0usize;
+
+    while value >= 999.5 && idx_suffix + 1 < SUFFIXES.len() {
+        value /= 1024.0;
+        idx_suffix += 1;
     }
+
+    let text = if value < 99.95 {
+        format! ("{:.1}{}", value, SUFFIXES[idx_suffix])
+    } else {
+        format! ("{:.0}{}", value, SUFFIXES[idx_suffix])
+    };
+
+    format! ("{:>width$}", text, width = COMPACT_SIZE_WIDTH)
 }
 
 
@@ -519,6 +1101,7 @@ pub fn format_abbreviated_size (cb_size: u64) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::make_file;
 
 
     ////////////////////////////////////////////////////////////////////////////
@@ -892,4 +1475,474 @@ fn abbreviated_size_fractional_gb () {
     fn abbreviated_size_1tb () {
         assert_eq! (format_abbreviated_size (1_099_511_627_776), "1.00 TB");
     }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  make_header_drive_info
+    //
+    //  Creates a populated local-drive DriveInfo for header tests.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn make_header_drive_info() -> DriveInfo {
+        DriveInfo {
+            unc_path:         std::path::PathBuf::new(),
+            root_path:        std::path::PathBuf::from ("C:\\"),
+            volume_name:      "Windows".to_string(),
+            file_system_name: "NTFS".to_string(),
+            volume_type:      crate::drive_info::DRIVE_FIXED,
+            is_unc_path:      false,
+            remote_name:      String::new(),
+            volume_serial:    Some (0x1A2B_3C4D),
+            total_bytes:      Some (1_000_000),
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  render_drive_header
+    //
+    //  Renders the drive header to plain text (ANSI stripped).
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn render_drive_header (drive_info: &DriveInfo) -> String {
+        let mut cfg = Config::new();
+        cfg.initialize (0x07);
+        let mut console = Console::new_for_testing (std::sync::Arc::new (cfg));
+        console.set_color_mode (crate::command_line::ColorMode::Never);
+
+        display_drive_header (&mut console, drive_info);
+        console.take_test_buffer()
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  drive_header_shows_serial_and_size
+    //
+    //  Verify the header prints the serial number as XXXX-XXXX and the
+    //  total volume size with separators.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn drive_header_shows_serial_and_size () {
+        let output = render_drive_header (&make_header_drive_info());
+
+        assert! (output.contains (" Volume Serial Number is 1A2B-3C4D\n"));
+        assert! (output.contains (&format! (" Volume size is {} bytes\n", format_number_with_separators (1_000_000))));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  drive_header_omits_unavailable_serial_and_size
+    //
+    //  Verify UNC shares without serial/capacity info omit those lines.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn drive_header_omits_unavailable_serial_and_size () {
+        let mut drive_info = make_header_drive_info();
+        drive_info.is_unc_path   = true;
+        drive_info.unc_path      = std::path::PathBuf::from ("\\\\server\\share");
+        drive_info.volume_type   = crate::drive_info::DRIVE_REMOTE;
+        drive_info.volume_serial = None;
+        drive_info.total_bytes   = None;
+
+        let output = render_drive_header (&drive_info);
+
+        assert! (output.contains ("Volume \\\\server\\share is a network drive"));
+        assert! (!output.contains ("Serial Number"));
+        assert! (!output.contains ("Volume size"));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  render_separator_line
+    //
+    //  Renders the separator line to plain text for a given RCDIR value.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn render_separator_line (env_value: &str) -> String {
+        let mut mock = crate::environment_provider::MockEnvironmentProvider::new();
+        mock.set (crate::config::RCDIR_ENV_VAR_NAME, env_value);
+
+        let mut cfg = Config::new();
+        cfg.initialize_with_provider (0x07, &mock);
+        let mut console = Console::new_for_testing (std::sync::Arc::new (cfg));
+        console.set_color_mode (crate::command_line::ColorMode::Never);
+
+        display_separator_line (&mut console);
+        console.take_test_buffer()
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  separator_uses_configured_char_and_width
+    //
+    //  Verify SepChar and SepWidth control the rule's glyph and length.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn separator_uses_configured_char_and_width () {
+        assert_eq! (render_separator_line ("SepChar==;SepWidth=5"), "=====\n");
+        assert_eq! (render_separator_line ("SepChar=U+2550;SepWidth=3"), "\u{2550}\u{2550}\u{2550}\n");
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  separator_defaults_to_horizontal_line_and_console_width
+    //
+    //  Verify an unset char falls back to LINE_HORIZONTAL and an unset
+    //  width to the console width.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn separator_defaults_to_horizontal_line_and_console_width () {
+        let expected: String = std::iter::repeat_n (LINE_HORIZONTAL, 4).collect();
+        assert_eq! (render_separator_line ("SepWidth=4"), expected + "\n");
+
+        let rule = render_separator_line ("SepChar=*");
+        assert_eq! (rule.trim_end(), "*".repeat (120));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  separator_omitted_when_unconfigured
+    //
+    //  Verify nothing is drawn without SepChar/SepWidth (TCDir parity).
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn separator_omitted_when_unconfigured () {
+        assert_eq! (render_separator_line (""), "");
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  omitted_line_reports_count
+    //
+    //  Verify the /CollapseEmpty summary line text and singular/plural.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn omitted_line_reports_count () {
+        let line = format_omitted_line (1, 3);
+        assert! (line.contains ("  1{Information} empty directory omitted\n"), "{}", line);
+
+        let line = format_omitted_line (42, 3);
+        assert! (line.contains (" 42{Information} empty directories omitted\n"), "{}", line);
+    }
+
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  reparse_points_line_lists_nonzero_kinds
+    //
+    //  Verify the link inventory line totals every kind, names only the
+    //  non-zero ones, and uses singular/plural per kind.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn reparse_points_line_lists_nonzero_kinds () {
+        let totals = ListingTotals { symlink_count: 3, other_reparse_count: 1, ..Default::default() };
+        let line   = format_reparse_points_line (&totals, 3);
+        assert_eq! (line, "{InformationHighlight}      4{Information} reparse points: {InformationHighlight}3{Information} symlinks, {InformationHighlight}1{Information} other\n");
+
+        let totals = ListingTotals { junction_count: 1, ..Default::default() };
+        let line   = format_reparse_points_line (&totals, 1);
+        assert! (line.contains ("1{Information} reparse point: {InformationHighlight}1{Information} junction\n"), "{}", line);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  stream_count_suffix_singular_and_plural
+    //
+    //  Verify the /HasStreams per-file suffix text.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn stream_count_suffix_singular_and_plural () {
+        assert_eq! (format_stream_count (1), " (1 stream)");
+        assert_eq! (format_stream_count (3), " (3 streams)");
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  breadcrumb_segments_limit_ancestors
+    //
+    //  Verify /Up:N keeps the directory plus N ancestors, with the drive
+    //  as the outermost segment once N reaches it.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn breadcrumb_segments_limit_ancestors () {
+        let path = Path::new ("C:\\a\\b\\c");
+
+        assert_eq! (breadcrumb_segments (path, 1),  ["b", "c"]);
+        assert_eq! (breadcrumb_segments (path, 3),  ["C:", "a", "b", "c"]);
+        assert_eq! (breadcrumb_segments (path, 10), ["C:", "a", "b", "c"]);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  breadcrumb_renders_colored_segments
+    //
+    //  Verify the breadcrumb for C:\a\b\c draws names in the Directory
+    //  color and separators in the SeparatorLine color.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn breadcrumb_renders_colored_segments () {
+        let mut cfg = Config::new();
+        cfg.initialize (0x07);
+        let directory_attr = cfg.attributes[Attribute::Directory as usize];
+        let separator_attr = cfg.attributes[Attribute::SeparatorLine as usize];
+
+        let mut console = Console::new_for_testing (std::sync::Arc::new (cfg));
+        display_breadcrumb (&mut console, Path::new ("C:\\a\\b\\c"), 3);
+        let colored = console.take_test_buffer();
+
+        let sgr = |attr: u16| { let mut s = String::new(); crate::ansi_codes::write_sgr (&mut s, attr); s };
+        assert! (colored.contains (&format! ("{}C:", sgr (directory_attr))), "{colored:?}");
+        assert! (colored.contains (&format! ("{}{}", sgr (separator_attr), BREADCRUMB_SEPARATOR)), "{colored:?}");
+
+        console.set_color_mode (crate::command_line::ColorMode::Never);
+        display_breadcrumb (&mut console, Path::new ("C:\\a\\b\\c"), 3);
+        assert_eq! (console.take_test_buffer(), " C: \u{203A} a \u{203A} b \u{203A} c\n");
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  stale_overlay_dims_files_beyond_window
+    //
+    //  Verify /Stale:30d dims a file last accessed 31 days ago but not one
+    //  accessed yesterday, a directory, or anything without /Stale, and
+    //  that --Highlight still wins over the dimming.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn stale_overlay_dims_files_beyond_window() {
+        use crate::color::{BC_BLUE, FC_LIGHT_GREEN};
+        use crate::file_info::{FILE_ATTRIBUTE_ARCHIVE, FILE_ATTRIBUTE_DIRECTORY, FILE_ATTRIBUTE_READONLY};
+
+        const NOW: u64 = 133_500_000_000_000_000;
+        const DAY: u64 = 24 * 60 * 60 * 10_000_000;
+
+        let accessed = |name: &str, attrs: u32, days_ago: u64| FileInfo {
+            last_access_time: NOW - days_ago * DAY,
+            ..make_file (name, attrs, 1)
+        };
+
+        let mut config = Config::new();
+        config.initialize (0x07);
+        let text_attr = FC_LIGHT_GREEN | BC_BLUE;
+
+        let recent  = accessed ("recent.txt", FILE_ATTRIBUTE_ARCHIVE,   1);
+        let old     = accessed ("old.txt",    FILE_ATTRIBUTE_ARCHIVE,   31);
+        let old_dir = accessed ("archive",    FILE_ATTRIBUTE_DIRECTORY, 31);
+        let old_ro  = accessed ("locked.txt", FILE_ATTRIBUTE_READONLY,  31);
+
+        let mut cmd = CommandLine::parse_from (["/Stale:30d", "/Highlight:R"]).unwrap();
+        cmd.apply_stale_window (NOW);
+
+        assert_eq! (highlight_text_attr (&config, &cmd, &recent,  text_attr), text_attr);
+        assert_eq! (highlight_text_attr (&config, &cmd, &old,     text_attr), FC_DARK_GREY | BC_BLUE);
+        assert_eq! (highlight_text_attr (&config, &cmd, &old_dir, text_attr), text_attr);
+        assert_eq! (highlight_text_attr (&config, &cmd, &old_ro,  text_attr),
+                    config.attributes[Attribute::InformationHighlight as usize]);
+
+        let plain = CommandLine::parse_from (Vec::<&str>::new()).unwrap();
+        assert_eq! (highlight_text_attr (&config, &plain, &old, text_attr), text_attr);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  listing_summary_splits_file_and_stream_bytes
+    //
+    //  Verify a tree with stream-bearing files reports file data, stream
+    //  data, and a total equal to their sum, and that the split line is
+    //  omitted when no streams were counted.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn listing_summary_splits_file_and_stream_bytes() {
+        use std::sync::Arc;
+
+        let mut config = Config::new();
+        config.initialize (0x07);
+        let config = Arc::new (config);
+
+        let render = |totals: &ListingTotals| -> String {
+            let mut console = Console::new_for_testing (Arc::clone (&config));
+            console.set_color_mode (crate::command_line::ColorMode::Never);
+            let di = DirectoryInfo::new (std::path::PathBuf::from ("C:\\tree"), "*".to_string());
+            display_listing_summary (&mut console, &di, totals, SummaryVerbosity::TotalsOnly);
+            console.take_test_buffer()
+        };
+
+        // Two directories' worth of totals, each with files carrying streams
+        let mut totals = ListingTotals::default();
+        totals.add (&ListingTotals { file_count: 3, file_bytes: 4_000, stream_count: 2, stream_bytes: 1_500, ..Default::default() });
+        totals.add (&ListingTotals { file_count: 1, file_bytes: 250,   stream_count: 1, stream_bytes: 26,    ..Default::default() });
+        assert_eq! (totals.total_bytes(), totals.file_bytes + totals.stream_bytes);
+
+        let output = render (&totals);
+        let line = output.lines().find (|l| l.contains ("bytes total:")).expect ("breakdown line");
+        assert_eq! (line.trim(), "5,776 bytes total: 4,250 file data + 1,526 stream data");
+        assert! (output.contains ("3 streams using 1,526 bytes"));
+        assert! (output.contains ("4 files using 4,250 bytes"));
+
+        let no_streams = ListingTotals { file_count: 1, file_bytes: 10, ..Default::default() };
+        assert! (!render (&no_streams).contains ("bytes total:"));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  drive_header_shows_volume_guid_path
+    //
+    //  Verify a volume reached by its GUID path is named by that path in
+    //  the header instead of a drive letter.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn drive_header_shows_volume_guid_path () {
+        let mut drive_info = make_header_drive_info();
+        drive_info.root_path = std::path::PathBuf::from ("\\\\?\\Volume{26a21bda-a627-11d7-9931-806e6f6e6963}\\");
+
+        let output = render_drive_header (&drive_info);
+
+        assert! (output.starts_with (" Volume \\\\?\\Volume{26a21bda-a627-11d7-9931-806e6f6e6963}\\ is a hard drive (NTFS)\n"), "{output}");
+        assert! (!output.contains ("Volume in drive"));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  compact_size_rounds_at_unit_boundaries
+    //
+    //  Verify bytes stay exact below 1000, one decimal is kept below 100 of
+    //  a unit, and values that would round to 1000 move to the next unit.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn compact_size_rounds_at_unit_boundaries () {
+        assert_eq! (format_compact_size (0),              "    0");
+        assert_eq! (format_compact_size (999),            "  999");
+        assert_eq! (format_compact_size (1000),           " 1.0K");
+        assert_eq! (format_compact_size (1229),           " 1.2K");
+        assert_eq! (format_compact_size (102_348),        "99.9K");
+        assert_eq! (format_compact_size (102_349),        " 100K");
+        assert_eq! (format_compact_size (1_023_487),      " 999K");
+        assert_eq! (format_compact_size (1_023_488),      " 1.0M");
+        assert_eq! (format_compact_size (13_207_024_435), "12.3G");
+        assert_eq! (format_compact_size (u64::MAX),       "16.0E");
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  compact_size_always_fits_width
+    //
+    //  Verify every power of two, every power of ten, and the values
+    //  either side of each stay within COMPACT_SIZE_WIDTH characters.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn compact_size_always_fits_width () {
+        let powers_of_two = (0..64).map (|shift| 1u64 << shift);
+        let powers_of_ten = (0..20).map (|exp| 10u64.pow (exp));
+
+        for base in powers_of_two.chain (powers_of_ten).chain ([u64::MAX]) {
+            for value in [base.saturating_sub (1), base, base.saturating_add (1)] {
+                let text = format_compact_size (value);
+                assert_eq! (text.len(), COMPACT_SIZE_WIDTH, "{} -> '{}'", value, text);
+            }
+        }
+    }
 }
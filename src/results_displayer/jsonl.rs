@@ -0,0 +1,338 @@
+// jsonl.rs — JSON Lines displayer for streaming pipelines (--Jsonl)
+//
+// Emits one self-contained JSON object per entry, one per line, with no
+// colors, headers, or summaries.  Unlike a single JSON array, nothing has
+// to be buffered until the end: each directory's lines are written and
+// flushed as soon as the lister hands it over (in the same stable order
+// as the other displayers, multi-threaded or not), so downstream tools
+// can start on the first directory while the rest is still enumerating.
+
+use std::sync::Arc;
+
+use crate::command_line::CommandLine;
+use crate::config::json_escape;
+use crate::console::Console;
+use crate::directory_info::DirectoryInfo;
+use crate::drive_info::DriveInfo;
+use crate::file_info::{build_attribute_display_string, FileInfo};
+use crate::listing_totals::ListingTotals;
+
+use super::normal::get_time_field_for_display;
+use super::tsv::format_local_timestamp;
+use super::{DirectoryLevel, ResultsDisplayer};
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// JSON Lines displayer — one JSON object per entry.
+pub struct JsonlDisplayer {
+    console: Console,
+    cmd:     Arc<CommandLine>,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl JsonlDisplayer
+//
+//  JSON Lines displayer construction and console access.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl JsonlDisplayer {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  new
+    //
+    //  Create a new JsonlDisplayer.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn new(console: Console, cmd: Arc<CommandLine>) -> Self {
+        JsonlDisplayer { console, cmd }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  into_console
+    //
+    //  Consume the displayer and return the Console.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn into_console(self) -> Console {
+        self.console
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  console_mut
+    //
+    //  Get a mutable reference to the console.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn console_mut(&mut self) -> &mut Console {
+        &mut self.console
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl ResultsDisplayer for JsonlDisplayer
+//
+//  One JSON object per entry; no recursive summary.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl ResultsDisplayer for JsonlDisplayer {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  display_results
+    //
+    //  Write one JSON line per entry, then flush so the directory reaches
+    //  the pipe before the next one is listed.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn display_results(&mut self, _drive_info: &DriveInfo, dir_info: &DirectoryInfo, _level: DirectoryLevel) {
+        for fi in &dir_info.matches {
+            let path = dir_info.dir_path.join (&fi.file_name).to_string_lossy().into_owned();
+            let line = format_jsonl_line (&path, fi, &self.cmd);
+            self.console.write_raw (&line);
+            self.console.write_raw ("\n");
+        }
+
+        let _ = self.console.flush();
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  display_recursive_summary
+    //
+    //  JSON Lines mode doesn't display a recursive summary.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn display_recursive_summary(&mut self, _dir_info: &DirectoryInfo, _totals: &ListingTotals) {
+        // No summary in JSON Lines mode
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  format_jsonl_line
+//
+//  Build one JSON object (no trailing newline) with the entry's full path,
+//  name, size in bytes (0 for directories), /T time as a local ISO
+//  timestamp, the 9-char attribute string, and a directory flag.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn format_jsonl_line(path: &str, fi: &FileInfo, cmd: &CommandLine) -> String {
+    let size  = if fi.is_directory() { 0 } else { fi.displayed_size() };
+    let time  = format_local_timestamp (get_time_field_for_display (fi, cmd.time_field));
+    let attrs = build_attribute_display_string (fi.file_attributes);
+
+    format! (
+        "{{\"path\":\"{}\",\"name\":\"{}\",\"size\":{},\"time\":\"{}\",\"attributes\":\"{}\",\"directory\":{}}}",
+        json_escape (path),
+        json_escape (&fi.file_name.to_string_lossy()),
+        size,
+        time,
+        json_escape (&attrs),
+        fi.is_directory(),
+    )
+}
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::iter::Peekable;
+    use std::str::Chars;
+
+    use crate::file_info::{FILE_ATTRIBUTE_ARCHIVE, FILE_ATTRIBUTE_DIRECTORY};
+    use crate::test_support::make_file;
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  skip_json_value
+    //
+    //  Minimal JSON validator: consume one value (object, array, string,
+    //  number, true/false/null) or return None if it is malformed.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn skip_json_value(it: &mut Peekable<Chars>) -> Option<()> {
+        match *it.peek()? {
+            '{' | '[' => {
+                let close = if it.next()? == '{' { '}' } else { ']' };
+                if it.peek() == Some (&close) {
+                    it.next();
+                    return Some (());
+                }
+                loop {
+                    if close == '}' {
+                        if it.peek() != Some (&'"') {
+                            return None;
+                        }
+                        skip_json_value (it)?;
+                        if it.next()? != ':' {
+                            return None;
+                        }
+                    }
+                    skip_json_value (it)?;
+                    match it.next()? {
+                        ',' => continue,
+                        c if c == close => return Some (()),
+                        _ => return None,
+                    }
+                }
+            }
+            '"' => {
+                it.next();
+                loop {
+                    match it.next()? {
+                        '"'  => return Some (()),
+                        '\\' => match it.next()? {
+                            '"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't' => {}
+                            'u' => for _ in 0..4 { it.next().filter (char::is_ascii_hexdigit)?; },
+                            _   => return None,
+                        },
+                        c if (c as u32) < 0x20 => return None,
+                        _ => {}
+                    }
+                }
+            }
+            c if c == '-' || c.is_ascii_digit() => {
+                it.next();
+                while it.peek().is_some_and (|c| c.is_ascii_digit() || matches! (c, '.' | 'e' | 'E' | '+' | '-')) {
+                    it.next();
+                }
+                Some (())
+            }
+            _ => {
+                let word: String = std::iter::from_fn (|| it.next_if (char::is_ascii_alphabetic)).collect();
+                matches! (word.as_str(), "true" | "false" | "null").then_some (())
+            }
+        }
+    }
+
+    fn is_valid_json(line: &str) -> bool {
+        let mut it = line.chars().peekable();
+        skip_json_value (&mut it).is_some() && it.next().is_none()
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  jsonl_lines_are_independently_valid_json
+    //
+    //  Verify every emitted line parses on its own as a JSON object, even
+    //  for names with quotes, backslashes, control characters, and
+    //  non-ASCII text, and that the output has no color codes.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn jsonl_lines_are_independently_valid_json() {
+        let mut cfg = crate::config::Config::new();
+        cfg.initialize (0x07);
+        let cfg = Arc::new (cfg);
+
+        let mut console = Console::new_for_testing (Arc::clone (&cfg));
+        console.set_color_mode (crate::command_line::ColorMode::Never);
+
+        let cmd = Arc::new (CommandLine::parse_from (["--Jsonl", "/S"]).unwrap());
+        let mut di = DirectoryInfo::new (std::path::PathBuf::from ("C:\\data\\\"quoted\""), "*".to_string());
+        di.matches.push (make_file ("report.txt",        FILE_ATTRIBUTE_ARCHIVE,   1_234));
+        di.matches.push (make_file ("src",               FILE_ATTRIBUTE_DIRECTORY, 4_096));
+        di.matches.push (make_file ("tab\there\u{1}.md", FILE_ATTRIBUTE_ARCHIVE,   7));
+        di.matches.push (make_file ("résumé \\ 日本.doc", FILE_ATTRIBUTE_ARCHIVE,   42));
+
+        let drive_info = DriveInfo {
+            unc_path:         std::path::PathBuf::new(),
+            root_path:        std::path::PathBuf::from ("C:\\"),
+            volume_name:      String::new(),
+            file_system_name: "NTFS".to_string(),
+            volume_type:      crate::drive_info::DRIVE_FIXED,
+            is_unc_path:      false,
+            remote_name:      String::new(),
+            volume_serial:    None,
+            total_bytes:      None,
+        };
+
+        let mut displayer = JsonlDisplayer::new (console, cmd);
+        displayer.display_results (&drive_info, &di, DirectoryLevel::Initial);
+        let output = displayer.console_mut().take_test_buffer();
+
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq! (lines.len(), 4, "{output}");
+        for line in &lines {
+            assert! (line.starts_with ('{') && is_valid_json (line), "invalid JSON line: {line}");
+        }
+
+        assert! (!output.contains ('\x1b'));
+        assert! (lines[0].contains ("\"path\":\"C:\\\\data\\\\\\\"quoted\\\"\\\\report.txt\""), "{}", lines[0]);
+        assert! (lines[0].contains ("\"size\":1234"), "{}", lines[0]);
+        assert! (lines[1].contains ("\"size\":0") && lines[1].ends_with ("\"directory\":true}"), "{}", lines[1]);
+        assert! (lines[2].contains ("\"name\":\"tab\\there\\u0001.md\""), "{}", lines[2]);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  json_validator_rejects_malformed_lines
+    //
+    //  Verify the test validator itself catches broken JSON, so the check
+    //  above can't pass vacuously.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn json_validator_rejects_malformed_lines() {
+        assert! (is_valid_json ("{\"a\":1,\"b\":[true,null,\"x\"]}"));
+        assert! (!is_valid_json ("{\"a\":\"tab\there\"}"));
+        assert! (!is_valid_json ("{\"a\":\"C:\\data\"}"));
+        assert! (!is_valid_json ("{\"a\":1}{\"b\":2}"));
+        assert! (!is_valid_json ("{\"a\":1"));
+    }
+}
@@ -14,12 +14,20 @@
 //   normal.rs — NormalDisplayer + normal-specific display routines
 //   wide.rs   — WideDisplayer + column-major wide display routines
 //   bare.rs   — BareDisplayer + bare (filename-only) display
+//   tsv.rs    — TsvDisplayer + tab-separated fields for scripting
+//   jsonl.rs  — JsonlDisplayer + one JSON object per entry (JSON Lines)
+//   brief.rs  — BriefDisplayer + one summary line per directory (/Brief)
+//   template.rs — TemplateDisplayer + one /Fmt template line per entry
 
 mod bare;
+mod brief;
 mod column_layout;
 mod common;
+mod jsonl;
 mod normal;
+mod template;
 mod tree;
+mod tsv;
 mod wide;
 
 use std::sync::Arc;
@@ -32,9 +40,13 @@
 use crate::listing_totals::ListingTotals;
 
 pub use self::bare::BareDisplayer;
-pub use self::common::format_number_with_separators;
+pub use self::brief::BriefDisplayer;
+pub use self::common::{format_abbreviated_size, format_number_with_separators};
+pub use self::jsonl::JsonlDisplayer;
 pub use self::normal::NormalDisplayer;
+pub use self::template::TemplateDisplayer;
 pub use self::tree::TreeDisplayer;
+pub use self::tsv::TsvDisplayer;
 pub use self::wide::WideDisplayer;
 
 
@@ -84,7 +96,7 @@ pub trait ResultsDisplayer {
 
 ////////////////////////////////////////////////////////////////////////////////
 
-/// Polymorphic displayer wrapping Normal, Wide, Bare, or Tree variants.
+/// Polymorphic displayer wrapping Normal, Wide, Bare, Tree, Tsv, Jsonl, Brief, or Template variants.
 ///
 /// Provides `into_console()` and `console_mut()` without trait object issues.
 pub enum Displayer {
@@ -92,6 +104,10 @@ pub enum Displayer {
     Wide(WideDisplayer),
     Bare(BareDisplayer),
     Tree(TreeDisplayer),
+    Tsv(TsvDisplayer),
+    Jsonl(JsonlDisplayer),
+    Brief(BriefDisplayer),
+    Template(TemplateDisplayer),
 }
 
 
@@ -113,17 +129,26 @@ impl Displayer {
     //  new
     //
     //  Create the appropriate displayer based on command-line switches.
-    //  Priority: bare > wide > normal (matching TCDir).
+    //  Priority: bare > wide > normal (matching TCDir); --Tsv and --Jsonl
+    //  override all three, and /Brief or /Fmt replaces the normal listing.
     //
     ////////////////////////////////////////////////////////////////////////////
 
     pub fn new(console: Console, cmd: Arc<CommandLine>, config: Arc<Config>, icons_active: bool) -> Self {
-        if cmd.tree.unwrap_or (false) {
+        if cmd.tsv_listing {
+            Displayer::Tsv(TsvDisplayer::new(console, cmd))
+        } else if cmd.jsonl_listing {
+            Displayer::Jsonl(JsonlDisplayer::new(console, cmd))
+        } else if let Some (template) = cmd.format_template.clone() {
+            Displayer::Template(TemplateDisplayer::new(console, cmd, config, template))
+        } else if cmd.tree.unwrap_or (false) {
             Displayer::Tree(TreeDisplayer::new(console, cmd, config, icons_active))
         } else if cmd.bare_listing {
             Displayer::Bare(BareDisplayer::new(console, cmd, config, icons_active))
         } else if cmd.wide_listing {
             Displayer::Wide(WideDisplayer::new(console, cmd, config, icons_active))
+        } else if cmd.brief {
+            Displayer::Brief(BriefDisplayer::new(console, config))
         } else {
             Displayer::Normal(NormalDisplayer::new(console, cmd, config, icons_active))
         }
@@ -147,6 +172,10 @@ pub fn into_console(self) -> Console {
             Displayer::Wide(d)   => d.into_console(),
             Displayer::Bare(d)   => d.into_console(),
             Displayer::Tree(d)   => d.into_console(),
+            Displayer::Tsv(d)    => d.into_console(),
+            Displayer::Jsonl(d)  => d.into_console(),
+            Displayer::Brief(d)  => d.into_console(),
+            Displayer::Template(d) => d.into_console(),
         }
     }
 
@@ -168,6 +197,32 @@ pub fn console_mut(&mut self) -> &mut Console {
             Displayer::Wide(d)   => d.console_mut(),
             Displayer::Bare(d)   => d.console_mut(),
             Displayer::Tree(d)   => d.console_mut(),
+            Displayer::Tsv(d)    => d.console_mut(),
+            Displayer::Jsonl(d)  => d.console_mut(),
+            Displayer::Brief(d)  => d.console_mut(),
+            Displayer::Template(d) => d.console_mut(),
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  skip_drive_header
+    //
+    //  Omit the drive header because an earlier group on the same volume
+    //  printed it.  Only Normal, Wide, and Tree show one.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn skip_drive_header(&mut self) {
+        match self {
+            Displayer::Normal(d) => d.skip_drive_header(),
+            Displayer::Wide(d)   => d.skip_drive_header(),
+            Displayer::Tree(d)   => d.skip_drive_header(),
+            Displayer::Bare(_) | Displayer::Tsv(_) | Displayer::Jsonl(_) | Displayer::Brief(_) | Displayer::Template(_) => {}
         }
     }
 }
@@ -180,7 +235,8 @@ pub fn console_mut(&mut self) -> &mut Console {
 //
 //  impl ResultsDisplayer for Displayer
 //
-//  Dispatch to the underlying Normal, Wide, or Bare displayer variant.
+//  Dispatch to the underlying Normal, Wide, Bare, Tree, Tsv, Jsonl, Brief,
+//  or Template variant.
 //
 ////////////////////////////////////////////////////////////////////////////////
 
@@ -200,6 +256,10 @@ fn display_results(&mut self, drive_info: &DriveInfo, dir_info: &DirectoryInfo,
             Displayer::Wide(d)   => d.display_results(drive_info, dir_info, level),
             Displayer::Bare(d)   => d.display_results(drive_info, dir_info, level),
             Displayer::Tree(d)   => d.display_results(drive_info, dir_info, level),
+            Displayer::Tsv(d)    => d.display_results(drive_info, dir_info, level),
+            Displayer::Jsonl(d)  => d.display_results(drive_info, dir_info, level),
+            Displayer::Brief(d)  => d.display_results(drive_info, dir_info, level),
+            Displayer::Template(d) => d.display_results(drive_info, dir_info, level),
         }
     }
 
@@ -221,6 +281,10 @@ fn display_recursive_summary(&mut self, dir_info: &DirectoryInfo, totals: &Listi
             Displayer::Wide(d)   => d.display_recursive_summary(dir_info, totals),
             Displayer::Bare(d)   => d.display_recursive_summary(dir_info, totals),
             Displayer::Tree(d)   => d.display_recursive_summary(dir_info, totals),
+            Displayer::Tsv(d)    => d.display_recursive_summary(dir_info, totals),
+            Displayer::Jsonl(d)  => d.display_recursive_summary(dir_info, totals),
+            Displayer::Brief(d)  => d.display_recursive_summary(dir_info, totals),
+            Displayer::Template(d) => d.display_recursive_summary(dir_info, totals),
         }
     }
 }
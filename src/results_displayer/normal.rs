@@ -2,30 +2,52 @@
 //
 // Port of: CResultsDisplayerNormal + CResultsDisplayerWithHeaderAndFooter
 
+use std::collections::BTreeMap;
+use std::path::Path;
 use std::sync::Arc;
 
+use crate::age_tier::{AgeTierThresholds, TIER_COLUMN_WIDTH};
 use crate::cloud_status;
-use crate::command_line::{CommandLine, SizeFormat, TimeField};
+use crate::color::BC_MASK;
+use crate::command_line::{CommandLine, NameColumn, SizeFormat, SortOrder, SummaryVerbosity, TimeField};
 use crate::config::{Attribute, Config};
 use crate::console::Console;
+use crate::date_format;
 use crate::directory_info::DirectoryInfo;
 use crate::drive_info::DriveInfo;
-use crate::file_info::{FileInfo, FILE_ATTRIBUTE_MAP};
+use crate::encoding::{TextEncoding, ENCODING_COLUMN_WIDTH};
+use crate::file_comparator;
+use crate::file_id::{self, FILE_ID_COLUMN_WIDTH};
+use crate::file_info::{AttributeColumn, FileInfo, FILE_ATTRIBUTE_DIRECTORY};
+use crate::file_type::{format_file_type, FILE_TYPE_COLUMN_WIDTH};
 use crate::listing_totals::ListingTotals;
+use crate::on_disk_size::ON_DISK_MARKER;
 use crate::owner;
 use crate::path_ellipsis;
+use crate::since_last::{Clock, SystemClock};
+use crate::version_info::VERSION_COLUMN_WIDTH;
 
 use super::common::{
+    cloud_column_shown,
+    cloud_column_width,
+    COMPACT_SIZE_WIDTH,
+    display_breadcrumb,
     display_cloud_status_symbol,
     display_directory_summary,
     display_drive_header,
     display_empty_directory_message,
+    display_filtered_count,
     display_listing_summary,
     display_path_header,
+    display_summary_details,
     display_volume_footer,
     format_abbreviated_size,
+    format_compact_size,
+    format_name_suffix,
     format_number_with_separators,
     get_string_length_of_max_file_size,
+    highlight_text_attr,
+    DIRECTORY_MARKER,
 };
 use super::{DirectoryLevel, ResultsDisplayer};
 
@@ -33,6 +55,19 @@
 
 
 
+/// Width of the --Delta column, including its trailing space ("+364d ").
+pub(super) const DELTA_COLUMN_WIDTH: usize = 6;
+
+/// Width of the /Pct column, including its leading space (" 100.0%").
+pub(super) const PERCENT_COLUMN_WIDTH: usize = 7;
+
+/// --Columns header label for the owner column (the column is at least this wide).
+const OWNER_HEADER: &str = "Owner";
+
+
+
+
+
 ////////////////////////////////////////////////////////////////////////////////
 
 /// Standard format displayer — date, time, attributes, size, filename.
@@ -43,6 +78,10 @@ pub struct NormalDisplayer {
     cmd:          Arc<CommandLine>,
     config:       Arc<Config>,
     icons_active: bool,
+    /// Widest name column so far, for /NameCol auto alignment
+    name_column_floor: usize,
+    /// An earlier group on the same volume already printed the drive header
+    skip_drive_header: bool,
 }
 
 
@@ -68,7 +107,7 @@ impl NormalDisplayer {
     ////////////////////////////////////////////////////////////////////////////
 
     pub fn new(console: Console, cmd: Arc<CommandLine>, config: Arc<Config>, icons_active: bool) -> Self {
-        NormalDisplayer { console, cmd, config, icons_active }
+        NormalDisplayer { console, cmd, config, icons_active, name_column_floor: 0, skip_drive_header: false }
     }
 
 
@@ -102,6 +141,23 @@ pub fn into_console(self) -> Console {
     pub fn console_mut(&mut self) -> &mut Console {
         &mut self.console
     }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  skip_drive_header
+    //
+    //  Omit the drive header for this group's listing: an earlier group
+    //  on the same volume already printed it.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn skip_drive_header(&mut self) {
+        self.skip_drive_header = true;
+    }
 }
 
 
@@ -134,20 +190,36 @@ fn display_results(&mut self, drive_info: &DriveInfo, dir_info: &DirectoryInfo,
         }
 
         if level == DirectoryLevel::Initial {
-            // Show drive header only for initial directory
-            display_drive_header(&mut self.console, drive_info);
+            // Show drive header only for initial directory (once per volume)
+            if !self.skip_drive_header {
+                display_drive_header(&mut self.console, drive_info);
+            }
+
+            if let Some(levels) = self.cmd.up_levels {
+                display_breadcrumb(&mut self.console, &dir_info.dir_path, levels);
+            }
         }
 
         display_path_header(&mut self.console, dir_info);
 
         if dir_info.matches.is_empty() {
-            display_empty_directory_message(&mut self.console, dir_info);
+            if self.cmd.shows_empty_message() {
+                display_empty_directory_message(&mut self.console, dir_info);
+            }
         } else {
-            display_file_results(&mut self.console, &self.cmd, &self.config, dir_info, self.icons_active);
+            display_file_results(&mut self.console, &self.cmd, &self.config, dir_info, self.icons_active, &mut self.name_column_floor);
             display_directory_summary(&mut self.console, dir_info);
 
+            if self.cmd.show_filtered {
+                display_filtered_count(&mut self.console, dir_info);
+            }
+
+            if self.cmd.verbosity == SummaryVerbosity::Detailed {
+                display_summary_details(&mut self.console, &self.cmd, dir_info);
+            }
+
             // Only show volume footer if we're not doing recursive listing
-            if !self.cmd.recurse {
+            if !self.cmd.recurse && self.cmd.verbosity != SummaryVerbosity::TotalsOnly {
                 display_volume_footer(&mut self.console, dir_info);
             }
         }
@@ -173,7 +245,7 @@ fn display_results(&mut self, drive_info: &DriveInfo, dir_info: &DirectoryInfo,
     ////////////////////////////////////////////////////////////////////////////
 
     fn display_recursive_summary(&mut self, dir_info: &DirectoryInfo, totals: &ListingTotals) {
-        display_listing_summary(&mut self.console, dir_info, totals);
+        display_listing_summary(&mut self.console, dir_info, totals, self.cmd.verbosity);
     }
 }
 
@@ -196,101 +268,417 @@ fn display_file_results(
     config: &Config,
     dir_info: &DirectoryInfo,
     icons_active: bool,
+    name_column_floor: &mut usize,
 ) {
     let max_size_width = get_string_length_of_max_file_size(dir_info.largest_file_size);
-    let in_sync_root = cloud_status::is_under_sync_root(dir_info.dir_path.as_os_str());
+
+    // Only query the sync root when the column can be shown (/NoCloud skips detection)
+    let in_sync_root = !cmd.no_cloud && cloud_status::is_under_sync_root(dir_info.dir_path.as_os_str());
+    let show_cloud   = cloud_column_shown (cmd, in_sync_root);
 
     // Collect file owners if --owner is enabled (two-pass: first collect, then display)
-    let (owners, max_owner_len) = if cmd.show_owner {
-        owner::get_file_owners(dir_info)
+    let (owners, mut max_owner_len) = if cmd.show_owner {
+        owner::get_file_owners(dir_info, cmd.owner_sid)
     } else {
         (Vec::new(), 0)
     };
 
-    for (idx, file_info) in dir_info.matches.iter().enumerate() {
-        let style = config.get_display_style_for_file (file_info);
-        let text_attr = style.text_attr;
-
-        // Date and time
-        let time_value = get_time_field_for_display(file_info, cmd.time_field);
-        display_date_and_time(console, time_value);
+    // /FileId, /Encoding, /Tier, /VerInfo, and /Desc sit beside /Hash, so all widen the same gap before the name
+    let mut hash_width = hash_column_width (cmd)
+        + file_id_column_width (cmd)
+        + encoding_column_width (cmd)
+        + tier_column_width (cmd)
+        + version_column_width (cmd)
+        + file_type_column_width (cmd);
+
+    // /Tier ages are measured from one "now" per directory
+    let tier_now = if cmd.show_tier { SystemClock.now() } else { 0 };
+    let age_tiers = config.age_tiers.unwrap_or_default();
+    let date_time_width = date_time_column_width (cmd);
+
+    // /T:both ages are measured from one "now" per directory
+    let age_now = cmd.time_with_age.then (|| SystemClock.now());
+
+    // Under --Columns the owner column widens to fit its label
+    if cmd.columns && cmd.show_owner {
+        max_owner_len = max_owner_len.max (OWNER_HEADER.len());
+    }
 
-        // Attributes
-        display_attributes(console, config, file_info.file_attributes);
+    // /NameCol pads that same gap so names start at a common column
+    let natural_name_column = compute_name_column (
+        date_time_width,
+        max_size_width,
+        cmd.resolved_size_format(),
+        icons_active,
+        show_cloud,
+        cmd.show_delta,
+        cmd.show_percent,
+        cmd.attribute_column(),
+        #[cfg(debug_assertions)]
+        cmd.debug,
+        cmd.show_owner,
+        max_owner_len,
+        hash_width,
+        0, // tree_prefix_width: 0 for normal mode
+    );
+    let name_column  = align_name_column (cmd.name_column, natural_name_column, name_column_floor);
+    let name_padding = name_column - natural_name_column;
+    hash_width += name_padding;
+
+    // Column header row (if --Columns)
+    if cmd.columns {
+        let header = build_column_header (cmd, date_time_width, max_size_width, show_cloud, icons_active, max_owner_len, name_padding);
+        console.puts (Attribute::Information, &header);
+    }
 
-        // File size or <DIR>
-        display_file_size (console, file_info, max_size_width, cmd.resolved_size_format());
+    // Sections: one per extension under /Group:ext, else a single unlabeled one
+    let groups = if cmd.group_by_ext {
+        let mut groups = group_by_extension (&dir_info.matches);
+        apply_group_sorts (&mut groups, &dir_info.matches, cmd, config);
+        groups
+    } else {
+        vec![ExtensionGroup { label: String::new(), is_directory: false, indices: (0..dir_info.matches.len()).collect() }]
+    };
 
-        // Cloud status symbol
-        let cloud = cloud_status::get_cloud_status(file_info.file_attributes, in_sync_root);
-        display_cloud_status_symbol(console, config, cloud, icons_active);
+    // /Limit caps the rows shown; the totals still count every entry
+    let limit     = cmd.entry_limit.unwrap_or (usize::MAX);
+    let mut shown = 0usize;
 
-        // Debug attribute display (debug builds only, gated by --debug)
-        #[cfg(debug_assertions)]
-        if cmd.debug {
-            display_raw_attributes(console, config, file_info);
+    'groups: for (group_idx, group) in groups.iter().enumerate() {
+        if shown == limit {
+            break;
         }
 
-        // Owner column (if --owner)
-        if let (true, Some(owner_str)) = (cmd.show_owner, owners.get(idx)) {
-            display_file_owner(console, config, owner_str, max_owner_len);
+        if cmd.group_by_ext {
+            if group_idx > 0 {
+                console.puts (Attribute::Default, "");
+            }
+            display_group_heading (console, config, dir_info, group);
         }
 
-        // Icon glyph (when icons are active and not suppressed)
-        if icons_active {
-            if let Some(icon) = style.icon_code_point {
-                if !style.icon_suppressed {
-                    console.writef (text_attr, format_args! ("{} ", icon));
+        for &idx in &group.indices {
+            if shown == limit {
+                break 'groups;
+            }
+            shown += 1;
+
+            let file_info = &dir_info.matches[idx];
+            let style = config.get_display_style_for_file (file_info);
+            let text_attr = highlight_text_attr (config, cmd, file_info, style.text_attr);
+
+            // Date and time
+            let time_value = get_time_field_for_display(file_info, cmd.time_field);
+            display_date_and_time(console, time_value, cmd.date_format.as_deref(), age_now);
+
+            // Creation → last-write delta (if --Delta)
+            if cmd.show_delta {
+                display_time_delta (console, config, file_info);
+            }
+
+            // Attributes
+            display_attributes(console, config, file_info.file_attributes, cmd.attribute_column());
+
+            // File size or <DIR>
+            display_file_size (console, file_info, max_size_width, cmd.resolved_size_format());
+
+            // Share of the directory's bytes (if /Pct); directories stay blank
+            if cmd.show_percent {
+                display_size_percentage (console, file_info, dir_info.bytes_used);
+            }
+
+            // Cloud status symbol (omitted entirely under /NoCloud, or /NoCloud:auto outside a sync root)
+            if show_cloud {
+                let cloud = cloud_status::get_cloud_status(file_info.file_attributes, in_sync_root);
+                display_cloud_status_symbol(console, config, cloud, icons_active);
+            }
+
+            // Debug attribute display (debug builds only, gated by --debug)
+            #[cfg(debug_assertions)]
+            if cmd.debug {
+                display_raw_attributes(console, config, file_info);
+            }
+
+            // Owner column (if --owner)
+            if let (true, Some(owner_str)) = (cmd.show_owner, owners.get(idx)) {
+                display_file_owner(console, config, owner_str, max_owner_len);
+            }
+
+            // Hash column (if --Hash)
+            if let Some (algorithm) = cmd.hash_algorithm {
+                display_file_hash (console, config, file_info.hash.as_deref(), algorithm.hex_len());
+            }
+
+            // File ID column (if --FileId)
+            if cmd.show_file_id {
+                display_file_id (console, config, file_info.file_id);
+            }
+
+            // Encoding column (if --Encoding)
+            if cmd.show_encoding {
+                display_file_encoding (console, config, file_info.encoding);
+            }
+
+            // Age tier column (if --Tier)
+            if cmd.show_tier {
+                display_age_tier (console, config, &age_tiers, file_info.creation_time, tier_now);
+            }
+
+            // Version column (if --VerInfo)
+            if cmd.show_version {
+                display_file_version (console, config, file_info.version.as_deref());
+            }
+
+            // File type column (if --Desc)
+            if cmd.show_file_type {
+                display_file_type (console, config, file_info.type_name.as_deref());
+            }
+
+            // Padding out to the /NameCol start
+            if name_padding > 0 {
+                console.writef (config.attributes[Attribute::Default as usize], format_args! ("{:width$}", "", width = name_padding));
+            }
+
+            // Icon glyph (when icons are active and not suppressed)
+            if icons_active {
+                if let Some(icon) = style.icon_code_point {
+                    if !style.icon_suppressed {
+                        console.writef (text_attr, format_args! ("{} ", icon));
+                    } else {
+                        console.printf (text_attr, "  ");
+                    }
                 } else {
                     console.printf (text_attr, "  ");
                 }
-            } else {
-                console.printf (text_attr, "  ");
             }
-        }
 
-        // Filename
-        let name_str = file_info.file_name.to_string_lossy();
-
-        if !file_info.reparse_target.is_empty() {
-            // Reparse point: filename → target (FR-003, FR-006, FR-007)
-            console.writef (text_attr, format_args! ("{}", name_str));
-            console.printf (config.attributes[Attribute::Information as usize], " \u{2192} ");
-
-            // Ellipsize long target paths to prevent line wrapping (spec 008)
-            if cmd.ellipsize.unwrap_or (true) {
-                let available_width = compute_available_width_for_target (
-                    console.width() as usize,
-                    max_size_width,
-                    cmd.resolved_size_format(),
-                    icons_active,
-                    #[cfg(debug_assertions)]
-                    cmd.debug,
-                    cmd.show_owner,
-                    max_owner_len,
-                    0, // tree_prefix_width: 0 for normal mode
-                    name_str.len(),
-                );
-                let ep = path_ellipsis::ellipsize_path (&file_info.reparse_target, available_width);
-                if ep.truncated {
-                    console.writef (text_attr, format_args! ("{}", ep.prefix));
-                    console.printf (config.attributes[Attribute::Default as usize], "\u{2026}");
-                    console.writef_line (text_attr, format_args! ("{}", ep.suffix));
+            // Filename (absolute path under /FullPath)
+            let mut name_str = format_display_name (&dir_info.dir_path, file_info, cmd);
+
+            // Long names continue on lines indented to the name column (/WrapNames)
+            if cmd.wrap_names {
+                name_str = display_wrapped_name_head (console, text_attr, &name_str, name_column);
+            }
+
+            if !file_info.reparse_target.is_empty() {
+                // Reparse point: filename → target (FR-003, FR-006, FR-007)
+                console.writef (text_attr, format_args! ("{}", name_str));
+                console.printf (config.attributes[Attribute::Information as usize], " \u{2192} ");
+
+                // Ellipsize long target paths to prevent line wrapping (spec 008)
+                if cmd.ellipsize.unwrap_or (true) {
+                    let available_width = compute_available_width_for_target (
+                        console.width() as usize,
+                        date_time_width,
+                        max_size_width,
+                        cmd.resolved_size_format(),
+                        icons_active,
+                        show_cloud,
+                        cmd.show_delta,
+                        cmd.show_percent,
+                        cmd.attribute_column(),
+                        #[cfg(debug_assertions)]
+                        cmd.debug,
+                        cmd.show_owner,
+                        max_owner_len,
+                        hash_width,
+                        0, // tree_prefix_width: 0 for normal mode
+                        name_str.len(),
+                    );
+                    let ep = path_ellipsis::ellipsize_path (&file_info.reparse_target, available_width);
+                    if ep.truncated {
+                        console.writef (text_attr, format_args! ("{}", ep.prefix));
+                        console.printf (config.attributes[Attribute::Default as usize], "\u{2026}");
+                        console.writef_line (text_attr, format_args! ("{}", ep.suffix));
+                    } else {
+                        console.writef_line (text_attr, format_args! ("{}", ep.prefix));
+                    }
                 } else {
-                    console.writef_line (text_attr, format_args! ("{}", ep.prefix));
+                    console.writef_line (text_attr, format_args! ("{}", file_info.reparse_target));
                 }
             } else {
-                console.writef_line (text_attr, format_args! ("{}", file_info.reparse_target));
+                // Stream count (/HasStreams) and matched spec (/ShowMask) follow the name
+                let suffix = format_name_suffix (cmd, file_info);
+
+                if suffix.is_empty() {
+                    console.writef_line (text_attr, format_args! ("{}", name_str));
+                } else {
+                    console.writef (text_attr, format_args! ("{}", name_str));
+                    console.writef_line (config.attributes[Attribute::Information as usize], format_args! ("{}", suffix));
+                }
             }
-        } else {
-            console.writef_line (text_attr, format_args! ("{}", name_str));
+
+            // Streams (if --streams and this is a file, not a directory)
+            if cmd.show_streams && !file_info.streams.is_empty() {
+                let owner_width = if cmd.show_owner { max_owner_len } else { 0 };
+                display_file_streams(console, config, file_info, date_time_width, max_size_width, owner_width, hash_width, icons_active, show_cloud, cmd.show_delta, cmd.show_percent, cmd.attribute_column());
+            }
+        }
+    }
+
+    if dir_info.matches.len() > shown {
+        console.color_puts (&format! (
+            "{{Information}}\u{2026} and {{InformationHighlight}}{}{{Information}} more",
+            format_number_with_separators ((dir_info.matches.len() - shown) as u64),
+        ));
+    }
+}
+
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// One /Group:ext section of a directory's entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct ExtensionGroup {
+    /// ".rs", "(no extension)", or "Directories"
+    pub label:        String,
+    pub is_directory: bool,
+    /// Positions in `matches`, in their sorted order
+    pub indices:      Vec<usize>,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  group_by_extension
+//
+//  Bucket sorted entries for /Group:ext: all directories first, then one
+//  group per lowercase extension in alphabetical order (files with no
+//  extension first).  Each group keeps the entries' sort order.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn group_by_extension(matches: &[FileInfo]) -> Vec<ExtensionGroup> {
+    let mut directories = Vec::new();
+    let mut by_extension: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+
+    for (idx, file_info) in matches.iter().enumerate() {
+        if file_info.is_directory() {
+            directories.push (idx);
+            continue;
+        }
+
+        let extension = Path::new (&file_info.file_name).extension()
+            .map (|ext| format! (".{}", ext.to_string_lossy()).to_lowercase())
+            .unwrap_or_default();
+
+        by_extension.entry (extension).or_default().push (idx);
+    }
+
+    let mut groups = Vec::new();
+
+    if !directories.is_empty() {
+        groups.push (ExtensionGroup { label: "Directories".into(), is_directory: true, indices: directories });
+    }
+
+    for (extension, indices) in by_extension {
+        let label = if extension.is_empty() { "(no extension)".to_string() } else { extension };
+        groups.push (ExtensionGroup { label, is_directory: false, indices });
+    }
+
+    groups
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  apply_group_sorts
+//
+//  Re-sort each /Group:ext section whose extension has a file-type
+//  category (images by date, documents by name, binaries by size, or the
+//  RCDIR groupsort: override).  An explicit file sort (/O, O=, /OFile)
+//  keeps the listing-wide order instead.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn apply_group_sorts(groups: &mut [ExtensionGroup], matches: &[FileInfo], cmd: &CommandLine, config: &Config) {
+    if cmd.sort_order != SortOrder::Default || cmd.file_sort.is_some() {
+        return;
+    }
+
+    for group in groups.iter_mut().filter (|g| !g.is_directory) {
+        if let Some (key) = file_comparator::group_sort_key (config, &group.label) {
+            file_comparator::sort_group (matches, &mut group.indices, key, cmd);
         }
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  display_group_heading
+//
+//  Print a /Group:ext subheading with its entry count.  An extension
+//  with a grouplabel: entry shows that label, in its color or the
+//  SeparatorLine color; otherwise the heading is the raw extension,
+//  colored like the group's entries (the directory color, or the
+//  extension's color).
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn display_group_heading(console: &mut Console, config: &Config, dir_info: &DirectoryInfo, group: &ExtensionGroup) {
+    let Some (&first) = group.indices.first() else {
+        return;
+    };
 
-        // Streams (if --streams and this is a file, not a directory)
-        if cmd.show_streams && !file_info.streams.is_empty() {
-            let owner_width = if cmd.show_owner { max_owner_len } else { 0 };
-            display_file_streams(console, config, file_info, max_size_width, owner_width, icons_active);
+    let custom = if group.is_directory { None } else { config.group_labels.get (&group.label) };
+
+    let (label, color) = match custom {
+        Some ((text, color)) => {
+            let default_attr = config.attributes[Attribute::Default as usize];
+            let mut attr     = color.unwrap_or (config.attributes[Attribute::SeparatorLine as usize]);
+            if attr & BC_MASK == 0 {
+                attr |= default_attr & BC_MASK;
+            }
+            (text.as_str(), attr)
         }
+        None => {
+            let attributes = if group.is_directory { FILE_ATTRIBUTE_DIRECTORY } else { 0 };
+            (group.label.as_str(), config.get_text_attr_for_file (attributes, &dir_info.matches[first].file_name))
+        }
+    };
+
+    console.writef_line (color, format_args! ("{} ({})", label, group.indices.len()));
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  format_display_name
+//
+//  The name shown in the filename column: the bare file name, or with
+//  /FullPath the directory path joined with it, for pasting into other
+//  tools.  /Mark prefixes directories with DIRECTORY_MARKER.  Only this
+//  field grows; the metadata columns are unchanged.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn format_display_name(dir_path: &Path, file_info: &FileInfo, cmd: &CommandLine) -> String {
+    let name = if cmd.full_path {
+        dir_path.join (&file_info.file_name).to_string_lossy().into_owned()
+    } else {
+        file_info.file_name.to_string_lossy().into_owned()
+    };
+
+    if cmd.mark_dirs && file_info.is_directory() {
+        format! ("{}{}", DIRECTORY_MARKER, name)
+    } else {
+        name
     }
 }
 
@@ -323,13 +711,42 @@ pub(super) fn get_time_field_for_display(fi: &FileInfo, time_field: TimeField) -
 //
 //  display_date_and_time
 //
+//  Display the date/time column.  With `age_now` (/T:both) the absolute
+//  date and time are followed by the age relative to it, padded to
+//  RELATIVE_AGE_WIDTH so the columns after it stay aligned.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn display_date_and_time(console: &mut Console, filetime_u64: u64, date_format: Option<&str>, age_now: Option<u64>) {
+    display_absolute_date_and_time (console, filetime_u64, date_format);
+
+    if let Some (now) = age_now {
+        let age = date_format::format_relative_age (filetime_u64, now);
+        console.writef_attr (Attribute::Time, format_args! ("{:<width$} ", age, width = date_format::RELATIVE_AGE_WIDTH - 1));
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  display_absolute_date_and_time
+//
 //  Display date and time from a FILETIME (as u64).
-//  Uses Win32 APIs for locale-aware formatting.
+//  Uses Win32 APIs for locale-aware formatting.  A /DateFmt pattern
+//  replaces the fixed MM/dd/yyyy hh:mm tt layout.
 //  Port of: CResultsDisplayerNormal::DisplayResultsNormalDateAndTime
 //
 ////////////////////////////////////////////////////////////////////////////////
 
-pub(super) fn display_date_and_time(console: &mut Console, filetime_u64: u64) {
+fn display_absolute_date_and_time(console: &mut Console, filetime_u64: u64, date_format: Option<&str>) {
+    if let Some (pattern) = date_format {
+        display_custom_date_and_time (console, filetime_u64, pattern);
+        return;
+    }
+
     let ft = windows::Win32::Foundation::FILETIME {
         dwLowDateTime:  (filetime_u64 & 0xFFFF_FFFF) as u32,
         dwHighDateTime: ((filetime_u64 >> 32) & 0xFFFF_FFFF) as u32,
@@ -401,26 +818,151 @@ pub(super) fn display_date_and_time(console: &mut Console, filetime_u64: u64) {
 
 
 
+////////////////////////////////////////////////////////////////////////////////
+//
+//  display_custom_date_and_time
+//
+//  Display date and time using a /DateFmt pattern.  A time that can't be
+//  converted shows as '?' padded to the column width.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn display_custom_date_and_time(console: &mut Console, filetime_u64: u64, pattern: &str) {
+    let formatted = date_format::filetime_to_local (filetime_u64)
+        .and_then (|st| date_format::format_system_time (&st, pattern));
+
+    match formatted {
+        Some ((date_str, time_str)) => {
+            console.printf_attr (Attribute::Date, &date_str);
+            console.printf_attr (Attribute::Time, &time_str);
+            console.printf_attr (Attribute::Default, " ");
+        }
+        None => {
+            let width = date_format::column_width (Some (pattern)) - 1;
+            console.writef_attr (Attribute::Date, format_args! ("{:<width$} ", "?", width = width));
+        }
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  format_time_delta
+//
+//  Format last-write minus creation time as a compact duration in the
+//  largest whole unit: "+45s", "+12m", "+5h", "+3d", "+2y".  Zero or
+//  negative deltas (written before or at creation) render as "0".
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn format_time_delta(creation_time: u64, last_write_time: u64) -> String {
+    const TICKS_PER_SECOND: u64 = 10_000_000;   // FILETIME is in 100 ns units
+    const MINUTE: u64 = 60;
+    const HOUR:   u64 = 60 * MINUTE;
+    const DAY:    u64 = 24 * HOUR;
+    const YEAR:   u64 = 365 * DAY;
+
+    let seconds = last_write_time.saturating_sub (creation_time) / TICKS_PER_SECOND;
+
+    if seconds == 0 {
+        return "0".to_string();
+    }
+
+    let (value, unit) = if seconds >= YEAR {
+        (seconds / YEAR, 'y')
+    } else if seconds >= DAY {
+        (seconds / DAY, 'd')
+    } else if seconds >= HOUR {
+        (seconds / HOUR, 'h')
+    } else if seconds >= MINUTE {
+        (seconds / MINUTE, 'm')
+    } else {
+        (seconds, 's')
+    };
+
+    format! ("+{}{}", value, unit)
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  display_time_delta
+//
+//  Display the --Delta column: creation → last-write duration,
+//  right-aligned in DELTA_COLUMN_WIDTH columns (including the trailing
+//  separator space).
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn display_time_delta(console: &mut Console, config: &Config, file_info: &FileInfo) {
+    let delta = format_time_delta (file_info.creation_time, file_info.last_write_time);
+    console.writef (
+        config.attributes[Attribute::Time as usize],
+        format_args! ("{:>width$} ", delta, width = DELTA_COLUMN_WIDTH - 1),
+    );
+}
+
+
+
+
+
 ////////////////////////////////////////////////////////////////////////////////
 //
 //  display_attributes
 //
-//  Display the 9-char attribute column with colorization.
+//  Display the attribute column (9 chars; wider with /AttrStyle:ls or
+//  /Attr:full) with colorization.  Under /Attr:auto, ordinary entries
+//  get blanks of the same width so the columns stay aligned.
 //  Port of: CResultsDisplayerNormal::DisplayResultsNormalAttributes
 //
 ////////////////////////////////////////////////////////////////////////////////
 
-pub(super) fn display_attributes(console: &mut Console, config: &Config, file_attributes: u32) {
+pub(super) fn display_attributes(console: &mut Console, config: &Config, file_attributes: u32, columns: AttributeColumn) {
+    if columns.auto && !columns.is_notable (file_attributes) {
+        console.printf (config.attributes[Attribute::Default as usize], &" ".repeat (columns.width()));
+        return;
+    }
+
+    for (color, ch) in build_attribute_cells (config, file_attributes, columns) {
+        console.putchar(color, ch);
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  build_attribute_cells
+//
+//  The color and character for each attribute column position.  A present
+//  flag uses its attr:X letter color if one is configured, otherwise the
+//  FileAttributePresent color; absent flags always use the absent color.
+//  `columns` picks the letter scheme (/AttrStyle) and the /Attr:full
+//  rare-flag positions.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn build_attribute_cells(config: &Config, file_attributes: u32, columns: AttributeColumn) -> Vec<(u16, char)> {
     let present_attr = config.attributes[Attribute::FileAttributePresent as usize];
     let absent_attr  = config.attributes[Attribute::FileAttributeNotPresent as usize];
 
-    for &(flag, ch) in &FILE_ATTRIBUTE_MAP {
-        if (file_attributes & flag) != 0 {
-            console.putchar(present_attr, ch);
-        } else {
-            console.putchar(absent_attr, '-');
-        }
-    }
+    columns.entries()
+        .map (|&(flag, ch)| {
+            if (file_attributes & flag) != 0 {
+                (config.attr_letter_colors.get (&flag).copied().unwrap_or (present_attr), ch)
+            } else {
+                (absent_attr, '-')
+            }
+        })
+        .collect()
 }
 
 
@@ -432,7 +974,10 @@ pub(super) fn display_attributes(console: &mut Console, config: &Config, file_at
 //  display_file_size
 //
 //  Display file size (right-aligned with separators) or centered <DIR>.
-//  In abbreviated mode (SizeFormat::Auto), uses a fixed 7-char field.
+//  In abbreviated mode (SizeFormat::Auto), uses a fixed 7-char field, and
+//  in compact mode a 5-char one.  Under /OnDisk the size is the allocated
+//  one, and ON_DISK_MARKER takes the place of the leading space when it
+//  differs from the logical size.
 //  Port of: CResultsDisplayerNormal::DisplayResultsNormalFileSize
 //
 ////////////////////////////////////////////////////////////////////////////////
@@ -444,12 +989,14 @@ pub(super) fn display_file_size(
     size_format: SizeFormat,
 ) {
     let dir_label = "<DIR>";
+    let lead      = if fi.is_size_on_disk_different() { ON_DISK_MARKER } else { ' ' };
+    let size      = fi.displayed_size();
 
     // Abbreviated size mode (Auto): fixed 7-character field, Explorer-style
     if size_format == SizeFormat::Auto {
         if !fi.is_directory() {
-            let abbreviated = format_abbreviated_size (fi.file_size);
-            console.writef_attr (Attribute::Size, format_args! ("  {}", abbreviated));
+            let abbreviated = format_abbreviated_size (size);
+            console.writef_attr (Attribute::Size, format_args! (" {}{}", lead, abbreviated));
         } else {
             // " <DIR>   " — 1 leading space + <DIR> + 3 trailing spaces = 9 chars
             console.printf_attr (Attribute::Directory, " <DIR>   ");
@@ -457,12 +1004,22 @@ pub(super) fn display_file_size(
         return;
     }
 
+    // Compact mode: fixed 5-character field ("12.3G"), <DIR> fits exactly
+    if size_format == SizeFormat::Compact {
+        if !fi.is_directory() {
+            console.writef_attr (Attribute::Size, format_args! (" {}{}", lead, format_compact_size (size)));
+        } else {
+            console.printf_attr (Attribute::Directory, "  <DIR>");
+        }
+        return;
+    }
+
     // Bytes mode (comma-separated exact size): variable-width column
     let col_width = max_size_width.max(dir_label.len());
 
     if !fi.is_directory() {
-        let formatted = format_number_with_separators(fi.file_size);
-        console.writef_attr (Attribute::Size, format_args! ("  {:>width$}", formatted, width = col_width));
+        let formatted = format_number_with_separators(size);
+        console.writef_attr (Attribute::Size, format_args! (" {}{:>width$}", lead, formatted, width = col_width));
     } else {
         // Center <DIR> within the column — same total width as file sizes
         // (2 leading spaces + col_width chars, no trailing space)
@@ -481,6 +1038,49 @@ pub(super) fn display_file_size(
 
 
 
+////////////////////////////////////////////////////////////////////////////////
+//
+//  display_size_percentage
+//
+//  Display a file's share of the directory's total bytes in the /Pct
+//  column (" 100.0%"), colored as a size.  Directories get a blank column.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn display_size_percentage(console: &mut Console, fi: &FileInfo, total_bytes: u64) {
+    if fi.is_directory() {
+        console.printf_attr (Attribute::Default, &" ".repeat (PERCENT_COLUMN_WIDTH));
+        return;
+    }
+
+    let percent = size_percentage (fi.displayed_size(), total_bytes);
+    console.writef_attr (Attribute::Size, format_args! (" {:>width$.1}%", percent, width = PERCENT_COLUMN_WIDTH - 2));
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  size_percentage
+//
+//  A size as a percentage of a total; an empty total yields 0.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn size_percentage(size: u64, total: u64) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+
+    size as f64 * 100.0 / total as f64
+}
+
+
+
+
+
 ////////////////////////////////////////////////////////////////////////////////
 //
 //  display_raw_attributes
@@ -529,75 +1129,53 @@ pub(super) fn display_file_owner(console: &mut Console, config: &Config, owner:
 
 ////////////////////////////////////////////////////////////////////////////////
 //
-//  compute_available_width_for_target
+//  display_file_hash
 //
-//  Calculate how many characters remain on the current line for the
-//  reparse target path, after all metadata columns have been rendered.
-//  Used by both normal and tree displayers to feed `ellipsize_path()`.
-//  Port of: ComputeAvailableWidthForTarget
+//  Display a /Hash digest padded to the column width.  Entries without a
+//  digest (directories, cloud-only or unreadable files) get a blank cell
+//  so the names stay aligned.
 //
 ////////////////////////////////////////////////////////////////////////////////
 
-#[allow(clippy::too_many_arguments)]
-pub(super) fn compute_available_width_for_target(
-    console_width: usize,
-    max_size_width: usize,
-    size_format: SizeFormat,
-    icons_active: bool,
-    #[cfg(debug_assertions)]
-    debug: bool,
-    show_owner: bool,
-    max_owner_len: usize,
-    tree_prefix_width: usize,
-    filename_len: usize,
-) -> usize {
-    // Date/time: "MM/dd/yyyy  hh:mm tt " = 21 chars
-    let date_time_width = 21;
+pub(super) fn display_file_hash(console: &mut Console, config: &Config, hash: Option<&str>, hex_len: usize) {
+    let color = config.attributes[Attribute::Size as usize];
+    console.writef (color, format_args! ("{:<width$} ", hash.unwrap_or (""), width = hex_len));
+}
 
-    // Attributes: one char per FILE_ATTRIBUTE_MAP entry = 9 chars
-    let attributes_width = FILE_ATTRIBUTE_MAP.len();
 
-    // File size column:
-    //   Auto mode: "  " + 7-char abbreviated = 9
-    //   Bytes mode: "  " + max(max_size_width, 5)
-    let size_col_width = if size_format == SizeFormat::Auto {
-        9
-    } else {
-        2 + max_size_width.max (5)
-    };
 
-    // Cloud status: always displayed (even CloudStatus::None emits a space)
-    //   With icons: " {icon} " = 4 visual columns
-    //   Without icons: " ● " = 3 chars
-    let cloud_width = if icons_active { 4 } else { 3 };
 
-    // Debug column: "[XXXXXXXX:YY] " = 14 chars (debug builds only)
-    #[cfg(debug_assertions)]
-    let debug_width = if debug { 14 } else { 0 };
-    #[cfg(not(debug_assertions))]
-    let debug_width = 0;
 
-    // Owner column: "owner + padding + space" = max_owner_len + 1
-    let owner_width = if show_owner { max_owner_len + 1 } else { 0 };
+////////////////////////////////////////////////////////////////////////////////
+//
+//  display_file_id
+//
+//  Display a /FileId value as fixed-width hex; entries whose ID couldn't
+//  be read get a blank cell.
+//
+////////////////////////////////////////////////////////////////////////////////
 
-    // Icon glyph: icon (2 cols) + space = 3 visual columns
-    let icon_width = if icons_active { 3 } else { 0 };
+pub(super) fn display_file_id(console: &mut Console, config: &Config, file_id: Option<u64>) {
+    let color = config.attributes[Attribute::Size as usize];
+    let text  = file_id.map (file_id::format_file_id).unwrap_or_default();
+    console.writef (color, format_args! ("{:<width$}", text, width = FILE_ID_COLUMN_WIDTH));
+}
 
-    // Arrow separator: " → " = 3 chars
-    let arrow_width = 3;
 
-    let used = date_time_width
-        + attributes_width
-        + size_col_width
-        + cloud_width
-        + debug_width
-        + owner_width
-        + icon_width
-        + tree_prefix_width
-        + filename_len
-        + arrow_width;
 
-    console_width.saturating_sub (used)
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  date_time_column_width
+//
+//  Width of the date/time column, plus the age suffix with /T:both.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn date_time_column_width(cmd: &CommandLine) -> usize {
+    let age_width = if cmd.time_with_age { date_format::RELATIVE_AGE_WIDTH } else { 0 };
+    date_format::column_width (cmd.date_format.as_deref()) + age_width
 }
 
 
@@ -606,42 +1184,1738 @@ pub(super) fn compute_available_width_for_target(
 
 ////////////////////////////////////////////////////////////////////////////////
 //
-//  display_file_streams
+//  file_id_column_width
 //
-//  Display alternate data streams below a file entry.
-//  Port of: CResultsDisplayerNormal::DisplayFileStreams
+//  Visual width of the /FileId column, or 0 when it is off.
 //
 ////////////////////////////////////////////////////////////////////////////////
 
-pub(super) fn display_file_streams(
-    console: &mut Console,
-    config: &Config,
-    file_info: &FileInfo,
+pub(super) fn file_id_column_width(cmd: &CommandLine) -> usize {
+    if cmd.show_file_id { FILE_ID_COLUMN_WIDTH } else { 0 }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  display_file_encoding
+//
+//  Display an /Encoding guess; directories, binaries, and files that
+//  aren't text files get a blank cell.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn display_file_encoding(console: &mut Console, config: &Config, encoding: Option<TextEncoding>) {
+    let color = config.attributes[Attribute::Size as usize];
+    let text  = encoding.map (TextEncoding::label).unwrap_or_default();
+    console.writef (color, format_args! ("{:<width$}", text, width = ENCODING_COLUMN_WIDTH));
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  encoding_column_width
+//
+//  Visual width of the /Encoding column, or 0 when it is off.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn encoding_column_width(cmd: &CommandLine) -> usize {
+    if cmd.show_encoding { ENCODING_COLUMN_WIDTH } else { 0 }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  display_age_tier
+//
+//  Display the /Tier label for an entry's creation-time age, in the
+//  tier's color on the default background.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn display_age_tier(console: &mut Console, config: &Config, tiers: &AgeTierThresholds, creation_time: u64, now: u64) {
+    let tier  = tiers.tier (creation_time, now);
+    let color = tier.color() | (config.attributes[Attribute::Default as usize] & BC_MASK);
+    console.writef (color, format_args! ("{:<width$}", tier.label(), width = TIER_COLUMN_WIDTH));
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  tier_column_width
+//
+//  Visual width of the /Tier column, or 0 when it is off.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn tier_column_width(cmd: &CommandLine) -> usize {
+    if cmd.show_tier { TIER_COLUMN_WIDTH } else { 0 }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  display_file_version
+//
+//  Display a /VerInfo version left-aligned in the column; files without
+//  a version resource (and non-PE files) get a blank cell.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn display_file_version(console: &mut Console, config: &Config, version: Option<&str>) {
+    let color = config.attributes[Attribute::Size as usize];
+    console.writef (color, format_args! ("{:<width$}", version.unwrap_or_default(), width = VERSION_COLUMN_WIDTH));
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  version_column_width
+//
+//  Visual width of the /VerInfo column, or 0 when it is off.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn version_column_width(cmd: &CommandLine) -> usize {
+    if cmd.show_version { VERSION_COLUMN_WIDTH } else { 0 }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  display_file_type
+//
+//  Display a /Desc type description left-aligned in the column, cut to
+//  fit so the names stay aligned.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn display_file_type(console: &mut Console, config: &Config, type_name: Option<&str>) {
+    let color = config.attributes[Attribute::Information as usize];
+    let text  = format_file_type (type_name.unwrap_or_default());
+    console.writef (color, format_args! ("{:<width$}", text, width = FILE_TYPE_COLUMN_WIDTH));
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  file_type_column_width
+//
+//  Visual width of the /Desc column, or 0 when it is off.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn file_type_column_width(cmd: &CommandLine) -> usize {
+    if cmd.show_file_type { FILE_TYPE_COLUMN_WIDTH } else { 0 }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  hash_column_width
+//
+//  Visual width of the /Hash column (digest + trailing space), or 0 when
+//  hashing is off.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn hash_column_width(cmd: &CommandLine) -> usize {
+    cmd.hash_algorithm.map_or (0, |algorithm| algorithm.hex_len() + 1)
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  size_column_width
+//
+//  Visual width of the size column including its two leading spaces:
+//  9 in abbreviated (Auto) mode, else room for the largest size or <DIR>.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn size_column_width(size_format: SizeFormat, max_size_width: usize) -> usize {
+    if size_format == SizeFormat::Auto {
+        9
+    } else if size_format == SizeFormat::Compact {
+        2 + COMPACT_SIZE_WIDTH
+    } else {
+        2 + max_size_width.max (5)
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  build_column_header
+//
+//  The --Columns header row: a label over each column, laid out with the
+//  same widths display_file_results uses for the data rows.  Size and
+//  Delta labels are right-aligned like their values; columns without a
+//  label (cloud status, icon) are blank.  A label wider than its column
+//  is truncated.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn build_column_header(
+    cmd: &CommandLine,
+    date_time_width: usize,
     max_size_width: usize,
-    owner_width: usize,
+    show_cloud: bool,
     icons_active: bool,
-) {
-    let size_field_width = max_size_width.max(5);
-    let file_name = file_info.file_name.to_string_lossy();
-    let stream_color = config.attributes[Attribute::Stream as usize];
-    let size_color = config.attributes[Attribute::Size as usize];
-    let owner_padding = if owner_width > 0 { owner_width + 1 } else { 0 };
+    max_owner_len: usize,
+    name_padding: usize,
+) -> String {
+    fn left(header: &mut String, label: &str, width: usize) {
+        let label: String = label.chars().take (width.saturating_sub (1)).collect();
+        header.push_str (&format! ("{:<width$}", label, width = width));
+    }
 
-    let default_color = config.attributes[Attribute::Default as usize];
+    let date_width = date_format::date_part_width (cmd.date_format.as_deref()).min (date_time_width);
+    let mut header = String::new();
 
-    // Cloud status gap: leading space + symbol/icon + trailing space
-    //   Non-icon mode: 3 chars (space + symbol + space)
-    //   Icon mode:     4 visual cols (space + 2-col icon + space)
-    let cloud_status_gap = if icons_active { "    " } else { "   " };
+    left (&mut header, "Date", date_width);
+    left (&mut header, "Time", date_time_width - date_width);
 
-    for si in &file_info.streams {
-        let formatted_size = format_number_with_separators(si.size as u64);
+    if cmd.show_delta {
+        header.push_str (&format! ("{:>width$} ", "Delta", width = DELTA_COLUMN_WIDTH - 1));
+    }
 
-        // 30 chars indentation (date/time 21 + attributes 9)
-        // Then size field with padding, cloud placeholder, owner padding, then filename:stream
-        console.writef (default_color, format_args! ("{:30}", ""));
-        console.writef (size_color, format_args! ("  {:>width$}", formatted_size, width = size_field_width));
-        console.writef (default_color, format_args! ("{}  {:width$}", cloud_status_gap, "", width = owner_padding));
-        console.writef_line (stream_color, format_args! ("{}{}", file_name, si.name));
+    left (&mut header, "Attr", cmd.attribute_column().width());
+
+    let size_width = size_column_width (cmd.resolved_size_format(), max_size_width);
+    header.push_str (&format! ("{:>width$}", "Size", width = size_width));
+
+    if cmd.show_percent {
+        header.push_str (&format! ("{:>width$}", "Pct", width = PERCENT_COLUMN_WIDTH));
+    }
+
+    header.push_str (&" ".repeat (cloud_column_width (show_cloud, icons_active)));
+
+    #[cfg(debug_assertions)]
+    if cmd.debug {
+        header.push_str (&" ".repeat (14));
+    }
+
+    if cmd.show_owner {
+        left (&mut header, OWNER_HEADER, max_owner_len + 1);
+    }
+
+    left (&mut header, "Hash", hash_column_width (cmd));
+    left (&mut header, "FileId", file_id_column_width (cmd));
+    left (&mut header, "Encoding", encoding_column_width (cmd));
+    left (&mut header, "Tier", tier_column_width (cmd));
+    left (&mut header, "Version", version_column_width (cmd));
+    left (&mut header, "Type", file_type_column_width (cmd));
+    header.push_str (&" ".repeat (name_padding));
+
+    if icons_active {
+        header.push_str ("  ");
+    }
+
+    header.push_str ("Name");
+    header
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  split_name_for_wrap
+//
+//  Split a name into chunks of at most line_width characters for
+//  /WrapNames.  A zero width (no room left on the line) keeps the name
+//  whole rather than emitting one character per line.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn split_name_for_wrap(name: &str, line_width: usize) -> Vec<&str> {
+    if line_width == 0 {
+        return vec![name];
+    }
+
+    let mut chunks = Vec::new();
+    let mut rest   = name;
+
+    while rest.chars().count() > line_width {
+        let split_at = rest.char_indices().nth (line_width).map_or (rest.len(), |(i, _)| i);
+        let (chunk, tail) = rest.split_at (split_at);
+        chunks.push (chunk);
+        rest = tail;
+    }
+
+    chunks.push (rest);
+    chunks
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  display_wrapped_name_head
+//
+//  Write every full-width chunk of a /WrapNames name, each followed by a
+//  continuation line indented to the name column, and return the final
+//  chunk for the caller to finish the line with (reparse arrow, stream
+//  count, or plain newline).
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn display_wrapped_name_head(console: &mut Console, text_attr: u16, name: &str, name_column: usize) -> String {
+    let line_width = (console.width() as usize).saturating_sub (name_column);
+    let chunks     = split_name_for_wrap (name, line_width);
+    let indent     = " ".repeat (name_column);
+
+    let (last, head) = chunks.split_last().expect ("split_name_for_wrap returns at least one chunk");
+
+    for chunk in head {
+        console.writef_line (text_attr, format_args! ("{}", chunk));
+        console.printf_attr (Attribute::Default, &indent);
+    }
+
+    last.to_string()
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  compute_available_width_for_target
+//
+//  Calculate how many characters remain on the current line for the
+//  reparse target path, after all metadata columns have been rendered.
+//  Used by both normal and tree displayers to feed `ellipsize_path()`.
+//  Port of: ComputeAvailableWidthForTarget
+//
+////////////////////////////////////////////////////////////////////////////////
+
+#[allow(clippy::too_many_arguments)]
+pub(super) fn compute_available_width_for_target(
+    console_width: usize,
+    date_time_width: usize,
+    max_size_width: usize,
+    size_format: SizeFormat,
+    icons_active: bool,
+    show_cloud: bool,
+    show_delta: bool,
+    show_percent: bool,
+    attribute_column: AttributeColumn,
+    #[cfg(debug_assertions)]
+    debug: bool,
+    show_owner: bool,
+    max_owner_len: usize,
+    hash_width: usize,
+    tree_prefix_width: usize,
+    filename_len: usize,
+) -> usize {
+    let name_column = compute_name_column (
+        date_time_width,
+        max_size_width,
+        size_format,
+        icons_active,
+        show_cloud,
+        show_delta,
+        show_percent,
+        attribute_column,
+        #[cfg(debug_assertions)]
+        debug,
+        show_owner,
+        max_owner_len,
+        hash_width,
+        tree_prefix_width,
+    );
+
+    // Arrow separator: " → " = 3 chars
+    let arrow_width = 3;
+
+    console_width.saturating_sub (name_column + filename_len + arrow_width)
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  align_name_column
+//
+//  Resolve /NameCol for one directory: the column its names start at,
+//  given the column its own metadata needs.  Auto keeps a run-wide
+//  high-water mark in `run_floor`, so a directory never starts names
+//  left of an earlier one; Fixed never starts them left of `natural`.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn align_name_column(setting: Option<NameColumn>, natural: usize, run_floor: &mut usize) -> usize {
+    match setting {
+        None                         => natural,
+        Some (NameColumn::Fixed (n)) => natural.max (n),
+        Some (NameColumn::Auto)      => {
+            *run_floor = (*run_floor).max (natural);
+            *run_floor
+        }
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  compute_name_column
+//
+//  Calculate the console column where the filename starts, i.e. the
+//  width of all metadata columns rendered before it.  Shared by the
+//  reparse target ellipsis and /WrapNames continuation indent.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+#[allow(clippy::too_many_arguments)]
+pub(super) fn compute_name_column(
+    date_time_width: usize,
+    max_size_width: usize,
+    size_format: SizeFormat,
+    icons_active: bool,
+    show_cloud: bool,
+    show_delta: bool,
+    show_percent: bool,
+    attribute_column: AttributeColumn,
+    #[cfg(debug_assertions)]
+    debug: bool,
+    show_owner: bool,
+    max_owner_len: usize,
+    hash_width: usize,
+    tree_prefix_width: usize,
+) -> usize {
+    // Date/time: "MM/dd/yyyy  hh:mm tt " = 21 chars, or the /DateFmt width
+    // Delta: "+364d " = DELTA_COLUMN_WIDTH chars (if --Delta)
+    let delta_width = if show_delta { DELTA_COLUMN_WIDTH } else { 0 };
+
+    // Attributes: one char per displayed flag = 9 chars by default
+    let attributes_width = attribute_column.width();
+
+    // Percentage: " 100.0%" = PERCENT_COLUMN_WIDTH chars (if /Pct)
+    let percent_width = if show_percent { PERCENT_COLUMN_WIDTH } else { 0 };
+
+    // File size column:
+    //   Auto mode: "  " + 7-char abbreviated = 9
+    //   Bytes mode: "  " + max(max_size_width, 5)
+    let size_col_width = size_column_width (size_format, max_size_width);
+
+    // Cloud status: displayed unless /NoCloud (even CloudStatus::None emits a space)
+    //   With icons: " {icon} " = 4 visual columns
+    //   Without icons: " ● " = 3 chars
+    let cloud_width = cloud_column_width (show_cloud, icons_active);
+
+    // Debug column: "[XXXXXXXX:YY] " = 14 chars (debug builds only)
+    #[cfg(debug_assertions)]
+    let debug_width = if debug { 14 } else { 0 };
+    #[cfg(not(debug_assertions))]
+    let debug_width = 0;
+
+    // Owner column: "owner + padding + space" = max_owner_len + 1
+    let owner_width = if show_owner { max_owner_len + 1 } else { 0 };
+
+    // Icon glyph: icon (2 cols) + space = 3 visual columns
+    let icon_width = if icons_active { 3 } else { 0 };
+
+    date_time_width
+        + delta_width
+        + attributes_width
+        + size_col_width
+        + percent_width
+        + cloud_width
+        + debug_width
+        + owner_width
+        + hash_width
+        + icon_width
+        + tree_prefix_width
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  display_file_streams
+//
+//  Display alternate data streams below a file entry.
+//  Port of: CResultsDisplayerNormal::DisplayFileStreams
+//
+////////////////////////////////////////////////////////////////////////////////
+
+#[allow(clippy::too_many_arguments)]
+pub(super) fn display_file_streams(
+    console: &mut Console,
+    config: &Config,
+    file_info: &FileInfo,
+    date_time_width: usize,
+    max_size_width: usize,
+    owner_width: usize,
+    hash_width: usize,
+    icons_active: bool,
+    show_cloud: bool,
+    show_delta: bool,
+    show_percent: bool,
+    attribute_column: AttributeColumn,
+) {
+    let size_field_width = max_size_width.max(5);
+    let file_name = file_info.file_name.to_string_lossy();
+    let stream_color = config.attributes[Attribute::Stream as usize];
+    let size_color = config.attributes[Attribute::Size as usize];
+    let owner_padding = (if owner_width > 0 { owner_width + 1 } else { 0 }) + hash_width;
+
+    let default_color = config.attributes[Attribute::Default as usize];
+
+    // Metadata indentation: date/time + delta (if --Delta) + attributes
+    let metadata_width = date_time_width + attribute_column.width() + if show_delta { DELTA_COLUMN_WIDTH } else { 0 };
+
+    // Cloud status gap: leading space + symbol/icon + trailing space
+    //   Non-icon mode: 3 chars (space + symbol + space)
+    //   Icon mode:     4 visual cols (space + 2-col icon + space)
+    //   /NoCloud:      none
+    let cloud_status_gap = " ".repeat (cloud_column_width (show_cloud, icons_active));
+
+    // Percentage column (/Pct) is blank on stream lines
+    let percent_gap = if show_percent { PERCENT_COLUMN_WIDTH } else { 0 };
+
+    for si in &file_info.streams {
+        let formatted_size = format_number_with_separators(si.size as u64);
+
+        // Metadata indentation, then size field with padding, cloud placeholder,
+        // owner/hash padding, then filename:stream
+        console.writef (default_color, format_args! ("{:width$}", "", width = metadata_width));
+        console.writef (size_color, format_args! ("  {:>width$}", formatted_size, width = size_field_width));
+        console.writef (default_color, format_args! ("{:width$}", "", width = percent_gap));
+        console.writef (default_color, format_args! ("{}  {:width$}", cloud_status_gap, "", width = owner_padding));
+        console.writef_line (stream_color, format_args! ("{}{}", file_name, si.name));
+    }
+}
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::make_file;
+
+    /// 100 ns FILETIME ticks per second.
+    const SECOND: u64 = 10_000_000;
+
+    /// Arbitrary creation time (2024-01-17) used as the base for deltas.
+    const CREATED: u64 = 133_500_000_000_000_000;
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  time_delta_picks_largest_whole_unit
+    //
+    //  Verify known creation/last-write pairs format in the largest whole
+    //  unit, truncating partial units.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn time_delta_picks_largest_whole_unit() {
+        let cases: &[(u64, &str)] = &[
+            (45,                      "+45s"),
+            (12 * 60 + 59,            "+12m"),
+            (5 * 3600,                "+5h"),
+            (3 * 86_400 + 7200,       "+3d"),
+            (364 * 86_400,            "+364d"),
+            (2 * 365 * 86_400 + 1,    "+2y"),
+        ];
+
+        for &(seconds, expected) in cases {
+            assert_eq! (format_time_delta (CREATED, CREATED + seconds * SECOND), expected, "{} s", seconds);
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  time_delta_zero_or_negative_is_zero
+    //
+    //  Verify equal times, sub-second deltas, and files written before
+    //  they were created (e.g. copied files) all render as "0".
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn time_delta_zero_or_negative_is_zero() {
+        assert_eq! (format_time_delta (CREATED, CREATED), "0");
+        assert_eq! (format_time_delta (CREATED, CREATED + SECOND / 2), "0");
+        assert_eq! (format_time_delta (CREATED, CREATED - 86_400 * SECOND), "0");
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  time_delta_fits_column
+    //
+    //  Verify the widest in-range values fit DELTA_COLUMN_WIDTH with the
+    //  trailing separator space.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn time_delta_fits_column() {
+        let widest = format_time_delta (CREATED, CREATED + 364 * 86_400 * SECOND);
+        assert! (widest.len() < DELTA_COLUMN_WIDTH);
+
+        let ancient = format_time_delta (0, CREATED);
+        assert! (ancient.len() < DELTA_COLUMN_WIDTH, "{}", ancient);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  render_listing
+    //
+    //  Renders one file through display_file_results as plain text.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn render_listing (args: &[&str], dir_path: &str, file_name: &str) -> String {
+        let mut cfg = Config::new();
+        cfg.initialize (0x07);
+        let cfg = Arc::new (cfg);
+
+        let mut console = Console::new_for_testing (Arc::clone (&cfg));
+        console.set_color_mode (crate::command_line::ColorMode::Never);
+
+        let cmd = CommandLine::parse_from (args.iter().copied()).unwrap();
+        let mut di = DirectoryInfo::new (std::path::PathBuf::from (dir_path), "*".to_string());
+        di.matches.push (make_file (file_name, crate::file_info::FILE_ATTRIBUTE_ARCHIVE, 1234));
+        di.largest_file_size = 1234;
+
+        display_file_results (&mut console, &cmd, &cfg, &di, false, &mut 0);
+        console.take_test_buffer()
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  full_path_renders_absolute_name
+    //
+    //  Verify /FullPath renders the name as dir_path\name while the
+    //  metadata columns keep the same width.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn full_path_renders_absolute_name() {
+        let plain = render_listing (&["/NoCloud"], "C:\\Projects", "report.txt");
+        let full  = render_listing (&["/NoCloud", "/FullPath"], "C:\\Projects", "report.txt");
+
+        assert! (plain.trim_end().ends_with (" report.txt"), "{}", plain);
+        assert! (full.trim_end().ends_with (" C:\\Projects\\report.txt"), "{}", full);
+
+        let metadata_width = plain.len() - "report.txt\n".len();
+        assert_eq! (&full[..metadata_width], &plain[..metadata_width]);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  attr_letter_color_applies_to_its_column
+    //
+    //  Verify an attr:R color colors only the R position when read-only is
+    //  set, other present flags keep FileAttributePresent, and absent
+    //  flags keep the absent color.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn attr_letter_color_applies_to_its_column() {
+        use crate::color::FC_YELLOW;
+        use crate::environment_provider::MockEnvironmentProvider;
+        use crate::file_info::{FILE_ATTRIBUTE_ARCHIVE, FILE_ATTRIBUTE_MAP, FILE_ATTRIBUTE_READONLY};
+
+        let mut mock = MockEnvironmentProvider::new();
+        mock.set (crate::config::RCDIR_ENV_VAR_NAME, "attr:R=Yellow");
+
+        let mut cfg = Config::new();
+        cfg.initialize_with_provider (0x07, &mock);
+
+        let present = cfg.attributes[Attribute::FileAttributePresent as usize];
+        let absent  = cfg.attributes[Attribute::FileAttributeNotPresent as usize];
+
+        let cells = build_attribute_cells (&cfg, FILE_ATTRIBUTE_READONLY | FILE_ATTRIBUTE_ARCHIVE, AttributeColumn::default());
+        let r_pos = FILE_ATTRIBUTE_MAP.iter().position (|&(_, ch)| ch == 'R').unwrap();
+        let a_pos = FILE_ATTRIBUTE_MAP.iter().position (|&(_, ch)| ch == 'A').unwrap();
+
+        assert_eq! (cells.len(), FILE_ATTRIBUTE_MAP.len());
+        assert_eq! (cells[r_pos], (FC_YELLOW, 'R'));
+        assert_eq! (cells[a_pos], (present, 'A'));
+
+        let cells = build_attribute_cells (&cfg, FILE_ATTRIBUTE_ARCHIVE, AttributeColumn::default());
+        assert_eq! (cells[r_pos], (absent, '-'));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  column_header_aligns_with_data_row
+    //
+    //  Verify --Columns prints a header whose labels start (or, for Size,
+    //  end) at the same positions as the row's date, time, attribute,
+    //  size, hash, and name fields.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn column_header_aligns_with_data_row() {
+        let output = render_listing (&["/NoCloud", "/Columns", "--Delta", "--Hash=CRC32"], "C:\\Projects", "report.txt");
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq! (lines.len(), 2, "{}", output);
+
+        let (header, row) = (lines[0], lines[1]);
+        let time_col  = date_format::DEFAULT_DATE_PART_WIDTH;
+        let attr_col  = date_format::DEFAULT_DATE_TIME_WIDTH + DELTA_COLUMN_WIDTH;
+        let size_end  = row.find ("1,234").unwrap() + "1,234".len();
+        let name_col  = row.find ("report.txt").unwrap();
+
+        assert_eq! (header.find ("Date"), Some (0));
+        assert_eq! (header.find ("Time"), Some (time_col));
+        assert! (row[time_col..].starts_with (|c: char| c.is_ascii_digit()), "{}", row);
+        assert_eq! (header.find ("Attr"), Some (attr_col));
+        assert_eq! (header.find ("Size").map (|i| i + "Size".len()), Some (size_end));
+        assert_eq! (header.find ("Hash"), Some (size_end));
+        assert_eq! (header.find ("Name"), Some (name_col));
+        assert_eq! (header.len(), name_col + "Name".len());
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  column_header_follows_custom_date_format
+    //
+    //  Verify the Time label moves with a /DateFmt date half and the Name
+    //  label still lines up with the file name.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn column_header_follows_custom_date_format() {
+        let output = render_listing (&["/NoCloud", "/Columns", "/DateFmt:yyyy-MM-dd HH:mm"], "C:\\Projects", "report.txt");
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq! (lines[0].find ("Time"), Some ("2024-01-17 ".len()));
+        assert_eq! (lines[0].find ("Name"), lines[1].find ("report.txt"));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  no_column_header_by_default
+    //
+    //  Verify the default listing has no header row.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn no_column_header_by_default() {
+        let output = render_listing (&["/NoCloud"], "C:\\Projects", "report.txt");
+        assert_eq! (output.lines().count(), 1);
+        assert! (!output.contains ("Attr"));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  no_cloud_auto_drops_column_outside_sync_root
+    //
+    //  Verify /NoCloud:auto removes the cloud cell for a directory that
+    //  isn't under a sync root, exactly as /NoCloud does, while the
+    //  default keeps it blank.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn no_cloud_auto_drops_column_outside_sync_root() {
+        let default = render_listing (&[], "C:\\Projects", "report.txt");
+        let hidden  = render_listing (&["/NoCloud"], "C:\\Projects", "report.txt");
+        let auto    = render_listing (&["/NoCloud:auto"], "C:\\Projects", "report.txt");
+
+        assert_eq! (auto, hidden);
+        assert_eq! (default.chars().count() - auto.chars().count(), 3, "default:\n{default}\nauto:\n{auto}");
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  full_attributes_render_rare_flags
+    //
+    //  Verify /Attr:full appends X/I/B/O/L/V positions that light up for
+    //  files carrying those flags, and that the default column omits them.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn full_attributes_render_rare_flags() {
+        use crate::file_info::{
+            FILE_ATTRIBUTE_ARCHIVE, FILE_ATTRIBUTE_INTEGRITY_STREAM, FILE_ATTRIBUTE_NO_SCRUB_DATA,
+            FILE_ATTRIBUTE_NOT_CONTENT_INDEXED, FILE_ATTRIBUTE_OFFLINE, FILE_ATTRIBUTE_PINNED,
+            FILE_ATTRIBUTE_RECALL_ON_OPEN, FILE_ATTRIBUTE_UNPINNED,
+        };
+
+        let cfg = Config::new();
+        let letters = |attrs: u32, full: bool| -> String {
+            let columns = AttributeColumn { full, ..AttributeColumn::default() };
+            build_attribute_cells (&cfg, attrs, columns).into_iter().map (|(_, ch)| ch).collect()
+        };
+
+        let rare = FILE_ATTRIBUTE_NOT_CONTENT_INDEXED
+                 | FILE_ATTRIBUTE_INTEGRITY_STREAM
+                 | FILE_ATTRIBUTE_NO_SCRUB_DATA
+                 | FILE_ATTRIBUTE_OFFLINE;
+
+        assert_eq! (letters (FILE_ATTRIBUTE_ARCHIVE | rare, true), "---A-----XIBO--");
+        assert_eq! (letters (FILE_ATTRIBUTE_ARCHIVE | rare, false), "---A-----");
+        assert_eq! (letters (FILE_ATTRIBUTE_RECALL_ON_OPEN, true), "------------O--");
+        assert_eq! (letters (FILE_ATTRIBUTE_UNPINNED, true), "-------------L-");
+        assert_eq! (letters (FILE_ATTRIBUTE_PINNED, true), "--------------V");
+
+        assert_eq! (AttributeColumn::default().width(), 9);
+        assert_eq! (AttributeColumn { full: true, ..AttributeColumn::default() }.width(), 15);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  group_by_extension_buckets_entries
+    //
+    //  Verify directories form the first group, files bucket by
+    //  case-insensitive extension in alphabetical order, and each group
+    //  keeps the sorted order of its entries.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn group_by_extension_buckets_entries() {
+        use crate::file_info::{FILE_ATTRIBUTE_ARCHIVE, FILE_ATTRIBUTE_DIRECTORY};
+
+        let matches = vec![
+            make_file ("src",      FILE_ATTRIBUTE_DIRECTORY, 10),
+            make_file ("b.rs",     FILE_ATTRIBUTE_ARCHIVE, 10),
+            make_file ("Makefile", FILE_ATTRIBUTE_ARCHIVE, 10),
+            make_file ("a.TXT",    FILE_ATTRIBUTE_ARCHIVE, 10),
+            make_file ("c.rs",     FILE_ATTRIBUTE_ARCHIVE, 10),
+            make_file ("d.txt",    FILE_ATTRIBUTE_ARCHIVE, 10),
+        ];
+
+        let groups: Vec<(String, Vec<usize>)> = group_by_extension (&matches).into_iter()
+            .map (|g| (g.label, g.indices))
+            .collect();
+
+        assert_eq! (groups, vec![
+            ("Directories".to_string(),    vec![0]),
+            ("(no extension)".to_string(), vec![2]),
+            (".rs".to_string(),            vec![1, 4]),
+            (".txt".to_string(),           vec![3, 5]),
+        ]);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  group_ext_renders_headings_with_counts
+    //
+    //  Verify /Group:ext prints each heading with its count, directly
+    //  above the rows of the files it holds.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn group_ext_renders_headings_with_counts() {
+        use crate::file_info::FILE_ATTRIBUTE_ARCHIVE;
+
+        let mut cfg = Config::new();
+        cfg.initialize (0x07);
+        let cfg = Arc::new (cfg);
+
+        let mut console = Console::new_for_testing (Arc::clone (&cfg));
+        console.set_color_mode (crate::command_line::ColorMode::Never);
+
+        let cmd = CommandLine::parse_from (["/Group:ext", "/NoCloud"]).unwrap();
+        let mut di = DirectoryInfo::new (std::path::PathBuf::from ("C:\\test"), "*".to_string());
+        for name in ["one.rs", "notes.txt", "two.rs"] {
+            di.matches.push (make_file (name, FILE_ATTRIBUTE_ARCHIVE, 10));
+        }
+        di.largest_file_size = 10;
+
+        display_file_results (&mut console, &cmd, &cfg, &di, false, &mut 0);
+        let output = console.take_test_buffer();
+        let lines: Vec<&str> = output.lines().collect();
+
+        let rs_heading  = lines.iter().position (|l| *l == ".rs (2)").expect (".rs heading");
+        let txt_heading = lines.iter().position (|l| *l == ".txt (1)").expect (".txt heading");
+
+        assert! (rs_heading < txt_heading);
+        assert! (lines[rs_heading + 1].ends_with ("one.rs"));
+        assert! (lines[rs_heading + 2].ends_with ("two.rs"));
+        assert! (lines[txt_heading + 1].ends_with ("notes.txt"));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  group_ext_uses_configured_labels
+    //
+    //  Verify an extension with a grouplabel: entry gets its label as the
+    //  heading, and other extensions keep the raw extension.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn group_ext_uses_configured_labels() {
+        use crate::file_info::FILE_ATTRIBUTE_ARCHIVE;
+
+        let mut cfg = Config::new();
+        cfg.initialize (0x07);
+        cfg.group_labels.insert (".cpp".to_string(), ("C++ sources".to_string(), None));
+        let cfg = Arc::new (cfg);
+
+        let mut console = Console::new_for_testing (Arc::clone (&cfg));
+        console.set_color_mode (crate::command_line::ColorMode::Never);
+
+        let cmd = CommandLine::parse_from (["/Group:ext", "/NoCloud"]).unwrap();
+        let mut di = DirectoryInfo::new (std::path::PathBuf::from ("C:\\test"), "*".to_string());
+        for name in ["main.cpp", "notes.txt", "util.CPP"] {
+            di.matches.push (make_file (name, FILE_ATTRIBUTE_ARCHIVE, 10));
+        }
+        di.largest_file_size = 10;
+
+        display_file_results (&mut console, &cmd, &cfg, &di, false, &mut 0);
+        let output = console.take_test_buffer();
+        let lines: Vec<&str> = output.lines().collect();
+
+        let cpp_heading = lines.iter().position (|l| *l == "C++ sources (2)").expect ("labeled heading");
+        assert! (lines[cpp_heading + 1].ends_with ("main.cpp"));
+        assert! (lines.contains (&".txt (1)"));
+        assert! (!lines.contains (&".cpp (2)"));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  ls_style_renders_alternate_letters
+    //
+    //  Verify /AttrStyle:ls renders the same attributes with the ls-like
+    //  letters (leading 'd' for directories) and keeps the colors.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn ls_style_renders_alternate_letters() {
+        use crate::file_info::{
+            AttributeStyle, FILE_ATTRIBUTE_ARCHIVE, FILE_ATTRIBUTE_DIRECTORY, FILE_ATTRIBUTE_HIDDEN,
+            FILE_ATTRIBUTE_READONLY, FILE_ATTRIBUTE_REPARSE_POINT,
+        };
+
+        let cfg = Config::new();
+        let render = |attrs: u32, style: AttributeStyle| -> Vec<(u16, char)> {
+            build_attribute_cells (&cfg, attrs, AttributeColumn { style, full: false, auto: false })
+        };
+        let letters = |cells: &[(u16, char)]| -> String { cells.iter().map (|&(_, ch)| ch).collect() };
+
+        let file = FILE_ATTRIBUTE_READONLY | FILE_ATTRIBUTE_HIDDEN | FILE_ATTRIBUTE_ARCHIVE;
+        assert_eq! (letters (&render (file, AttributeStyle::Dir)), "RH-A-----");
+        assert_eq! (letters (&render (file, AttributeStyle::Ls)),  "-rh-a-----");
+
+        let link_dir = FILE_ATTRIBUTE_DIRECTORY | FILE_ATTRIBUTE_REPARSE_POINT;
+        assert_eq! (letters (&render (link_dir, AttributeStyle::Ls)), "d-------l-");
+
+        // Same colors: present/absent positions map one-to-one
+        let present = |cells: &[(u16, char)]| cells.iter().filter (|&&(_, ch)| ch != '-').map (|&(c, _)| c).collect::<Vec<_>>();
+        assert_eq! (present (&render (file, AttributeStyle::Dir)), present (&render (file, AttributeStyle::Ls)));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  split_name_for_wrap_chunks_at_width
+    //
+    //  Verify names split into line-width chunks on character boundaries
+    //  and that short names or a zero width keep the name whole.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn split_name_for_wrap_chunks_at_width() {
+        assert_eq! (split_name_for_wrap ("abcdefghij", 4), ["abcd", "efgh", "ij"]);
+        assert_eq! (split_name_for_wrap ("abcdefgh", 4), ["abcd", "efgh"]);
+        assert_eq! (split_name_for_wrap ("short.txt", 40), ["short.txt"]);
+        assert_eq! (split_name_for_wrap ("short.txt", 0), ["short.txt"]);
+        assert_eq! (split_name_for_wrap ("ääääää", 4), ["ääää", "ää"]);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  wrap_names_continues_at_name_column
+    //
+    //  Verify /WrapNames fills the first line to the console width and
+    //  indents the continuation line to the column where the name began.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn wrap_names_continues_at_name_column() {
+        let name = format! ("{}.txt", "n".repeat (150));
+
+        let plain   = render_listing (&["/NoCloud"], "C:\\test", &name);
+        let wrapped = render_listing (&["/NoCloud", "/WrapNames"], "C:\\test", &name);
+
+        assert_eq! (plain.lines().count(), 1, "{}", plain);
+
+        let lines: Vec<&str> = wrapped.lines().collect();
+        assert_eq! (lines.len(), 2, "{}", wrapped);
+
+        let name_column = lines[0].find (&"n".repeat (10)).expect ("name on first line");
+        assert_eq! (lines[0].chars().count(), 120);
+        assert_eq! (&lines[1][..name_column], " ".repeat (name_column));
+        assert_eq! (format! ("{}{}", &lines[0][name_column..], &lines[1][name_column..]), name);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  show_mask_appends_matched_spec
+    //
+    //  Verify /ShowMask appends the spec a file matched after its name,
+    //  and that the name line is unchanged without the switch.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn show_mask_appends_matched_spec() {
+        use crate::file_info::FILE_ATTRIBUTE_ARCHIVE;
+
+        let mut cfg = Config::new();
+        cfg.initialize (0x07);
+        let cfg = Arc::new (cfg);
+
+        let render = |args: &[&str]| -> String {
+            let mut console = Console::new_for_testing (Arc::clone (&cfg));
+            console.set_color_mode (crate::command_line::ColorMode::Never);
+
+            let cmd = CommandLine::parse_from (args.iter().copied()).unwrap();
+            let mut di = DirectoryInfo::new (std::path::PathBuf::from ("C:\\src"), "*.rs".to_string());
+            let mut file_info = make_file ("main.rs", FILE_ATTRIBUTE_ARCHIVE, 10);
+            file_info.matched_spec = Some ("*.rs".to_string());
+            di.matches.push (file_info);
+            di.largest_file_size = 10;
+
+            display_file_results (&mut console, &cmd, &cfg, &di, false, &mut 0);
+            console.take_test_buffer()
+        };
+
+        assert! (render (&["/NoCloud", "/ShowMask"]).trim_end().ends_with ("main.rs  [*.rs]"));
+        assert! (render (&["/NoCloud"]).trim_end().ends_with (" main.rs"));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  version_column_shows_version_or_blank
+    //
+    //  Verify /VerInfo pads a (mocked) version string to the column, a file
+    //  without one gets a blank cell of the same width, and both names
+    //  start at the same column.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn version_column_shows_version_or_blank() {
+        use crate::file_info::FILE_ATTRIBUTE_ARCHIVE;
+
+        let mut cfg = Config::new();
+        cfg.initialize (0x07);
+        let cfg = Arc::new (cfg);
+
+        let mut console = Console::new_for_testing (Arc::clone (&cfg));
+        console.set_color_mode (crate::command_line::ColorMode::Never);
+
+        let cmd = CommandLine::parse_from (["/NoCloud", "/VerInfo"]).unwrap();
+        let mut di = DirectoryInfo::new (std::path::PathBuf::from ("C:\\bin"), "*".to_string());
+        let mut app = make_file ("app.dll", FILE_ATTRIBUTE_ARCHIVE, 10);
+        app.version = Some ("10.0.19041.1".to_string());
+        di.matches.push (app);
+        di.matches.push (make_file ("plain.dll", FILE_ATTRIBUTE_ARCHIVE, 10));
+        di.largest_file_size = 10;
+
+        display_file_results (&mut console, &cmd, &cfg, &di, false, &mut 0);
+        let output = console.take_test_buffer();
+        let lines: Vec<&str> = output.lines().collect();
+
+        let versioned = format! ("{:<width$}app.dll", "10.0.19041.1", width = VERSION_COLUMN_WIDTH);
+        let blank     = format! ("{:<width$}plain.dll", "", width = VERSION_COLUMN_WIDTH);
+        assert! (lines[0].ends_with (&versioned), "{output}");
+        assert! (lines[1].ends_with (&blank), "{output}");
+        assert_eq! (lines[0].find ("app.dll"), lines[1].find ("plain.dll"));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  size_percentages_sum_to_total
+    //
+    //  Verify /Pct shares of a known set of sizes sum to ~100% and an empty
+    //  total yields 0 rather than NaN.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn size_percentages_sum_to_total() {
+        let sizes = [500_u64, 300, 150, 50];
+        let total: u64 = sizes.iter().sum();
+
+        assert_eq! (size_percentage (500, total), 50.0);
+
+        let sum: f64 = sizes.iter().map (|&size| size_percentage (size, total)).sum();
+        assert! ((sum - 100.0).abs() < 1e-9, "{}", sum);
+
+        let thirds: f64 = (0..3).map (|_| size_percentage (1, 3)).sum();
+        assert! ((thirds - 100.0).abs() < 1e-9, "{}", thirds);
+
+        assert_eq! (size_percentage (0, 0), 0.0);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  percent_column_aligns_with_header
+    //
+    //  Verify the /Pct value sits under its --Columns label and shifts the
+    //  name right by PERCENT_COLUMN_WIDTH.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn percent_column_aligns_with_header() {
+        let plain  = render_listing (&["/NoCloud"], "C:\\test", "report.txt");
+        let output = render_listing (&["/NoCloud", "/Columns", "/Pct"], "C:\\test", "report.txt");
+        let lines: Vec<&str> = output.lines().collect();
+
+        let label_end = lines[0].find ("Pct").expect ("Pct label") + "Pct".len();
+        let value_end = lines[1].find ('%').expect ("percentage") + 1;
+        assert_eq! (label_end, value_end, "{}", output);
+
+        let plain_name = plain.find ("report.txt").unwrap();
+        assert_eq! (lines[1].find ("report.txt").unwrap(), plain_name + PERCENT_COLUMN_WIDTH);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  render_directory_summary
+    //
+    //  Renders a subdirectory section holding the given (name, size,
+    //  write time) files through NormalDisplayer as plain text.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn render_directory_summary (args: &[&str], files: &[(&str, u64, u64)]) -> String {
+        let mut cfg = Config::new();
+        cfg.initialize (0x07);
+        let cfg = Arc::new (cfg);
+
+        let mut console = Console::new_for_testing (Arc::clone (&cfg));
+        console.set_color_mode (crate::command_line::ColorMode::Never);
+
+        let cmd = Arc::new (CommandLine::parse_from (args.iter().copied()).unwrap());
+        let mut di = DirectoryInfo::new (std::env::temp_dir(), "*".to_string());
+        for &(name, size, written) in files {
+            di.matches.push (FileInfo {
+                creation_time:    written,
+                last_write_time:  written,
+                last_access_time: written,
+                ..make_file (name, crate::file_info::FILE_ATTRIBUTE_ARCHIVE, size)
+            });
+            di.file_count += 1;
+            di.bytes_used += size;
+            di.largest_file_size = di.largest_file_size.max (size);
+        }
+
+        let drive_info = DriveInfo::new (&di.dir_path).unwrap();
+        let mut displayer = NormalDisplayer::new (console, cmd, cfg, false);
+        displayer.display_results (&drive_info, &di, DirectoryLevel::Subdirectory);
+        displayer.into_console().take_test_buffer()
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  verbosity_levels_emit_expected_sections
+    //
+    //  Verify /V:0 drops the free-space footer, /V:1 (the default) keeps
+    //  it, and /V:2 adds the extension, oldest/newest and histogram
+    //  sections with the right counts.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn verbosity_levels_emit_expected_sections() {
+        let files = [
+            ("old.txt",   100,       CREATED),
+            ("new.TXT",   2048,      CREATED + 86_400 * SECOND),
+            ("big.iso",   5 << 20,   CREATED + 3600 * SECOND),
+            ("README",    10,        CREATED + 60 * SECOND),
+        ];
+
+        let totals   = render_directory_summary (&["/NoCloud", "/V:0"], &files);
+        let standard = render_directory_summary (&["/NoCloud"], &files);
+        let detailed = render_directory_summary (&["/NoCloud", "/V:2"], &files);
+
+        for output in [&totals, &standard, &detailed] {
+            assert! (output.contains ("4 files using"), "{}", output);
+        }
+
+        assert! (!totals.contains ("free on volume"), "{}", totals);
+        assert! (standard.contains ("free on volume"), "{}", standard);
+        assert! (!standard.contains ("By extension:"), "{}", standard);
+
+        assert! (detailed.contains ("free on volume"), "{}", detailed);
+        assert! (detailed.contains ("By extension:"), "{}", detailed);
+        assert! (detailed.lines().any (|l| l.trim_start().starts_with (".txt") && l.contains ("2 files using 2,148 bytes")), "{}", detailed);
+        assert! (detailed.lines().any (|l| l.trim_start().starts_with ("(none)") && l.contains ("1 file using 10 bytes")), "{}", detailed);
+        assert! (detailed.lines().any (|l| l.starts_with (" Oldest:") && l.ends_with ("old.txt")), "{}", detailed);
+        assert! (detailed.lines().any (|l| l.starts_with (" Newest:") && l.ends_with ("new.TXT")), "{}", detailed);
+        assert! (detailed.contains ("Size histogram:"), "{}", detailed);
+        assert! (detailed.lines().any (|l| l.trim_start().starts_with ("< 1 KB") && l.contains ("  2  ")), "{}", detailed);
+        assert! (detailed.lines().any (|l| l.trim_start().starts_with ("1 MB - 1 GB") && l.contains ("  1  ")), "{}", detailed);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  group_sorts_apply_per_category
+    //
+    //  Verify /Group:ext re-sorts a categorized section by its configured
+    //  key (images by size here), leaves uncategorized sections in name
+    //  order, and an explicit /O keeps the listing-wide order.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn group_sorts_apply_per_category() {
+        use crate::command_line::SortDirection;
+        use crate::file_info::FILE_ATTRIBUTE_ARCHIVE;
+
+        let sized = |name: &str, size: u64, age: u64| FileInfo {
+            last_write_time: CREATED - age * SECOND,
+            ..make_file (name, FILE_ATTRIBUTE_ARCHIVE, size)
+        };
+
+        // Already in the default name order, as sort_files leaves them
+        let matches = vec![
+            sized ("a.jpg", 300, 3),
+            sized ("a.log", 300, 3),
+            sized ("b.jpg", 100, 1),
+            sized ("b.log", 100, 1),
+            sized ("c.jpg", 200, 2),
+        ];
+
+        let sorted_labels = |cmd: &CommandLine, config: &Config| -> Vec<Vec<String>> {
+            let mut groups = group_by_extension (&matches);
+            apply_group_sorts (&mut groups, &matches, cmd, config);
+            groups.iter()
+                .map (|g| g.indices.iter().map (|&i| matches[i].file_name.to_string_lossy().into_owned()).collect())
+                .collect()
+        };
+
+        let cmd = CommandLine::parse_from (["/Group:ext"]).unwrap();
+
+        // Built-in default: images by date, oldest first
+        let config = Config::new();
+        assert_eq! (sorted_labels (&cmd, &config), vec![
+            vec!["a.jpg", "c.jpg", "b.jpg"],
+            vec!["a.log", "b.log"],
+        ]);
+
+        // groupsort:img=s applies to the image section only
+        let mut config = Config::new();
+        config.group_sorts.insert ("img".into(), (SortOrder::Size, SortDirection::Ascending));
+        assert_eq! (sorted_labels (&cmd, &config), vec![
+            vec!["b.jpg", "c.jpg", "a.jpg"],
+            vec!["a.log", "b.log"],
+        ]);
+
+        config.group_sorts.insert ("img".into(), (SortOrder::Size, SortDirection::Descending));
+        assert_eq! (sorted_labels (&cmd, &config)[0], vec!["a.jpg", "c.jpg", "b.jpg"]);
+
+        // An explicit /O keeps every section in the listing order
+        let cmd = CommandLine::parse_from (["/Group:ext", "/O:N"]).unwrap();
+        assert_eq! (sorted_labels (&cmd, &config)[0], vec!["a.jpg", "b.jpg", "c.jpg"]);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  name_column_aligns_across_size_widths
+    //
+    //  Verify /NameCol starts names at the same column in directories
+    //  whose byte-size columns differ in width, both at a fixed column
+    //  and in auto mode, while the default layout stays ragged.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn name_column_aligns_across_size_widths() {
+        use crate::file_info::FILE_ATTRIBUTE_ARCHIVE;
+
+        let mut cfg = Config::new();
+        cfg.initialize (0x07);
+        let cfg = Arc::new (cfg);
+
+        let make_dir = |name: &str, size: u64| {
+            let mut di = DirectoryInfo::new (std::path::PathBuf::from ("C:\\test"), "*".to_string());
+            let mut file_info = make_file (name, FILE_ATTRIBUTE_ARCHIVE, 10);
+            file_info.file_size = size;
+            di.largest_file_size = size;
+            di.matches.push (file_info);
+            di
+        };
+        let dirs = [make_dir ("huge.bin", 12_345_678_901), make_dir ("tiny.txt", 7), make_dir ("mid.dat", 40_000)];
+
+        let name_starts = |args: &[&str]| -> Vec<usize> {
+            let cmd = CommandLine::parse_from (args.iter().copied()).unwrap();
+            let mut floor = 0;
+            dirs.iter().map (|di| {
+                let mut console = Console::new_for_testing (Arc::clone (&cfg));
+                console.set_color_mode (crate::command_line::ColorMode::Never);
+                display_file_results (&mut console, &cmd, &cfg, di, false, &mut floor);
+                let output = console.take_test_buffer();
+                let name = di.matches[0].file_name.to_string_lossy().into_owned();
+                output.find (&name).expect ("name in row")
+            }).collect()
+        };
+
+        let ragged = name_starts (&["/Size:Bytes", "/NoCloud"]);
+        assert_ne! (ragged[0], ragged[1]);
+
+        let auto = name_starts (&["/Size:Bytes", "/NoCloud", "/NameCol"]);
+        assert_eq! (auto, vec![ragged[0]; 3]);
+
+        let fixed = name_starts (&["/Size:Bytes", "/NoCloud", "/NameCol:60"]);
+        assert_eq! (fixed, vec![60; 3]);
+
+        // A fixed column narrower than the metadata never overlaps it
+        let narrow = name_starts (&["/Size:Bytes", "/NoCloud", "/NameCol:5"]);
+        assert_eq! (narrow, ragged);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  align_name_column_tracks_run_floor
+    //
+    //  Verify auto mode only ever moves the name column right, and a
+    //  fixed column is a minimum.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn align_name_column_tracks_run_floor() {
+        let mut floor = 0;
+        assert_eq! (align_name_column (None, 40, &mut floor), 40);
+        assert_eq! (floor, 0);
+
+        assert_eq! (align_name_column (Some (NameColumn::Auto), 40, &mut floor), 40);
+        assert_eq! (align_name_column (Some (NameColumn::Auto), 45, &mut floor), 45);
+        assert_eq! (align_name_column (Some (NameColumn::Auto), 38, &mut floor), 45);
+
+        assert_eq! (align_name_column (Some (NameColumn::Fixed (50)), 38, &mut floor), 50);
+        assert_eq! (align_name_column (Some (NameColumn::Fixed (30)), 38, &mut floor), 38);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  auto_attributes_blank_ordinary_entries
+    //
+    //  Verify /Attr:auto blanks the column for archive-only files and plain
+    //  directories at full width, while a hidden file shows its flags.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn auto_attributes_blank_ordinary_entries() {
+        use crate::file_info::{FILE_ATTRIBUTE_ARCHIVE, FILE_ATTRIBUTE_DIRECTORY, FILE_ATTRIBUTE_HIDDEN};
+
+        let mut cfg = Config::new();
+        cfg.initialize (0x07);
+        let cfg = Arc::new (cfg);
+
+        let render = |attrs: u32, columns: AttributeColumn| -> String {
+            let mut console = Console::new_for_testing (Arc::clone (&cfg));
+            console.set_color_mode (crate::command_line::ColorMode::Never);
+            display_attributes (&mut console, &cfg, attrs, columns);
+            console.take_test_buffer()
+        };
+
+        let auto = CommandLine::parse_from (["/Attr:auto"]).unwrap().attribute_column();
+
+        assert_eq! (render (FILE_ATTRIBUTE_ARCHIVE, auto), " ".repeat (9));
+        assert_eq! (render (FILE_ATTRIBUTE_DIRECTORY, auto), " ".repeat (9));
+        assert_eq! (render (FILE_ATTRIBUTE_ARCHIVE | FILE_ATTRIBUTE_HIDDEN, auto), "-H-A-----");
+        assert_eq! (render (FILE_ATTRIBUTE_ARCHIVE, AttributeColumn::default()), "---A-----");
+
+        let auto_ls = CommandLine::parse_from (["/Attr:auto", "/AttrStyle:ls"]).unwrap().attribute_column();
+        assert_eq! (render (FILE_ATTRIBUTE_DIRECTORY, auto_ls), " ".repeat (10));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  mark_interleaves_and_marks_directories
+    //
+    //  Verify /Mark sorts a directory in among files by name and prefixes
+    //  it with DIRECTORY_MARKER, while the default listing groups it first
+    //  unmarked.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn mark_interleaves_and_marks_directories() {
+        use crate::file_info::{FILE_ATTRIBUTE_ARCHIVE, FILE_ATTRIBUTE_DIRECTORY};
+
+        let mut cfg = Config::new();
+        cfg.initialize (0x07);
+        let cfg = Arc::new (cfg);
+
+        let render = |args: &[&str]| -> Vec<String> {
+            let mut console = Console::new_for_testing (Arc::clone (&cfg));
+            console.set_color_mode (crate::command_line::ColorMode::Never);
+
+            let cmd = CommandLine::parse_from (args.iter().copied()).unwrap();
+            let mut di = DirectoryInfo::new (std::path::PathBuf::from ("C:\\src"), "*".to_string());
+            di.matches.push (make_file ("gamma.txt", FILE_ATTRIBUTE_ARCHIVE, 10));
+            di.matches.push (make_file ("beta",      FILE_ATTRIBUTE_DIRECTORY, 10));
+            di.matches.push (make_file ("alpha.txt", FILE_ATTRIBUTE_ARCHIVE, 10));
+            di.largest_file_size = 10;
+
+            crate::file_comparator::sort_files (&mut di.matches, &cmd, false);
+            display_file_results (&mut console, &cmd, &cfg, &di, false, &mut 0);
+            console.take_test_buffer().lines().map (|l| l.trim_end().to_string()).collect()
+        };
+
+        let marked = render (&["/NoCloud", "/Mark"]);
+        assert_eq! (marked.len(), 3);
+        assert! (marked[0].ends_with (" alpha.txt"));
+        assert! (marked[1].ends_with (&format! (" {}beta", DIRECTORY_MARKER)));
+        assert! (marked[2].ends_with (" gamma.txt"));
+
+        let grouped = render (&["/NoCloud"]);
+        assert! (grouped[0].ends_with (" beta"));
+        assert! (!grouped[0].contains (DIRECTORY_MARKER));
+        assert! (grouped[1].ends_with (" alpha.txt"));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  on_disk_size_replaces_logical_size
+    //
+    //  Verify a simulated sparse file shows its logical size normally, and
+    //  its marked on-disk size (right-aligned in the same column) once
+    //  /OnDisk has read one; an unsparse neighbor keeps a plain size.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn on_disk_size_replaces_logical_size() {
+        use crate::file_info::{FILE_ATTRIBUTE_ARCHIVE, FILE_ATTRIBUTE_SPARSE_FILE};
+
+        let mut cfg = Config::new();
+        cfg.initialize (0x07);
+        let cfg = Arc::new (cfg);
+
+        let render = |on_disk: Option<u64>| -> Vec<String> {
+            let mut console = Console::new_for_testing (Arc::clone (&cfg));
+            console.set_color_mode (crate::command_line::ColorMode::Never);
+
+            let cmd = CommandLine::parse_from (["/NoCloud", "/OnDisk"]).unwrap();
+            let mut di = DirectoryInfo::new (std::path::PathBuf::from ("C:\\vm"), "*".to_string());
+
+            let mut sparse = make_file ("disk.vhdx", FILE_ATTRIBUTE_SPARSE_FILE, 10);
+            sparse.file_size    = 10_000_000;
+            sparse.on_disk_size = on_disk;
+            di.matches.push (sparse);
+            di.matches.push (make_file ("notes.txt", FILE_ATTRIBUTE_ARCHIVE, 10));
+            di.largest_file_size = 10_000_000;
+
+            display_file_results (&mut console, &cmd, &cfg, &di, false, &mut 0);
+            console.take_test_buffer().lines().map (str::to_string).collect()
+        };
+
+        let logical = render (None);
+        assert! (logical[0].contains ("  10,000,000 "), "{}", logical[0]);
+        assert! (!logical[0].contains (ON_DISK_MARKER));
+
+        let on_disk = render (Some (4_096));
+        assert! (on_disk[0].contains (&format! (" {}     4,096 ", ON_DISK_MARKER)), "{}", on_disk[0]);
+        assert! (!on_disk[0].contains ("10,000,000"));
+        assert_eq! (on_disk[0].find ("disk.vhdx"), logical[0].find ("disk.vhdx"));
+        assert! (!on_disk[1].contains (ON_DISK_MARKER));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  time_both_appends_relative_age
+    //
+    //  Verify /T:both renders the absolute date/time followed by the
+    //  parenthetical age, padded to the widened column.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn time_both_appends_relative_age() {
+        const DAY: u64 = 86_400 * SECOND;
+
+        let mut cfg = Config::new();
+        cfg.initialize (0x07);
+        let cfg = Arc::new (cfg);
+
+        let mut console = Console::new_for_testing (Arc::clone (&cfg));
+        console.set_color_mode (crate::command_line::ColorMode::Never);
+
+        let pattern = "yyyy-MM-dd HH:mm";
+        display_date_and_time (&mut console, CREATED, Some (pattern), Some (CREATED + 3 * DAY));
+        let output = console.take_test_buffer();
+
+        let (date, time) = date_format::filetime_to_local (CREATED)
+            .and_then (|st| date_format::format_system_time (&st, pattern))
+            .unwrap();
+        assert_eq! (output, format! ("{}{} (3d ago)   ", date, time));
+
+        let cmd = CommandLine::parse_from (["/DateFmt:yyyy-MM-dd HH:mm", "/T:both"]).unwrap();
+        assert_eq! (output.chars().count(), date_time_column_width (&cmd));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  no_color_icons_emit_glyph_for_every_entry
+    //
+    //  Verify that under /NoColor with icons active (glyphs_required),
+    //  entries whose icons are suppressed still get a glyph before the
+    //  name, so directories and files stay distinguishable.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn no_color_icons_emit_glyph_for_every_entry() {
+        use crate::file_info::{FILE_ATTRIBUTE_ARCHIVE, FILE_ATTRIBUTE_DIRECTORY};
+
+        let mut cfg = Config::new();
+        cfg.initialize (0x07);
+        cfg.extension_icons.insert (".txt".to_string(), '\0');
+        cfg.dir_icons_off   = true;
+        cfg.glyphs_required = true;
+        let cfg = Arc::new (cfg);
+
+        let mut console = Console::new_for_testing (Arc::clone (&cfg));
+        console.set_color_mode (crate::command_line::ColorMode::Never);
+
+        let cmd = CommandLine::parse_from (["/NoColor", "/Icons", "/NoCloud"]).unwrap();
+        let mut di = DirectoryInfo::new (std::path::PathBuf::from ("C:\\work"), "*".to_string());
+        di.matches.push (make_file ("src",       FILE_ATTRIBUTE_DIRECTORY, 10));
+        di.matches.push (make_file ("notes.txt", FILE_ATTRIBUTE_ARCHIVE, 10));
+        di.matches.push (make_file ("main.rs",   FILE_ATTRIBUTE_ARCHIVE, 10));
+
+        display_file_results (&mut console, &cmd, &cfg, &di, true, &mut 0);
+        let output = console.take_test_buffer();
+
+        for (line, name) in output.lines().zip (["src", "notes.txt", "main.rs"]) {
+            let before: Vec<char> = line[..line.find (name).unwrap()].chars().collect();
+            assert! (before.len() >= 2, "{line}");
+            assert_eq! (before[before.len() - 1], ' ', "{line}");
+            assert_ne! (before[before.len() - 2], ' ', "no glyph before {name}: {line}");
+        }
+
+        assert! (output.contains (&format! ("{} src", cfg.icon_directory_default)), "{output}");
+        assert! (output.contains (&format! ("{} notes.txt", cfg.icon_file_default)), "{output}");
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  limit_shows_first_entries_and_more_note
+    //
+    //  Verify /Limit:3 on a five-entry directory prints exactly the first
+    //  three entries in sorted order, then a note counting the other two.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn limit_shows_first_entries_and_more_note() {
+        use crate::file_info::FILE_ATTRIBUTE_ARCHIVE;
+
+        let mut cfg = Config::new();
+        cfg.initialize (0x07);
+        let cfg = Arc::new (cfg);
+
+        let mut console = Console::new_for_testing (Arc::clone (&cfg));
+        console.set_color_mode (crate::command_line::ColorMode::Never);
+
+        let cmd = CommandLine::parse_from (["/NoCloud", "/Limit:3"]).unwrap();
+        let mut di = DirectoryInfo::new (std::path::PathBuf::from ("C:\\big"), "*".to_string());
+        for name in ["a.txt", "b.txt", "c.txt", "d.txt", "e.txt"] {
+            di.matches.push (make_file (name, FILE_ATTRIBUTE_ARCHIVE, 10));
+        }
+
+        display_file_results (&mut console, &cmd, &cfg, &di, false, &mut 0);
+        let output = console.take_test_buffer();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq! (lines.len(), 4, "{output}");
+        assert! (lines[0].ends_with ("a.txt") && lines[1].ends_with ("b.txt") && lines[2].ends_with ("c.txt"), "{output}");
+        assert_eq! (lines[3], "\u{2026} and 2 more");
+
+        let cmd = CommandLine::parse_from (["/NoCloud", "/Limit:5"]).unwrap();
+        display_file_results (&mut console, &cmd, &cfg, &di, false, &mut 0);
+        let output = console.take_test_buffer();
+        assert_eq! (output.lines().count(), 5, "{output}");
+        assert! (!output.contains ("more"));
     }
 }
@@ -0,0 +1,365 @@
+// template.rs — Output template displayer (/Fmt)
+//
+// Renders one line per entry from a parsed FormatTemplate: literal text in
+// the default color, each placeholder padded to its width and colored like
+// the matching column of the normal listing.  Like bare mode there are no
+// headers or summaries, and names are full paths when recursing.
+
+use std::sync::Arc;
+
+use crate::cloud_status;
+use crate::command_line::{CommandLine, SizeFormat};
+use crate::config::{Attribute, Config};
+use crate::console::Console;
+use crate::date_format;
+use crate::directory_info::DirectoryInfo;
+use crate::drive_info::DriveInfo;
+use crate::file_info::{build_attribute_display_string, FileInfo};
+use crate::format_template::{FormatTemplate, Placeholder, Segment};
+use crate::listing_totals::ListingTotals;
+use crate::owner;
+
+use super::common::{cloud_status_attribute, format_abbreviated_size, format_compact_size, format_number_with_separators, highlight_text_attr};
+use super::normal::get_time_field_for_display;
+use super::{DirectoryLevel, ResultsDisplayer};
+
+
+
+
+
+/// Date/time pattern for {date} and {time} without /DateFmt.
+const DEFAULT_DATE_TIME_PATTERN: &str = "MM/dd/yyyy hh:mm tt";
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Template displayer — one /Fmt line per entry.
+pub struct TemplateDisplayer {
+    console:  Console,
+    cmd:      Arc<CommandLine>,
+    config:   Arc<Config>,
+    template: FormatTemplate,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl TemplateDisplayer
+//
+//  Template displayer construction, console access, and field values.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl TemplateDisplayer {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  new
+    //
+    //  Create a new TemplateDisplayer.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn new(console: Console, cmd: Arc<CommandLine>, config: Arc<Config>, template: FormatTemplate) -> Self {
+        TemplateDisplayer { console, cmd, config, template }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  into_console
+    //
+    //  Consume the displayer and return the Console.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn into_console(self) -> Console {
+        self.console
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  console_mut
+    //
+    //  Get a mutable reference to the console.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn console_mut(&mut self) -> &mut Console {
+        &mut self.console
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  field_value
+    //
+    //  The text and color of one placeholder for one entry.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn field_value(&self, placeholder: Placeholder, dir_info: &DirectoryInfo, fi: &FileInfo, in_sync_root: bool) -> (u16, String) {
+        let color = |attr: Attribute| self.config.attributes[attr as usize];
+
+        match placeholder {
+            Placeholder::Name => {
+                let style = self.config.get_display_style_for_file (fi);
+                let name  = if self.cmd.recurse {
+                    dir_info.dir_path.join (&fi.file_name).to_string_lossy().into_owned()
+                } else {
+                    fi.file_name.to_string_lossy().into_owned()
+                };
+                (highlight_text_attr (&self.config, &self.cmd, fi, style.text_attr), name)
+            }
+            Placeholder::Size => (color (Attribute::Size), format_template_size (fi, self.cmd.resolved_size_format())),
+            Placeholder::Date | Placeholder::Time => {
+                let filetime     = get_time_field_for_display (fi, self.cmd.time_field);
+                let (date, time) = format_template_date_time (filetime, self.cmd.date_format.as_deref());
+                if placeholder == Placeholder::Date {
+                    (color (Attribute::Date), date)
+                } else {
+                    (color (Attribute::Time), time)
+                }
+            }
+            Placeholder::Attrs => (color (Attribute::FileAttributePresent), build_attribute_display_string (fi.file_attributes)),
+            Placeholder::Owner => {
+                let full_path = dir_info.dir_path.join (&fi.file_name);
+                let owner     = if self.cmd.owner_sid {
+                    owner::get_file_owner_sid (full_path.as_os_str())
+                } else {
+                    owner::get_file_owner (full_path.as_os_str())
+                };
+                (color (Attribute::Owner), owner)
+            }
+            Placeholder::Cloud => {
+                let status = cloud_status::get_cloud_status (fi.file_attributes, in_sync_root);
+                (color (cloud_status_attribute (status)), status.symbol().to_string())
+            }
+        }
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl ResultsDisplayer for TemplateDisplayer
+//
+//  Template lines per entry; no recursive summary.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl ResultsDisplayer for TemplateDisplayer {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  display_results
+    //
+    //  Write one template line per entry.  The sync-root check is only
+    //  made when the template shows {cloud}.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn display_results(&mut self, _drive_info: &DriveInfo, dir_info: &DirectoryInfo, _level: DirectoryLevel) {
+        let default_attr = self.config.attributes[Attribute::Default as usize];
+        let in_sync_root = self.template.uses (Placeholder::Cloud)
+            && !self.cmd.no_cloud
+            && cloud_status::is_under_sync_root (dir_info.dir_path.as_os_str());
+
+        for fi in &dir_info.matches {
+            for segment in &self.template.segments {
+                match segment {
+                    Segment::Literal (text) => {
+                        self.console.writef (default_attr, format_args! ("{}", text));
+                    }
+                    Segment::Field (field) => {
+                        let (attr, value) = self.field_value (field.placeholder, dir_info, fi, in_sync_root);
+                        self.console.writef (attr, format_args! ("{}", field.pad (&value)));
+                    }
+                }
+            }
+
+            self.console.writef_line (default_attr, format_args! (""));
+        }
+
+        let _ = self.console.flush();
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  display_recursive_summary
+    //
+    //  Template mode doesn't display a recursive summary.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn display_recursive_summary(&mut self, _dir_info: &DirectoryInfo, _totals: &ListingTotals) {
+        // No summary in template mode
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  format_template_size
+//
+//  {size}: "<DIR>" for directories, otherwise grouped bytes, or
+//  abbreviated with --Size=Auto.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn format_template_size(fi: &FileInfo, size_format: SizeFormat) -> String {
+    if fi.is_directory() {
+        return "<DIR>".to_string();
+    }
+
+    match size_format {
+        SizeFormat::Auto    => format_abbreviated_size (fi.displayed_size()),
+        SizeFormat::Compact => format_compact_size (fi.displayed_size()).trim_start().to_string(),
+        _                   => format_number_with_separators (fi.displayed_size()),
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  format_template_date_time
+//
+//  {date} and {time}: the halves of the /DateFmt pattern (or the normal
+//  listing's MM/dd/yyyy hh:mm tt), or "?" if the time can't be converted.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn format_template_date_time(filetime_u64: u64, date_format: Option<&str>) -> (String, String) {
+    let pattern = date_format.unwrap_or (DEFAULT_DATE_TIME_PATTERN);
+
+    date_format::filetime_to_local (filetime_u64)
+        .and_then (|st| date_format::format_system_time (&st, pattern))
+        .map (|(date, time)| (date.trim_end().to_string(), time))
+        .unwrap_or_else (|| ("?".to_string(), "?".to_string()))
+}
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::file_info::{FILE_ATTRIBUTE_ARCHIVE, FILE_ATTRIBUTE_DIRECTORY};
+    use crate::test_support::make_file;
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  render_template
+    //
+    //  Renders a directory's matches through TemplateDisplayer as plain
+    //  text, with the template given as a /Fmt switch.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn render_template(args: &[&str], files: Vec<FileInfo>) -> String {
+        let mut cfg = Config::new();
+        cfg.initialize (0x07);
+        let cfg = Arc::new (cfg);
+
+        let mut console = Console::new_for_testing (Arc::clone (&cfg));
+        console.set_color_mode (crate::command_line::ColorMode::Never);
+
+        let cmd      = Arc::new (CommandLine::parse_from (args.iter().copied()).unwrap());
+        let template = cmd.format_template.clone().unwrap();
+        let mut di   = DirectoryInfo::new (std::path::PathBuf::from ("C:\\test"), "*".to_string());
+        di.matches = files;
+
+        let drive_info = DriveInfo {
+            unc_path:         std::path::PathBuf::new(),
+            root_path:        std::path::PathBuf::from ("C:\\"),
+            volume_name:      String::new(),
+            file_system_name: "NTFS".to_string(),
+            volume_type:      crate::drive_info::DRIVE_FIXED,
+            is_unc_path:      false,
+            remote_name:      String::new(),
+            volume_serial:    None,
+            total_bytes:      None,
+        };
+
+        let mut displayer = TemplateDisplayer::new (console, cmd, cfg, template);
+        displayer.display_results (&drive_info, &di, DirectoryLevel::Initial);
+        displayer.console_mut().take_test_buffer()
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  template_renders_sample_file
+    //
+    //  Verify "{size:>12} {name}" right-aligns the grouped size before the
+    //  name, and directories show <DIR>.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn template_renders_sample_file() {
+        let output = render_template (&["/Fmt:{size:>12} {name}"], vec![
+            make_file ("report.txt", FILE_ATTRIBUTE_ARCHIVE, 1_234_567),
+            make_file ("src",        FILE_ATTRIBUTE_DIRECTORY, 0),
+        ]);
+
+        assert_eq! (output, "   1,234,567 report.txt\n       <DIR> src\n");
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  template_keeps_literals_and_widths
+    //
+    //  Verify literal text, escaped braces, a left-padded name, and the
+    //  attribute string all land in place.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn template_keeps_literals_and_widths() {
+        let output = render_template (&["/Fmt:{{{attrs}}} {name:<8}|"], vec![
+            make_file ("a.txt", FILE_ATTRIBUTE_ARCHIVE, 1),
+        ]);
+
+        let attrs = build_attribute_display_string (FILE_ATTRIBUTE_ARCHIVE);
+        assert_eq! (output, format! ("{{{}}} a.txt   |\n", attrs));
+    }
+}
@@ -20,24 +20,33 @@
 use crate::listing_totals::ListingTotals;
 use crate::owner;
 use crate::path_ellipsis;
+use crate::since_last::{Clock, SystemClock};
 use crate::tree_connector_state::TreeConnectorState;
 
 use super::common::{
+    cloud_column_shown,
+    cloud_column_width,
+    display_breadcrumb,
     display_cloud_status_symbol,
     display_drive_header,
     display_empty_directory_message,
     display_listing_summary,
     display_path_header,
+    format_name_suffix,
     format_number_with_separators,
     get_string_length_of_max_file_size,
+    highlight_text_attr,
 };
 use super::normal::{
     compute_available_width_for_target,
+    date_time_column_width,
     display_attributes,
     display_date_and_time,
     display_file_owner,
     display_file_size,
+    display_time_delta,
     get_time_field_for_display,
+    DELTA_COLUMN_WIDTH,
 };
 #[cfg(debug_assertions)]
 use super::normal::display_raw_attributes;
@@ -74,6 +83,7 @@ pub struct TreeDisplayer {
     cmd:                         Arc<CommandLine>,
     config:                      Arc<Config>,
     icons_active:                bool,
+    skip_drive_header:           bool,
 
     // Per-directory state set by begin_directory()
     largest_file_size_str_len:   usize,
@@ -111,6 +121,7 @@ pub fn new (console: Console, cmd: Arc<CommandLine>, config: Arc<Config>, icons_
             cmd,
             config,
             icons_active,
+            skip_drive_header:         false,
             largest_file_size_str_len: 0,
             in_sync_root:              false,
             owners:                    Vec::new(),
@@ -154,6 +165,24 @@ pub fn console_mut (&mut self) -> &mut Console {
 
 
 
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  skip_drive_header
+    //
+    //  Omit the drive header for this group's listing: an earlier group
+    //  on the same volume already printed it.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn skip_drive_header (&mut self) {
+        self.skip_drive_header = true;
+        self.inner.skip_drive_header();
+    }
+
+
+
+
+
     ////////////////////////////////////////////////////////////////////////////
     //
     //  display_tree_root_header
@@ -164,8 +193,16 @@ pub fn console_mut (&mut self) -> &mut Console {
     ////////////////////////////////////////////////////////////////////////////
 
     pub fn display_tree_root_header (&mut self, drive_info: &DriveInfo, dir_info: &DirectoryInfo) {
-        let console = self.inner.console_mut();
-        display_drive_header (console, drive_info);
+        let up_levels = self.cmd.up_levels;
+        let console   = self.inner.console_mut();
+        if !self.skip_drive_header {
+            display_drive_header (console, drive_info);
+        }
+
+        if let Some (levels) = up_levels {
+            display_breadcrumb (console, &dir_info.dir_path, levels);
+        }
+
         display_path_header (console, dir_info);
     }
 
@@ -183,6 +220,11 @@ pub fn display_tree_root_header (&mut self, drive_info: &DriveInfo, dir_info: &D
     ////////////////////////////////////////////////////////////////////////////
 
     pub fn display_tree_empty_root_message (&mut self, dir_info: &DirectoryInfo) {
+        // /Quiet, /FailOnEmpty, and /IgnoreEmpty drop the empty-directory chatter entirely
+        if !self.cmd.shows_empty_message() {
+            return;
+        }
+
         let console = self.inner.console_mut();
         display_empty_directory_message (console, dir_info);
         console.puts (Attribute::Default, "");
@@ -226,12 +268,12 @@ pub fn display_tree_root_summary (&mut self) {
 
     pub fn begin_directory (&mut self, dir_info: &DirectoryInfo) {
         self.largest_file_size_str_len = get_string_length_of_max_file_size (dir_info.largest_file_size);
-        self.in_sync_root             = cloud_status::is_under_sync_root (dir_info.dir_path.as_os_str());
+        self.in_sync_root             = !self.cmd.no_cloud && cloud_status::is_under_sync_root (dir_info.dir_path.as_os_str());
         self.owners.clear();
         self.max_owner_len = 0;
 
         if self.cmd.show_owner {
-            let (owners, max_len) = owner::get_file_owners (dir_info);
+            let (owners, max_len) = owner::get_file_owners (dir_info, self.cmd.owner_sid);
             self.owners           = owners;
             self.max_owner_len    = max_len;
         }
@@ -303,22 +345,30 @@ pub fn display_single_entry (
     ) {
         let console     = self.inner.console_mut();
         let style       = self.config.get_display_style_for_file (file_info);
-        let text_attr   = style.text_attr;
+        let text_attr   = highlight_text_attr (&self.config, &self.cmd, file_info, style.text_attr);
         let size_format = self.cmd.resolved_size_format();
 
         // Date and time
         let time_value = get_time_field_for_display (file_info, self.cmd.time_field);
-        display_date_and_time (console, time_value);
+        let age_now    = self.cmd.time_with_age.then (|| SystemClock.now());
+        display_date_and_time (console, time_value, self.cmd.date_format.as_deref(), age_now);
+
+        // Creation → last-write delta (if --Delta)
+        if self.cmd.show_delta {
+            display_time_delta (console, &self.config, file_info);
+        }
 
         // Attributes
-        display_attributes (console, &self.config, file_info.file_attributes);
+        display_attributes (console, &self.config, file_info.file_attributes, self.cmd.attribute_column());
 
         // File size or <DIR>
         display_file_size (console, file_info, self.largest_file_size_str_len, size_format);
 
-        // Cloud status symbol
-        let cloud = cloud_status::get_cloud_status (file_info.file_attributes, self.in_sync_root);
-        display_cloud_status_symbol (console, &self.config, cloud, self.icons_active);
+        // Cloud status symbol (omitted entirely under /NoCloud, or /NoCloud:auto outside a sync root)
+        if cloud_column_shown (&self.cmd, self.in_sync_root) {
+            let cloud = cloud_status::get_cloud_status (file_info.file_attributes, self.in_sync_root);
+            display_cloud_status_symbol (console, &self.config, cloud, self.icons_active);
+        }
 
         // Debug attribute display (debug builds only, gated by --debug)
         #[cfg(debug_assertions)]
@@ -363,13 +413,19 @@ pub fn display_single_entry (
             if self.cmd.ellipsize.unwrap_or (true) {
                 let available_width = compute_available_width_for_target (
                     console.width() as usize,
+                    date_time_column_width (&self.cmd),
                     self.largest_file_size_str_len,
                     self.cmd.resolved_size_format(),
                     self.icons_active,
+                    cloud_column_shown (&self.cmd, self.in_sync_root),
+                    self.cmd.show_delta,
+                    false, // show_percent: /Pct is rejected with /Tree
+                    self.cmd.attribute_column(),
                     #[cfg(debug_assertions)]
                     self.cmd.debug,
                     self.cmd.show_owner,
                     self.max_owner_len,
+                    0, // hash_width: --Hash is rejected with --Tree
                     prefix.len(),
                     name_str.len(),
                 );
@@ -385,7 +441,15 @@ pub fn display_single_entry (
                 console.writef_line (text_attr, format_args! ("{}", file_info.reparse_target));
             }
         } else {
-            console.writef_line (text_attr, format_args! ("{}", name_str));
+            // Stream count (/HasStreams) and matched spec (/ShowMask) follow the name
+            let suffix = format_name_suffix (&self.cmd, file_info);
+
+            if suffix.is_empty() {
+                console.writef_line (text_attr, format_args! ("{}", name_str));
+            } else {
+                console.writef (text_attr, format_args! ("{}", name_str));
+                console.writef_line (self.config.attributes[Attribute::Information as usize], format_args! ("{}", suffix));
+            }
         }
 
         // Alternate data streams (if --streams and this entry has them)
@@ -417,6 +481,9 @@ fn display_file_streams_with_tree_prefix (
         let max_file_size       = self.largest_file_size_str_len.max (5);
         let owner_padding       = if self.max_owner_len > 0 { self.max_owner_len + 1 } else { 0 };
         let file_name           = file_info.file_name.to_string_lossy();
+        let metadata_width      = date_time_column_width (&self.cmd)
+                                + self.cmd.attribute_column().width()
+                                + if self.cmd.show_delta { DELTA_COLUMN_WIDTH } else { 0 };
 
         let console   = self.inner.console_mut();
         let default_c = self.config.attributes[Attribute::Default as usize];
@@ -425,13 +492,13 @@ fn display_file_streams_with_tree_prefix (
         let tree_c    = self.config.attributes[Attribute::TreeConnector as usize];
 
         // Cloud status gap: leading space + symbol/icon + trailing space
-        let cloud_gap = if self.icons_active { "    " } else { "   " };
+        let cloud_gap = " ".repeat (cloud_column_width (cloud_column_shown (&self.cmd, self.in_sync_root), self.icons_active));
 
         for si in &file_info.streams {
             let formatted_size = format_number_with_separators (si.size as u64);
 
-            // 30 chars indentation (date/time 21 + attributes 9)
-            console.writef (default_c, format_args! ("{:30}", ""));
+            // Metadata indentation (date/time + delta if --Delta + attributes 9)
+            console.writef (default_c, format_args! ("{:width$}", "", width = metadata_width));
             console.writef (size_c, format_args! ("  {:>width$}", formatted_size, width = max_file_size));
             console.writef (default_c, format_args! ("{}  {:width$}", cloud_gap, "", width = owner_padding));
 
@@ -489,7 +556,7 @@ fn display_results (&mut self, drive_info: &DriveInfo, dir_info: &DirectoryInfo,
 
     fn display_recursive_summary (&mut self, dir_info: &DirectoryInfo, totals: &ListingTotals) {
         let console = self.inner.console_mut();
-        display_listing_summary (console, dir_info, totals);
+        display_listing_summary (console, dir_info, totals, self.cmd.verbosity);
     }
 }
 
@@ -500,7 +567,6 @@ fn display_recursive_summary (&mut self, dir_info: &DirectoryInfo, totals: &List
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::ffi::OsString;
     use std::path::PathBuf;
     use std::sync::Arc;
 
@@ -509,7 +575,9 @@ mod tests {
     use crate::console::Console;
     use crate::directory_info::DirectoryInfo;
     use crate::drive_info::{DriveInfo, DRIVE_FIXED};
-    use crate::file_info::{FileInfo, FILE_ATTRIBUTE_DIRECTORY, FILE_ATTRIBUTE_ARCHIVE};
+    use crate::ansi_codes::write_sgr;
+    use crate::file_info::{FileInfo, FILE_ATTRIBUTE_DIRECTORY, FILE_ATTRIBUTE_ARCHIVE, FILE_ATTRIBUTE_READONLY};
+    use crate::test_support::make_file;
     use crate::tree_connector_state::TreeConnectorState;
 
 
@@ -539,36 +607,8 @@ fn make_test_drive_info() -> DriveInfo {
             volume_type:      DRIVE_FIXED,
             is_unc_path:      false,
             remote_name:      String::new(),
-        }
-    }
-
-
-    fn make_file (name: &str, size: u64) -> FileInfo {
-        FileInfo {
-            file_name:        OsString::from (name),
-            file_attributes:  FILE_ATTRIBUTE_ARCHIVE,
-            file_size:        size,
-            creation_time:    133_500_000_000_000_000,
-            last_write_time:  133_500_000_000_000_000,
-            last_access_time: 133_500_000_000_000_000,
-            reparse_tag:      0,
-            reparse_target:   String::new(),
-            streams:          Vec::new(),
-        }
-    }
-
-
-    fn make_dir (name: &str) -> FileInfo {
-        FileInfo {
-            file_name:        OsString::from (name),
-            file_attributes:  FILE_ATTRIBUTE_DIRECTORY,
-            file_size:        0,
-            creation_time:    133_500_000_000_000_000,
-            last_write_time:  133_500_000_000_000_000,
-            last_access_time: 133_500_000_000_000_000,
-            reparse_tag:      0,
-            reparse_target:   String::new(),
-            streams:          Vec::new(),
+            volume_serial:    None,
+            total_bytes:      None,
         }
     }
 
@@ -627,7 +667,7 @@ fn root_header_contains_directory_of() {
 
         let mut displayer = TreeDisplayer::new (console, cmd, config, false);
         let drive_info    = make_test_drive_info();
-        let dir_info      = make_dir_info ("C:\\TestDir", vec![make_file ("a.txt", 100)]);
+        let dir_info      = make_dir_info ("C:\\TestDir", vec![make_file ("a.txt", FILE_ATTRIBUTE_ARCHIVE, 100)]);
 
         displayer.display_tree_root_header (&drive_info, &dir_info);
         let output = strip_ansi (&displayer.into_console().take_test_buffer());
@@ -658,7 +698,7 @@ fn single_entry_shows_dir_tag() {
         let cmd     = make_test_cmd (&["--Tree"]);
 
         let mut displayer = TreeDisplayer::new (console, cmd, config, false);
-        let dir_info = make_dir_info ("C:\\TestDir", vec![make_dir ("subdir")]);
+        let dir_info = make_dir_info ("C:\\TestDir", vec![make_file ("subdir", FILE_ATTRIBUTE_DIRECTORY, 0)]);
 
         displayer.begin_directory (&dir_info);
 
@@ -694,7 +734,7 @@ fn single_entry_contains_filename() {
         let cmd     = make_test_cmd (&["--Tree"]);
 
         let mut displayer = TreeDisplayer::new (console, cmd, config, false);
-        let dir_info = make_dir_info ("C:\\TestDir", vec![make_file ("hello.txt", 42)]);
+        let dir_info = make_dir_info ("C:\\TestDir", vec![make_file ("hello.txt", FILE_ATTRIBUTE_ARCHIVE, 42)]);
 
         displayer.begin_directory (&dir_info);
 
@@ -732,7 +772,7 @@ fn entry_with_icons_includes_icon_glyph_space() {
         let console_no = make_test_console (Arc::clone (&config));
         let cmd_no     = make_test_cmd (&["--Tree"]);
         let mut disp_no = TreeDisplayer::new (console_no, cmd_no, Arc::clone (&config), false);
-        let dir_info = make_dir_info ("C:\\TestDir", vec![make_file ("test.rs", 100)]);
+        let dir_info = make_dir_info ("C:\\TestDir", vec![make_file ("test.rs", FILE_ATTRIBUTE_ARCHIVE, 100)]);
         disp_no.begin_directory (&dir_info);
         let mut ts_no = TreeConnectorState::new (4);
         ts_no.push (false);
@@ -743,7 +783,7 @@ fn entry_with_icons_includes_icon_glyph_space() {
         let console_yes = make_test_console (Arc::clone (&config));
         let cmd_yes     = make_test_cmd (&["--Tree"]);
         let mut disp_yes = TreeDisplayer::new (console_yes, cmd_yes, config, true);
-        let dir_info2 = make_dir_info ("C:\\TestDir", vec![make_file ("test.rs", 100)]);
+        let dir_info2 = make_dir_info ("C:\\TestDir", vec![make_file ("test.rs", FILE_ATTRIBUTE_ARCHIVE, 100)]);
         disp_yes.begin_directory (&dir_info2);
         let mut ts_yes = TreeConnectorState::new (4);
         ts_yes.push (false);
@@ -778,7 +818,7 @@ fn tree_connector_tee_for_non_last_entry() {
         let cmd     = make_test_cmd (&["--Tree"]);
 
         let mut displayer = TreeDisplayer::new (console, cmd, config, false);
-        let files = vec![make_file ("first.txt", 100), make_file ("second.txt", 200)];
+        let files = vec![make_file ("first.txt", FILE_ATTRIBUTE_ARCHIVE, 100), make_file ("second.txt", FILE_ATTRIBUTE_ARCHIVE, 200)];
         let dir_info = make_dir_info ("C:\\TestDir", files);
 
         displayer.begin_directory (&dir_info);
@@ -817,7 +857,7 @@ fn tree_connector_corner_for_last_entry() {
         let cmd     = make_test_cmd (&["--Tree"]);
 
         let mut displayer = TreeDisplayer::new (console, cmd, config, false);
-        let dir_info = make_dir_info ("C:\\TestDir", vec![make_file ("only.txt", 100)]);
+        let dir_info = make_dir_info ("C:\\TestDir", vec![make_file ("only.txt", FILE_ATTRIBUTE_ARCHIVE, 100)]);
 
         displayer.begin_directory (&dir_info);
 
@@ -854,7 +894,7 @@ fn tree_continuation_pipe_at_depth() {
         let cmd     = make_test_cmd (&["--Tree"]);
 
         let mut displayer = TreeDisplayer::new (console, cmd, config, false);
-        let dir_info = make_dir_info ("C:\\TestDir\\Sub\\Inner", vec![make_file ("deep.txt", 100)]);
+        let dir_info = make_dir_info ("C:\\TestDir\\Sub\\Inner", vec![make_file ("deep.txt", FILE_ATTRIBUTE_ARCHIVE, 100)]);
 
         displayer.begin_directory (&dir_info);
 
@@ -895,7 +935,7 @@ fn custom_indent_changes_prefix_width() {
         let console4 = make_test_console (Arc::clone (&config));
         let cmd4     = make_test_cmd (&["--Tree"]);
         let mut disp4 = TreeDisplayer::new (console4, cmd4, Arc::clone (&config), false);
-        let dir_info = make_dir_info ("C:\\TestDir", vec![make_file ("a.txt", 100)]);
+        let dir_info = make_dir_info ("C:\\TestDir", vec![make_file ("a.txt", FILE_ATTRIBUTE_ARCHIVE, 100)]);
         disp4.begin_directory (&dir_info);
         let mut ts4 = TreeConnectorState::new (4);
         ts4.push (false);
@@ -906,7 +946,7 @@ fn custom_indent_changes_prefix_width() {
         let console8 = make_test_console (Arc::clone (&config));
         let cmd8     = make_test_cmd (&["--Tree", "/TreeIndent=8"]);
         let mut disp8 = TreeDisplayer::new (console8, cmd8, config, false);
-        let dir_info2 = make_dir_info ("C:\\TestDir", vec![make_file ("a.txt", 100)]);
+        let dir_info2 = make_dir_info ("C:\\TestDir", vec![make_file ("a.txt", FILE_ATTRIBUTE_ARCHIVE, 100)]);
         disp8.begin_directory (&dir_info2);
         let mut ts8 = TreeConnectorState::new (8);
         ts8.push (false);
@@ -943,9 +983,9 @@ fn multiple_entries_interleaved_order() {
 
         let mut displayer = TreeDisplayer::new (console, cmd, config, false);
         let files = vec![
-            make_dir ("alpha_dir"),
-            make_file ("beta.txt", 100),
-            make_dir ("gamma_dir"),
+            make_file ("alpha_dir", FILE_ATTRIBUTE_DIRECTORY, 0),
+            make_file ("beta.txt", FILE_ATTRIBUTE_ARCHIVE, 100),
+            make_file ("gamma_dir", FILE_ATTRIBUTE_DIRECTORY, 0),
         ];
         let dir_info = make_dir_info ("C:\\TestDir", files);
         displayer.begin_directory (&dir_info);
@@ -987,7 +1027,7 @@ fn large_file_size_shows_comma_numbers() {
         let cmd     = make_test_cmd (&["--Tree"]);
 
         let mut displayer = TreeDisplayer::new (console, cmd, config, false);
-        let dir_info = make_dir_info ("C:\\TestDir", vec![make_file ("big.dat", 12_345_678)]);
+        let dir_info = make_dir_info ("C:\\TestDir", vec![make_file ("big.dat", FILE_ATTRIBUTE_ARCHIVE, 12_345_678)]);
 
         displayer.begin_directory (&dir_info);
 
@@ -1030,4 +1070,93 @@ fn max_depth_stored_in_command_line() {
         let cmd = CommandLine::parse_from (["--Tree", "/Depth=3"]).unwrap();
         assert_eq! (cmd.max_depth, 3);
     }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  no_cloud_omits_cloud_status_cell
+    //
+    //  Verify /NoCloud removes the cloud status cell (3 columns without
+    //  icons, 4 with icons) rather than rendering it blank, and that
+    //  /NoCloud:auto does the same outside a sync root.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn no_cloud_omits_cloud_status_cell() {
+        for icons_active in [false, true] {
+            let render = |args: &[&str]| {
+                let config    = make_test_config();
+                let console   = make_test_console (Arc::clone (&config));
+                let mut disp  = TreeDisplayer::new (console, make_test_cmd (args), config, icons_active);
+                let dir_info  = make_dir_info ("C:\\TestDir", vec![make_file ("hello.txt", FILE_ATTRIBUTE_ARCHIVE, 42)]);
+                disp.begin_directory (&dir_info);
+                let mut ts = TreeConnectorState::new (4);
+                ts.push (false);
+                disp.display_single_entry (&dir_info.matches[0], &mut ts, true, 0);
+                strip_ansi (&disp.into_console().take_test_buffer())
+            };
+
+            let with_cloud    = render (&["--Tree"]);
+            let without_cloud = render (&["--Tree", "--NoCloud"]);
+            let auto_cloud    = render (&["--Tree", "--NoCloud:auto"]);
+            let cloud_width   = if icons_active { 4 } else { 3 };
+
+            assert_eq! (
+                with_cloud.chars().count() - without_cloud.chars().count(),
+                cloud_width,
+                "with:\n{}\nwithout:\n{}",
+                with_cloud,
+                without_cloud,
+            );
+            assert! (without_cloud.contains ("hello.txt"));
+
+            // C:\TestDir isn't under a sync root, so /NoCloud:auto drops the cell too
+            assert_eq! (auto_cloud, without_cloud);
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  highlight_renders_matching_name_in_highlight_color
+    //
+    //  Verify --Highlight=R renders a read-only file's name in the
+    //  InformationHighlight color, and leaves it alone without the switch.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn highlight_renders_matching_name_in_highlight_color() {
+        let render = |args: &[&str]| {
+            let config   = make_test_config();
+            let console  = make_test_console (Arc::clone (&config));
+            let mut disp = TreeDisplayer::new (console, make_test_cmd (args), config, false);
+            let mut file = make_file ("readonly.txt", FILE_ATTRIBUTE_ARCHIVE, 42);
+            file.file_attributes |= FILE_ATTRIBUTE_READONLY;
+            let dir_info = make_dir_info ("C:\\TestDir", vec![file]);
+            disp.begin_directory (&dir_info);
+            let mut ts = TreeConnectorState::new (4);
+            ts.push (false);
+            disp.display_single_entry (&dir_info.matches[0], &mut ts, true, 0);
+            disp.into_console().take_test_buffer()
+        };
+
+        let config = make_test_config();
+        let mut highlight_sgr = String::new();
+        write_sgr (&mut highlight_sgr, config.attributes[Attribute::InformationHighlight as usize]);
+        let highlighted_name = format! ("{}readonly.txt", highlight_sgr);
+
+        let with_highlight    = render (&["--Tree", "--Highlight=R"]);
+        let without_highlight = render (&["--Tree"]);
+
+        assert! (with_highlight.contains (&highlighted_name), "got:\n{:?}", with_highlight);
+        assert! (!without_highlight.contains (&highlighted_name), "got:\n{:?}", without_highlight);
+    }
 }
@@ -0,0 +1,286 @@
+// tsv.rs — Tab-separated displayer for scripting (--Tsv)
+//
+// Emits one line per entry: name<TAB>size<TAB>mtime<TAB>attrs, with no
+// colors, headers, or summaries, for quick `awk -F'\t'` / `cut` pipelines.
+// Fields are not quoted or escaped: names containing tabs or newlines are
+// out of scope and will produce ambiguous lines.
+
+use std::sync::Arc;
+
+use crate::command_line::CommandLine;
+use crate::console::Console;
+use crate::directory_info::DirectoryInfo;
+use crate::drive_info::DriveInfo;
+use crate::file_info::{build_attribute_display_string, FileInfo};
+use crate::listing_totals::ListingTotals;
+
+use super::normal::get_time_field_for_display;
+use super::{DirectoryLevel, ResultsDisplayer};
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Tab-separated displayer — name, size, time, attributes per line.
+pub struct TsvDisplayer {
+    console: Console,
+    cmd:     Arc<CommandLine>,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl TsvDisplayer
+//
+//  TSV displayer construction and console access.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl TsvDisplayer {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  new
+    //
+    //  Create a new TsvDisplayer.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn new(console: Console, cmd: Arc<CommandLine>) -> Self {
+        TsvDisplayer { console, cmd }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  into_console
+    //
+    //  Consume the displayer and return the Console.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn into_console(self) -> Console {
+        self.console
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  console_mut
+    //
+    //  Get a mutable reference to the console.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn console_mut(&mut self) -> &mut Console {
+        &mut self.console
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl ResultsDisplayer for TsvDisplayer
+//
+//  Tab-separated directory listing; no recursive summary.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl ResultsDisplayer for TsvDisplayer {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  display_results
+    //
+    //  Write one tab-separated line per entry.  Names are full paths when
+    //  recursing (like bare mode), so lines stay unambiguous across dirs.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn display_results(&mut self, _drive_info: &DriveInfo, dir_info: &DirectoryInfo, _level: DirectoryLevel) {
+        for fi in &dir_info.matches {
+            let name = if self.cmd.recurse {
+                dir_info.dir_path.join (&fi.file_name).to_string_lossy().into_owned()
+            } else {
+                fi.file_name.to_string_lossy().into_owned()
+            };
+
+            let line = format_tsv_line (&name, fi, &self.cmd);
+            self.console.write_raw (&line);
+            self.console.write_raw ("\n");
+        }
+
+        let _ = self.console.flush();
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  display_recursive_summary
+    //
+    //  TSV mode doesn't display a recursive summary.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn display_recursive_summary(&mut self, _dir_info: &DirectoryInfo, _totals: &ListingTotals) {
+        // No summary in TSV mode
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  format_tsv_line
+//
+//  Build "name<TAB>size<TAB>time<TAB>attrs" (no trailing newline).  Size
+//  is in bytes (0 for directories), time honors /T, and attrs is the
+//  same 9-char string shown in normal mode.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn format_tsv_line(name: &str, fi: &FileInfo, cmd: &CommandLine) -> String {
+    let size = if fi.is_directory() { 0 } else { fi.displayed_size() };
+    let time = format_local_timestamp (get_time_field_for_display (fi, cmd.time_field));
+    let attrs = build_attribute_display_string (fi.file_attributes);
+
+    format! ("{}\t{}\t{}\t{}", name, size, time, attrs)
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  format_local_timestamp
+//
+//  Format a FILETIME (as u64) in local time as "YYYY-MM-DDTHH:MM:SS".
+//  No spaces, so the field also survives whitespace-splitting tools.
+//  Returns "?" if the time cannot be converted.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub(super) fn format_local_timestamp(filetime_u64: u64) -> String {
+    let ft = windows::Win32::Foundation::FILETIME {
+        dwLowDateTime:  (filetime_u64 & 0xFFFF_FFFF) as u32,
+        dwHighDateTime: ((filetime_u64 >> 32) & 0xFFFF_FFFF) as u32,
+    };
+
+    let mut st       = windows::Win32::Foundation::SYSTEMTIME::default();
+    let mut st_local = windows::Win32::Foundation::SYSTEMTIME::default();
+
+    let converted = unsafe {
+        windows::Win32::System::Time::FileTimeToSystemTime (&ft, &mut st).is_ok()
+            && windows::Win32::System::Time::SystemTimeToTzSpecificLocalTime (None, &st, &mut st_local).is_ok()
+    };
+
+    if !converted {
+        return "?".to_string();
+    }
+
+    format! (
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        st_local.wYear, st_local.wMonth, st_local.wDay,
+        st_local.wHour, st_local.wMinute, st_local.wSecond,
+    )
+}
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::file_info::{FILE_ATTRIBUTE_ARCHIVE, FILE_ATTRIBUTE_DIRECTORY, FILE_ATTRIBUTE_READONLY};
+    use crate::test_support::{make_file, SAMPLE_TIME};
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  tsv_line_has_four_fields_in_order
+    //
+    //  Verify a sample file produces name, size, time, attrs — in that
+    //  order, tab-separated, with no color codes.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn tsv_line_has_four_fields_in_order() {
+        let cmd  = CommandLine::parse_from (["--Tsv"]).unwrap();
+        let fi   = make_file ("report.txt", FILE_ATTRIBUTE_READONLY | FILE_ATTRIBUTE_ARCHIVE, 1234);
+        let line = format_tsv_line ("report.txt", &fi, &cmd);
+
+        let fields: Vec<&str> = line.split ('\t').collect();
+        assert_eq! (fields.len(), 4, "{:?}", line);
+        assert_eq! (fields[0], "report.txt");
+        assert_eq! (fields[1], "1234");
+        assert_eq! (fields[3], build_attribute_display_string (fi.file_attributes));
+        assert! (fields[3].starts_with ('R'));
+        assert! (!line.contains ('\x1b'));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  tsv_time_field_is_iso_timestamp
+    //
+    //  Verify the time field is "YYYY-MM-DDTHH:MM:SS" with no spaces.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn tsv_time_field_is_iso_timestamp() {
+        let time = format_local_timestamp (SAMPLE_TIME);
+
+        assert_eq! (time.len(), 19, "{}", time);
+        assert_eq! (&time[4..5], "-");
+        assert_eq! (&time[10..11], "T");
+        assert! (time.starts_with ("2024-01-"), "{}", time);
+        assert! (!time.contains (' '));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  tsv_directory_size_is_zero
+    //
+    //  Verify directories report a size of 0.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn tsv_directory_size_is_zero() {
+        let cmd  = CommandLine::parse_from (["--Tsv"]).unwrap();
+        let fi   = make_file ("src", FILE_ATTRIBUTE_DIRECTORY, 4096);
+        let line = format_tsv_line ("src", &fi, &cmd);
+
+        assert_eq! (line.split ('\t').nth (1), Some ("0"));
+    }
+}
@@ -5,7 +5,7 @@
 use std::sync::Arc;
 
 use crate::cloud_status;
-use crate::command_line::CommandLine;
+use crate::command_line::{CommandLine, SizeFormat, SummaryVerbosity};
 use crate::config::{Attribute, Config};
 use crate::console::Console;
 use crate::directory_info::DirectoryInfo;
@@ -15,13 +15,20 @@
 
 use super::column_layout::compute_column_layout;
 use super::common::{
+    display_breadcrumb,
     display_cloud_status_symbol,
     display_directory_summary,
     display_drive_header,
     display_empty_directory_message,
+    display_filtered_count,
     display_listing_summary,
     display_path_header,
+    display_summary_details,
     display_volume_footer,
+    format_compact_size,
+    highlight_text_attr,
+    COMPACT_SIZE_WIDTH,
+    DIRECTORY_MARKER,
 };
 use super::{DirectoryLevel, ResultsDisplayer};
 
@@ -39,6 +46,8 @@ pub struct WideDisplayer {
     cmd:          Arc<CommandLine>,
     config:       Arc<Config>,
     icons_active: bool,
+    /// An earlier group on the same volume already printed the drive header
+    skip_drive_header: bool,
 }
 
 
@@ -64,7 +73,7 @@ impl WideDisplayer {
     ////////////////////////////////////////////////////////////////////////////
 
     pub fn new(console: Console, cmd: Arc<CommandLine>, config: Arc<Config>, icons_active: bool) -> Self {
-        WideDisplayer { console, cmd, config, icons_active }
+        WideDisplayer { console, cmd, config, icons_active, skip_drive_header: false }
     }
 
 
@@ -98,6 +107,23 @@ pub fn into_console(self) -> Console {
     pub fn console_mut(&mut self) -> &mut Console {
         &mut self.console
     }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  skip_drive_header
+    //
+    //  Omit the drive header for this group's listing: an earlier group
+    //  on the same volume already printed it.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn skip_drive_header(&mut self) {
+        self.skip_drive_header = true;
+    }
 }
 
 
@@ -131,18 +157,34 @@ fn display_results(&mut self, drive_info: &DriveInfo, dir_info: &DirectoryInfo,
         }
 
         if level == DirectoryLevel::Initial {
-            display_drive_header(&mut self.console, drive_info);
+            if !self.skip_drive_header {
+                display_drive_header(&mut self.console, drive_info);
+            }
+
+            if let Some(levels) = self.cmd.up_levels {
+                display_breadcrumb(&mut self.console, &dir_info.dir_path, levels);
+            }
         }
 
         display_path_header(&mut self.console, dir_info);
 
         if dir_info.matches.is_empty() {
-            display_empty_directory_message(&mut self.console, dir_info);
+            if self.cmd.shows_empty_message() {
+                display_empty_directory_message(&mut self.console, dir_info);
+            }
         } else {
             display_wide_file_results (&mut self.console, &self.cmd, &self.config, dir_info, self.icons_active);
             display_directory_summary(&mut self.console, dir_info);
 
-            if !self.cmd.recurse {
+            if self.cmd.show_filtered {
+                display_filtered_count(&mut self.console, dir_info);
+            }
+
+            if self.cmd.verbosity == SummaryVerbosity::Detailed {
+                display_summary_details(&mut self.console, &self.cmd, dir_info);
+            }
+
+            if !self.cmd.recurse && self.cmd.verbosity != SummaryVerbosity::TotalsOnly {
                 display_volume_footer(&mut self.console, dir_info);
             }
         }
@@ -166,7 +208,7 @@ fn display_results(&mut self, drive_info: &DriveInfo, dir_info: &DirectoryInfo,
     ////////////////////////////////////////////////////////////////////////////
 
     fn display_recursive_summary(&mut self, dir_info: &DirectoryInfo, totals: &ListingTotals) {
-        display_listing_summary(&mut self.console, dir_info, totals);
+        display_listing_summary(&mut self.console, dir_info, totals, self.cmd.verbosity);
     }
 }
 
@@ -189,11 +231,13 @@ fn display_wide_file_results (console: &mut Console, cmd: &CommandLine, config:
     }
 
     let console_width = console.width() as usize;
-    let in_sync_root  = cloud_status::is_under_sync_root (di.dir_path.as_os_str());
+    let in_sync_root  = !cmd.no_cloud && cloud_status::is_under_sync_root (di.dir_path.as_os_str());
     let ellipsize     = cmd.ellipsize.unwrap_or (true);
+    let show_size     = cmd.size_format == SizeFormat::Compact;
+    let marker_width  = if cmd.mark_dirs { DIRECTORY_MARKER.len() } else { 0 };
 
     // Build per-entry display widths vector (T011).
-    // Each entry's width = filename + optional brackets/icon/cloud.
+    // Each entry's width = filename + optional brackets/icon/cloud/size.
 
     let display_widths: Vec<usize> = di.matches.iter().map (|fi| {
         let mut w = fi.file_name.to_string_lossy().len();
@@ -214,6 +258,16 @@ fn display_wide_file_results (console: &mut Console, cmd: &CommandLine, config:
             w += if icons_active { 4 } else { 3 };
         }
 
+        // Compact size + space
+        if show_size {
+            w += COMPACT_SIZE_WIDTH + 1;
+        }
+
+        // /Mark prefix on directories
+        if fi.is_directory() {
+            w += marker_width;
+        }
+
         w
     }).collect();
 
@@ -249,9 +303,19 @@ fn display_wide_file_results (console: &mut Console, cmd: &CommandLine, config:
 
             let fi = &di.matches[idx];
             let style = config.get_display_style_for_file (fi);
-            let text_attr = style.text_attr;
+            let text_attr = highlight_text_attr (config, cmd, fi, style.text_attr);
             let mut cch_name: usize = 0;
 
+            // Compact size (--Size=Compact), <DIR> for directories
+            if show_size {
+                if !fi.is_directory() {
+                    console.writef_attr (Attribute::Size, format_args! ("{} ", format_compact_size (fi.displayed_size())));
+                } else {
+                    console.printf_attr (Attribute::Directory, "<DIR> ");
+                }
+                cch_name += COMPACT_SIZE_WIDTH + 1;
+            }
+
             // Cloud status symbol (when in sync root)
             if in_sync_root {
                 let cloud = cloud_status::get_cloud_status (fi.file_attributes, true);
@@ -275,6 +339,12 @@ fn display_wide_file_results (console: &mut Console, cmd: &CommandLine, config:
                 }
             }
 
+            // Directory marker before the name (/Mark)
+            if fi.is_directory() && marker_width > 0 {
+                console.printf (text_attr, DIRECTORY_MARKER);
+                cch_name += marker_width;
+            }
+
             // Format filename, with outlier truncation when trunc_cap is active (T014)
             let name = fi.file_name.to_string_lossy();
 
@@ -0,0 +1,337 @@
+// run_stats.rs — I/O operation counters reported by /Stats
+//
+// Beyond /P's elapsed time, /Stats explains *why* a listing was slow by
+// counting the expensive operations performed during the run: directory
+// searches opened, entries enumerated, owner (security descriptor)
+// lookups, alternate stream enumerations, and reparse point resolutions.
+// The counters are process-wide atomics so the single-threaded lister,
+// the multi-threaded workers, and the displayers can all bump them
+// without threading a handle through every call.  The report goes to
+// stderr so piped listing output stays clean.
+
+use std::io::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::results_displayer::format_number_with_separators;
+
+
+
+
+
+/// Process-wide counters for the current run.
+pub static RUN_STATS: RunStats = RunStats::new();
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Shared I/O operation counters, safe to bump from any thread.
+#[derive(Debug)]
+pub struct RunStats {
+    directories_opened:  AtomicU64,
+    files_enumerated:    AtomicU64,
+    owner_lookups:       AtomicU64,
+    stream_enumerations: AtomicU64,
+    reparse_resolutions: AtomicU64,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Point-in-time copy of the counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RunStatsSnapshot {
+    pub directories_opened:  u64,
+    pub files_enumerated:    u64,
+    pub owner_lookups:       u64,
+    pub stream_enumerations: u64,
+    pub reparse_resolutions: u64,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl Default for RunStats
+//
+//  Default constructor — delegates to new().
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl Default for RunStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl RunStats
+//
+//  Counter increments and snapshotting.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl RunStats {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  new
+    //
+    //  Create a zeroed counter set (const so it can back a static).
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub const fn new() -> Self {
+        RunStats {
+            directories_opened:  AtomicU64::new (0),
+            files_enumerated:    AtomicU64::new (0),
+            owner_lookups:       AtomicU64::new (0),
+            stream_enumerations: AtomicU64::new (0),
+            reparse_resolutions: AtomicU64::new (0),
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  record_*
+    //
+    //  Count one operation of each kind.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn record_directory_opened (&self) {
+        self.directories_opened.fetch_add (1, Ordering::Relaxed);
+    }
+
+    pub fn record_file_enumerated (&self) {
+        self.files_enumerated.fetch_add (1, Ordering::Relaxed);
+    }
+
+    pub fn record_owner_lookup (&self) {
+        self.owner_lookups.fetch_add (1, Ordering::Relaxed);
+    }
+
+    pub fn record_stream_enumeration (&self) {
+        self.stream_enumerations.fetch_add (1, Ordering::Relaxed);
+    }
+
+    pub fn record_reparse_resolution (&self) {
+        self.reparse_resolutions.fetch_add (1, Ordering::Relaxed);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  snapshot
+    //
+    //  Read all counters.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn snapshot (&self) -> RunStatsSnapshot {
+        RunStatsSnapshot {
+            directories_opened:  self.directories_opened.load (Ordering::Relaxed),
+            files_enumerated:    self.files_enumerated.load (Ordering::Relaxed),
+            owner_lookups:       self.owner_lookups.load (Ordering::Relaxed),
+            stream_enumerations: self.stream_enumerations.load (Ordering::Relaxed),
+            reparse_resolutions: self.reparse_resolutions.load (Ordering::Relaxed),
+        }
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl RunStatsSnapshot
+//
+//  Delta computation and report formatting.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl RunStatsSnapshot {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  since
+    //
+    //  Counts accumulated between an earlier snapshot and this one.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn since (&self, earlier: &RunStatsSnapshot) -> RunStatsSnapshot {
+        RunStatsSnapshot {
+            directories_opened:  self.directories_opened.saturating_sub (earlier.directories_opened),
+            files_enumerated:    self.files_enumerated.saturating_sub (earlier.files_enumerated),
+            owner_lookups:       self.owner_lookups.saturating_sub (earlier.owner_lookups),
+            stream_enumerations: self.stream_enumerations.saturating_sub (earlier.stream_enumerations),
+            reparse_resolutions: self.reparse_resolutions.saturating_sub (earlier.reparse_resolutions),
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  format_report
+    //
+    //  Render the /Stats block with right-aligned, separator-grouped counts.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn format_report (&self) -> String {
+        let rows = [
+            ("Directories opened",    self.directories_opened),
+            ("Entries enumerated",    self.files_enumerated),
+            ("Owner lookups",         self.owner_lookups),
+            ("Stream enumerations",   self.stream_enumerations),
+            ("Reparse resolutions",   self.reparse_resolutions),
+        ];
+
+        let counts: Vec<String> = rows.iter().map (|&(_, n)| format_number_with_separators (n)).collect();
+        let width = counts.iter().map (|c| c.len()).max().unwrap_or (0);
+
+        let mut report = String::from ("RCDir stats:\n");
+        for ((label, _), count) in rows.iter().zip (&counts) {
+            report.push_str (&format! ("  {:<21}{:>width$}\n", format! ("{}:", label), count, width = width));
+        }
+        report
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  write_report
+//
+//  Write the /Stats block for the whole run to stderr.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn write_report() {
+    let mut stderr = std::io::stderr().lock();
+    let _ = write! (stderr, "{}", RUN_STATS.snapshot().format_report());
+    let _ = stderr.flush();
+}
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  counters_increment_independently
+    //
+    //  Verify each record_* call bumps only its own counter.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn counters_increment_independently () {
+        let stats = RunStats::new();
+
+        stats.record_directory_opened();
+        stats.record_file_enumerated();
+        stats.record_file_enumerated();
+        stats.record_owner_lookup();
+        stats.record_stream_enumeration();
+        stats.record_stream_enumeration();
+        stats.record_stream_enumeration();
+
+        assert_eq! (stats.snapshot(), RunStatsSnapshot {
+            directories_opened:  1,
+            files_enumerated:    2,
+            owner_lookups:       1,
+            stream_enumerations: 3,
+            reparse_resolutions: 0,
+        });
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  since_subtracts_earlier_snapshot
+    //
+    //  Verify deltas between snapshots isolate one run's work.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn since_subtracts_earlier_snapshot () {
+        let stats = RunStats::new();
+        stats.record_file_enumerated();
+        let before = stats.snapshot();
+
+        stats.record_file_enumerated();
+        stats.record_reparse_resolution();
+
+        let delta = stats.snapshot().since (&before);
+        assert_eq! (delta.files_enumerated, 1);
+        assert_eq! (delta.reparse_resolutions, 1);
+        assert_eq! (delta.directories_opened, 0);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  report_aligns_counts
+    //
+    //  Verify the report lists every counter with right-aligned values.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn report_aligns_counts () {
+        let snapshot = RunStatsSnapshot {
+            directories_opened:  12,
+            files_enumerated:    1234,
+            owner_lookups:       0,
+            stream_enumerations: 5,
+            reparse_resolutions: 1,
+        };
+
+        let report = snapshot.format_report();
+        let lines: Vec<&str> = report.lines().collect();
+
+        assert_eq! (lines[0], "RCDir stats:");
+        assert_eq! (lines[1], "  Directories opened:     12");
+        assert_eq! (lines[2], "  Entries enumerated:  1,234");
+        assert_eq! (lines.len(), 6);
+    }
+}
@@ -0,0 +1,327 @@
+// since_last.rs — "modified since last run" tracking for /SinceLast
+//
+// Each /SinceLast run records its start time (a FILETIME) in a marker file
+// next to .rcdirconfig and highlights entries whose last-write time is
+// newer than the marker left by the previous run.  The start time, not the
+// finish time, is written so a file changed while the listing was running
+// still shows up next time.  No marker means this is the first run, so
+// nothing is highlighted.  The clock and the marker store are traits so
+// tests can drive the compare-and-update cycle without touching disk.
+
+use std::io;
+use std::path::PathBuf;
+
+use crate::environment_provider::EnvironmentProvider;
+
+
+
+
+
+/// Marker file name, stored in USERPROFILE alongside .rcdirconfig.
+pub const MARKER_FILE_NAME: &str = ".rcdir_lastrun";
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  Clock
+//
+//  Source of the current time as a FILETIME (100ns ticks since 1601).
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub trait Clock {
+    fn now(&self) -> u64;
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  MarkerStore
+//
+//  Persists the previous run's timestamp.  `read` returns None when no
+//  run has been recorded yet (or the marker is unreadable).
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub trait MarkerStore {
+    fn read(&self) -> Option<u64>;
+    fn write(&self, timestamp: u64) -> io::Result<()>;
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Reads the system clock with GetSystemTimeAsFileTime.
+pub struct SystemClock;
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl Clock for SystemClock
+//
+//  Same FILETIME encoding as FileInfo::last_write_time.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        let ft = unsafe { windows::Win32::System::SystemInformation::GetSystemTimeAsFileTime() };
+        ((ft.dwHighDateTime as u64) << 32) | (ft.dwLowDateTime as u64)
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Marker kept as a decimal FILETIME in a small text file.
+pub struct FileMarkerStore {
+    pub path: PathBuf,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl FileMarkerStore
+//
+//  Marker path resolution.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl FileMarkerStore {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  from_environment
+    //
+    //  The marker in USERPROFILE, or None if USERPROFILE isn't set.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn from_environment(provider: &dyn EnvironmentProvider) -> Option<Self> {
+        let user_profile = provider.get_env_var ("USERPROFILE")?;
+        Some (FileMarkerStore { path: PathBuf::from (user_profile).join (MARKER_FILE_NAME) })
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl MarkerStore for FileMarkerStore
+//
+//  A missing or malformed file reads as "no previous run".
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl MarkerStore for FileMarkerStore {
+    fn read(&self) -> Option<u64> {
+        std::fs::read_to_string (&self.path).ok()?.trim().parse().ok()
+    }
+
+    fn write(&self, timestamp: u64) -> io::Result<()> {
+        std::fs::write (&self.path, format! ("{}\n", timestamp))
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// One /SinceLast run: the previous marker and this run's start time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SinceLastRun {
+    pub previous: Option<u64>,
+    pub started:  u64,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl SinceLastRun
+//
+//  Read the marker at start, compare entries, record the run at the end.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl SinceLastRun {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  begin
+    //
+    //  Read the previous run's marker and note when this run started.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn begin(store: &dyn MarkerStore, clock: &dyn Clock) -> Self {
+        SinceLastRun {
+            previous: store.read(),
+            started:  clock.now(),
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  is_modified_since
+    //
+    //  True if an entry was written after the previous run started.
+    //  Always false on the first run.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn is_modified_since(&self, last_write_time: u64) -> bool {
+        self.previous.is_some_and (|previous| last_write_time > previous)
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  finish
+    //
+    //  Record this run's start time for the next run to compare against.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn finish(&self, store: &dyn MarkerStore) -> io::Result<()> {
+        store.write (self.started)
+    }
+}
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  MockClock / MockMarkerStore
+    //
+    //  A settable clock and an in-memory marker.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    struct MockClock {
+        now: Cell<u64>,
+    }
+
+    impl Clock for MockClock {
+        fn now(&self) -> u64 {
+            self.now.get()
+        }
+    }
+
+    #[derive(Default)]
+    struct MockMarkerStore {
+        marker: Cell<Option<u64>>,
+    }
+
+    impl MarkerStore for MockMarkerStore {
+        fn read(&self) -> Option<u64> {
+            self.marker.get()
+        }
+
+        fn write(&self, timestamp: u64) -> io::Result<()> {
+            self.marker.set (Some (timestamp));
+            Ok(())
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  first_run_highlights_nothing
+    //
+    //  Verify a missing marker highlights nothing and the run still
+    //  records its start time.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn first_run_highlights_nothing() {
+        let store = MockMarkerStore::default();
+        let clock = MockClock { now: Cell::new (1_000) };
+
+        let run = SinceLastRun::begin (&store, &clock);
+
+        assert_eq! (run.previous, None);
+        assert! (!run.is_modified_since (0));
+        assert! (!run.is_modified_since (u64::MAX));
+
+        run.finish (&store).unwrap();
+        assert_eq! (store.marker.get(), Some (1_000));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  later_run_compares_against_previous_start
+    //
+    //  Verify the second run highlights only entries written after the
+    //  first run started (including during it), then advances the marker.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn later_run_compares_against_previous_start() {
+        let store = MockMarkerStore::default();
+        let clock = MockClock { now: Cell::new (1_000) };
+        SinceLastRun::begin (&store, &clock).finish (&store).unwrap();
+
+        clock.now.set (5_000);
+        let run = SinceLastRun::begin (&store, &clock);
+
+        assert_eq! (run.previous, Some (1_000));
+        assert! (!run.is_modified_since (999));
+        assert! (!run.is_modified_since (1_000));
+        assert! ( run.is_modified_since (1_001));
+        assert! ( run.is_modified_since (4_000));
+
+        run.finish (&store).unwrap();
+        assert_eq! (store.marker.get(), Some (5_000));
+
+        let next = SinceLastRun::begin (&store, &clock);
+        assert! (!next.is_modified_since (4_000));
+    }
+}
@@ -67,6 +67,57 @@ pub fn enumerate_streams(di: &mut DirectoryInfo, totals: &mut ListingTotals) {
 
 
 
+////////////////////////////////////////////////////////////////////////////////
+//
+//  retain_stream_bearing
+//
+//  /HasStreams audit filter.  Drops every file without alternate data
+//  streams from di.matches (run after enumerate_streams) and backs the
+//  dropped entries out of the directory and global totals.  Directories
+//  never carry enumerated streams; they are dropped too unless
+//  keep_directories is set (tree mode needs them for structure).
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn retain_stream_bearing(di: &mut DirectoryInfo, totals: &mut ListingTotals, keep_directories: bool) {
+    use windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_DIRECTORY;
+
+    let matches = std::mem::take(&mut di.matches);
+    di.largest_file_size = 0;
+
+    for file_info in matches {
+        let is_directory = (file_info.file_attributes & FILE_ATTRIBUTE_DIRECTORY.0) != 0;
+
+        if is_directory && keep_directories {
+            di.matches.push(file_info);
+            continue;
+        }
+
+        if file_info.streams.is_empty() {
+            if is_directory {
+                di.subdirectory_count = di.subdirectory_count.saturating_sub(1);
+            } else {
+                di.file_count = di.file_count.saturating_sub(1);
+                di.bytes_used = di.bytes_used.saturating_sub(file_info.file_size);
+
+                totals.file_count = totals.file_count.saturating_sub(1);
+                totals.file_bytes = totals.file_bytes.saturating_sub(file_info.file_size);
+            }
+            continue;
+        }
+
+        // Largest size now only considers surviving files and their streams
+        let largest_stream = file_info.streams.iter().map(|si| si.size as u64).max().unwrap_or(0);
+        di.largest_file_size = di.largest_file_size.max(file_info.file_size).max(largest_stream);
+
+        di.matches.push(file_info);
+    }
+}
+
+
+
+
+
 ////////////////////////////////////////////////////////////////////////////////
 //
 //  enumerate_file_streams
@@ -81,6 +132,8 @@ pub fn enumerate_streams(di: &mut DirectoryInfo, totals: &mut ListingTotals) {
 ////////////////////////////////////////////////////////////////////////////////
 
 pub fn enumerate_file_streams(file_path: &OsStr) -> Vec<StreamInfo> {
+    crate::run_stats::RUN_STATS.record_stream_enumeration();
+
     let path_wide: Vec<u16> = file_path.encode_wide().chain(Some(0)).collect();
     let mut stream_data = WIN32_FIND_STREAM_DATA::default();
     let mut results = Vec::new();
@@ -276,4 +329,108 @@ fn enumerate_current_exe_no_panic() {
         // Just verify it doesn't panic; streams may be empty
         let _ = streams.len();
     }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  entry
+    //
+    //  Build a FileInfo with the given size and stream sizes for the
+    //  retain_stream_bearing tests.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn entry(name: &str, file_attributes: u32, file_size: u64, stream_sizes: &[i64]) -> crate::file_info::FileInfo {
+        crate::file_info::FileInfo {
+            streams: stream_sizes.iter().map(|&size| StreamInfo { name: ":s".into(), size }).collect(),
+            ..crate::test_support::make_file(name, file_attributes, file_size)
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  stream_fixture
+    //
+    //  A directory with two stream-bearing files, one plain file and one
+    //  subdirectory, with counts and totals as the lister leaves them.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn stream_fixture() -> (DirectoryInfo, ListingTotals) {
+        use windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_DIRECTORY;
+
+        let mut di = DirectoryInfo::new(std::path::PathBuf::from("C:\\audit"), "*".into());
+        di.matches = vec![
+            entry("tagged.txt", 0, 100, &[30]),
+            entry("plain.txt",  0, 5000, &[]),
+            entry("sub",        FILE_ATTRIBUTE_DIRECTORY.0, 0, &[]),
+            entry("zone.exe",   0, 200, &[26, 900]),
+        ];
+        di.file_count         = 3;
+        di.subdirectory_count = 1;
+        di.bytes_used         = 5300;
+        di.largest_file_size  = 5000;
+
+        let totals = ListingTotals { file_count: 3, file_bytes: 5300, ..Default::default() };
+        (di, totals)
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  retain_stream_bearing_drops_plain_entries
+    //
+    //  Only files with alternate streams survive; counts, bytes and the
+    //  largest size are recomputed from the survivors.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn retain_stream_bearing_drops_plain_entries() {
+        let (mut di, mut totals) = stream_fixture();
+        retain_stream_bearing(&mut di, &mut totals, false);
+
+        let names: Vec<_> = di.matches.iter().map(|fi| fi.file_name.to_string_lossy().into_owned()).collect();
+        assert_eq!(names, ["tagged.txt", "zone.exe"]);
+        assert_eq!(di.file_count, 2);
+        assert_eq!(di.subdirectory_count, 0);
+        assert_eq!(di.bytes_used, 300);
+        assert_eq!(di.largest_file_size, 900);
+        assert_eq!(totals.file_count, 2);
+        assert_eq!(totals.file_bytes, 300);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  retain_stream_bearing_keeps_directories_for_tree
+    //
+    //  With keep_directories set, subdirectories stay for tree structure
+    //  while plain files are still dropped.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn retain_stream_bearing_keeps_directories_for_tree() {
+        let (mut di, mut totals) = stream_fixture();
+        retain_stream_bearing(&mut di, &mut totals, true);
+
+        let names: Vec<_> = di.matches.iter().map(|fi| fi.file_name.to_string_lossy().into_owned()).collect();
+        assert_eq!(names, ["tagged.txt", "sub", "zone.exe"]);
+        assert_eq!(di.file_count, 2);
+        assert_eq!(di.subdirectory_count, 1);
+    }
 }
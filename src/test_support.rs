@@ -0,0 +1,127 @@
+// test_support.rs — Shared fixtures for unit tests
+//
+// Entries are built from FileInfo::default() with only the fields a test
+// cares about, so adding a per-feature field to FileInfo doesn't touch
+// every test module.  Tests that need real files on disk get a TempDir,
+// which removes itself when dropped, even if an assert fails first.
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::file_info::FileInfo;
+
+
+
+
+
+/// 2024-01-17 21:20 UTC as a FILETIME; every fixture entry carries it as
+/// its creation, write, and access time.
+pub const SAMPLE_TIME: u64 = 133_500_000_000_000_000;
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  make_file
+//
+//  A FileInfo with the given name, attributes, and size, stamped with
+//  SAMPLE_TIME.  Everything else is left at its default.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn make_file(name: &str, attributes: u32, size: u64) -> FileInfo {
+    FileInfo {
+        file_name:        OsString::from (name),
+        file_attributes:  attributes,
+        file_size:        size,
+        creation_time:    SAMPLE_TIME,
+        last_write_time:  SAMPLE_TIME,
+        last_access_time: SAMPLE_TIME,
+        ..Default::default()
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// A uniquely named, initially empty directory under %TEMP%, deleted with
+/// everything in it when the guard is dropped.
+pub struct TempDir {
+    path: PathBuf,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl TempDir
+//
+//  Creation and access.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl TempDir {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  new
+    //
+    //  Create rcdir_<tag>_<pid>_<n>, where n keeps directories from tests
+    //  running in parallel in the same process apart.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn new(tag: &str) -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new (0);
+
+        let unique = NEXT.fetch_add (1, Ordering::Relaxed);
+        let path   = std::env::temp_dir().join (format! ("rcdir_{}_{}_{}", tag, std::process::id(), unique));
+
+        let _ = std::fs::remove_dir_all (&path);
+        std::fs::create_dir_all (&path).unwrap();
+        TempDir { path }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  path
+    //
+    //  The directory's full path.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl Drop for TempDir
+//
+//  Remove the directory tree; a failure here must not mask the test's
+//  own result, so it is ignored.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all (&self.path);
+    }
+}
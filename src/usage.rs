@@ -5,8 +5,9 @@
 
 use crate::cloud_status::{CloudStatus, CIRCLE_HOLLOW, CIRCLE_HALF_FILLED, CIRCLE_FILLED};
 use crate::color::*;
-use crate::config::{Attribute, AttributeSource, RCDIR_ENV_VAR_NAME};
+use crate::config::{active_env_var_name, Attribute, AttributeSource, RCDIR_ENV_VAR_NAME, RCDIR_PROFILE_ENV_VAR_NAME};
 use crate::console::Console;
+use crate::environment_provider::DefaultEnvironmentProvider;
 use crate::icon_mapping::{NF_MD_CLOUD_OUTLINE, NF_MD_CLOUD_CHECK, NF_MD_PIN};
 
 
@@ -293,6 +294,8 @@ fn display_synopsis(console: &mut Console, prefix: char) {
                  [[:]{{InformationHighlight}}attributes{{Information}}]] "),
         format!("[{{InformationHighlight}}{short}O{{Information}}\
                  [[:]{{InformationHighlight}}sortorder{{Information}}]] "),
+        format!("[{{InformationHighlight}}{long}ODir{{Information}}:{{InformationHighlight}}key{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}OFile{{Information}}:{{InformationHighlight}}key{{Information}}] "),
         format!("[{{InformationHighlight}}{short}T{{Information}}\
                  [[:]{{InformationHighlight}}timefield{{Information}}]] "),
         format!("[{{InformationHighlight}}{short}S{{Information}}] "),
@@ -300,16 +303,82 @@ fn display_synopsis(console: &mut Console, prefix: char) {
         format!("[{{InformationHighlight}}{short}B{{Information}}] "),
         format!("[{{InformationHighlight}}{short}P{{Information}}] "),
         format!("[{{InformationHighlight}}{short}M{{Information}}] "),
+        format!("[{{InformationHighlight}}{short}V{{Information}}:{{InformationHighlight}}level{{Information}}] "),
         format!("[{{InformationHighlight}}{long}Env{{Information}}] "),
         format!("[{{InformationHighlight}}{long}Config{{Information}}] "),
         format!("[{{InformationHighlight}}{long}Owner{{Information}}] "),
         format!("[{{InformationHighlight}}{long}Streams{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}HasStreams{{Information}}] "),
         format!("[{{InformationHighlight}}{long}Icons{{Information}}] "),
-        format!("[{{InformationHighlight}}{long}Tree{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Tree{{Information}}[:{{InformationHighlight}}dirs{{Information}}]] "),
         format!("[{{InformationHighlight}}{long}Ellipsize{{Information}}] "),
         format!("[{{InformationHighlight}}{long}Depth{{Information}}={{InformationHighlight}}N{{Information}}] "),
         format!("[{{InformationHighlight}}{long}TreeIndent{{Information}}={{InformationHighlight}}N{{Information}}] "),
-        format!("[{{InformationHighlight}}{long}Size{{Information}}={{InformationHighlight}}Auto{{Information}}|{{InformationHighlight}}Bytes{{Information}}]"),
+        format!("[{{InformationHighlight}}{long}Size{{Information}}={{InformationHighlight}}Auto{{Information}}|{{InformationHighlight}}Bytes{{Information}}|{{InformationHighlight}}Compact{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Color{{Information}}={{InformationHighlight}}Auto{{Information}}|{{InformationHighlight}}Always{{Information}}|{{InformationHighlight}}Never{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}NoCloud{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Highlight{{Information}}={{InformationHighlight}}attributes{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Delta{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}DateFmt{{Information}}={{InformationHighlight}}pattern{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Fmt{{Information}}={{InformationHighlight}}template{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Tsv{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Jsonl{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Count{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}FullPath{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Newer{{Information}}={{InformationHighlight}}file{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Older{{Information}}={{InformationHighlight}}file{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Recent{{Information}}[:{{InformationHighlight}}window{{Information}}]] "),
+        format!("[{{InformationHighlight}}{long}Stale{{Information}}[:{{InformationHighlight}}window{{Information}}]] "),
+        format!("[{{InformationHighlight}}{long}Contains{{Information}}:{{InformationHighlight}}text{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}XD{{Information}}={{InformationHighlight}}dir{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}XSZ{{Information}}:{{InformationHighlight}}min-max{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Base{{Information}}:{{InformationHighlight}}dir{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Pct{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}DirsFirst{{Information}}:{{InformationHighlight}}tree{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Brief{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}ShowDots{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}ShowFiltered{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Bom{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}RawColors{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Light{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}FileId{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Encoding{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Tier{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}VerInfo{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}SinceLast{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}NameCol{{Information}}[:{{InformationHighlight}}N{{Information}}]] "),
+        format!("[{{InformationHighlight}}{long}Literal{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Dedupe{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}NoReparse{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Mine{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Mark{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}FailOnEmpty{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}IgnoreEmpty{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Diff{{Information}}={{InformationHighlight}}dir{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}DiffMeta{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Progress{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Stats{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}CollapseEmpty{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}DrivePct{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}ErrorSummary{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}OnDisk{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Desc{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Limit{{Information}}:{{InformationHighlight}}N{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Status{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}DerefArg{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}NoSummary{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}InZip{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Columns{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}WrapNames{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}ShowMask{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Quiet{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Up{{Information}}:{{InformationHighlight}}N{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Target{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Attr{{Information}}:{{InformationHighlight}}full{{Information}}|{{InformationHighlight}}auto{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}AttrStyle{{Information}}:{{InformationHighlight}}ls{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Group{{Information}}:{{InformationHighlight}}ext{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Hash{{Information}}={{InformationHighlight}}CRC32{{Information}}|{{InformationHighlight}}SHA256{{Information}}] "),
+        format!("[{{InformationHighlight}}{long}Watch{{Information}}]"),
         format!(" [{{InformationHighlight}}{long}Set-Aliases{{Information}}]"),
         format!(" [{{InformationHighlight}}{long}Get-Aliases{{Information}}]"),
         format!(" [{{InformationHighlight}}{long}Remove-Aliases{{Information}}]"),
@@ -395,6 +464,7 @@ pub fn display_usage(console: &mut Console, prefix: char, icons_active: bool) {
 {{Information}}
   [drive:][path][filename]
                     Specifies drive, directory, and/or files to list.
+                    {{InformationHighlight}}%VAR%{{Information}} and a leading {{InformationHighlight}}~{{Information}} (USERPROFILE) are expanded.
 
   {{InformationHighlight}}{short}A{{Information}}                Displays files with specified attributes.
   attributes          {{InformationHighlight}}D{{Information}}  Directories                {{InformationHighlight}}R{{Information}}  Read-only files
@@ -415,29 +485,123 @@ pub fn display_usage(console: &mut Console, prefix: char, icons_active: bool) {
   {{InformationHighlight}}{short}O{{Information}}                List by files in sorted order.
   sortorder           {{InformationHighlight}}N{{Information}}  By name (alphabetic)       {{InformationHighlight}}S{{Information}}  By size (smallest first)
                       {{InformationHighlight}}E{{Information}}  By extension (alphabetic)  {{InformationHighlight}}D{{Information}}  By date/time (oldest first)
+                      {{InformationHighlight}}title{{Information}}  By name, ignoring leading symbols and articles (The, A, An)
                       {{InformationHighlight}}-{{Information}}  Prefix to reverse order
+  {{InformationHighlight}}{long}ODir{{Information}}:key, {{InformationHighlight}}{long}OFile{{Information}}:key
+                      Sort directories or files by their own key (same letters); the other uses {{InformationHighlight}}{short}O{{Information}}.
 
   {{InformationHighlight}}{short}T{{Information}}                Selects the time field for display and sorting.
   timefield           {{InformationHighlight}}C{{Information}}  Creation time              {{InformationHighlight}}A{{Information}}  Last access time
                       {{InformationHighlight}}W{{Information}}  Last write time (default)
+                      {{InformationHighlight}}{short}T:both{{Information}} adds each entry's age after its date and time, e.g. '(3d ago)'.
 
   {{InformationHighlight}}{short}S{{Information}}                Displays files in specified directory and all subdirectories.
   {{InformationHighlight}}{short}W{{Information}}                Displays results in a wide listing format.
   {{InformationHighlight}}{short}B{{Information}}                Displays bare file names only (no headers, footers, or details).
-  {{InformationHighlight}}{short}P{{Information}}                Displays performance timing information.
+                    Use {{InformationHighlight}}{short}B:size{{Information}} to print each entry's size before its name (directories show 0).
+                    Use {{InformationHighlight}}{short}B:numbered{{Information}} to prefix each line with a 1-based index (e.g. for picking the Nth result).
+  {{InformationHighlight}}{short}P{{Information}}                Displays performance timing information and enumeration throughput (files/sec).
   {{InformationHighlight}}{short}M{{Information}}                Enables multi-threaded enumeration (default). Use{{InformationHighlight}}{m_dis}{{Information}} to disable.
+  {{InformationHighlight}}{short}V{{Information}}:{{InformationHighlight}}0{{Information}}|{{InformationHighlight}}1{{Information}}|{{InformationHighlight}}2{{Information}}          Summary detail: {{InformationHighlight}}0{{Information}} = totals only, {{InformationHighlight}}1{{Information}} = default (adds free space),
+                    {{InformationHighlight}}2{{Information}} = also a per-extension breakdown, oldest/newest file, and size histogram.
   {{InformationHighlight}}{long}Env{{Information}}             {lpad}Displays {RCDIR_ENV_VAR_NAME} help, syntax, and current value.
   {{InformationHighlight}}{long}Config{{Information}}          {lpad}Displays config file diagnostics, syntax reference, and parse errors.
+  {lpad}                   Use {{InformationHighlight}}{long}Config{{Information}}={{InformationHighlight}}JSON{{Information}} to export the resolved colors and icons as JSON.
+  {lpad}                   Use {{InformationHighlight}}{long}Config{{Information}}={{InformationHighlight}}errors-json{{Information}} to report {RCDIR_ENV_VAR_NAME} errors as JSON (for editors and tools).
   {{InformationHighlight}}{long}Settings{{Information}}        {lpad}Displays current merged configuration for all items and extensions.
   {{InformationHighlight}}{long}Owner{{Information}}           {lpad}Displays the owner of each file and directory. Not allowed with {{InformationHighlight}}{long}Tree{{Information}}.
+  {lpad}                   Use {{InformationHighlight}}{long}Owner{{Information}}:{{InformationHighlight}}sid{{Information}} to show raw SID strings (S-1-5-...) instead of account names.
   {{InformationHighlight}}{long}Streams{{Information}}         {lpad}Displays alternate data streams (NTFS only).
+  {{InformationHighlight}}{long}HasStreams{{Information}}      {lpad}Lists only files with alternate data streams, with a stream count per file (implies {{InformationHighlight}}{long}Streams{{Information}}).
   {{InformationHighlight}}{long}Icons{{Information}}           {lpad}Enables file-type icons (Nerd Font required). Use {{InformationHighlight}}{long}Icons-{{Information}} to disable.
+  {lpad}                   Use {{InformationHighlight}}{long}Icons{{Information}}:{{InformationHighlight}}force{{Information}} to show icons even when output is redirected or no Nerd Font is detected.
+  {lpad}                   With {{InformationHighlight}}{long}NoColor{{Information}}, every entry gets a glyph, even where icons are suppressed.
   {{InformationHighlight}}{long}Tree{{Information}}            {lpad}Displays a hierarchical directory tree view. Use {{InformationHighlight}}{long}Tree-{{Information}} to disable.
+  {{InformationHighlight}}{long}Tree{{Information}}:{{InformationHighlight}}dirs{{Information}}       {lpad}Tree of directories only (files are counted in the summary but not listed).
+  {{InformationHighlight}}{long}DirsFirst{{Information}}:{{InformationHighlight}}tree{{Information}}  {lpad}Tree of directories that lists files only in the target and leaf directories (implies {{InformationHighlight}}{long}Tree{{Information}}).
   {{InformationHighlight}}{long}Ellipsize{{Information}}        {lpad}Truncates long link target paths with \u{2026} to prevent line wrapping. Default: on. Use {{InformationHighlight}}{long}Ellipsize-{{Information}} to disable.
   {{InformationHighlight}}{long}Depth{{Information}}={{InformationHighlight}}N{{Information}}         {lpad}Limits tree depth to N levels (requires {{InformationHighlight}}{long}Tree{{Information}}).
   {{InformationHighlight}}{long}TreeIndent{{Information}}={{InformationHighlight}}N{{Information}}    {lpad}Sets tree indent width (1-8, default 4; requires {{InformationHighlight}}{long}Tree{{Information}}).
-  {{InformationHighlight}}{long}Size{{Information}}={{InformationHighlight}}Auto{{Information}}|{{InformationHighlight}}Bytes{{Information}} {lpad}File size format: {{InformationHighlight}}Auto{{Information}} = abbreviated (KB/MB/GB), {{InformationHighlight}}Bytes{{Information}} = exact with commas.
+  {{InformationHighlight}}{long}Size{{Information}}={{InformationHighlight}}Auto{{Information}}|{{InformationHighlight}}Bytes{{Information}}|{{InformationHighlight}}Compact{{Information}}
+  {lpad}                   File size format: {{InformationHighlight}}Auto{{Information}} = abbreviated (KB/MB/GB), {{InformationHighlight}}Bytes{{Information}} = exact with commas,
+  {lpad}                   {{InformationHighlight}}Compact{{Information}} = always 5 characters (1.2K, 12.3G) for dense wide and tree listings.
   {lpad}                   Default: {{InformationHighlight}}Auto{{Information}} in tree mode, {{InformationHighlight}}Bytes{{Information}} otherwise.
+  {{InformationHighlight}}{long}Color{{Information}}={{InformationHighlight}}Auto{{Information}}|{{InformationHighlight}}Always{{Information}}|{{InformationHighlight}}Never{{Information}}
+  {lpad}                   Color output: {{InformationHighlight}}Auto{{Information}} = only when not redirected, {{InformationHighlight}}Always{{Information}} (default), {{InformationHighlight}}Never{{Information}} = plain text.
+  {lpad}                   {{InformationHighlight}}{long}NoColor{{Information}} is the same as {{InformationHighlight}}{long}Color{{Information}}={{InformationHighlight}}Never{{Information}}.
+  {lpad}                   Setting the {{InformationHighlight}}NO_COLOR{{Information}} environment variable defaults to {{InformationHighlight}}Never{{Information}}.
+  {{InformationHighlight}}{long}NoCloud{{Information}}         {lpad}Hides the cloud sync status column (OneDrive, iCloud, etc.).
+  {lpad}                   Outside a sync root the column is kept blank to match TCDir; use {{InformationHighlight}}{long}NoCloud{{Information}}:{{InformationHighlight}}auto{{Information}} to hide it only there.
+  {{InformationHighlight}}{long}Highlight{{Information}}={{InformationHighlight}}attrs{{Information}} {lpad}Shows names of entries with any of the given attributes (same letters as {{InformationHighlight}}{short}A{{Information}}) in the highlight color.
+  {{InformationHighlight}}{long}Delta{{Information}}           {lpad}Displays the time from creation to last write (e.g. +3d) after the date.
+  {{InformationHighlight}}{long}DateFmt{{Information}}={{InformationHighlight}}pat{{Information}}     {lpad}Formats the date/time column with a custom pattern, e.g. {{InformationHighlight}}\"yyyy-MM-dd HH:mm:ss\"{{Information}}.
+  {lpad}                   The date part (up to the first h, H, m, s, or t) uses GetDateFormatEx pictures; the rest GetTimeFormatEx.
+  {{InformationHighlight}}{long}Fmt{{Information}}={{InformationHighlight}}tmpl{{Information}}        {lpad}Prints one line per entry from a template, e.g. {{InformationHighlight}}\"{{size:>12}} {{name}}\"{{Information}}. Placeholders: name, size, date, time, attrs, owner, cloud.
+  {{InformationHighlight}}{long}Tsv{{Information}}             {lpad}Prints name, size, time, and attributes as tab-separated fields with no
+  {lpad}                   colors or headers, for scripting. Names containing tabs are not supported.
+  {{InformationHighlight}}{long}Jsonl{{Information}}           {lpad}Prints one JSON object per entry per line (JSON Lines), streamed as each directory
+  {lpad}                   is listed: path, name, size, time, attributes, and directory.
+  {{InformationHighlight}}{long}Count{{Information}}           {lpad}Prints only the number of matching entries (across subdirectories with {{InformationHighlight}}{short}S{{Information}}).
+  {{InformationHighlight}}{long}FullPath{{Information}}        {lpad}Displays each name as its absolute path (normal mode); other columns are unchanged.
+  {{InformationHighlight}}{long}Newer{{Information}}={{InformationHighlight}}file{{Information}}      {lpad}Lists only entries written more recently than the reference file.
+  {{InformationHighlight}}{long}Older{{Information}}={{InformationHighlight}}file{{Information}}      {lpad}Lists only entries written before the reference file.
+  {{InformationHighlight}}{long}Recent{{Information}}[:{{InformationHighlight}}window{{Information}}] {lpad}Lists only entries written within the window (default {{InformationHighlight}}24h{{Information}}; e.g. {{InformationHighlight}}30m{{Information}}, {{InformationHighlight}}7d{{Information}}, {{InformationHighlight}}2w{{Information}}).
+  {{InformationHighlight}}{long}Stale{{Information}}[:{{InformationHighlight}}window{{Information}}]  {lpad}Dims files not accessed within the window (default {{InformationHighlight}}90d{{Information}}), to spot cleanup candidates.
+  {{InformationHighlight}}{long}Contains{{Information}}:{{InformationHighlight}}text{{Information}}   {lpad}Lists only entries whose names contain the text, ignoring case (no wildcards needed).
+  {{InformationHighlight}}{long}XD{{Information}}={{InformationHighlight}}dir{{Information}}          {lpad}Excludes directories from recursion. A name (obj) matches at any depth; a
+  {lpad}                   relative path (src\\generated) matches only that directory. May be repeated.
+  {{InformationHighlight}}{long}XSZ{{Information}}:{{InformationHighlight}}min-max{{Information}}     {lpad}Excludes files sized within the band (bytes, or K/M/G; e.g. {{InformationHighlight}}0-0{{Information}} skips empty files). May be repeated.
+  {{InformationHighlight}}{long}Base{{Information}}:{{InformationHighlight}}dir{{Information}}        {lpad}Lists {{InformationHighlight}}dir{{Information}} instead of the current directory when no path is given (overrides {{InformationHighlight}}DefaultPath={{Information}}).
+  {{InformationHighlight}}{long}Pct{{Information}}             {lpad}Shows each file's size as a percentage of its directory's total bytes.
+  {{InformationHighlight}}{long}Brief{{Information}}           {lpad}Prints one line per directory (path, file count, bytes) instead of full listings; pair with {{InformationHighlight}}{short}S{{Information}}.
+  {{InformationHighlight}}{long}ShowDots{{Information}}        {lpad}Lists the {{InformationHighlight}}.{{Information}} and {{InformationHighlight}}..{{Information}} entries like {{InformationHighlight}}dir{{Information}} does (never recursed into).
+  {{InformationHighlight}}{long}ShowFiltered{{Information}}    {lpad}Adds \"Showing N of M entries\" to each directory summary (matches vs. all entries scanned).
+  {{InformationHighlight}}{long}Bom{{Information}}             {lpad}Starts output redirected to a file with a UTF-8 byte order mark (for Notepad and similar editors).
+  {{InformationHighlight}}{long}RawColors{{Information}}       {lpad}Emits configured file colors verbatim, even when the foreground matches the background.
+  {{InformationHighlight}}{long}Light{{Information}}           {lpad}Uses default colors for light-background terminals (dark text on white); {{InformationHighlight}}RCDIR{{Information}} overrides still apply.
+  {{InformationHighlight}}{long}FileId{{Information}}          {lpad}Displays each entry's NTFS file ID in hex (hard links share an ID). Opens every entry. Not allowed with {{InformationHighlight}}{long}Tree{{Information}}.
+  {{InformationHighlight}}{long}Encoding{{Information}}        {lpad}Guesses the encoding of text files (ASCII, UTF-8, UTF-16LE/BE, ANSI) from their first bytes; cloud-only files are not read. Not allowed with {{InformationHighlight}}{long}Tree{{Information}}.
+  {{InformationHighlight}}{long}Tier{{Information}}            {lpad}Labels each entry new, recent, old, or ancient by creation age (cutoffs from {{InformationHighlight}}AgeTiers={{Information}}). Not allowed with {{InformationHighlight}}{long}Tree{{Information}}.
+  {{InformationHighlight}}{long}VerInfo{{Information}}         {lpad}Displays the file version of .exe, .dll, and .sys files from their version resource. Not allowed with {{InformationHighlight}}{long}Tree{{Information}}.
+  {{InformationHighlight}}{long}SinceLast{{Information}}       {lpad}Highlights entries modified since the previous {{InformationHighlight}}{long}SinceLast{{Information}} run (timestamp kept in %USERPROFILE%\\.rcdir_lastrun).
+  {{InformationHighlight}}{long}NameCol{{Information}}[:{{InformationHighlight}}N{{Information}}]     {lpad}Starts names at console column {{InformationHighlight}}N{{Information}}, or (without {{InformationHighlight}}N{{Information}}) at the widest name column so far, so recursive listings line up. Not allowed with {{InformationHighlight}}{long}Tree{{Information}}.
+  {{InformationHighlight}}{long}Literal{{Information}}         {lpad}Treats file names without {{InformationHighlight}}*{{Information}} or {{InformationHighlight}}?{{Information}} as exact names (no {{InformationHighlight}}[...]{{Information}} classes or 8.3 short-name matches).
+  {{InformationHighlight}}{long}Dedupe{{Information}}          {lpad}Lists a file matched by several overlapping masks (e.g. {{InformationHighlight}}*.txt *.t*{{Information}}) only once per directory.
+  {{InformationHighlight}}{long}NoReparse{{Information}}       {lpad}Omits reparse points (symlinks, junctions, cloud placeholders) from the listing and from recursion.
+  {{InformationHighlight}}{long}Mine{{Information}}            {lpad}Lists only entries owned by the current user (owner SID matches the process token; no name lookups).
+  {{InformationHighlight}}{long}Mark{{Information}}            {lpad}Sorts directories in with files (any {{InformationHighlight}}{short}O{{Information}} order) and marks each directory name with a leading '+ '.
+  {{InformationHighlight}}{long}FailOnEmpty{{Information}}     {lpad}When nothing matches, prints no 'No files matching' message and exits with a non-zero status.
+  {{InformationHighlight}}{long}IgnoreEmpty{{Information}}     {lpad}When nothing matches, prints no 'No files matching' message and exits successfully.
+  {{InformationHighlight}}{long}Diff{{Information}}={{InformationHighlight}}dir{{Information}}        {lpad}Lists only entries whose names are not in the other directory (compare folder copies).
+  {{InformationHighlight}}{long}DiffMeta{{Information}}        {lpad}With {{InformationHighlight}}{long}Diff{{Information}}, also lists files present in both whose size or write time differs.
+  {{InformationHighlight}}{long}Progress{{Information}}        {lpad}Shows a count of directories scanned on stderr during multi-threaded recursion.
+  {{InformationHighlight}}{long}Stats{{Information}}           {lpad}Reports directories opened, entries enumerated, and owner/stream/reparse lookups on stderr.
+  {{InformationHighlight}}{long}CollapseEmpty{{Information}}   {lpad}With {{InformationHighlight}}{short}S{{Information}}, omits subdirectories with no matching files and reports how many were omitted.
+  {{InformationHighlight}}{long}DrivePct{{Information}}        {lpad}With {{InformationHighlight}}{short}S{{Information}}, ends with each top-level directory's total size as a percentage of the drive's capacity, largest first.
+  {{InformationHighlight}}{long}ErrorSummary{{Information}}    {lpad}Lists directories that could not be listed (e.g. an unavailable network drive) together at the end and exits with a non-zero status if there were any.
+  {{InformationHighlight}}{long}OnDisk{{Information}}          {lpad}Shows and totals the space sparse and compressed files actually use on disk; sizes that differ from the logical size are marked with '~'.
+  {{InformationHighlight}}{long}Desc{{Information}}            {lpad}Displays each entry's registered file type, e.g. 'Microsoft Word Document'; unknown types show the extension. Not allowed with {{InformationHighlight}}{long}Tree{{Information}}.
+  {{InformationHighlight}}{long}Limit{{Information}}:{{InformationHighlight}}N{{Information}}         {lpad}Shows only the first N entries (after sorting) of each directory, then how many more there are.
+  {lpad}                   The summary still counts every entry. Normal listing only.
+  {{InformationHighlight}}{long}Status{{Information}}          {lpad}Ends with one line on stderr such as '42 files, 3 dirs, 12.3 MB, 0 errors', for prompts and logs,
+  {lpad}                   whatever the summary settings; {{InformationHighlight}}{long}Status-{{Information}} turns it off again.
+  {{InformationHighlight}}{long}DerefArg{{Information}}        {lpad}When a directory argument is itself a symlink or junction, lists its target instead, with a note.
+  {{InformationHighlight}}{long}NoSummary{{Information}}       {lpad}With {{InformationHighlight}}{short}S{{Information}}, omits the closing \"Total files listed\" summary; per-directory output is unchanged.
+  {{InformationHighlight}}{long}InZip{{Information}}           {lpad}Lists the contents of a {{InformationHighlight}}.zip{{Information}} named by a mask as if it were a directory.
+  {{InformationHighlight}}{long}Columns{{Information}}         {lpad}Prints a header row (Date, Time, Attr, Size, Name) above the normal listing's columns.
+  {{InformationHighlight}}{long}WrapNames{{Information}}       {lpad}Wraps names too long for the line onto continuation lines indented to the name column.
+  {{InformationHighlight}}{long}ShowMask{{Information}}        {lpad}Appends the file spec each entry matched, to tell apart results from several masks.
+  {{InformationHighlight}}{long}Quiet{{Information}}           {lpad}Leaves only the listing: no RCDIR/config issue report and no empty-directory messages.
+  {{InformationHighlight}}{long}Up{{Information}}:{{InformationHighlight}}N{{Information}}            {lpad}Shows a breadcrumb of the listed directory and its {{InformationHighlight}}N{{Information}} parent directories above the listing.
+  {{InformationHighlight}}{long}Target{{Information}}          {lpad}Shows the volume label of each mount point's target (one extra volume lookup per mount point).
+  {{InformationHighlight}}{long}Attr{{Information}}:{{InformationHighlight}}full{{Information}}       {lpad}Widens the attribute column with the rarer flags ({{InformationHighlight}}X I B O L V{{Information}}, as listed for {{InformationHighlight}}{short}A{{Information}}).
+  {{InformationHighlight}}{long}Attr{{Information}}:{{InformationHighlight}}auto{{Information}}       {lpad}Shows the attribute column only for entries with a flag beyond archive (hidden, system, read-only, ...); blank otherwise.
+  {{InformationHighlight}}{long}AttrStyle{{Information}}:{{InformationHighlight}}ls{{Information}}    {lpad}Uses ls-like lowercase attribute letters with a leading {{InformationHighlight}}d{{Information}} for directories (default: {{InformationHighlight}}dir{{Information}}).
+  {{InformationHighlight}}{long}Group{{Information}}:{{InformationHighlight}}ext{{Information}}       {lpad}Lists each directory's entries in sections per extension (directories first), each with a count.
+  {lpad}                   Without {{InformationHighlight}}{short}O{{Information}}, image sections sort by date, documents by name, and binaries by size.
+  {{InformationHighlight}}{long}Hash{{Information}}={{InformationHighlight}}alg{{Information}}        {lpad}Displays a {{InformationHighlight}}CRC32{{Information}} or {{InformationHighlight}}SHA256{{Information}} checksum column (reads file contents; skips
+  {lpad}                   directories and cloud-only files). Not allowed with {{InformationHighlight}}{long}Tree{{Information}}.
+  {{InformationHighlight}}{long}Watch{{Information}}           {lpad}Re-displays the listing whenever the directory changes. Press Ctrl-C to exit.
 
   {{InformationHighlight}}{long}Set-Aliases{{Information}}     {lpad}Interactive wizard to configure PowerShell aliases for rcdir.
   {{InformationHighlight}}{long}Get-Aliases{{Information}}     {lpad}Display currently configured rcdir aliases and their source locations.
@@ -506,7 +670,17 @@ pub fn display_env_var_help(console: &mut Console, prefix: char) {
                   {{InformationHighlight}}Ellipsize{{Information}}  Truncate long link target paths; use {{InformationHighlight}}Ellipsize-{{Information}} to disable
                   {{InformationHighlight}}Depth=N{{Information}}  Limit tree depth to N levels
                   {{InformationHighlight}}TreeIndent=N{{Information}}  Tree indent width (1-8)
-                  {{InformationHighlight}}Size=Auto|Bytes{{Information}}  File size format
+                  {{InformationHighlight}}Size=Auto|Bytes|Compact{{Information}}  File size format
+                  {{InformationHighlight}}SepChar=X{{Information}}  Draws a rule of X above recursive totals (glyph or U+XXXX; default \u{2500})
+                  {{InformationHighlight}}SepWidth=N{{Information}}  Separator rule width (default: console width)
+                  {{InformationHighlight}}icons:dir=Off{{Information}}, {{InformationHighlight}}icons:file=Off{{Information}}  Hide all directory or file icons (colors are kept)
+                  {{InformationHighlight}}groupsort:img|doc|bin=[-]key{{Information}}  Sort key for that file type's {{InformationHighlight}}{prefix}Group:ext{{Information}} sections (defaults: img=D, doc=N, bin=S)
+                  {{InformationHighlight}}grouplabel:.ext=Label[,Color]{{Information}}  Heading text (and color) for that extension's {{InformationHighlight}}{prefix}Group:ext{{Information}} section
+                  {{InformationHighlight}}Sort=[-]N|E|S|D|title{{Information}}  Default sort order, as {{InformationHighlight}}{prefix}O{{Information}} (used when {{InformationHighlight}}{prefix}O{{Information}} is not given); {{InformationHighlight}}Sort=D{{Information}} is oldest first, use {{InformationHighlight}}Sort=-D{{Information}} for newest first
+                  {{InformationHighlight}}Time=C|A|W{{Information}}  Default time field, as {{InformationHighlight}}{prefix}T{{Information}} (used when {{InformationHighlight}}{prefix}T{{Information}} is not given)
+                  {{InformationHighlight}}DefaultPath=dir{{Information}}  Directory listed when no path is given, as {{InformationHighlight}}{prefix}Base{{Information}}
+                  {{InformationHighlight}}AgeTiers=new,recent,old{{Information}}  {{InformationHighlight}}{prefix}Tier{{Information}} age cutoffs (units m, h, d, w; default 1d,30d,365d)
+                  {{InformationHighlight}}Mode=normal|wide|bare|tree{{Information}}  Default listing style (used when no style switch is given; overrides {{InformationHighlight}}W{{Information}}, {{InformationHighlight}}B{{Information}}, and {{InformationHighlight}}Tree{{Information}})
 
   {{InformationHighlight}}<Item>{{Information}}      A display item:
                   {{InformationHighlight}}D{{Information}}  Date                     {{InformationHighlight}}T{{Information}}  Time
@@ -526,6 +700,7 @@ pub fn display_env_var_help(console: &mut Console, prefix: char) {
                   {{InformationHighlight}}T{{Information}}  Temporary                {{InformationHighlight}}E{{Information}}  Encrypted
                   {{InformationHighlight}}C{{Information}}  Compressed               {{InformationHighlight}}P{{Information}}  Reparse point
                   {{InformationHighlight}}0{{Information}}  Sparse file
+              Colors both the file name and the attribute's letter in the attribute column.
 
   {{InformationHighlight}}<.ext>{{Information}}      A file extension, including the leading period.
 
@@ -552,10 +727,16 @@ pub fn display_env_var_help(console: &mut Console, prefix: char) {
         ));
     }
 
+    console.color_puts(&format!(
+        "  {{Information}}Profiles: set {{InformationHighlight}}{RCDIR_PROFILE_ENV_VAR_NAME}{{Information}}={{InformationHighlight}}name{{Information}} to read {{InformationHighlight}}{RCDIR_ENV_VAR_NAME}_name{{Information}} instead (falls back to {{InformationHighlight}}{RCDIR_ENV_VAR_NAME}{{Information}} when unset)."
+    ));
+
     console.puts(Attribute::Default, "");
 
-    if is_env_var_set(RCDIR_ENV_VAR_NAME) {
-        display_env_var_current_value(console, RCDIR_ENV_VAR_NAME);
+    let env_name = active_env_var_name(&DefaultEnvironmentProvider);
+
+    if is_env_var_set(&env_name) {
+        display_env_var_current_value(console, &env_name);
         display_env_var_decoded_settings(console);
         display_env_var_issues(console, prefix, false);
     } else {
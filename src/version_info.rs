@@ -0,0 +1,338 @@
+// version_info.rs — PE version resources for the /VerInfo column
+//
+// Executables, DLLs, and drivers usually carry a VERSIONINFO resource
+// whose fixed part (VS_FIXEDFILEINFO) holds a four-part file version and
+// product version.  /VerInfo shows the file version (or the product
+// version when the file version is all zeros), which is handy when
+// auditing DLLs.  Only .exe, .dll, and .sys files are read, and only when
+// the switch is set.  The lookup goes through the VersionInfoProvider
+// trait so tests can substitute a mock.
+
+use std::ffi::{c_void, OsStr};
+use std::os::windows::ffi::OsStrExt;
+use std::path::Path;
+
+use windows::core::{w, PCWSTR};
+use windows::Win32::Storage::FileSystem::{
+    GetFileVersionInfoSizeW, GetFileVersionInfoW, VerQueryValueW, VS_FIXEDFILEINFO,
+};
+
+use crate::directory_info::DirectoryInfo;
+
+
+
+
+
+/// Width of the /VerInfo column: a typical "10.0.22621.3527" plus the
+/// trailing space.  Longer versions push the name over rather than
+/// being cut.
+pub const VERSION_COLUMN_WIDTH: usize = 16;
+
+/// Extensions whose version resource is read.
+const PE_EXTENSIONS: [&str; 3] = ["exe", "dll", "sys"];
+
+/// VS_FIXEDFILEINFO::dwSignature
+const FIXED_FILE_INFO_SIGNATURE: u32 = 0xFEEF_04BD;
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// The version numbers from a file's VS_FIXEDFILEINFO.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct VersionInfo {
+    pub file:    [u16; 4],
+    pub product: [u16; 4],
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl VersionInfo
+//
+//  Construction from the packed resource fields and column text.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl VersionInfo {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  from_packed
+    //
+    //  Unpack the MS/LS DWORD pairs (major.minor in MS, build.revision in
+    //  LS) of the file and product versions.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn from_packed(file_ms: u32, file_ls: u32, product_ms: u32, product_ls: u32) -> Self {
+        fn unpack(ms: u32, ls: u32) -> [u16; 4] {
+            [(ms >> 16) as u16, ms as u16, (ls >> 16) as u16, ls as u16]
+        }
+
+        VersionInfo {
+            file:    unpack (file_ms, file_ls),
+            product: unpack (product_ms, product_ls),
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  display_string
+    //
+    //  "major.minor.build.revision" of the file version, or of the product
+    //  version if the file version is unset.  None if both are unset.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn display_string(&self) -> Option<String> {
+        let parts = [self.file, self.product].into_iter().find (|v| v.iter().any (|&n| n != 0))?;
+        Some (format! ("{}.{}.{}.{}", parts[0], parts[1], parts[2], parts[3]))
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  VersionInfoProvider
+//
+//  Source of version resources.  The default implementation reads the
+//  file; tests supply a mock.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub trait VersionInfoProvider {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  version_info
+    //
+    //  The fixed version info of the file at `path`, or None if it has no
+    //  version resource or can't be read.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn version_info(&self, path: &Path) -> Option<VersionInfo>;
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Reads version resources with GetFileVersionInfoW + VerQueryValueW.
+pub struct DefaultVersionInfoProvider;
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl VersionInfoProvider for DefaultVersionInfoProvider
+//
+//  Loads the whole version resource, then queries its root block ("\")
+//  for VS_FIXEDFILEINFO.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl VersionInfoProvider for DefaultVersionInfoProvider {
+    fn version_info(&self, path: &Path) -> Option<VersionInfo> {
+        let wide: Vec<u16> = OsStr::new (path).encode_wide().chain (Some (0)).collect();
+
+        let size = unsafe { GetFileVersionInfoSizeW (PCWSTR (wide.as_ptr()), None) };
+        if size == 0 {
+            return None;
+        }
+
+        let mut data = vec![0u8; size as usize];
+        unsafe { GetFileVersionInfoW (PCWSTR (wide.as_ptr()), None, size, data.as_mut_ptr() as *mut c_void) }.ok()?;
+
+        let mut fixed: *mut c_void = std::ptr::null_mut();
+        let mut len = 0u32;
+        let found = unsafe { VerQueryValueW (data.as_ptr() as *const c_void, w!("\\"), &mut fixed, &mut len) };
+
+        if !found.as_bool() || fixed.is_null() || (len as usize) < std::mem::size_of::<VS_FIXEDFILEINFO>() {
+            return None;
+        }
+
+        // The pointer is into `data`, which is still alive here
+        let fixed = unsafe { &*(fixed as *const VS_FIXEDFILEINFO) };
+        if fixed.dwSignature != FIXED_FILE_INFO_SIGNATURE {
+            return None;
+        }
+
+        Some (VersionInfo::from_packed (
+            fixed.dwFileVersionMS,
+            fixed.dwFileVersionLS,
+            fixed.dwProductVersionMS,
+            fixed.dwProductVersionLS,
+        ))
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  is_pe_candidate
+//
+//  True for .exe, .dll, and .sys files (case-insensitive).
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn is_pe_candidate(file_name: &OsStr) -> bool {
+    Path::new (file_name)
+        .extension()
+        .is_some_and (|ext| PE_EXTENSIONS.iter().any (|pe| ext.eq_ignore_ascii_case (pe)))
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  read_version_info
+//
+//  Fill file_info.version for every PE file in a DirectoryInfo.
+//  Directories, other extensions, and files without a version resource
+//  stay None.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn read_version_info(di: &mut DirectoryInfo, provider: &dyn VersionInfoProvider) {
+    for file_info in &mut di.matches {
+        if file_info.is_directory() || !is_pe_candidate (&file_info.file_name) {
+            continue;
+        }
+
+        file_info.version = provider
+            .version_info (&di.dir_path.join (&file_info.file_name))
+            .and_then (|info| info.display_string());
+    }
+}
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    use crate::file_info::{FILE_ATTRIBUTE_ARCHIVE, FILE_ATTRIBUTE_DIRECTORY};
+    use crate::test_support::make_file;
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  MockVersionInfoProvider
+    //
+    //  Maps full paths to version info; every lookup is recorded so tests
+    //  can check which files were read.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    struct MockVersionInfoProvider {
+        versions: HashMap<PathBuf, VersionInfo>,
+        queried:  std::cell::RefCell<Vec<PathBuf>>,
+    }
+
+    impl VersionInfoProvider for MockVersionInfoProvider {
+        fn version_info(&self, path: &Path) -> Option<VersionInfo> {
+            self.queried.borrow_mut().push (path.to_path_buf());
+            self.versions.get (path).copied()
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  display_string_formats_four_parts
+    //
+    //  Verify the packed fields unpack to major.minor.build.revision, the
+    //  product version stands in for an unset file version, and no
+    //  version at all gives None.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn display_string_formats_four_parts() {
+        let info = VersionInfo::from_packed (0x000A_0000, 0x5A2B_0DC7, 0x000A_0000, 0x5A2B_0000);
+        assert_eq! (info.file, [10, 0, 23083, 3527]);
+        assert_eq! (info.display_string().as_deref(), Some ("10.0.23083.3527"));
+        assert! (info.display_string().unwrap().len() < VERSION_COLUMN_WIDTH);
+
+        let product_only = VersionInfo::from_packed (0, 0, 0x0002_0001, 0x0000_0007);
+        assert_eq! (product_only.display_string().as_deref(), Some ("2.1.0.7"));
+
+        assert_eq! (VersionInfo::default().display_string(), None);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  read_version_info_only_for_pe_files
+    //
+    //  Verify only .exe/.dll/.sys files are looked up (any case), a PE file
+    //  without a version resource stays blank, and directories and other
+    //  files are skipped.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn read_version_info_only_for_pe_files() {
+        let dir = PathBuf::from ("C:\\Windows\\System32");
+        let provider = MockVersionInfoProvider {
+            versions: HashMap::from ([
+                (dir.join ("kernel32.dll"), VersionInfo::from_packed (0x000A_0000, 0x4A61_0001, 0, 0)),
+                (dir.join ("NOTEPAD.EXE"),  VersionInfo::from_packed (0x000B_0000, 0x0000_0002, 0, 0)),
+            ]),
+            queried: Default::default(),
+        };
+
+        let mut di = DirectoryInfo::new (dir.clone(), "*".to_string());
+        di.matches.push (make_file ("kernel32.dll", FILE_ATTRIBUTE_ARCHIVE, 10));
+        di.matches.push (make_file ("NOTEPAD.EXE",  FILE_ATTRIBUTE_ARCHIVE, 10));
+        di.matches.push (make_file ("beep.sys",     FILE_ATTRIBUTE_ARCHIVE, 10));
+        di.matches.push (make_file ("readme.txt",   FILE_ATTRIBUTE_ARCHIVE, 10));
+        di.matches.push (make_file ("drivers.dll",  FILE_ATTRIBUTE_DIRECTORY, 10));
+
+        read_version_info (&mut di, &provider);
+
+        assert_eq! (di.matches[0].version.as_deref(), Some ("10.0.19041.1"));
+        assert_eq! (di.matches[1].version.as_deref(), Some ("11.0.0.2"));
+        assert_eq! (di.matches[2].version, None);
+        assert_eq! (di.matches[3].version, None);
+        assert_eq! (di.matches[4].version, None);
+
+        assert_eq! (*provider.queried.borrow(), vec![
+            dir.join ("kernel32.dll"),
+            dir.join ("NOTEPAD.EXE"),
+            dir.join ("beep.sys"),
+        ]);
+    }
+}
@@ -0,0 +1,454 @@
+// watch.rs — /Watch mode: re-render the listing when the directory changes
+//
+// One watcher thread per listed directory blocks in ReadDirectoryChangesW
+// and signals the main thread over a channel.  The main thread coalesces
+// bursts of notifications (e.g. a build writing many files) with a
+// Debouncer, then clears the screen (on a color console) and re-renders.
+// Runs until Ctrl-C.
+
+use std::ffi::OsStr;
+use std::os::windows::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use windows::Win32::Storage::FileSystem::{
+    CreateFileW, ReadDirectoryChangesW, FILE_FLAG_BACKUP_SEMANTICS, FILE_LIST_DIRECTORY,
+    FILE_NOTIFY_CHANGE_ATTRIBUTES, FILE_NOTIFY_CHANGE_CREATION, FILE_NOTIFY_CHANGE_DIR_NAME,
+    FILE_NOTIFY_CHANGE_FILE_NAME, FILE_NOTIFY_CHANGE_LAST_WRITE, FILE_NOTIFY_CHANGE_SIZE,
+    FILE_SHARE_DELETE, FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING,
+};
+
+use crate::console::Console;
+use crate::ehm::AppError;
+use crate::file_info::SafeHandle;
+
+
+
+
+
+/// ANSI sequence: clear screen and scrollback, move cursor home.
+pub const CLEAR_SCREEN: &str = "\x1b[2J\x1b[3J\x1b[H";
+
+/// Refresh once no change has arrived for this long.
+const QUIET_PERIOD: Duration = Duration::from_millis (250);
+
+/// Refresh at least this often while changes keep arriving.
+const MAX_DELAY: Duration = Duration::from_secs (2);
+
+/// ReadDirectoryChangesW notification buffer size.
+const NOTIFY_BUFFER_SIZE: usize = 64 * 1024;
+
+
+
+
+
+/// Coalesces bursts of change notifications into a single refresh.
+///
+/// A refresh is due once QUIET_PERIOD has passed since the last event, or
+/// MAX_DELAY has passed since the first unhandled event (so a directory
+/// that never stops changing still refreshes periodically).
+pub struct Debouncer {
+    quiet_period: Duration,
+    max_delay:    Duration,
+    first_event:  Option<Instant>,
+    last_event:   Option<Instant>,
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  impl Debouncer
+//
+//  Pure timing logic — all methods take `now` so tests can simulate time.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+impl Debouncer {
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  new
+    //
+    //  Create a Debouncer with the given quiet period and maximum delay.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn new(quiet_period: Duration, max_delay: Duration) -> Self {
+        Debouncer {
+            quiet_period,
+            max_delay,
+            first_event: None,
+            last_event:  None,
+        }
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  record_event
+    //
+    //  Note that a change notification arrived at `now`.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn record_event(&mut self, now: Instant) {
+        if self.first_event.is_none() {
+            self.first_event = Some (now);
+        }
+        self.last_event = Some (now);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  time_until_due
+    //
+    //  How long until a refresh is due.  None if no events are pending;
+    //  Duration::ZERO if a refresh is due now.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn time_until_due(&self, now: Instant) -> Option<Duration> {
+        let (first, last) = match (self.first_event, self.last_event) {
+            (Some (first), Some (last)) => (first, last),
+            _ => return None,
+        };
+
+        let quiet_deadline = last + self.quiet_period;
+        let max_deadline   = first + self.max_delay;
+        let deadline       = quiet_deadline.min (max_deadline);
+
+        Some (deadline.saturating_duration_since (now))
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  take_if_due
+    //
+    //  If a refresh is due at `now`, clear the pending events and return
+    //  true.  Otherwise leave state unchanged and return false.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    pub fn take_if_due(&mut self, now: Instant) -> bool {
+        if self.time_until_due (now) != Some (Duration::ZERO) {
+            return false;
+        }
+
+        self.first_event = None;
+        self.last_event  = None;
+        true
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  run
+//
+//  Render the listing, then re-render it (after clearing the screen,
+//  when escapes are enabled) each time one of `dirs` changes.  `render` produces one complete
+//  listing into the console and hands it back.  Returns on a console
+//  error or once no directory can be watched; otherwise the user stops
+//  watch mode with Ctrl-C.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn run<F>(mut console: Console, dirs: &[PathBuf], recursive: bool, mut render: F) -> Result<(), AppError>
+where
+    F: FnMut(Console) -> Console,
+{
+    let (tx, rx) = mpsc::channel();
+
+    for dir in dirs {
+        spawn_watcher (dir.clone(), recursive, tx.clone());
+    }
+    drop (tx);
+
+    let mut debouncer = Debouncer::new (QUIET_PERIOD, MAX_DELAY);
+
+    loop {
+        // Redirected or colorless output gets each listing appended instead
+        if console.escapes_enabled() {
+            console.write_raw (CLEAR_SCREEN);
+        }
+        console = render (console);
+        console.flush()?;
+
+        if !wait_for_changes (&rx, &mut debouncer) {
+            // Every watcher has exited (directories removed or unwatchable)
+            return Ok(());
+        }
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  wait_for_changes
+//
+//  Block until at least one change arrives, then keep absorbing changes
+//  until the debouncer says a refresh is due.  Returns false if all
+//  watcher threads have disconnected.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn wait_for_changes(rx: &Receiver<()>, debouncer: &mut Debouncer) -> bool {
+    if rx.recv().is_err() {
+        return false;
+    }
+    debouncer.record_event (Instant::now());
+
+    loop {
+        let now = Instant::now();
+        if debouncer.take_if_due (now) {
+            return true;
+        }
+
+        let wait = debouncer.time_until_due (now).unwrap_or (Duration::ZERO);
+
+        match rx.recv_timeout (wait) {
+            Ok(())                               => debouncer.record_event (Instant::now()),
+            Err (RecvTimeoutError::Timeout)      => {}
+            Err (RecvTimeoutError::Disconnected) => return true, // Render pending changes once more
+        }
+    }
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  spawn_watcher
+//
+//  Start a thread that sends () on `tx` for every batch of change
+//  notifications in `dir`.  The thread exits if the directory cannot be
+//  opened or watched, or once the receiver is gone.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn spawn_watcher(dir: PathBuf, recursive: bool, tx: Sender<()>) {
+    thread::spawn (move || {
+        let Some (handle) = open_directory (&dir) else {
+            return;
+        };
+
+        let mut buffer = vec![0u8; NOTIFY_BUFFER_SIZE];
+        let filter = FILE_NOTIFY_CHANGE_FILE_NAME
+                   | FILE_NOTIFY_CHANGE_DIR_NAME
+                   | FILE_NOTIFY_CHANGE_ATTRIBUTES
+                   | FILE_NOTIFY_CHANGE_SIZE
+                   | FILE_NOTIFY_CHANGE_LAST_WRITE
+                   | FILE_NOTIFY_CHANGE_CREATION;
+
+        loop {
+            let mut bytes_returned = 0u32;
+
+            // Synchronous call: blocks until something in the directory changes.
+            // Contents are not parsed — any change triggers a full re-render.
+            let result = unsafe {
+                ReadDirectoryChangesW (
+                    handle.0,
+                    buffer.as_mut_ptr().cast(),
+                    buffer.len() as u32,
+                    recursive,
+                    filter,
+                    Some (&mut bytes_returned),
+                    None,
+                    None,
+                )
+            };
+
+            if result.is_err() || tx.send (()).is_err() {
+                return;
+            }
+        }
+    });
+}
+
+
+
+
+
+////////////////////////////////////////////////////////////////////////////////
+//
+//  open_directory
+//
+//  Open a directory handle suitable for ReadDirectoryChangesW.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+fn open_directory(dir: &Path) -> Option<SafeHandle> {
+    let wide_path: Vec<u16> = OsStr::new (dir)
+        .encode_wide()
+        .chain (std::iter::once (0))
+        .collect();
+
+    let handle = unsafe {
+        CreateFileW (
+            windows::core::PCWSTR (wide_path.as_ptr()),
+            FILE_LIST_DIRECTORY.0,
+            FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE,
+            None,
+            OPEN_EXISTING,
+            FILE_FLAG_BACKUP_SEMANTICS,
+            None,
+        )
+    };
+
+    handle.ok().map (SafeHandle)
+}
+
+
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  make_debouncer
+    //
+    //  Creates a Debouncer with a 100 ms quiet period and 1 s max delay.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    fn make_debouncer() -> Debouncer {
+        Debouncer::new (Duration::from_millis (100), Duration::from_secs (1))
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  no_events_nothing_due
+    //
+    //  Verify an idle debouncer never reports a refresh.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn no_events_nothing_due () {
+        let mut debouncer = make_debouncer();
+        let now = Instant::now();
+
+        assert_eq! (debouncer.time_until_due (now), None);
+        assert! (!debouncer.take_if_due (now + Duration::from_secs (10)));
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  single_event_due_after_quiet_period
+    //
+    //  Verify one event becomes due exactly after the quiet period.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn single_event_due_after_quiet_period () {
+        let mut debouncer = make_debouncer();
+        let t0 = Instant::now();
+
+        debouncer.record_event (t0);
+
+        assert_eq! (debouncer.time_until_due (t0), Some (Duration::from_millis (100)));
+        assert! (!debouncer.take_if_due (t0 + Duration::from_millis (99)));
+        assert! (debouncer.take_if_due (t0 + Duration::from_millis (100)));
+
+        // Consumed — nothing pending afterwards
+        assert_eq! (debouncer.time_until_due (t0 + Duration::from_millis (200)), None);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  burst_coalesces_into_one_refresh
+    //
+    //  Verify a burst of closely spaced events yields a single refresh,
+    //  due one quiet period after the last event.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn burst_coalesces_into_one_refresh () {
+        let mut debouncer = make_debouncer();
+        let t0 = Instant::now();
+        let mut refreshes = 0;
+
+        // Ten events 20 ms apart (t0 .. t0+180ms), polling every 10 ms
+        for ms in (0..400).step_by (10) {
+            let now = t0 + Duration::from_millis (ms);
+            if ms.is_multiple_of (20) && ms < 200 {
+                debouncer.record_event (now);
+            }
+            if debouncer.take_if_due (now) {
+                refreshes += 1;
+                assert_eq! (ms, 280, "refresh should fire 100 ms after the last event");
+            }
+        }
+
+        assert_eq! (refreshes, 1);
+    }
+
+
+
+
+
+    ////////////////////////////////////////////////////////////////////////////
+    //
+    //  continuous_changes_refresh_at_max_delay
+    //
+    //  Verify a directory that never goes quiet still refreshes once the
+    //  max delay since the first pending event has elapsed.
+    //
+    ////////////////////////////////////////////////////////////////////////////
+
+    #[test]
+    fn continuous_changes_refresh_at_max_delay () {
+        let mut debouncer = make_debouncer();
+        let t0 = Instant::now();
+
+        // Events every 50 ms — never a 100 ms quiet gap
+        for ms in (0..1000).step_by (50) {
+            let now = t0 + Duration::from_millis (ms);
+            debouncer.record_event (now);
+            assert! (!debouncer.take_if_due (now), "refreshed early at {} ms", ms);
+        }
+
+        assert! (debouncer.take_if_due (t0 + Duration::from_millis (1000)));
+    }
+}
@@ -2,7 +2,7 @@
 //
 // Runs both executables with the same arguments and compares output line-by-line.
 // Output is compared WITH ANSI escape codes (they must be byte-identical).
-// Skips comparison of timing lines (RCDir/TCDir time elapsed) and volume free space
+// Skips comparison of timing lines (RCDir/TCDir time elapsed, RCDir throughput) and volume free space
 // (which may differ between runs).
 //
 // Requirements:
@@ -106,8 +106,8 @@ fn run_command(exe: &Path, args: &[&str]) -> String {
 //
 //  filter_lines
 //
-//  Filter lines for comparison — remove timing lines, free space lines, and
-//  bytes-available lines.  Does NOT strip ANSI codes — output must be
+//  Filter lines for comparison — remove timing lines, free space lines,
+//  bytes-available lines, and the RCDir-only volume serial/size lines.  Does NOT strip ANSI codes — output must be
 //  byte-identical including escape sequences.
 //
 ////////////////////////////////////////////////////////////////////////////////
@@ -123,6 +123,10 @@ fn filter_lines(output: &str) -> Vec<String> {
             if trimmed.starts_with("RCDir time elapsed:") || trimmed.starts_with("TCDir time elapsed:") {
                 return false;
             }
+            // Skip the RCDir-only throughput line (varies between runs)
+            if trimmed.starts_with("RCDir throughput:") {
+                return false;
+            }
             // Skip free space lines (vary between runs)
             if trimmed.ends_with("bytes free on volume") {
                 return false;
@@ -131,6 +135,10 @@ fn filter_lines(output: &str) -> Vec<String> {
             if trimmed.ends_with("bytes available to user") {
                 return false;
             }
+            // Skip volume serial/size lines (RCDir-only header extension)
+            if trimmed.starts_with("Volume Serial Number is") || trimmed.starts_with("Volume size is") {
+                return false;
+            }
             true
         })
         .map(|s| s.to_string())